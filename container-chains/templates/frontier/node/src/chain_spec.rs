@@ -168,6 +168,7 @@ fn testnet_genesis(
                 .map(|k| (k, 1 << 80))
                 .collect(),
         },
+        orchestrator_balances: Default::default(),
         parachain_info: container_chain_template_frontier_runtime::ParachainInfoConfig {
             parachain_id: id,
         },
@@ -46,7 +46,7 @@ use {
         traits::{
             ConstU128, ConstU32, ConstU64, ConstU8, Contains, Currency as CurrencyT, Imbalance,
             InstanceFilter, OffchainWorker, OnFinalize, OnIdle, OnInitialize, OnRuntimeUpgrade,
-            OnUnbalanced,
+            OnUnbalanced, StorageMapShim,
         },
         weights::{
             constants::{
@@ -495,6 +495,32 @@ impl pallet_balances::Config for Runtime {
     type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
 }
 
+/// Balances of the native token of the orchestrator chain, received from it with reserve
+/// transfers.
+pub type OrchestratorBalancesInstance = pallet_balances::Instance2;
+
+impl pallet_balances::Config<OrchestratorBalancesInstance> for Runtime {
+    type MaxLocks = ConstU32<50>;
+    /// The type for recording an account's balance.
+    type Balance = Balance;
+    /// The ubiquitous event type.
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = StorageMapShim<
+        pallet_balances::Account<Runtime, OrchestratorBalancesInstance>,
+        AccountId,
+        pallet_balances::AccountData<Balance>,
+    >;
+    type MaxReserves = ConstU32<50>;
+    type ReserveIdentifier = [u8; 8];
+    type FreezeIdentifier = [u8; 8];
+    type MaxFreezes = ConstU32<0>;
+    type HoldIdentifier = [u8; 8];
+    type MaxHolds = ConstU32<0>;
+    type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
+}
+
 parameter_types! {
     pub const ReservedXcmpWeight: Weight = MAXIMUM_BLOCK_WEIGHT.saturating_div(4);
     pub const ReservedDmpWeight: Weight = MAXIMUM_BLOCK_WEIGHT.saturating_div(4);
@@ -635,6 +661,7 @@ impl Contains<RuntimeCall> for MaintenanceFilter {
     fn contains(c: &RuntimeCall) -> bool {
         match c {
             RuntimeCall::Balances(_) => false,
+            RuntimeCall::OrchestratorBalances(_) => false,
             RuntimeCall::Ethereum(_) => false,
             RuntimeCall::EVM(_) => false,
             RuntimeCall::PolkadotXcm(_) => false,
@@ -878,6 +905,7 @@ construct_runtime!(
 
         // Monetary stuff.
         Balances: pallet_balances = 10,
+        OrchestratorBalances: pallet_balances::<Instance2> = 12,
 
         // ContainerChain
         AuthoritiesNoting: pallet_cc_authorities_noting = 50,
@@ -16,8 +16,9 @@
 
 use {
     super::{
-        AccountId, AllPalletsWithSystem, Balances, ParachainInfo, ParachainSystem, PolkadotXcm,
-        Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, WeightToFee, XcmpQueue,
+        AccountId, AllPalletsWithSystem, AuthoritiesNoting, Balances, OrchestratorBalances,
+        ParachainInfo, ParachainSystem, PolkadotXcm, Runtime, RuntimeCall, RuntimeEvent,
+        RuntimeOrigin, WeightToFee, XcmpQueue, UNIT,
     },
     ccp_xcm::SignedToAccountKey20,
     frame_support::{
@@ -31,12 +32,12 @@ use {
     xcm::latest::prelude::*,
     xcm_builder::{
         AccountKey20Aliases, AllowKnownQueryResponses, AllowSubscriptionsFrom,
-        AllowTopLevelPaidExecutionFrom, CurrencyAdapter, EnsureXcmOrigin, FixedWeightBounds,
-        IsConcrete, ParentIsPreset, RelayChainAsNative, SiblingParachainAsNative,
-        SiblingParachainConvertsVia, SignedAccountKey20AsNative, SovereignSignedViaLocation,
-        TakeWeightCredit, UsingComponents, WithComputedOrigin,
+        AllowTopLevelPaidExecutionFrom, Case, CurrencyAdapter, EnsureXcmOrigin,
+        FixedRateOfFungible, FixedWeightBounds, IsConcrete, ParentIsPreset, RelayChainAsNative,
+        SiblingParachainAsNative, SiblingParachainConvertsVia, SignedAccountKey20AsNative,
+        SovereignSignedViaLocation, TakeWeightCredit, UsingComponents, WithComputedOrigin,
     },
-    xcm_executor::XcmExecutor,
+    xcm_executor::{traits::ConvertOrigin, XcmExecutor},
 };
 
 parameter_types! {
@@ -69,6 +70,37 @@ parameter_types! {
     // The universal location within the global consensus system
     pub UniversalLocation: InteriorMultiLocation =
     X2(GlobalConsensus(RelayNetwork::get()), Parachain(ParachainInfo::parachain_id().into()));
+
+    // The orchestrator chain, reserve of its native token
+    pub OrchestratorLocation: MultiLocation = MultiLocation {
+        parents: 1,
+        interior: X1(Parachain(AuthoritiesNoting::orchestrator_para_id().into())),
+    };
+
+    /// Index of the `Balances` pallet of the orchestrator chain, which transacts its native
+    /// token.
+    pub const OrchestratorBalancesPalletIndex: u8 = 10;
+
+    // Native token of the orchestrator chain, forwarded here with `DepositReserveAsset`
+    pub OrchestratorNativeToken: MultiLocation = MultiLocation {
+        parents: 1,
+        interior: X2(
+            Parachain(AuthoritiesNoting::orchestrator_para_id().into()),
+            PalletInstance(OrchestratorBalancesPalletIndex::get()),
+        ),
+    };
+
+    pub OrchestratorNativeTokenFromOrchestrator: (MultiAssetFilter, MultiLocation) = (
+        Wild(AllOf { id: Concrete(OrchestratorNativeToken::get()), fun: WildFungible }),
+        OrchestratorLocation::get(),
+    );
+
+    // Execution can be paid in the orchestrator native token. Proof size is not charged yet
+    pub OrchestratorNativeTokenFeeRate: (AssetId, u128, u128) = (
+        Concrete(OrchestratorNativeToken::get()),
+        UNIT,
+        0,
+    );
 }
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -135,6 +167,9 @@ pub type XcmOriginToTransactDispatchOrigin = (
     // Native converter for Relay-chain (Parent) location; will convert to a `Relay` origin when
     // recognised.
     RelayChainAsNative<RelayChainOrigin, RuntimeOrigin>,
+    // Superuser converter for the orchestrator chain; will convert to a `Root` origin when
+    // recognised, so that the orchestrator governance can dispatch calls on this chain.
+    OrchestratorAsSuperuser,
     // Native converter for sibling Parachains; will convert to a `SiblingPara` origin when
     // recognised.
     SiblingParachainAsNative<cumulus_pallet_xcm::Origin, RuntimeOrigin>,
@@ -145,8 +180,37 @@ pub type XcmOriginToTransactDispatchOrigin = (
     XcmPassthrough<RuntimeOrigin>,
 );
 
+/// Converts a `Superuser` origin coming from the orchestrator chain into `Root`.
+pub struct OrchestratorAsSuperuser;
+impl ConvertOrigin<RuntimeOrigin> for OrchestratorAsSuperuser {
+    fn convert_origin(
+        origin: impl Into<MultiLocation>,
+        kind: OriginKind,
+    ) -> Result<RuntimeOrigin, MultiLocation> {
+        let origin = origin.into();
+        let orchestrator = MultiLocation::new(
+            1,
+            X1(Parachain(AuthoritiesNoting::orchestrator_para_id().into())),
+        );
+        match kind {
+            OriginKind::Superuser if origin == orchestrator => Ok(RuntimeOrigin::root()),
+            _ => Err(origin),
+        }
+    }
+}
+
+/// Means for transacting the native token of the orchestrator chain, which is its reserve.
+pub type OrchestratorCurrencyTransactor = CurrencyAdapter<
+    OrchestratorBalances,
+    IsConcrete<OrchestratorNativeToken>,
+    LocationToAccountId,
+    AccountId,
+    // Reserve transferred, not teleported.
+    (),
+>;
+
 /// Means for transacting assets on this chain.
-pub type AssetTransactors = CurrencyTransactor;
+pub type AssetTransactors = (CurrencyTransactor, OrchestratorCurrencyTransactor);
 pub type XcmWeigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
 
 /// The means for routing XCM messages which are not for local execution into the right message
@@ -164,14 +228,18 @@ impl xcm_executor::Config for XcmConfig {
     type XcmSender = XcmRouter;
     type AssetTransactor = AssetTransactors;
     type OriginConverter = XcmOriginToTransactDispatchOrigin;
-    type IsReserve = ();
+    // The orchestrator chain forwards its native token here
+    type IsReserve = Case<OrchestratorNativeTokenFromOrchestrator>;
     type IsTeleporter = ();
     type UniversalLocation = UniversalLocation;
     type Barrier = XcmBarrier;
     type Weigher = XcmWeigher;
-    // Local token trader only
+    // Local token trader, also accepting the orchestrator native token at a fixed rate
     // TODO: update once we have a way to do fees
-    type Trader = UsingComponents<WeightToFee, SelfReserve, AccountId, Balances, ()>;
+    type Trader = (
+        UsingComponents<WeightToFee, SelfReserve, AccountId, Balances, ()>,
+        FixedRateOfFungible<OrchestratorNativeTokenFeeRate, ()>,
+    );
     type ResponseHandler = PolkadotXcm;
     type AssetTrap = PolkadotXcm;
     type AssetClaims = PolkadotXcm;
@@ -183,6 +183,7 @@ fn testnet_genesis(
                 .map(|k| (k, 1 << 60))
                 .collect(),
         },
+        orchestrator_balances: Default::default(),
         parachain_info: container_chain_template_simple_runtime::ParachainInfoConfig {
             parachain_id: id,
         },
@@ -337,6 +337,8 @@ fn testnet_genesis(
                 )
                 .collect(),
         },
+        // Collators are assigned upon the first session
+        collator_assignment: Default::default(),
         sudo: SudoConfig {
             key: Some(root_key),
         },
@@ -45,7 +45,7 @@ use {
     sp_runtime::{traits::Header, DispatchResult, RuntimeString},
     tp_author_noting_inherent::INHERENT_IDENTIFIER,
     tp_core::well_known_keys::PARAS_HEADS_INDEX,
-    tp_traits::{GetContainerChainAuthor, GetCurrentContainerChains},
+    tp_traits::{AuthorNotingHook, GetContainerChainAuthor, GetCurrentContainerChains},
 };
 
 #[cfg(test)]
@@ -81,6 +81,9 @@ pub mod pallet {
 
         type RelayChainStateProvider: cumulus_pallet_parachain_system::RelaychainStateProvider;
 
+        /// Notified of the author of each new container chain block that is noted.
+        type AuthorNotingHook: AuthorNotingHook<Self::AccountId>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
@@ -127,7 +130,12 @@ pub mod pallet {
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         #[pallet::call_index(0)]
-        #[pallet::weight((0, DispatchClass::Mandatory))]
+        #[pallet::weight((
+            T::WeightInfo::set_latest_author_data(
+                T::ContainerChains::current_container_chains().len() as u32
+            ),
+            DispatchClass::Mandatory
+        ))]
         pub fn set_latest_author_data(
             origin: OriginFor<T>,
             data: tp_author_noting_inherent::OwnParachainInherentData,
@@ -159,7 +167,44 @@ pub mod pallet {
 
                 for para_id in registered_para_ids {
                     match Self::fetch_block_info_from_proof(&relay_storage_rooted_proof, para_id) {
-                        Ok(block_info) => LatestAuthor::<T>::insert(para_id, block_info),
+                        Ok(block_info) => {
+                            if T::ContainerChainAuthor::is_author_assigned(
+                                para_id,
+                                &block_info.author,
+                            ) {
+                                // The same block can be noted again if the container chain
+                                // did not progress
+                                let is_new_block = LatestAuthor::<T>::get(para_id)
+                                    .map_or(true, |latest| {
+                                        latest.block_number != block_info.block_number
+                                    });
+                                if is_new_block {
+                                    let hook_weight =
+                                        T::AuthorNotingHook::on_container_author_noted(
+                                            &block_info.author,
+                                            block_info.block_number,
+                                            para_id,
+                                        );
+                                    // The hook weight is not known before dispatching, and
+                                    // the actual weight cannot exceed the declared one
+                                    frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                                        hook_weight,
+                                        DispatchClass::Mandatory,
+                                    );
+                                }
+                                LatestAuthor::<T>::insert(para_id, block_info);
+                            } else {
+                                log::warn!(
+                                    "Author-noting found an author not assigned to para {:?}",
+                                    u32::from(para_id)
+                                );
+                                Self::deposit_event(Event::InvalidAuthorNoted {
+                                    para_id,
+                                    block_number: block_info.block_number,
+                                    author: block_info.author,
+                                });
+                            }
+                        }
                         Err(e) => log::warn!(
                             "Author-noting error {:?} found in para {:?}",
                             e,
@@ -212,6 +257,12 @@ pub mod pallet {
             block_number: BlockNumber,
             new_author: T::AccountId,
         },
+        /// The author of a container chain block is not assigned to that container chain
+        InvalidAuthorNoted {
+            para_id: ParaId,
+            block_number: BlockNumber,
+            author: T::AccountId,
+        },
     }
 
     #[pallet::storage]
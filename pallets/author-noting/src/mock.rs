@@ -24,6 +24,7 @@ use {
         traits::{
             ConstU32, ConstU64, Everything, OnFinalize, OnInitialize, UnfilteredDispatchable,
         },
+        weights::Weight,
     },
     frame_system::RawOrigin,
     parity_scale_codec::{Decode, Encode},
@@ -123,12 +124,16 @@ impl mock_data::Config for Test {}
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mocks {
     pub container_chains: Vec<ParaId>,
+    pub unassigned_authors: Vec<AccountId>,
+    pub noted_authors: Vec<(AccountId, u32, ParaId)>,
 }
 
 impl Default for Mocks {
     fn default() -> Self {
         Self {
             container_chains: vec![1001.into()],
+            unassigned_authors: vec![],
+            noted_authors: vec![],
         }
     }
 }
@@ -140,10 +145,26 @@ impl tp_traits::GetContainerChainAuthor<AccountId> for MockAuthorFetcher {
         Some(slot.into())
     }
 
+    fn is_author_assigned(_para_id: ParaId, author: &AccountId) -> bool {
+        !MockData::mock().unassigned_authors.contains(author)
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     fn set_authors_for_para_id(_para_id: ParaId, _authors: Vec<AccountId>) {}
 }
 
+pub const NOTING_HOOK_WEIGHT: Weight = Weight::from_parts(1_000, 0);
+
+pub struct MockAuthorNotingHook;
+
+impl tp_traits::AuthorNotingHook<AccountId> for MockAuthorNotingHook {
+    fn on_container_author_noted(author: &AccountId, block_number: u32, para_id: ParaId) -> Weight {
+        MockData::mutate(|m| m.noted_authors.push((*author, block_number, para_id)));
+
+        NOTING_HOOK_WEIGHT
+    }
+}
+
 pub struct MockContainerChainGetter;
 
 impl tp_traits::GetCurrentContainerChains for MockContainerChainGetter {
@@ -188,6 +209,7 @@ impl Config for Test {
     type SelfParaId = ParachainId;
     type ContainerChains = MockContainerChainGetter;
     type RelayChainStateProvider = MockRelayStateProvider;
+    type AuthorNotingHook = MockAuthorNotingHook;
 }
 
 struct BlockTest {
@@ -19,9 +19,10 @@ use {
     cumulus_primitives_core::ParaId,
     frame_support::{
         assert_ok,
-        dispatch::GetDispatchInfo,
+        dispatch::{DispatchClass, GetDispatchInfo},
         inherent::{InherentData, ProvideInherent},
         traits::UnfilteredDispatchable,
+        weights::Weight,
     },
     frame_system::RawOrigin,
     hex_literal::hex,
@@ -69,6 +70,90 @@ fn test_author_id_insertion() {
         });
 }
 
+#[test]
+fn test_author_not_assigned_is_not_inserted() {
+    BlockTests::new()
+        .with_relay_sproof_builder(|_, relay_block_num, sproof| match relay_block_num {
+            1 => {
+                // Author 13 is not assigned to para 1001
+                MockData::mutate(|m| m.unassigned_authors = vec![13u64]);
+
+                let slot: InherentType = 13u64.into();
+                let mut s = ParaHeaderSproofBuilderItem::default();
+                s.para_id = 1001.into();
+                s.author_id =
+                    HeaderAs::NonEncoded(sp_runtime::generic::Header::<u32, BlakeTwo256> {
+                        parent_hash: Default::default(),
+                        number: 1,
+                        state_root: Default::default(),
+                        extrinsics_root: Default::default(),
+                        digest: sp_runtime::generic::Digest {
+                            logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())],
+                        },
+                    });
+                sproof.items.push(s);
+            }
+            _ => unreachable!(),
+        })
+        .add(1, || {
+            assert_eq!(AuthorNoting::latest_author(ParaId::from(1001)), None);
+            System::assert_last_event(
+                Event::InvalidAuthorNoted {
+                    para_id: 1001.into(),
+                    block_number: 1,
+                    author: 13u64,
+                }
+                .into(),
+            );
+        });
+}
+
+#[test]
+fn test_hook_is_notified_once_per_container_block() {
+    BlockTests::new()
+        .with_relay_sproof_builder(|_, relay_block_num, sproof| {
+            // The container chain does not progress in the second block
+            let (slot, number): (InherentType, u32) = match relay_block_num {
+                1 | 2 => (13u64.into(), 1),
+                3 => (14u64.into(), 2),
+                _ => unreachable!(),
+            };
+            let mut s = ParaHeaderSproofBuilderItem::default();
+            s.para_id = 1001.into();
+            s.author_id = HeaderAs::NonEncoded(sp_runtime::generic::Header::<u32, BlakeTwo256> {
+                parent_hash: Default::default(),
+                number,
+                state_root: Default::default(),
+                extrinsics_root: Default::default(),
+                digest: sp_runtime::generic::Digest {
+                    logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())],
+                },
+            });
+            sproof.items.push(s);
+        })
+        .add(1, || {
+            assert_eq!(MockData::mock().noted_authors, vec![(13, 1, 1001.into())]);
+            // The hook weight is registered for the block
+            assert_eq!(
+                System::block_weight().get(DispatchClass::Mandatory),
+                &NOTING_HOOK_WEIGHT
+            );
+        })
+        .add(2, || {
+            assert_eq!(MockData::mock().noted_authors, vec![(13, 1, 1001.into())]);
+            assert_eq!(
+                System::block_weight().get(DispatchClass::Mandatory),
+                &Weight::zero()
+            );
+        })
+        .add(3, || {
+            assert_eq!(
+                MockData::mock().noted_authors,
+                vec![(13, 1, 1001.into()), (14, 2, 1001.into())]
+            );
+        });
+}
+
 #[test]
 fn test_author_id_insertion_real_data() {
     BlockTests::new()
@@ -637,8 +722,14 @@ fn weights_assigned_to_extrinsics_are_correct() {
                 &system_inherent_data,
             )
             .expect("failed to put VFP inherent");
-        let inherent_weight = AuthorNoting::create_inherent(&inherent_data)
-            .expect("got an inherent")
+        let inherent = AuthorNoting::create_inherent(&inherent_data).expect("got an inherent");
+        assert_eq!(
+            inherent.get_dispatch_info().weight,
+            <() as crate::weights::WeightInfo>::set_latest_author_data(
+                <Test as crate::Config>::ContainerChains::current_container_chains().len() as u32
+            )
+        );
+        let inherent_weight = inherent
             .dispatch_bypass_filter(RawOrigin::None.into())
             .expect("dispatch succeeded");
 
@@ -20,7 +20,10 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use scale_info::prelude::vec::Vec;
+use {
+    scale_info::prelude::vec::Vec,
+    tp_collator_assignment::{AssignedCollators, AssignmentConfig},
+};
 
 sp_api::decl_runtime_apis! {
     pub trait CollatorAssignmentApi<AccountId, ParaId> where
@@ -37,5 +40,18 @@ sp_api::decl_runtime_apis! {
         /// Return the list of collators of the given `ParaId`.
         /// Returns `None` if the `ParaId` is not in the registrar.
         fn parachain_collators(para_id: ParaId) -> Option<Vec<AccountId>>;
+        /// Return the list of collators currently assigned to the orchestrator chain.
+        fn orchestrator_collators() -> Vec<AccountId>;
+        /// Return the list of collators currently assigned to the given container chain.
+        /// Returns an empty list if the container chain has no collators.
+        fn container_collators(para_id: ParaId) -> Vec<AccountId>;
+        /// Return the assignment that the next session change would produce if the host
+        /// configuration was `proposed_config`, without modifying storage.
+        fn dry_run_assignment(proposed_config: AssignmentConfig) -> AssignedCollators<AccountId>;
+        /// Return the collators eligible for the assignment that are currently not assigned to
+        /// any chain.
+        fn idle_collators() -> Vec<AccountId>;
+        /// Return the number of sessions the given `AccountId` was assigned to any chain.
+        fn collator_sessions_served(account: AccountId) -> u32;
     }
 }
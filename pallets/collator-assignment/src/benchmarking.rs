@@ -66,9 +66,16 @@ mod benchmarks {
         T::ContainerChains::set_session_container_chains(session_index, &container_chains);
 
         // Assign random collators to test worst case: when collators need to be checked against existing collators
-        // In this case all of the old collators don't exist anymore
+        // In this case all of the old collators don't exist anymore.
+        // The old container chains are also different from the new ones, so the metadata of every
+        // chain needs to be updated.
         let old_container_chains: Vec<(ParaId, _)> = (0..20)
-            .map(|para_id| (para_id.into(), invulnerables::<T>(10, SEED + 2 + para_id)))
+            .map(|para_id| {
+                (
+                    (1000 + para_id).into(),
+                    invulnerables::<T>(10, SEED + 2 + para_id),
+                )
+            })
             .collect();
         let old_assigned = AssignedCollators {
             orchestrator_chain: invulnerables::<T>(100, SEED + 1),
@@ -76,6 +83,23 @@ mod benchmarks {
         };
         <CollatorContainerChain<T>>::put(&old_assigned);
 
+        // Every collator has the maximum number of preferences, capabilities and a recent chain,
+        // and every container chain requires capabilities, so that all of them are read.
+        let preferences: Vec<_> = (0..T::MaxCollatorPreferences::get())
+            .map(ParaId::from)
+            .collect();
+        for collator in &collators {
+            <CollatorPreferences<T>>::insert(
+                collator,
+                BoundedVec::truncate_from(preferences.clone()),
+            );
+            <CollatorCapabilities<T>>::insert(collator, 1);
+            <RecentCollatorChain<T>>::insert(collator, (ParaId::from(1000), session_index));
+        }
+        for para_id in &container_chains {
+            <RequiredCapabilities<T>>::insert(para_id, 1);
+        }
+
         #[block]
         {
             <Pallet<T>>::initializer_on_new_session(&session_index, collators);
@@ -92,5 +116,225 @@ mod benchmarks {
         Ok(())
     }
 
-    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test,);
+    #[benchmark]
+    fn force_unassign_collator() -> Result<(), BenchmarkError> {
+        // Worst case: the collator is assigned to the last container chain and the only free
+        // collator is the last one
+        let collators = invulnerables::<T>(200, SEED);
+        let container_chains: Vec<(ParaId, _)> = collators[100..]
+            .chunks(10)
+            .enumerate()
+            .map(|(para_id, chunk)| (ParaId::from(para_id as u32), chunk.to_vec()))
+            .collect();
+        let assigned = AssignedCollators {
+            orchestrator_chain: collators[..99].to_vec(),
+            container_chains: BTreeMap::from_iter(container_chains),
+        };
+        <CollatorContainerChain<T>>::put(&assigned);
+        <PendingCollatorContainerChain<T>>::put(Some(assigned));
+        <Collators<T>>::put(&collators);
+        let collator = collators[199].clone();
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, collator.clone());
+
+        assert!(!<CollatorContainerChain::<T>>::get().find_collator(&collator));
+        assert!(<CollatorContainerChain::<T>>::get().find_collator(&collators[99]));
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn force_recompute_assignment() -> Result<(), BenchmarkError> {
+        frame_system::Pallet::<T>::set_block_number(0u32.into());
+
+        let container_chains: Vec<_> = (0..20).map(|para_id| ParaId::from(para_id)).collect();
+        let session_index = T::CurrentSessionIndex::session_index();
+        T::ContainerChains::set_session_container_chains(session_index, &container_chains);
+        T::ContainerChains::set_session_container_chains(
+            session_index.saturating_add(1u32.into()),
+            &container_chains,
+        );
+
+        // Worst case: none of the assigned collators are part of the current collators, so both
+        // the active and the pending assignments are computed from scratch
+        let old_container_chains: Vec<(ParaId, _)> = (0..20)
+            .map(|para_id| {
+                (
+                    (1000 + para_id).into(),
+                    invulnerables::<T>(10, SEED + 2 + para_id),
+                )
+            })
+            .collect();
+        let old_assigned = AssignedCollators {
+            orchestrator_chain: invulnerables::<T>(100, SEED + 1),
+            container_chains: BTreeMap::from_iter(old_container_chains),
+        };
+        <CollatorContainerChain<T>>::put(&old_assigned);
+        <PendingCollatorContainerChain<T>>::put(Some(old_assigned.clone()));
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin);
+
+        assert_ne!(<CollatorContainerChain::<T>>::get(), old_assigned);
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_preferences(
+        x: Linear<0, { T::MaxCollatorPreferences::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = account("caller", 0, SEED);
+        <Collators<T>>::put(vec![caller.clone()]);
+        let preferences: Vec<_> = (0..x).map(ParaId::from).collect();
+        let preferences = BoundedVec::truncate_from(preferences);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), preferences.clone());
+
+        assert_eq!(
+            <CollatorPreferences<T>>::get(&caller),
+            Some(preferences).filter(|p| !p.is_empty())
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_activation_session() -> Result<(), BenchmarkError> {
+        let para_id = ParaId::from(1000);
+        let session_index: T::SessionIndex = 2u32.into();
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, para_id, Some(session_index));
+
+        assert_eq!(<ActivationSession<T>>::get(para_id), Some(session_index));
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn reassign_chain_collators() -> Result<(), BenchmarkError> {
+        let from = ParaId::from(1000);
+        let to = ParaId::from(1001);
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, from, to);
+
+        assert_eq!(<ChainReassignments<T>>::get(from), Some(to));
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn freeze_chain() -> Result<(), BenchmarkError> {
+        let para_id = ParaId::from(1000);
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, para_id);
+
+        assert!(<FrozenChains<T>>::get().contains(&para_id));
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn unfreeze_chain() -> Result<(), BenchmarkError> {
+        let para_id = ParaId::from(1000);
+        <FrozenChains<T>>::mutate(|frozen| frozen.try_insert(para_id))
+            .expect("MaxFrozenChains should not be zero");
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, para_id);
+
+        assert!(!<FrozenChains<T>>::get().contains(&para_id));
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_collator_capabilities() -> Result<(), BenchmarkError> {
+        let collator: T::AccountId = account("collator", 0, SEED);
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, collator.clone(), 1);
+
+        assert_eq!(<CollatorCapabilities<T>>::get(&collator), 1);
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_required_capabilities() -> Result<(), BenchmarkError> {
+        let para_id = ParaId::from(1000);
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, para_id, 1);
+
+        assert_eq!(<RequiredCapabilities<T>>::get(para_id), 1);
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_assignment_paused() -> Result<(), BenchmarkError> {
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, true);
+
+        assert!(<AssignmentPaused<T>>::get());
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn pin_collator() -> Result<(), BenchmarkError> {
+        let collator: T::AccountId = account("collator", 0, SEED);
+        let para_id = ParaId::from(1000);
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, collator.clone(), para_id);
+
+        assert_eq!(<PinnedAssignments<T>>::get(&collator), Some(para_id));
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn unpin_collator() -> Result<(), BenchmarkError> {
+        let collator: T::AccountId = account("collator", 0, SEED);
+        <PinnedAssignments<T>>::insert(&collator, ParaId::from(1000));
+        let origin =
+            T::ForceOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, collator.clone());
+
+        assert_eq!(<PinnedAssignments<T>>::get(&collator), None);
+
+        Ok(())
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_bench_ext(), crate::mock::Test,);
 }
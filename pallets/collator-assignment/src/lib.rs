@@ -21,13 +21,21 @@
 //!    - a set of container chains
 //!
 //! The set of container chains is retrieved thanks to the GetContainerChains trait
+//! Container chains classified as parathreads by the GetParathreadChains trait are only
+//! assigned collators while they have an active on-demand order.
 //! The number of collators to assign to the orchestrator chain and the number
 //! of collators to assign to each container chain is retrieved through the GetHostConfiguration
 //! trait.
 //!  
 //! The pallet uses the following approach:
 //!
-//! - First, it aims at filling the necessary collators to serve the orchestrator chain
+//! - Each collator is considered once, under the first rule it qualifies for, in order of
+//!   precedence: invulnerable, pinned to a container chain, with chain preferences, and then
+//!   the general pool. Duplicated collators are only assigned once
+//! - First, it assigns the invulnerable collators to the orchestrator chain. Invulnerables are
+//!   never assigned to container chains, and invulnerables exceeding the maximum number of
+//!   orchestrator chain collators are left unassigned
+//! - Then, it aims at filling the necessary collators to serve the orchestrator chain
 //! - Second, it aims at filling in-order (FIFO) the existing containerChains
 //!
 //! Upon new session, this pallet takes whatever assignation was in the PendingCollatorContainerChain
@@ -44,20 +52,28 @@
 pub use pallet::*;
 use {
     crate::weights::WeightInfo,
-    frame_support::pallet_prelude::*,
+    frame_support::{pallet_prelude::*, traits::EstimateNextSessionRotation},
+    sp_core::hashing::blake2_256,
     sp_runtime::{
         traits::{AtLeast32BitUnsigned, One, Zero},
-        Saturating,
+        BoundedBTreeSet, Saturating,
     },
-    sp_std::{prelude::*, vec},
-    tp_collator_assignment::AssignedCollators,
+    sp_std::{
+        collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+        prelude::*,
+        vec,
+    },
+    tp_collator_assignment::{AssignedCollators, AssignmentConfig},
     tp_traits::{
-        GetContainerChainAuthor, GetHostConfiguration, GetSessionContainerChains, ParaId, Slot,
+        GetContainerChainAuthor, GetContainerChainCollators, GetHostConfiguration,
+        GetParathreadChains, GetRandomnessForSession, GetSessionContainerChains, GetSessionIndex,
+        OnContainerChainCollatorsChanged, ParaId, Slot,
     },
 };
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
 pub mod weights;
 
 #[cfg(test)]
@@ -66,23 +82,268 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+/// Metadata of a container chain present in the collator assignment.
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContainerChainMetadata<BlockNumber> {
+    /// Block at which the container chain was first assigned collators. For chains assigned
+    /// before storage version 1, the block at which they were migrated.
+    pub assigned_since: BlockNumber,
+}
+
+/// How the collators kept in the orchestrator chain are selected when it has more collators
+/// than `max_collators_for_orchestrator`.
+#[derive(Clone, Copy, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum OrchestratorSelection {
+    /// Keep collators chosen pseudo-randomly from the assignment seed. With a zero seed, keep
+    /// the collators that were assigned first, following the order of the collators received
+    /// upon new session, which has no particular meaning.
+    #[default]
+    RandomSeeded,
+    /// Keep the collators with the lowest account ids.
+    LowestAccountId,
+    /// Keep the collators that come first in the collators received upon new session, which
+    /// are expected to be sorted by stake, highest first.
+    HighestStake,
+    /// Same as `HighestStake`, but the removed collators, which are the lowest-priority ones,
+    /// are assigned to the container chains before the collators that were not assigned,
+    /// instead of staying idle if there are enough of those.
+    HighestStakeReassignExcess,
+}
+
+/// What to do with the collators left once all the container chains have
+/// `collators_per_container` collators and the orchestrator chain has
+/// `max_collators_for_orchestrator` collators.
+#[derive(Clone, Copy, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum SurplusCollators {
+    /// Leave them unassigned, they are used to backfill force unassigned collators.
+    #[default]
+    Unassigned,
+    /// Assign them to the active container chains as extra collators, one at a time to the
+    /// chain with the fewest collators, so that chains differ by at most one collator.
+    SpreadSurplus,
+}
+
+/// Chain treated like the orchestrator chain: its minimum number of collators is filled before
+/// the container chains, and it is filled up to its maximum after them. The orchestrator chain
+/// is always a core chain, with the bounds of the host configuration.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoreChain {
+    pub para_id: ParaId,
+    pub min_collators: u32,
+    pub max_collators: u32,
+}
+
+/// Why a registered container chain is not assigned any collators.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum NotServedReason {
+    /// There are not enough free collators to meet `MinCollatorsToActivate`.
+    NotEnoughCollators,
+    /// The chain is a parathread without an active order.
+    NoActiveOrder,
+    /// The `ActivationSession` of the chain is not reached yet.
+    NotActivatedYet,
+}
+
+/// How well a registered container chain is served by the latest computed assignment.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum AssignmentStatus {
+    /// The chain has at least `collators_per_container` collators.
+    FullyServed,
+    /// The chain has `have` collators, fewer than the `want` requested by the configuration.
+    PartiallyServed { have: u32, want: u32 },
+    /// The chain has no collators.
+    NotServed { reason: NotServedReason },
+}
+
+/// Changes between two assignments, reported by `Event::AssignmentChanged`. Chains are `None`
+/// for the orchestrator chain. Collators reordered within the same chain are not reported.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct AssignmentDiff<AccountId> {
+    /// Collators assigned to a different chain, as `(collator, from_chain, to_chain)`.
+    pub moved: Vec<(AccountId, Option<ParaId>, Option<ParaId>)>,
+    /// Collators that were not assigned, with their new chain.
+    pub added: Vec<(AccountId, Option<ParaId>)>,
+    /// Collators that are no longer assigned, with their previous chain.
+    pub removed: Vec<(AccountId, Option<ParaId>)>,
+    /// More than `MaxAssignmentDiffEntries` collators changed, in which case the lists above
+    /// are left empty and the whole assignment should be read again.
+    pub full_rotation: bool,
+}
+
+// Manual default impl that does not require AccountId: Default
+impl<AccountId> Default for AssignmentDiff<AccountId> {
+    fn default() -> Self {
+        Self {
+            moved: Default::default(),
+            added: Default::default(),
+            removed: Default::default(),
+            full_rotation: false,
+        }
+    }
+}
+
+/// Capabilities of a collator, such as being able to generate proofs, as a bitset.
+pub type Capabilities = u32;
+
+/// Inputs of `Pallet::compute_assignment` other than the collators, container chains and
+/// previous assignment, usually read from the host configuration and the pallet config.
+#[derive(Clone, PartialEq, RuntimeDebug)]
+pub struct AssignmentParams<AccountId> {
+    /// Host configuration values used for the assignment.
+    pub config: AssignmentConfig,
+    /// Collators pinned to the orchestrator chain.
+    pub invulnerables: Vec<AccountId>,
+    /// Minimum number of collators a container chain needs to be assigned any.
+    pub min_collators_to_activate: u32,
+    /// Maximum number of collators moved between chains.
+    pub max_churn: u32,
+    /// Orchestrator chain collators added to the minimum for each container chain.
+    pub orchestrator_collators_per_container_chain: u32,
+    /// How the collators kept in the orchestrator chain are selected.
+    pub orchestrator_selection: OrchestratorSelection,
+    /// What to do with the collators left once all chains are full.
+    pub surplus_collators: SurplusCollators,
+    /// Recent container chain of collators that left the assignment.
+    pub recent_chains: BTreeMap<AccountId, ParaId>,
+    /// Container chains preferred by each collator, most preferred first.
+    pub preferences: BTreeMap<AccountId, Vec<ParaId>>,
+    /// Container chains whose collators are moved to another container chain, as `from -> to`.
+    pub chain_reassignments: BTreeMap<ParaId, ParaId>,
+    /// Container chains that keep the collators they were assigned.
+    pub frozen_chains: BTreeSet<ParaId>,
+    /// Capabilities of each collator. Collators without an entry have none.
+    pub collator_capabilities: BTreeMap<AccountId, Capabilities>,
+    /// Capabilities a collator needs to be assigned to each container chain. Chains without an
+    /// entry can be assigned any collator.
+    pub required_capabilities: BTreeMap<ParaId, Capabilities>,
+    /// Core chains other than the orchestrator chain.
+    pub core_chains: Vec<CoreChain>,
+    /// Container chain each collator is pinned to, regardless of the other rules except being
+    /// invulnerable.
+    pub pinned_assignments: BTreeMap<AccountId, ParaId>,
+}
+
+impl<AccountId: Ord> AssignmentParams<AccountId> {
+    /// Whether `collator` has all the capabilities required by container chain `para_id`.
+    pub fn can_collate(&self, collator: &AccountId, para_id: &ParaId) -> bool {
+        let required = self
+            .required_capabilities
+            .get(para_id)
+            .copied()
+            .unwrap_or_default();
+        let capabilities = self
+            .collator_capabilities
+            .get(collator)
+            .copied()
+            .unwrap_or_default();
+
+        capabilities & required == required
+    }
+}
+
+/// Handler of the assignments computed in the block after their session change, when
+/// `Pallet::initializer_on_new_session` deferred them for lack of block weight.
+pub trait OnDeferredAssignment<T: Config> {
+    /// Called with the session index and the outcome the session change would have returned.
+    fn on_deferred_assignment(session_index: &T::SessionIndex, outcome: &SessionChangeOutcome<T>);
+}
+
+impl<T: Config> OnDeferredAssignment<T> for () {
+    fn on_deferred_assignment(_: &T::SessionIndex, _: &SessionChangeOutcome<T>) {}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
-    use super::*;
+    use {
+        super::*,
+        frame_system::pallet_prelude::{ensure_signed, BlockNumberFor, OriginFor},
+    };
+
+    /// The in-code storage version.
+    pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(_);
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
     pub trait Config: frame_system::Config {
+        /// The overarching event type.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
         type SessionIndex: parity_scale_codec::FullCodec + TypeInfo + Copy + AtLeast32BitUnsigned;
         // `SESSION_DELAY` is used to delay any changes to Paras registration or configurations.
         // Wait until the session index is 2 larger then the current index to apply any changes,
         // which guarantees that at least one full session has passed before any changes are applied.
         type HostConfiguration: GetHostConfiguration<Self::SessionIndex>;
         type ContainerChains: GetSessionContainerChains<Self::SessionIndex>;
+        /// Container chains that only need collators while they have an active order
+        type ParathreadChains: GetParathreadChains;
+        /// Collators that must always be assigned to the orchestrator chain
+        type Invulnerables: Get<Vec<Self::AccountId>>;
+        /// Minimum number of collators a container chain needs to be assigned to be activated.
+        /// Chains that cannot reach it are left without collators. Capped at
+        /// `collators_per_container`.
+        type MinCollatorsToActivate: Get<u32>;
+        /// Maximum number of collators that can be moved to a different chain in one session.
+        /// Moved collators need to sync the new chain, so excess moves are deferred to the
+        /// following sessions.
+        type MaxChurnPerSession: Get<u32>;
+        /// Number of sessions during which a collator that left a container chain is restored
+        /// to it when coming back, if the chain has a free slot.
+        type RecentCollatorChainTtl: Get<u32>;
+        /// Orchestrator chain collators required for each container chain to assign, on top of
+        /// `min_collators_for_orchestrator`. The resulting minimum is capped at
+        /// `max_collators_for_orchestrator`. Zero keeps the minimum fixed.
+        type OrchestratorCollatorsPerContainerChain: Get<u32>;
+        /// Selection of the orchestrator chain collators to keep when there are too many
+        type OrchestratorSelection: Get<OrchestratorSelection>;
+        /// What to do with the collators left once all chains are full
+        type SurplusCollators: Get<SurplusCollators>;
+        /// Core chains other than the orchestrator chain, assigned collators like it. Their
+        /// collators are part of the container chains of the assignment.
+        type CoreChains: Get<Vec<CoreChain>>;
+        /// Seed of the assignment, used to select the orchestrator chain collators with
+        /// `OrchestratorSelection::RandomSeeded`
+        type Randomness: GetRandomnessForSession<Self::SessionIndex>;
+        /// Number of blocks before the end of a session at which the seed of the assignment
+        /// computed by the next session change is read from `Randomness` and committed. With
+        /// zero, the seed is read upon the session change.
+        type RandomnessDelay: Get<BlockNumberFor<Self>>;
+        /// Estimation of the next session change, used to commit the seed
+        type NextSessionRotation: EstimateNextSessionRotation<BlockNumberFor<Self>>;
+        /// Notified of the container chains whose collators change in the next session
+        type CollatorsChangedHook: OnContainerChainCollatorsChanged<Self::AccountId>;
+        /// Whether `CollatorsChangedHook` is called
+        type NotifyCollatorsChanged: Get<bool>;
+        /// Notified of the assignments deferred to the block after their session change
+        type DeferredAssignmentHook: OnDeferredAssignment<Self>;
+        /// Maximum number of container chains a collator can declare as preferred
+        #[pallet::constant]
+        type MaxCollatorPreferences: Get<u32>;
+        /// Maximum number of container chains whose assignment can be frozen at the same time
+        #[pallet::constant]
+        type MaxFrozenChains: Get<u32>;
+        /// Maximum number of collators reported in the diff of `Event::AssignmentChanged`,
+        /// above which it only reports a full rotation
+        #[pallet::constant]
+        type MaxAssignmentDiffEntries: Get<u32>;
+        /// Collators that would be received upon a new session starting now, used to recompute
+        /// the assignment on demand
+        type CurrentCollators: Get<Vec<Self::AccountId>>;
+        /// Current session index
+        type CurrentSessionIndex: GetSessionIndex<Self::SessionIndex>;
+        /// Origin allowed to force unassign collators, recompute the assignment and schedule the
+        /// activation of container chains
+        type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
         /// The weight information of this pallet.
         type WeightInfo: WeightInfo;
     }
@@ -92,6 +353,13 @@ pub mod pallet {
     pub(crate) type CollatorContainerChain<T: Config> =
         StorageValue<_, AssignedCollators<T::AccountId>, ValueQuery>;
 
+    /// Assignment that was active during the previous session, if it differs from the current
+    /// one. Container chain blocks of the previous session can still be noted after the session
+    /// change, so their authors are accepted for one more session.
+    #[pallet::storage]
+    pub(crate) type PreviousCollatorContainerChain<T: Config> =
+        StorageValue<_, AssignedCollators<T::AccountId>, OptionQuery>;
+
     /// Pending configuration changes.
     ///
     /// This is a list of configuration changes, each with a session index at which it should
@@ -104,8 +372,605 @@ pub mod pallet {
     pub(crate) type PendingCollatorContainerChain<T: Config> =
         StorageValue<_, Option<AssignedCollators<T::AccountId>>, ValueQuery>;
 
+    /// Collators received in the latest assignment. Those not assigned to any chain are used to
+    /// backfill the slots of force unassigned collators.
+    #[pallet::storage]
+    pub(crate) type Collators<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+    /// Metadata of each container chain present in the latest computed assignment.
+    #[pallet::storage]
+    #[pallet::getter(fn container_chain_metadata)]
+    pub type ContainerChainsMetadata<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        ParaId,
+        ContainerChainMetadata<BlockNumberFor<T>>,
+        OptionQuery,
+    >;
+
+    /// Container chain each collator was assigned to when it left the collators, along with the
+    /// session at which it left. Forgotten after `RecentCollatorChainTtl` sessions.
+    #[pallet::storage]
+    pub(crate) type RecentCollatorChain<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (ParaId, T::SessionIndex), OptionQuery>;
+
+    /// Container chains each collator prefers to be assigned to, most preferred first. Honored
+    /// when the collator is assigned to a container chain, if one of them has a free slot.
+    #[pallet::storage]
+    #[pallet::getter(fn collator_preferences)]
+    pub type CollatorPreferences<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<ParaId, T::MaxCollatorPreferences>,
+        OptionQuery,
+    >;
+
+    /// First session at which each container chain can be assigned collators. Chains without
+    /// an entry are assigned collators as soon as they are registered.
+    #[pallet::storage]
+    #[pallet::getter(fn activation_session)]
+    pub type ActivationSession<T: Config> =
+        StorageMap<_, Blake2_128Concat, ParaId, T::SessionIndex, OptionQuery>;
+
+    /// Status of each container chain registered at the session targeted by the latest computed
+    /// assignment, explaining why chains are not fully served.
+    #[pallet::storage]
+    #[pallet::getter(fn chain_assignment_status)]
+    pub type ChainAssignmentStatus<T: Config> =
+        StorageMap<_, Blake2_128Concat, ParaId, AssignmentStatus, OptionQuery>;
+
+    /// Container chains whose collators are moved to another container chain in the next
+    /// computed assignment, as `from -> to`.
+    #[pallet::storage]
+    #[pallet::getter(fn chain_reassignment)]
+    pub type ChainReassignments<T: Config> =
+        StorageMap<_, Blake2_128Concat, ParaId, ParaId, OptionQuery>;
+
+    /// Container chains whose collators are kept by the assignment, while the other chains are
+    /// assigned as usual. Collators leaving the collators are still removed from them.
+    #[pallet::storage]
+    #[pallet::getter(fn frozen_chains)]
+    pub type FrozenChains<T: Config> =
+        StorageValue<_, BoundedBTreeSet<ParaId, T::MaxFrozenChains>, ValueQuery>;
+
+    /// Capabilities of each collator, as a bitset. Collators without an entry have none.
+    #[pallet::storage]
+    #[pallet::getter(fn collator_capabilities)]
+    pub type CollatorCapabilities<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Capabilities, ValueQuery>;
+
+    /// Capabilities required to be assigned to each container chain, as a bitset. Only
+    /// collators having all of them are assigned to the chain.
+    #[pallet::storage]
+    #[pallet::getter(fn required_capabilities)]
+    pub type RequiredCapabilities<T: Config> =
+        StorageMap<_, Blake2_128Concat, ParaId, Capabilities, ValueQuery>;
+
+    /// Seed used by the latest computed assignment. Along with the collators, the container
+    /// chains and the previous assignment, it allows anyone to recompute the assignment with
+    /// `compute_assignment` and verify it.
+    #[pallet::storage]
+    #[pallet::getter(fn last_assignment_seed)]
+    pub type LastAssignmentSeed<T: Config> = StorageValue<_, [u8; 32], ValueQuery>;
+
+    /// Seed committed `RandomnessDelay` blocks before the end of the session, used by the next
+    /// session change instead of reading `Randomness` then.
+    #[pallet::storage]
+    #[pallet::getter(fn committed_seed)]
+    pub type CommittedSeed<T: Config> = StorageValue<_, [u8; 32], OptionQuery>;
+
+    /// Number of sessions each collator was assigned to a chain, whether the orchestrator chain
+    /// or a container chain.
+    #[pallet::storage]
+    #[pallet::getter(fn collator_sessions_served)]
+    pub type CollatorSessionsServed<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Container chain each collator is pinned to. Pinned collators are always assigned to
+    /// their chain while it is assigned collators, regardless of the other rules. Invulnerables
+    /// stay in the orchestrator chain even if pinned.
+    #[pallet::storage]
+    #[pallet::getter(fn pinned_assignment)]
+    pub type PinnedAssignments<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, ParaId, OptionQuery>;
+
+    /// While true, session changes keep the current assignment instead of computing a new one.
+    #[pallet::storage]
+    #[pallet::getter(fn assignment_paused)]
+    pub type AssignmentPaused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// Session change whose assignment is computed in the next block, as the weight left in its
+    /// block was not enough. Holds the session index and the collators of the session change.
+    #[pallet::storage]
+    pub type DeferredAssignment<T: Config> =
+        StorageValue<_, (T::SessionIndex, Vec<T::AccountId>), OptionQuery>;
+
+    /// Last container chain migrated by the lazy migration to storage version 1. Only present
+    /// while the migration is in progress.
+    #[pallet::storage]
+    pub(crate) type MigrationCursor<T: Config> = StorageValue<_, ParaId, OptionQuery>;
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Collators assigned to the orchestrator chain from block 1, before the assignment
+        /// of the first session is computed. They are kept in the orchestrator chain by the
+        /// following assignments like any previously assigned collator.
+        pub initial_orchestrator_collators: Vec<T::AccountId>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+        fn build(&self) {
+            let mut collators = self.initial_orchestrator_collators.clone();
+            collators.sort();
+            collators.dedup();
+            assert!(
+                collators.len() == self.initial_orchestrator_collators.len(),
+                "duplicate initial orchestrator collators in genesis."
+            );
+
+            Collators::<T>::put(&self.initial_orchestrator_collators);
+            CollatorContainerChain::<T>::put(AssignedCollators {
+                orchestrator_chain: self.initial_orchestrator_collators.clone(),
+                container_chains: Default::default(),
+            });
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Collator was removed from the chain it was assigned to. `container_chain` is `None`
+        /// if it was assigned to the orchestrator chain.
+        CollatorForceUnassigned {
+            collator: T::AccountId,
+            container_chain: Option<ParaId>,
+            replacement: Option<T::AccountId>,
+        },
+        /// Container chain was not assigned any collators because there are not enough free
+        /// collators to meet `MinCollatorsToActivate`. Not emitted for frozen chains.
+        ChainActivationDeferred { para_id: ParaId },
+        /// The latest computed assignment has fewer orchestrator chain collators than the
+        /// `min_orchestrator_collators` of the host configuration, because there are not
+        /// enough collators.
+        OrchestratorUnderMinimum { have: u32, need: u32 },
+        /// Number of collators assigned to the container chain changed in the latest computed
+        /// assignment. `count` is zero if the chain is no longer assigned collators.
+        ContainerChainCollatorCount { para_id: ParaId, count: u32 },
+        /// The latest computed assignment changed during session `session_index`, upon a
+        /// session change or a forced recompute. `diff` lists the collators whose chain changed.
+        AssignmentChanged {
+            session_index: T::SessionIndex,
+            diff: AssignmentDiff<T::AccountId>,
+        },
+        /// Collator declared the container chains it prefers to be assigned to. Empty if it
+        /// removed its preferences.
+        CollatorPreferencesSet {
+            collator: T::AccountId,
+            preferences: Vec<ParaId>,
+        },
+        /// Session from which the container chain can be assigned collators was set. `None` if
+        /// the chain can be assigned collators as soon as it is registered.
+        ActivationSessionSet {
+            para_id: ParaId,
+            session_index: Option<T::SessionIndex>,
+        },
+        /// Collators of container chain `from` will be moved to container chain `to` in the next
+        /// computed assignment.
+        ChainCollatorsReassignmentScheduled { from: ParaId, to: ParaId },
+        /// The collators of the container chain are kept by the next computed assignments.
+        ChainFrozen { para_id: ParaId },
+        /// The container chain is assigned collators as usual again.
+        ChainUnfrozen { para_id: ParaId },
+        /// Capabilities of the collator were set.
+        CollatorCapabilitiesSet {
+            collator: T::AccountId,
+            capabilities: Capabilities,
+        },
+        /// Capabilities required to be assigned to the container chain were set.
+        RequiredCapabilitiesSet {
+            para_id: ParaId,
+            capabilities: Capabilities,
+        },
+        /// The assignment was paused or resumed.
+        AssignmentPausedSet { paused: bool },
+        /// The collator is pinned to the container chain from the next computed assignment.
+        CollatorPinned {
+            collator: T::AccountId,
+            para_id: ParaId,
+        },
+        /// The collator is assigned as usual again from the next computed assignment.
+        CollatorUnpinned { collator: T::AccountId },
+        /// Not enough weight was left to compute the assignment of the session change, it is
+        /// computed in the next block and the current assignment is kept until then.
+        AssignmentDeferred { session_index: T::SessionIndex },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The collator is not assigned to any chain
+        CollatorNotAssigned,
+        /// Collators of a container chain cannot be reassigned to the same chain
+        ReassignmentToSameChain,
+        /// The container chain is already frozen
+        ChainAlreadyFrozen,
+        /// The container chain is not frozen
+        ChainNotFrozen,
+        /// The maximum number of frozen container chains was reached
+        TooManyFrozenChains,
+        /// The assignment cannot be recomputed while it is paused
+        AssignmentIsPaused,
+        /// The container chain cannot fit more pinned collators
+        TooManyPinnedCollators,
+        /// The collator is not pinned to any container chain
+        CollatorNotPinned,
+        /// The account is neither a collator nor a collator candidate
+        NotACollator,
+    }
+
     #[pallet::call]
-    impl<T: Config> Pallet<T> {}
+    impl<T: Config> Pallet<T> {
+        /// Remove `collator` from the chain it is currently assigned to, and replace it with a
+        /// collator that is not assigned to any chain, if there is one.
+        /// The collator is also removed from the pending assignment, but it can be assigned
+        /// again at the next session if it is still part of the collators.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::force_unassign_collator())]
+        pub fn force_unassign_collator(
+            origin: OriginFor<T>,
+            collator: T::AccountId,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            let mut assigned = CollatorContainerChain::<T>::get();
+            let container_chain = assigned
+                .container_chains
+                .iter()
+                .find(|(_, collators)| collators.contains(&collator))
+                .map(|(para_id, _)| *para_id);
+            ensure!(
+                container_chain.is_some() || assigned.orchestrator_chain.contains(&collator),
+                Error::<T>::CollatorNotAssigned
+            );
+
+            // Invulnerables can only backfill the orchestrator chain
+            let invulnerables = T::Invulnerables::get();
+            let replacement = Collators::<T>::get().into_iter().find(|c| {
+                c != &collator
+                    && !assigned.find_collator(c)
+                    && (container_chain.is_none() || !invulnerables.contains(c))
+            });
+
+            let chain_collators = match container_chain {
+                Some(para_id) => assigned
+                    .container_chains
+                    .get_mut(&para_id)
+                    .expect("container chain was found above; qed"),
+                None => &mut assigned.orchestrator_chain,
+            };
+            chain_collators.retain(|c| c != &collator);
+            if let Some(replacement) = &replacement {
+                chain_collators.push(replacement.clone());
+            }
+            CollatorContainerChain::<T>::put(assigned);
+
+            PendingCollatorContainerChain::<T>::mutate(|pending| {
+                if let Some(pending) = pending {
+                    pending.orchestrator_chain.retain(|c| c != &collator);
+                    for collators in pending.container_chains.values_mut() {
+                        collators.retain(|c| c != &collator);
+                    }
+                }
+            });
+            PreviousCollatorContainerChain::<T>::mutate(|previous| {
+                if let Some(previous) = previous {
+                    for collators in previous.container_chains.values_mut() {
+                        collators.retain(|c| c != &collator);
+                    }
+                }
+            });
+
+            Self::deposit_event(Event::<T>::CollatorForceUnassigned {
+                collator,
+                container_chain,
+                replacement,
+            });
+
+            Ok(())
+        }
+
+        /// Recompute the assignment immediately, using the collators that would be received
+        /// upon a new session, instead of waiting for the next session change. The pending
+        /// assignment is recomputed as well, so that the next session change keeps the result.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::force_recompute_assignment())]
+        pub fn force_recompute_assignment(origin: OriginFor<T>) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+            ensure!(
+                !AssignmentPaused::<T>::get(),
+                Error::<T>::AssignmentIsPaused
+            );
+
+            let session_index = T::CurrentSessionIndex::session_index();
+            let collators = T::CurrentCollators::get();
+            Collators::<T>::put(&collators);
+
+            let old_active = CollatorContainerChain::<T>::get();
+            let new_active = Self::recompute_assignment(
+                &session_index,
+                session_index,
+                collators.clone(),
+                &old_active,
+            );
+            let mut changed = old_active != new_active;
+            CollatorContainerChain::<T>::put(&new_active);
+
+            // The latest computed assignment is the pending one, if any
+            let (old_latest, new_latest, latest_session_index) =
+                match PendingCollatorContainerChain::<T>::get() {
+                    Some(old_pending) => {
+                        let pending_session_index =
+                            session_index.saturating_add(T::SessionIndex::one());
+                        let new_pending = Self::recompute_assignment(
+                            &session_index,
+                            pending_session_index,
+                            collators,
+                            &old_pending,
+                        );
+                        changed |= old_pending != new_pending;
+                        PendingCollatorContainerChain::<T>::put(Some(new_pending.clone()));
+                        (old_pending, new_pending, pending_session_index)
+                    }
+                    None => (old_active, new_active, session_index),
+                };
+            Self::update_chain_assignment_status(latest_session_index, &new_latest);
+            let _ = ChainReassignments::<T>::clear(u32::MAX, None);
+
+            if changed {
+                Self::update_container_chains_metadata(&old_latest, &new_latest);
+                Self::deposit_collator_count_events(&old_latest, &new_latest);
+                if T::NotifyCollatorsChanged::get() {
+                    Self::notify_collators_changed(&old_latest, &new_latest);
+                }
+                Self::deposit_event(Event::<T>::AssignmentChanged {
+                    session_index,
+                    diff: Self::assignment_diff(&old_latest, &new_latest),
+                });
+            }
+
+            Ok(())
+        }
+
+        /// Set the container chains the caller prefers to be assigned to, most preferred first.
+        /// They are honored the next time the caller is assigned to a container chain, if one of
+        /// them has a free slot. An empty list removes the preferences.
+        /// Only collators and collator candidates can set preferences.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::set_preferences(preferences.len() as u32))]
+        pub fn set_preferences(
+            origin: OriginFor<T>,
+            preferences: BoundedVec<ParaId, T::MaxCollatorPreferences>,
+        ) -> DispatchResult {
+            let collator = ensure_signed(origin)?;
+            ensure!(
+                Collators::<T>::get().contains(&collator)
+                    || T::CurrentCollators::get().contains(&collator),
+                Error::<T>::NotACollator
+            );
+
+            if preferences.is_empty() {
+                CollatorPreferences::<T>::remove(&collator);
+            } else {
+                CollatorPreferences::<T>::insert(&collator, &preferences);
+            }
+
+            Self::deposit_event(Event::<T>::CollatorPreferencesSet {
+                collator,
+                preferences: preferences.into_inner(),
+            });
+
+            Ok(())
+        }
+
+        /// Set the first session at which `para_id` can be assigned collators. Until then, the
+        /// chain is skipped by the assignment even if it is registered. `None` removes the
+        /// restriction.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::set_activation_session())]
+        pub fn set_activation_session(
+            origin: OriginFor<T>,
+            para_id: ParaId,
+            session_index: Option<T::SessionIndex>,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            ActivationSession::<T>::set(para_id, session_index);
+
+            Self::deposit_event(Event::<T>::ActivationSessionSet {
+                para_id,
+                session_index,
+            });
+
+            Ok(())
+        }
+
+        /// Move the collators of container chain `from` to container chain `to` in the next
+        /// computed assignment, as long as `to` has free slots. The collators that don't fit
+        /// are freed and assigned as usual.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::reassign_chain_collators())]
+        pub fn reassign_chain_collators(
+            origin: OriginFor<T>,
+            from: ParaId,
+            to: ParaId,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+            ensure!(from != to, Error::<T>::ReassignmentToSameChain);
+
+            ChainReassignments::<T>::insert(from, to);
+
+            Self::deposit_event(Event::<T>::ChainCollatorsReassignmentScheduled { from, to });
+
+            Ok(())
+        }
+
+        /// Keep the collators currently assigned to `para_id` in the next computed assignments,
+        /// until the chain is unfrozen. The other chains keep being assigned as usual.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::freeze_chain())]
+        pub fn freeze_chain(origin: OriginFor<T>, para_id: ParaId) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            FrozenChains::<T>::try_mutate(|frozen| {
+                let inserted = frozen
+                    .try_insert(para_id)
+                    .map_err(|_| Error::<T>::TooManyFrozenChains)?;
+                ensure!(inserted, Error::<T>::ChainAlreadyFrozen);
+                Ok::<_, Error<T>>(())
+            })?;
+
+            Self::deposit_event(Event::<T>::ChainFrozen { para_id });
+
+            Ok(())
+        }
+
+        /// Assign collators to `para_id` as usual again from the next computed assignment.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::unfreeze_chain())]
+        pub fn unfreeze_chain(origin: OriginFor<T>, para_id: ParaId) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            FrozenChains::<T>::try_mutate(|frozen| {
+                ensure!(frozen.remove(&para_id), Error::<T>::ChainNotFrozen);
+                Ok::<_, Error<T>>(())
+            })?;
+
+            Self::deposit_event(Event::<T>::ChainUnfrozen { para_id });
+
+            Ok(())
+        }
+
+        /// Set the capabilities of `collator`, taken into account from the next computed
+        /// assignment. Zero removes them.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::set_collator_capabilities())]
+        pub fn set_collator_capabilities(
+            origin: OriginFor<T>,
+            collator: T::AccountId,
+            capabilities: Capabilities,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            CollatorCapabilities::<T>::insert(&collator, capabilities);
+
+            Self::deposit_event(Event::<T>::CollatorCapabilitiesSet {
+                collator,
+                capabilities,
+            });
+
+            Ok(())
+        }
+
+        /// Set the capabilities required to be assigned to `para_id`, taken into account from
+        /// the next computed assignment. Zero allows any collator again.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::set_required_capabilities())]
+        pub fn set_required_capabilities(
+            origin: OriginFor<T>,
+            para_id: ParaId,
+            capabilities: Capabilities,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            RequiredCapabilities::<T>::insert(para_id, capabilities);
+
+            Self::deposit_event(Event::<T>::RequiredCapabilitiesSet {
+                para_id,
+                capabilities,
+            });
+
+            Ok(())
+        }
+
+        /// Pause or resume the assignment. While paused, session changes keep the current
+        /// assignment: the pending assignment is not applied and no new one is computed.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::set_assignment_paused())]
+        pub fn set_assignment_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            AssignmentPaused::<T>::put(paused);
+
+            Self::deposit_event(Event::<T>::AssignmentPausedSet { paused });
+
+            Ok(())
+        }
+
+        /// Pin `collator` to `para_id` from the next computed assignment, replacing its
+        /// previous pin. Fails if the collators pinned to `para_id` would not fit in it.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::pin_collator())]
+        pub fn pin_collator(
+            origin: OriginFor<T>,
+            collator: T::AccountId,
+            para_id: ParaId,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            let max_collators = T::CoreChains::get()
+                .into_iter()
+                .find(|core_chain| core_chain.para_id == para_id)
+                .map_or_else(
+                    || {
+                        let session_index = T::CurrentSessionIndex::session_index();
+                        Self::assignment_config(session_index).collators_per_container
+                    },
+                    |core_chain| core_chain.max_collators,
+                );
+            let num_pinned = PinnedAssignments::<T>::iter()
+                .filter(|(c, pinned_para_id)| c != &collator && pinned_para_id == &para_id)
+                .count() as u32;
+            ensure!(
+                num_pinned < max_collators,
+                Error::<T>::TooManyPinnedCollators
+            );
+
+            PinnedAssignments::<T>::insert(&collator, para_id);
+
+            Self::deposit_event(Event::<T>::CollatorPinned { collator, para_id });
+
+            Ok(())
+        }
+
+        /// Assign `collator` as usual again from the next computed assignment.
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::unpin_collator())]
+        pub fn unpin_collator(origin: OriginFor<T>, collator: T::AccountId) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                PinnedAssignments::<T>::take(&collator).is_some(),
+                Error::<T>::CollatorNotPinned
+            );
+
+            Self::deposit_event(Event::<T>::CollatorUnpinned { collator });
+
+            Ok(())
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            Self::compute_deferred_assignment().saturating_add(Self::commit_assignment_seed(n))
+        }
+
+        fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            migrations::lazy_migrate_to_v1::<T>(remaining_weight)
+        }
+    }
 
     /// A struct that holds the assignment that is active after the session change and optionally
     /// the assignment that becomes active after the next session change.
@@ -127,30 +992,89 @@ pub mod pallet {
             let session_delay = T::SessionIndex::one();
             let target_session_index = current_session_index.saturating_add(session_delay);
             // We get the containerChains that we will have at the target session
-            let container_chain_ids =
-                T::ContainerChains::session_container_chains(target_session_index);
+            let container_chain_ids = Self::container_chains_to_assign(target_session_index);
+            // We use the config scheduled at the target_session_index
+            let params = Self::assignment_params(
+                current_session_index,
+                Self::assignment_config(target_session_index),
+                &collators,
+            );
+            Collators::<T>::put(&collators);
+            let num_collators = collators.iter().collect::<BTreeSet<_>>().len();
             // We read current assigned collators
             let old_assigned = Self::read_assigned_collators();
-            // We assign new collators
-            // we use the config scheduled at the target_session_index
-            let new_assigned = Self::assign_collators_always_keep_old(
+            let seed = CommittedSeed::<T>::take()
+                .unwrap_or_else(|| T::Randomness::randomness_for_session(target_session_index));
+            LastAssignmentSeed::<T>::put(seed);
+            let new_assigned = Self::compute_assignment(
                 collators,
                 &container_chain_ids,
-                T::HostConfiguration::min_collators_for_orchestrator(target_session_index) as usize,
-                T::HostConfiguration::max_collators_for_orchestrator(target_session_index) as usize,
-                T::HostConfiguration::collators_per_container(target_session_index) as usize,
-                old_assigned.clone(),
+                &params,
+                &old_assigned,
+                seed,
+            );
+            let _ = ChainReassignments::<T>::clear(u32::MAX, None);
+            Self::update_recent_collator_chains(
+                current_session_index,
+                &params.recent_chains,
+                &old_assigned,
+                &new_assigned,
             );
 
+            Self::update_chain_assignment_status(target_session_index, &new_assigned);
+
+            let have = new_assigned.orchestrator_chain.len() as u32;
+            let need = params.config.min_orchestrator_collators;
+            if have < need {
+                Self::deposit_event(Event::<T>::OrchestratorUnderMinimum { have, need });
+            }
+
+            // Chains without collators are only deferred if the collators left unassigned or
+            // above the orchestrator chain minimum could not activate them. Frozen chains keep
+            // their collators, so they are never deferred
+            let free_collators = num_collators
+                .saturating_sub(new_assigned.container_chains.values().map(Vec::len).sum())
+                .saturating_sub(have.min(need) as usize);
+            let min_num_to_activate = params
+                .min_collators_to_activate
+                .min(params.config.collators_per_container)
+                as usize;
+            if free_collators < min_num_to_activate {
+                for para_id in &container_chain_ids {
+                    let is_deferred = !params.frozen_chains.contains(para_id)
+                        && new_assigned
+                            .container_chains
+                            .get(para_id)
+                            .map_or(true, |collators| collators.is_empty());
+                    if is_deferred {
+                        Self::deposit_event(Event::<T>::ChainActivationDeferred {
+                            para_id: *para_id,
+                        });
+                    }
+                }
+            }
+
             let mut pending = PendingCollatorContainerChain::<T>::get();
             let old_assigned_changed = old_assigned != new_assigned;
             let mut pending_changed = false;
             // Update CollatorContainerChain using last entry of pending, if needed
             if let Some(current) = pending.take() {
                 pending_changed = true;
+                PreviousCollatorContainerChain::<T>::put(CollatorContainerChain::<T>::get());
                 CollatorContainerChain::<T>::put(current);
+            } else {
+                PreviousCollatorContainerChain::<T>::kill();
             }
             if old_assigned_changed {
+                Self::update_container_chains_metadata(&old_assigned, &new_assigned);
+                Self::deposit_collator_count_events(&old_assigned, &new_assigned);
+                if T::NotifyCollatorsChanged::get() {
+                    Self::notify_collators_changed(&old_assigned, &new_assigned);
+                }
+                Self::deposit_event(Event::<T>::AssignmentChanged {
+                    session_index: *current_session_index,
+                    diff: Self::assignment_diff(&old_assigned, &new_assigned),
+                });
                 pending = Some(new_assigned.clone());
                 pending_changed = true;
             }
@@ -174,15 +1098,396 @@ pub mod pallet {
             }
         }
 
+        /// Compute the assignment that the next session change would produce if the host
+        /// configuration was `proposed_config`, as if it started session `session_index`.
+        /// The current collators are used, and storage is not modified.
+        pub fn dry_run_assignment(
+            session_index: &T::SessionIndex,
+            proposed_config: AssignmentConfig,
+        ) -> AssignedCollators<T::AccountId> {
+            let target_session_index = session_index.saturating_add(T::SessionIndex::one());
+            let container_chain_ids = Self::container_chains_to_assign(target_session_index);
+
+            let collators = Collators::<T>::get();
+            let params = Self::assignment_params(session_index, proposed_config, &collators);
+
+            Self::compute_assignment(
+                collators,
+                &container_chain_ids,
+                &params,
+                &Self::read_assigned_collators(),
+                CommittedSeed::<T>::get()
+                    .unwrap_or_else(|| T::Randomness::randomness_for_session(target_session_index)),
+            )
+        }
+
+        /// Commit the seed of the assignment computed by the next session change if block `n`
+        /// is `RandomnessDelay` blocks before it, so that the seed is known before the
+        /// collators of that session change.
+        fn commit_assignment_seed(n: BlockNumberFor<T>) -> Weight {
+            let delay = T::RandomnessDelay::get();
+            if delay.is_zero() {
+                return Weight::zero();
+            }
+
+            let (next_rotation, weight) = T::NextSessionRotation::estimate_next_session_rotation(n);
+            if next_rotation.map_or(true, |next_rotation| {
+                next_rotation.saturating_sub(delay) != n
+            }) {
+                return weight;
+            }
+
+            // The next session change computes the assignment of the session after it
+            let target_session_index =
+                T::CurrentSessionIndex::session_index().saturating_add(2u32.into());
+            CommittedSeed::<T>::put(T::Randomness::randomness_for_session(target_session_index));
+
+            weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+        }
+
+        /// Compute the assignment of the session change deferred to this block, if any.
+        fn compute_deferred_assignment() -> Weight {
+            let Some((session_index, collators)) = DeferredAssignment::<T>::take() else {
+                return T::DbWeight::get().reads(1);
+            };
+
+            let num_collators = collators.len();
+            let outcome = Self::assign_collators(&session_index, collators);
+            Self::count_session_served(&outcome.active_assignment);
+            let num_parachains = outcome.next_assignment.container_chains.len();
+            T::DeferredAssignmentHook::on_deferred_assignment(&session_index, &outcome);
+
+            T::WeightInfo::new_session(num_collators as u32, num_parachains as u32)
+                .saturating_add(T::DbWeight::get().reads_writes(1, 1))
+        }
+
+        /// Weight left in the current block, across all dispatch classes.
+        fn remaining_block_weight() -> Weight {
+            <T as frame_system::Config>::BlockWeights::get()
+                .max_block
+                .saturating_sub(frame_system::Pallet::<T>::block_weight().total())
+        }
+
+        /// Compute the assignment active at `target_session_index` from `previous`, during
+        /// session `current_session_index`, and update the recent chains of the collators.
+        fn recompute_assignment(
+            current_session_index: &T::SessionIndex,
+            target_session_index: T::SessionIndex,
+            collators: Vec<T::AccountId>,
+            previous: &AssignedCollators<T::AccountId>,
+        ) -> AssignedCollators<T::AccountId> {
+            let container_chain_ids = Self::container_chains_to_assign(target_session_index);
+            let params = Self::assignment_params(
+                current_session_index,
+                Self::assignment_config(target_session_index),
+                &collators,
+            );
+            let seed = T::Randomness::randomness_for_session(target_session_index);
+            LastAssignmentSeed::<T>::put(seed);
+            let new_assigned =
+                Self::compute_assignment(collators, &container_chain_ids, &params, previous, seed);
+            Self::update_recent_collator_chains(
+                current_session_index,
+                &params.recent_chains,
+                previous,
+                &new_assigned,
+            );
+
+            new_assigned
+        }
+
+        /// Container chains that will be assigned collators at `session_index`. Parathreads
+        /// without an active order release their collators, and chains are skipped until their
+        /// `ActivationSession`.
+        fn container_chains_to_assign(session_index: T::SessionIndex) -> Vec<ParaId> {
+            let mut container_chain_ids =
+                T::ContainerChains::session_container_chains(session_index);
+            container_chain_ids.retain(|para_id| {
+                (!T::ParathreadChains::is_parathread(*para_id)
+                    || T::ParathreadChains::has_active_order(*para_id))
+                    && ActivationSession::<T>::get(para_id)
+                        .map_or(true, |activation| activation <= session_index)
+            });
+
+            container_chain_ids
+        }
+
+        /// Assignment parameters scheduled for `session_index` in the host configuration.
+        pub fn assignment_config(session_index: T::SessionIndex) -> AssignmentConfig {
+            AssignmentConfig {
+                min_orchestrator_collators: T::HostConfiguration::min_collators_for_orchestrator(
+                    session_index,
+                ),
+                max_orchestrator_collators: T::HostConfiguration::max_collators_for_orchestrator(
+                    session_index,
+                ),
+                collators_per_container: T::HostConfiguration::collators_per_container(
+                    session_index,
+                ),
+            }
+        }
+
+        /// Parameters of the assignment of `collators` computed upon the start of session
+        /// `session_index`, using `config` as host configuration.
+        pub fn assignment_params(
+            session_index: &T::SessionIndex,
+            config: AssignmentConfig,
+            collators: &[T::AccountId],
+        ) -> AssignmentParams<T::AccountId> {
+            AssignmentParams {
+                config,
+                invulnerables: T::Invulnerables::get(),
+                min_collators_to_activate: T::MinCollatorsToActivate::get(),
+                max_churn: T::MaxChurnPerSession::get(),
+                orchestrator_collators_per_container_chain:
+                    T::OrchestratorCollatorsPerContainerChain::get(),
+                orchestrator_selection: T::OrchestratorSelection::get(),
+                surplus_collators: T::SurplusCollators::get(),
+                recent_chains: Self::recent_collator_chains(session_index),
+                // Only the preferences of the collators being assigned are read.
+                preferences: collators
+                    .iter()
+                    .filter_map(|c| {
+                        CollatorPreferences::<T>::get(c)
+                            .map(|preferences| (c.clone(), preferences.into_inner()))
+                    })
+                    .collect(),
+                chain_reassignments: ChainReassignments::<T>::iter().collect(),
+                frozen_chains: FrozenChains::<T>::get().into_inner(),
+                collator_capabilities: CollatorCapabilities::<T>::iter().collect(),
+                required_capabilities: RequiredCapabilities::<T>::iter().collect(),
+                core_chains: T::CoreChains::get(),
+                pinned_assignments: PinnedAssignments::<T>::iter().collect(),
+            }
+        }
+
+        /// Compute the new assignment of `collators` to the orchestrator chain and
+        /// `container_chains`, starting from `previous`.
+        /// This function is pure: it does not access storage nor the pallet config, everything
+        /// it depends on is provided in `params`. A non-zero `seed` is used to select the
+        /// orchestrator chain collators with `OrchestratorSelection::RandomSeeded`.
+        pub fn compute_assignment(
+            collators: Vec<T::AccountId>,
+            container_chains: &[ParaId],
+            params: &AssignmentParams<T::AccountId>,
+            previous: &AssignedCollators<T::AccountId>,
+            seed: [u8; 32],
+        ) -> AssignedCollators<T::AccountId> {
+            // A collator present several times is only assigned once
+            let collators = Self::dedup_collators(collators);
+            // The minimum grows with the number of container chains, up to the maximum
+            let min_num_orchestrator_chain = params
+                .config
+                .min_orchestrator_collators
+                .saturating_add(
+                    params
+                        .orchestrator_collators_per_container_chain
+                        .saturating_mul(container_chains.len() as u32),
+                )
+                .min(params.config.max_orchestrator_collators)
+                .max(params.config.min_orchestrator_collators)
+                as usize;
+            let max_num_orchestrator_chain = params.config.max_orchestrator_collators as usize;
+            let num_each_container_chain = params.config.collators_per_container as usize;
+            let min_num_to_activate =
+                (params.min_collators_to_activate as usize).min(num_each_container_chain);
+            // Reassigned collators are treated as if they were already in their new chain, so
+            // they are kept there and not counted as churn
+            let mut reassigned = previous.clone();
+            Self::apply_chain_reassignments(
+                &mut reassigned,
+                &params.chain_reassignments,
+                num_each_container_chain,
+            );
+            // Frozen chains keep their collators and are left out of the assignment
+            let frozen_chains = Self::take_frozen_chains(
+                &mut reassigned,
+                &params.frozen_chains,
+                container_chains,
+                &collators,
+            );
+            let collators: Vec<_> = collators
+                .into_iter()
+                .filter(|c| {
+                    !frozen_chains
+                        .values()
+                        .any(|collators| collators.contains(c))
+                })
+                .collect();
+            let mut unfrozen_chains: Vec<_> = container_chains
+                .iter()
+                .filter(|para_id| !frozen_chains.contains_key(para_id))
+                .copied()
+                .collect();
+            // Core chains are assigned collators whether they are container chains or not
+            let core_chains: BTreeMap<_, _> = params
+                .core_chains
+                .iter()
+                .map(|core_chain| {
+                    (
+                        core_chain.para_id,
+                        (
+                            core_chain.min_collators as usize,
+                            core_chain.max_collators as usize,
+                        ),
+                    )
+                })
+                .collect();
+            for para_id in core_chains.keys() {
+                if !unfrozen_chains.contains(para_id) && !frozen_chains.contains_key(para_id) {
+                    unfrozen_chains.push(*para_id);
+                }
+            }
+            let container_chains = &unfrozen_chains[..];
+            let previous = &reassigned;
+            // Collators qualifying for several rules only follow the one with the highest
+            // precedence: invulnerable, then pinned, then preferences and the general pool.
+            // Invulnerables are pinned to the orchestrator chain, the ones that do not fit in it
+            // are not assigned anywhere
+            let (invulnerable_collators, collators): (Vec<_>, Vec<_>) = collators
+                .into_iter()
+                .partition(|c| params.invulnerables.contains(c));
+            // Collators pinned to a chain being assigned are left out of the assignment and
+            // added to their chain at the end
+            let (pinned_to_chains, collators): (Vec<_>, Vec<_>) =
+                collators.into_iter().partition(|c| {
+                    params
+                        .pinned_assignments
+                        .get(c)
+                        .map_or(false, |para_id| container_chains.contains(para_id))
+                });
+            // Sort orchestrator chain collators so that the excess ones are the last ones
+            let mut old_assigned = previous.clone();
+            match params.orchestrator_selection {
+                OrchestratorSelection::RandomSeeded if seed == [0; 32] => {}
+                OrchestratorSelection::RandomSeeded => old_assigned
+                    .orchestrator_chain
+                    .sort_by_cached_key(|c| blake2_256(&(seed, c).encode())),
+                OrchestratorSelection::LowestAccountId => old_assigned.orchestrator_chain.sort(),
+                OrchestratorSelection::HighestStake
+                | OrchestratorSelection::HighestStakeReassignExcess => old_assigned
+                    .orchestrator_chain
+                    .sort_by_key(|c| collators.iter().position(|x| x == c)),
+            }
+            let pinned_collators: Vec<_> = invulnerable_collators
+                .into_iter()
+                .take(max_num_orchestrator_chain)
+                .collect();
+            // We assign new collators to the orchestrator chain seats not taken by invulnerables
+            let mut new_assigned = Self::assign_collators_always_keep_old(
+                collators,
+                container_chains,
+                min_num_orchestrator_chain.saturating_sub(pinned_collators.len()),
+                max_num_orchestrator_chain.saturating_sub(pinned_collators.len()),
+                num_each_container_chain,
+                min_num_to_activate,
+                params.surplus_collators == SurplusCollators::SpreadSurplus,
+                params.orchestrator_selection == OrchestratorSelection::HighestStakeReassignExcess,
+                &core_chains,
+                &params.recent_chains,
+                &params.preferences,
+                &|c, para_id| params.can_collate(c, para_id),
+                old_assigned,
+            );
+            new_assigned.orchestrator_chain = pinned_collators
+                .into_iter()
+                .chain(new_assigned.orchestrator_chain)
+                .collect();
+            Self::limit_churn(previous, &mut new_assigned, params.max_churn as usize);
+            // Undone moves can put collators back in a chain whose required capabilities they
+            // lack, those are left unassigned
+            for (para_id, collators) in new_assigned.container_chains.iter_mut() {
+                collators.retain(|c| params.can_collate(c, para_id));
+            }
+            // Pinned collators go first, so that they are kept if the chain is over its maximum
+            for c in pinned_to_chains.into_iter().rev() {
+                let para_id = params.pinned_assignments[&c];
+                let max_collators = core_chains
+                    .get(&para_id)
+                    .map_or(num_each_container_chain, |(_, max)| *max);
+                let chain_collators = new_assigned.container_chains.entry(para_id).or_default();
+                chain_collators.insert(0, c);
+                chain_collators.truncate(max_collators);
+            }
+            new_assigned.container_chains.extend(frozen_chains);
+
+            new_assigned
+        }
+
+        /// Remove the duplicates of `collators`, keeping the first occurrence of each of them.
+        fn dedup_collators(collators: Vec<T::AccountId>) -> Vec<T::AccountId> {
+            let mut seen = BTreeSet::new();
+            collators
+                .into_iter()
+                .filter(|c| seen.insert(c.clone()))
+                .collect()
+        }
+
+        /// Remove the frozen chains still present in `container_chains` from `assigned`, and
+        /// return them along with their collators that are still part of `collators`.
+        fn take_frozen_chains(
+            assigned: &mut AssignedCollators<T::AccountId>,
+            frozen_chains: &BTreeSet<ParaId>,
+            container_chains: &[ParaId],
+            collators: &[T::AccountId],
+        ) -> BTreeMap<ParaId, Vec<T::AccountId>> {
+            frozen_chains
+                .iter()
+                .filter(|para_id| container_chains.contains(para_id))
+                .filter_map(|para_id| {
+                    let mut chain_collators = assigned.container_chains.remove(para_id)?;
+                    chain_collators.retain(|c| collators.contains(c));
+                    Some((*para_id, chain_collators))
+                })
+                .collect()
+        }
+
+        /// Move the collators of each `from` container chain of `reassignments` to its `to`
+        /// chain, up to `num_each_container_chain` collators. The collators that don't fit are
+        /// unassigned.
+        fn apply_chain_reassignments(
+            assigned: &mut AssignedCollators<T::AccountId>,
+            reassignments: &BTreeMap<ParaId, ParaId>,
+            num_each_container_chain: usize,
+        ) {
+            for (from, to) in reassignments {
+                let Some(collators) = assigned.container_chains.remove(from) else {
+                    continue;
+                };
+                let to_collators = assigned.container_chains.entry(*to).or_default();
+                let free_slots = num_each_container_chain.saturating_sub(to_collators.len());
+                to_collators.extend(collators.into_iter().take(free_slots));
+            }
+        }
+
         /// Assign new collators to missing container_chains.
         /// Old collators always have preference to remain on the same chain.
+        /// Collators coming back are restored to their chain in `recent_chains`, if it has a
+        /// free slot. Otherwise, collators are assigned to the first chain of `preferences` with
+        /// a free slot, if any.
         /// If there are no missing collators, nothing is changed.
+        /// With `spread_surplus`, the collators left once all chains are full are spread across
+        /// the active container chains.
+        /// With `reassign_excess`, the collators removed from the orchestrator chain are assigned
+        /// to container chains before new collators.
+        /// The `core_chains` in `container_chain_ids` are filled up to their minimum, as
+        /// `(min, max)`, after the orchestrator chain and before the other container chains, and
+        /// up to their maximum after the orchestrator chain, lowest para id first.
+        /// Collators are only assigned to the container chains for which `can_collate` is true.
+        #[allow(clippy::too_many_arguments)]
         fn assign_collators_always_keep_old(
             collators: Vec<T::AccountId>,
             container_chain_ids: &[ParaId],
             min_num_orchestrator_chain: usize,
             max_num_orchestrator_chain: usize,
             num_each_container_chain: usize,
+            min_num_to_activate: usize,
+            spread_surplus: bool,
+            reassign_excess: bool,
+            core_chains: &BTreeMap<ParaId, (usize, usize)>,
+            recent_chains: &BTreeMap<T::AccountId, ParaId>,
+            preferences: &BTreeMap<T::AccountId, Vec<ParaId>>,
+            can_collate: &impl Fn(&T::AccountId, &ParaId) -> bool,
             old_assigned: AssignedCollators<T::AccountId>,
         ) -> AssignedCollators<T::AccountId> {
             // TODO: the performance of this function is sad, could be improved by having sets of
@@ -193,7 +1498,24 @@ pub mod pallet {
             let extra_orchestrator_collators =
                 new_assigned.remove_orchestrator_chain_excess_collators(min_num_orchestrator_chain);
             // Only need to do this if the config params change
-            new_assigned.remove_container_chain_excess_collators(num_each_container_chain);
+            for (para_id, collators) in new_assigned.container_chains.iter_mut() {
+                let max_collators = core_chains
+                    .get(para_id)
+                    .map_or(num_each_container_chain, |(_, max)| *max);
+                collators.truncate(max_collators);
+            }
+            // Number of collators each container chain is filled up to before the orchestrator
+            // chain maximum
+            let num_collators = |para_id: &ParaId| {
+                core_chains
+                    .get(para_id)
+                    .map_or(num_each_container_chain, |(min, _)| *min)
+            };
+            // Collators lacking the capabilities required by their chain are assigned again as
+            // new collators
+            for (para_id, collators) in new_assigned.container_chains.iter_mut() {
+                collators.retain(|c| can_collate(c, para_id));
+            }
 
             // Collators that are not present in old_assigned
             // TODO: unless we save all the old_collators somewhere, it is still possible for a
@@ -211,50 +1533,456 @@ pub mod pallet {
             let mut new_collators = new_collators.into_iter();
             new_assigned
                 .fill_orchestrator_chain_collators(min_num_orchestrator_chain, &mut new_collators);
+            // The orchestrator chain minimum must be satisfied before any container chain is
+            // served, so if there were not enough new collators take them from the container
+            // chains, even if that leaves them without collators
+            new_assigned.fill_orchestrator_chain_from_container_chains(min_num_orchestrator_chain);
+
+            // Restore returning collators to their recent container chain if it has a free slot,
+            // then assign collators to their preferred chains with a free slot. The others are
+            // assigned as usual
+            new_assigned.add_new_container_chains(container_chain_ids);
+
+            // Fill core chain collators up to their minimum
+            for (para_id, (min_collators, _)) in core_chains {
+                if let Some(chain_collators) = new_assigned.container_chains.get_mut(para_id) {
+                    while chain_collators.len() < *min_collators {
+                        match new_collators.by_ref().find(|c| can_collate(c, para_id)) {
+                            Some(c) => chain_collators.push(c),
+                            None => break,
+                        }
+                    }
+                }
+            }
+
+            let mut not_restored_collators = vec![];
+            for c in new_collators {
+                let chain = recent_chains
+                    .get(&c)
+                    .into_iter()
+                    .chain(preferences.get(&c).into_iter().flatten())
+                    .find(|para_id| {
+                        can_collate(&c, para_id)
+                            && new_assigned
+                                .container_chains
+                                .get(para_id)
+                                .map_or(false, |collators| collators.len() < num_collators(para_id))
+                    })
+                    .copied();
+                match chain {
+                    Some(para_id) => new_assigned
+                        .container_chains
+                        .entry(para_id)
+                        .or_default()
+                        .push(c),
+                    None => not_restored_collators.push(c),
+                }
+            }
 
             // Fill container chain collators using new collators and also the extra
             // collators that were previously assigned to the orchestrator chain,
-            // but give preference to new collators
-            let mut extra_orchestrator_collators = extra_orchestrator_collators.into_iter();
-            let mut new_plus_extra_collators = new_collators
-                .by_ref()
-                .chain(&mut extra_orchestrator_collators);
-            new_assigned.add_new_container_chains(container_chain_ids);
-            new_assigned.fill_container_chain_collators(
-                num_each_container_chain,
-                &mut new_plus_extra_collators,
-            );
+            // but give preference to new collators unless `reassign_excess`
+            let new_plus_extra_collators = if reassign_excess {
+                extra_orchestrator_collators
+                    .iter()
+                    .cloned()
+                    .chain(not_restored_collators)
+                    .collect()
+            } else {
+                not_restored_collators
+                    .into_iter()
+                    .chain(extra_orchestrator_collators.iter().cloned())
+                    .collect()
+            };
+            let (extra_orchestrator_collators, new_collators): (Vec<_>, Vec<_>) =
+                Self::fill_container_chain_collators(
+                    &mut new_assigned,
+                    &num_collators,
+                    new_plus_extra_collators,
+                    can_collate,
+                )
+                .into_iter()
+                .partition(|c| extra_orchestrator_collators.contains(c));
 
             // Fill orchestrator chain collators back up to max_num_orchestrator_chain,
             // but give preference to collators that were already there
             let mut extra_collators_plus_new = extra_orchestrator_collators
-                .by_ref()
-                .chain(&mut new_collators);
+                .into_iter()
+                .chain(new_collators);
             new_assigned.fill_orchestrator_chain_collators(
                 max_num_orchestrator_chain,
                 &mut extra_collators_plus_new,
             );
+            let surplus_collators = Self::fill_container_chain_collators(
+                &mut new_assigned,
+                &|para_id| core_chains.get(para_id).map_or(0, |(_, max)| *max),
+                extra_collators_plus_new.collect(),
+                can_collate,
+            );
+
+            // Core chains are not container chains to activate nor to spread surplus across
+            let core_chain_collators: Vec<_> = core_chains
+                .keys()
+                .filter_map(|para_id| {
+                    let collators = new_assigned.container_chains.remove(para_id)?;
+                    Some((*para_id, collators))
+                })
+                .collect();
 
             // Reorganize container chain collators to fill the maximum number of container
-            // chains. For example, if num_each_container_chain == 2 and the number of collators
+            // chains. For example, if min_num_to_activate == 2 and the number of collators
             // in each container chain is
             // [1, 1, 1, 1, 1]
             // Then we can convert that into
             // [2, 2, 0, 0, 0]
             // and assign 1 extra collator to the orchestrator chain, if needed.
-            let incomplete_container_chains_collators = new_assigned
-                .reorganize_incomplete_container_chains_collators(num_each_container_chain);
+            let incomplete_container_chains_collators =
+                new_assigned.reorganize_incomplete_container_chains_collators(min_num_to_activate);
 
             // Assign collators from container chains that do not reach
-            // "num_each_container_chain" to orchestrator chain
+            // "min_num_to_activate" to orchestrator chain
+            let mut incomplete_container_chains_collators =
+                incomplete_container_chains_collators.into_iter();
             new_assigned.fill_orchestrator_chain_collators(
                 max_num_orchestrator_chain,
-                &mut incomplete_container_chains_collators.into_iter(),
+                &mut incomplete_container_chains_collators,
             );
 
+            if spread_surplus {
+                let surplus_collators = surplus_collators
+                    .into_iter()
+                    .chain(incomplete_container_chains_collators);
+                Self::spread_surplus_collators(&mut new_assigned, surplus_collators, can_collate);
+            }
+            new_assigned.container_chains.extend(core_chain_collators);
+
             new_assigned
         }
 
+        /// Fill each container chain of `assigned` up to `num_collators` collators, taking
+        /// `collators` in order but skipping the ones for which `can_collate` is false.
+        /// Returns the collators that were not assigned, in order.
+        fn fill_container_chain_collators(
+            assigned: &mut AssignedCollators<T::AccountId>,
+            num_collators: &impl Fn(&ParaId) -> usize,
+            mut collators: Vec<T::AccountId>,
+            can_collate: &impl Fn(&T::AccountId, &ParaId) -> bool,
+        ) -> Vec<T::AccountId> {
+            for (para_id, chain_collators) in assigned.container_chains.iter_mut() {
+                while chain_collators.len() < num_collators(para_id) {
+                    match collators.iter().position(|c| can_collate(c, para_id)) {
+                        Some(i) => chain_collators.push(collators.remove(i)),
+                        None => break,
+                    }
+                }
+            }
+
+            collators
+        }
+
+        /// Assign `surplus_collators` to the container chains that have collators, each one to
+        /// the chain with the fewest collators for which `can_collate` is true, lowest para id
+        /// first.
+        fn spread_surplus_collators(
+            assigned: &mut AssignedCollators<T::AccountId>,
+            surplus_collators: impl Iterator<Item = T::AccountId>,
+            can_collate: &impl Fn(&T::AccountId, &ParaId) -> bool,
+        ) {
+            for c in surplus_collators {
+                let emptiest_chain = assigned
+                    .container_chains
+                    .iter_mut()
+                    .filter(|(para_id, collators)| {
+                        !collators.is_empty() && can_collate(&c, para_id)
+                    })
+                    .min_by_key(|(_, collators)| collators.len());
+                if let Some((_, collators)) = emptiest_chain {
+                    collators.push(c);
+                }
+            }
+        }
+
+        /// Undo the moves between chains of `new_assigned` that exceed `max_churn`, putting
+        /// those collators back in the chain they were assigned to in `old_assigned`. Moves are
+        /// allowed in assignment order, orchestrator chain first.
+        /// Moves away from chains that are not in `new_assigned` anymore are not limited.
+        fn limit_churn(
+            old_assigned: &AssignedCollators<T::AccountId>,
+            new_assigned: &mut AssignedCollators<T::AccountId>,
+            max_churn: usize,
+        ) {
+            // `None` is the orchestrator chain
+            let chain_of = |assigned: &AssignedCollators<T::AccountId>, c: &T::AccountId| {
+                if assigned.orchestrator_chain.contains(c) {
+                    return Some(None);
+                }
+                assigned
+                    .container_chains
+                    .iter()
+                    .find(|(_, collators)| collators.contains(c))
+                    .map(|(para_id, _)| Some(*para_id))
+            };
+
+            let new_chains = sp_std::iter::once((None, &new_assigned.orchestrator_chain)).chain(
+                new_assigned
+                    .container_chains
+                    .iter()
+                    .map(|(para_id, collators)| (Some(*para_id), collators)),
+            );
+            let mut num_moves = 0;
+            let mut deferred_moves = vec![];
+            for (new_chain, collators) in new_chains {
+                for c in collators {
+                    let Some(old_chain) = chain_of(old_assigned, c) else {
+                        continue;
+                    };
+                    let old_chain_exists = old_chain.map_or(true, |para_id| {
+                        new_assigned.container_chains.contains_key(&para_id)
+                    });
+                    if old_chain == new_chain || !old_chain_exists {
+                        continue;
+                    }
+                    if num_moves < max_churn {
+                        num_moves += 1;
+                    } else {
+                        deferred_moves.push((c.clone(), new_chain, old_chain));
+                    }
+                }
+            }
+
+            for (c, new_chain, old_chain) in deferred_moves {
+                match new_chain {
+                    Some(para_id) => new_assigned
+                        .container_chains
+                        .entry(para_id)
+                        .or_default()
+                        .retain(|x| x != &c),
+                    None => new_assigned.orchestrator_chain.retain(|x| x != &c),
+                }
+                match old_chain {
+                    Some(para_id) => new_assigned
+                        .container_chains
+                        .entry(para_id)
+                        .or_default()
+                        .push(c),
+                    None => new_assigned.orchestrator_chain.push(c),
+                }
+            }
+        }
+
+        /// Call `CollatorsChangedHook` for each container chain whose collators differ between
+        /// `old_assigned` and `new_assigned`.
+        fn notify_collators_changed(
+            old_assigned: &AssignedCollators<T::AccountId>,
+            new_assigned: &AssignedCollators<T::AccountId>,
+        ) {
+            let para_ids: BTreeSet<_> = old_assigned
+                .container_chains
+                .keys()
+                .chain(new_assigned.container_chains.keys())
+                .collect();
+            for para_id in para_ids {
+                let old_collators = old_assigned
+                    .container_chains
+                    .get(para_id)
+                    .map_or(&[][..], |collators| collators.as_slice());
+                let new_collators = new_assigned
+                    .container_chains
+                    .get(para_id)
+                    .map_or(&[][..], |collators| collators.as_slice());
+                if old_collators != new_collators {
+                    T::CollatorsChangedHook::on_collators_changed(*para_id, new_collators);
+                }
+            }
+        }
+
+        /// Replace `ChainAssignmentStatus` with the status of the container chains registered at
+        /// `session_index` in `assigned`, the assignment computed for that session.
+        fn update_chain_assignment_status(
+            session_index: T::SessionIndex,
+            assigned: &AssignedCollators<T::AccountId>,
+        ) {
+            let want = T::HostConfiguration::collators_per_container(session_index);
+
+            let _ = ChainAssignmentStatus::<T>::clear(u32::MAX, None);
+            for para_id in T::ContainerChains::session_container_chains(session_index) {
+                let have = assigned
+                    .container_chains
+                    .get(&para_id)
+                    .map_or(0, |collators| collators.len() as u32);
+                let status = if T::ParathreadChains::is_parathread(para_id)
+                    && !T::ParathreadChains::has_active_order(para_id)
+                {
+                    AssignmentStatus::NotServed {
+                        reason: NotServedReason::NoActiveOrder,
+                    }
+                } else if ActivationSession::<T>::get(para_id)
+                    .map_or(false, |activation| activation > session_index)
+                {
+                    AssignmentStatus::NotServed {
+                        reason: NotServedReason::NotActivatedYet,
+                    }
+                } else if have == 0 {
+                    AssignmentStatus::NotServed {
+                        reason: NotServedReason::NotEnoughCollators,
+                    }
+                } else if have < want {
+                    AssignmentStatus::PartiallyServed { have, want }
+                } else {
+                    AssignmentStatus::FullyServed
+                };
+                ChainAssignmentStatus::<T>::insert(para_id, status);
+            }
+        }
+
+        /// Collators whose chain differs between `old_assigned` and `new_assigned`, bounded by
+        /// `MaxAssignmentDiffEntries`.
+        pub fn assignment_diff(
+            old_assigned: &AssignedCollators<T::AccountId>,
+            new_assigned: &AssignedCollators<T::AccountId>,
+        ) -> AssignmentDiff<T::AccountId> {
+            let chain_of_collators = |assigned: &AssignedCollators<T::AccountId>| {
+                let mut chains = BTreeMap::new();
+                for collator in &assigned.orchestrator_chain {
+                    chains.insert(collator.clone(), None);
+                }
+                for (para_id, collators) in &assigned.container_chains {
+                    for collator in collators {
+                        chains.insert(collator.clone(), Some(*para_id));
+                    }
+                }
+                chains
+            };
+            let old_chains = chain_of_collators(old_assigned);
+            let mut new_chains = chain_of_collators(new_assigned);
+
+            let mut diff = AssignmentDiff::default();
+            for (collator, from) in old_chains {
+                match new_chains.remove(&collator) {
+                    Some(to) if to != from => diff.moved.push((collator, from, to)),
+                    Some(_) => {}
+                    None => diff.removed.push((collator, from)),
+                }
+            }
+            diff.added = new_chains.into_iter().collect();
+
+            let entries = diff.moved.len() + diff.added.len() + diff.removed.len();
+            if entries > T::MaxAssignmentDiffEntries::get() as usize {
+                diff = AssignmentDiff {
+                    full_rotation: true,
+                    ..Default::default()
+                };
+            }
+
+            diff
+        }
+
+        /// Emit `ContainerChainCollatorCount` for the container chains whose number of collators
+        /// differs between `old_assigned` and `new_assigned`.
+        fn deposit_collator_count_events(
+            old_assigned: &AssignedCollators<T::AccountId>,
+            new_assigned: &AssignedCollators<T::AccountId>,
+        ) {
+            let count = |assigned: &AssignedCollators<T::AccountId>, para_id: &ParaId| {
+                assigned
+                    .container_chains
+                    .get(para_id)
+                    .map_or(0, |collators| collators.len() as u32)
+            };
+            let para_ids: BTreeSet<_> = old_assigned
+                .container_chains
+                .keys()
+                .chain(new_assigned.container_chains.keys())
+                .collect();
+            for para_id in para_ids {
+                let new_count = count(new_assigned, para_id);
+                if count(old_assigned, para_id) != new_count {
+                    Self::deposit_event(Event::<T>::ContainerChainCollatorCount {
+                        para_id: *para_id,
+                        count: new_count,
+                    });
+                }
+            }
+        }
+
+        /// Whether an entry of `RecentCollatorChain` recorded at `left_at` is expired.
+        fn is_recent_chain_expired(
+            current_session_index: &T::SessionIndex,
+            left_at: T::SessionIndex,
+        ) -> bool {
+            let ttl: T::SessionIndex = T::RecentCollatorChainTtl::get().into();
+            current_session_index.saturating_sub(left_at) > ttl
+        }
+
+        /// Recent container chain of the collators that left less than `RecentCollatorChainTtl`
+        /// sessions ago.
+        fn recent_collator_chains(
+            current_session_index: &T::SessionIndex,
+        ) -> BTreeMap<T::AccountId, ParaId> {
+            RecentCollatorChain::<T>::iter()
+                .filter(|(_, (_, left_at))| {
+                    !Self::is_recent_chain_expired(current_session_index, *left_at)
+                })
+                .map(|(c, (para_id, _))| (c, para_id))
+                .collect()
+        }
+
+        /// Remember the container chain of collators that are not in `new_assigned` anymore,
+        /// and forget collators of `recent_chains` that were assigned again. Expired entries
+        /// are removed.
+        fn update_recent_collator_chains(
+            current_session_index: &T::SessionIndex,
+            recent_chains: &BTreeMap<T::AccountId, ParaId>,
+            old_assigned: &AssignedCollators<T::AccountId>,
+            new_assigned: &AssignedCollators<T::AccountId>,
+        ) {
+            let expired: Vec<_> = RecentCollatorChain::<T>::iter()
+                .filter(|(_, (_, left_at))| {
+                    Self::is_recent_chain_expired(current_session_index, *left_at)
+                })
+                .map(|(c, _)| c)
+                .collect();
+            for c in expired {
+                RecentCollatorChain::<T>::remove(c);
+            }
+            for (para_id, collators) in &old_assigned.container_chains {
+                for c in collators {
+                    if !new_assigned.find_collator(c) {
+                        RecentCollatorChain::<T>::insert(c, (*para_id, *current_session_index));
+                    }
+                }
+            }
+            for c in recent_chains.keys() {
+                if new_assigned.find_collator(c) {
+                    RecentCollatorChain::<T>::remove(c);
+                }
+            }
+        }
+
+        /// Add the metadata of container chains that were not in `old_assigned`, and remove the
+        /// metadata of container chains that are not in `new_assigned` anymore.
+        fn update_container_chains_metadata(
+            old_assigned: &AssignedCollators<T::AccountId>,
+            new_assigned: &AssignedCollators<T::AccountId>,
+        ) {
+            let now = frame_system::Pallet::<T>::block_number();
+            for para_id in new_assigned.container_chains.keys() {
+                if !old_assigned.container_chains.contains_key(para_id) {
+                    ContainerChainsMetadata::<T>::insert(
+                        para_id,
+                        ContainerChainMetadata {
+                            assigned_since: now,
+                        },
+                    );
+                }
+            }
+            for para_id in old_assigned.container_chains.keys() {
+                if !new_assigned.container_chains.contains_key(para_id) {
+                    ContainerChainsMetadata::<T>::remove(para_id);
+                }
+            }
+        }
+
         // Returns the assigned collators as read from storage.
         // If there is any item in PendingCollatorContainerChain, returns that element.
         // Otherwise, reads and returns the current CollatorContainerChain
@@ -269,12 +1997,82 @@ pub mod pallet {
             }
         }
 
+        /// Collators currently assigned to the orchestrator chain.
+        pub fn orchestrator_collators() -> Vec<T::AccountId> {
+            CollatorContainerChain::<T>::get().orchestrator_chain
+        }
+
+        /// Collators currently assigned to container chain `para_id`, empty if it has none.
+        pub fn container_collators(para_id: ParaId) -> Vec<T::AccountId> {
+            CollatorContainerChain::<T>::get()
+                .container_chains
+                .get(&para_id)
+                .cloned()
+                .unwrap_or_default()
+        }
+
+        /// Collators received in the latest assignment that are currently not assigned to any
+        /// chain.
+        pub fn idle_collators() -> Vec<T::AccountId> {
+            let assigned = CollatorContainerChain::<T>::get();
+
+            Collators::<T>::get()
+                .into_iter()
+                .filter(|c| !assigned.find_collator(c))
+                .collect()
+        }
+
+        /// Count one more session served by each collator of `active_assignment`.
+        fn count_session_served(active_assignment: &AssignedCollators<T::AccountId>) {
+            let collators = active_assignment
+                .orchestrator_chain
+                .iter()
+                .chain(active_assignment.container_chains.values().flatten());
+            for collator in collators {
+                CollatorSessionsServed::<T>::mutate(collator, |sessions| {
+                    *sessions = sessions.saturating_add(1)
+                });
+            }
+        }
+
         pub fn initializer_on_new_session(
             session_index: &T::SessionIndex,
             collators: Vec<T::AccountId>,
         ) -> SessionChangeOutcome<T> {
+            if AssignmentPaused::<T>::get() {
+                let assigned = CollatorContainerChain::<T>::get();
+                Self::count_session_served(&assigned);
+                return SessionChangeOutcome {
+                    active_assignment: assigned.clone(),
+                    next_assignment: assigned,
+                };
+            }
+
+            // The assignment is computed in the next block if it does not fit in this one. The
+            // first session has no assignment to keep, so it is always computed.
             let num_collators = collators.len();
+            let target_session_index = session_index.saturating_add(T::SessionIndex::one());
+            let num_container_chains = Self::container_chains_to_assign(target_session_index).len();
+            let weight =
+                T::WeightInfo::new_session(num_collators as u32, num_container_chains as u32);
+            if !session_index.is_zero() && weight.any_gt(Self::remaining_block_weight()) {
+                let active_assignment = CollatorContainerChain::<T>::get();
+                // Only the collators of this session change can be assigned
+                let mut next_assignment = active_assignment.clone();
+                next_assignment.remove_collators_not_in_list(&collators);
+                DeferredAssignment::<T>::put((*session_index, collators));
+                Self::deposit_event(Event::<T>::AssignmentDeferred {
+                    session_index: *session_index,
+                });
+
+                return SessionChangeOutcome {
+                    active_assignment,
+                    next_assignment,
+                };
+            }
+
             let assigned_collators = Self::assign_collators(session_index, collators);
+            Self::count_session_served(&assigned_collators.active_assignment);
             let num_parachains = assigned_collators.next_assignment.container_chains.len();
 
             frame_system::Pallet::<T>::register_extra_weight_unchecked(
@@ -286,6 +2084,12 @@ pub mod pallet {
         }
     }
 
+    impl<T: Config> GetContainerChainCollators<T::AccountId> for Pallet<T> {
+        fn container_chain_collators(para_id: ParaId) -> Vec<T::AccountId> {
+            Self::container_collators(para_id)
+        }
+    }
+
     impl<T: Config> GetContainerChainAuthor<T::AccountId> for Pallet<T> {
         fn author_for_slot(slot: Slot, para_id: ParaId) -> Option<T::AccountId> {
             let assigned_collators = Pallet::<T>::collator_container_chain();
@@ -298,6 +2102,19 @@ pub mod pallet {
             collators.get(author_index as usize).cloned()
         }
 
+        fn is_author_assigned(para_id: ParaId, author: &T::AccountId) -> bool {
+            let is_assigned = |assigned: &AssignedCollators<T::AccountId>| {
+                assigned
+                    .container_chains
+                    .get(&para_id)
+                    .map_or(false, |collators| collators.contains(author))
+            };
+
+            is_assigned(&Pallet::<T>::collator_container_chain())
+                || PreviousCollatorContainerChain::<T>::get()
+                    .map_or(false, |previous| is_assigned(&previous))
+        }
+
         #[cfg(feature = "runtime-benchmarks")]
         fn set_authors_for_para_id(para_id: ParaId, authors: Vec<T::AccountId>) {
             let mut assigned_collators = Pallet::<T>::collator_container_chain();
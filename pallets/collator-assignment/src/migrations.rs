@@ -0,0 +1,113 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+//! # Migrations
+//!
+//! Storage version 1 stores per-chain metadata of the container chains present in
+//! `CollatorContainerChain` in `ContainerChainsMetadata`. Chains already assigned before the
+//! upgrade are migrated lazily in `on_idle`, a few chains per block depending on the available
+//! weight.
+
+use {
+    crate::{
+        CollatorContainerChain, Config, ContainerChainMetadata, ContainerChainsMetadata,
+        MigrationCursor, Pallet, PendingCollatorContainerChain, STORAGE_VERSION,
+    },
+    frame_support::{traits::GetStorageVersion, weights::Weight},
+    sp_core::Get,
+    sp_std::collections::btree_set::BTreeSet,
+};
+
+const LOG_TARGET: &str = "pallet_collator_assignment::migrations";
+
+/// Migrate the container chains of `CollatorContainerChain` and `PendingCollatorContainerChain`
+/// to storage version 1, using at most `remaining_weight`.
+///
+/// The last migrated chain is stored in `MigrationCursor`, so the migration resumes where it
+/// stopped in the next block. Chains that already have metadata are left untouched, so running
+/// it more than once is harmless. Once all chains are migrated the storage version is set to 1
+/// and this becomes a no-op.
+pub fn lazy_migrate_to_v1<T: Config>(remaining_weight: Weight) -> Weight {
+    let db_weight = T::DbWeight::get();
+    // Storage version
+    let mut used_weight = db_weight.reads(1);
+    if Pallet::<T>::on_chain_storage_version() >= STORAGE_VERSION {
+        return used_weight;
+    }
+
+    // Cost of migrating one chain, and of writing the cursor or the storage version once done
+    let step_weight = db_weight.reads_writes(1, 1);
+    let finish_weight = db_weight.writes(2);
+    // Assignments and cursor
+    used_weight = used_weight.saturating_add(db_weight.reads(3));
+    if remaining_weight.any_lt(
+        used_weight
+            .saturating_add(step_weight)
+            .saturating_add(finish_weight),
+    ) {
+        return db_weight.reads(1);
+    }
+
+    let mut container_chains: BTreeSet<_> = CollatorContainerChain::<T>::get()
+        .container_chains
+        .into_keys()
+        .collect();
+    if let Some(pending) = PendingCollatorContainerChain::<T>::get() {
+        container_chains.extend(pending.container_chains.into_keys());
+    }
+    let cursor = MigrationCursor::<T>::get();
+    let now = frame_system::Pallet::<T>::block_number();
+
+    let mut last_migrated = None;
+    let mut remaining_chains = container_chains
+        .into_iter()
+        .filter(|para_id| cursor.map_or(true, |cursor| *para_id > cursor));
+
+    loop {
+        let next_weight = used_weight
+            .saturating_add(step_weight)
+            .saturating_add(finish_weight);
+        if remaining_weight.any_lt(next_weight) {
+            break;
+        }
+
+        let Some(para_id) = remaining_chains.next() else {
+            MigrationCursor::<T>::kill();
+            STORAGE_VERSION.put::<Pallet<T>>();
+            log::info!(target: LOG_TARGET, "migration to storage version 1 completed");
+
+            return used_weight.saturating_add(finish_weight);
+        };
+
+        if !ContainerChainsMetadata::<T>::contains_key(para_id) {
+            ContainerChainsMetadata::<T>::insert(
+                para_id,
+                ContainerChainMetadata {
+                    assigned_since: now,
+                },
+            );
+        }
+        used_weight = used_weight.saturating_add(step_weight);
+        last_migrated = Some(para_id);
+    }
+
+    if let Some(para_id) = last_migrated {
+        MigrationCursor::<T>::put(para_id);
+        used_weight = used_weight.saturating_add(db_weight.writes(1));
+    }
+
+    used_weight
+}
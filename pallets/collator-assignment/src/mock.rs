@@ -15,14 +15,22 @@
 // along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
 
 use {
-    crate::{self as pallet_collator_assignment},
-    frame_support::traits::{ConstU16, ConstU64},
+    crate::{
+        self as pallet_collator_assignment, CoreChain, OrchestratorSelection, SurplusCollators,
+    },
+    frame_support::{
+        traits::{
+            ConstU16, ConstU32, ConstU64, EstimateNextSessionRotation, GenesisBuild, Get, Hooks,
+        },
+        weights::{constants::RocksDbWeight, Weight},
+    },
     frame_system as system,
     parity_scale_codec::{Decode, Encode},
     sp_core::H256,
     sp_runtime::{
         testing::Header,
         traits::{BlakeTwo256, IdentityLookup},
+        Permill,
     },
     tp_traits::ParaId,
 };
@@ -47,7 +55,7 @@ impl system::Config for Test {
     type BaseCallFilter = frame_support::traits::Everything;
     type BlockWeights = ();
     type BlockLength = ();
-    type DbWeight = ();
+    type DbWeight = RocksDbWeight;
     type RuntimeOrigin = RuntimeOrigin;
     type RuntimeCall = RuntimeCall;
     type Index = u64;
@@ -112,6 +120,20 @@ pub struct Mocks {
     pub collators_per_container: u32,
     pub collators: Vec<u64>,
     pub container_chains: Vec<u32>,
+    pub invulnerables: Vec<u64>,
+    pub parathreads: Vec<u32>,
+    pub parathread_orders: Vec<u32>,
+    pub min_collators_to_activate: Option<u32>,
+    pub max_churn_per_session: Option<u32>,
+    pub orchestrator_collators_per_container_chain: u32,
+    pub recent_collator_chain_ttl: Option<u32>,
+    pub orchestrator_selection: OrchestratorSelection,
+    pub surplus_collators: SurplusCollators,
+    pub core_chains: Vec<CoreChain>,
+    pub random_seed: [u8; 32],
+    pub randomness_delay: u64,
+    pub notify_collators_changed: bool,
+    pub collators_changed_notifications: Vec<(u32, Vec<u64>)>,
 }
 
 impl mock_data::Config for Test {}
@@ -142,6 +164,21 @@ impl GetCollators<u64, u32> for CollatorsGetter {
     }
 }
 
+impl Get<Vec<u64>> for CollatorsGetter {
+    fn get() -> Vec<u64> {
+        MockData::mock().collators
+    }
+}
+
+pub struct CurrentSessionIndexGetter;
+
+impl tp_traits::GetSessionIndex<u32> for CurrentSessionIndexGetter {
+    fn session_index() -> u32 {
+        // Same session length as `run_to_block`
+        (System::block_number() / SESSION_LEN) as u32
+    }
+}
+
 pub struct ContainerChainsGetter;
 
 impl tp_traits::GetSessionContainerChains<u32> for ContainerChainsGetter {
@@ -162,10 +199,174 @@ impl tp_traits::GetSessionContainerChains<u32> for ContainerChainsGetter {
     }
 }
 
+pub struct InvulnerablesGetter;
+
+impl Get<Vec<u64>> for InvulnerablesGetter {
+    fn get() -> Vec<u64> {
+        MockData::mock().invulnerables
+    }
+}
+
+pub struct MinCollatorsToActivateGetter;
+
+impl Get<u32> for MinCollatorsToActivateGetter {
+    fn get() -> u32 {
+        // By default only activate chains that can be fully served
+        MockData::mock()
+            .min_collators_to_activate
+            .unwrap_or(u32::MAX)
+    }
+}
+
+pub struct MaxChurnPerSessionGetter;
+
+impl Get<u32> for MaxChurnPerSessionGetter {
+    fn get() -> u32 {
+        // No churn limit by default
+        MockData::mock().max_churn_per_session.unwrap_or(u32::MAX)
+    }
+}
+
+pub struct RecentCollatorChainTtlGetter;
+
+impl Get<u32> for RecentCollatorChainTtlGetter {
+    fn get() -> u32 {
+        // Returning collators are treated as new collators by default
+        MockData::mock().recent_collator_chain_ttl.unwrap_or(0)
+    }
+}
+
+pub struct OrchestratorCollatorsPerContainerChainGetter;
+
+impl Get<u32> for OrchestratorCollatorsPerContainerChainGetter {
+    fn get() -> u32 {
+        MockData::mock().orchestrator_collators_per_container_chain
+    }
+}
+
+pub struct OrchestratorSelectionGetter;
+
+impl Get<OrchestratorSelection> for OrchestratorSelectionGetter {
+    fn get() -> OrchestratorSelection {
+        MockData::mock().orchestrator_selection
+    }
+}
+
+pub struct SurplusCollatorsGetter;
+
+impl Get<SurplusCollators> for SurplusCollatorsGetter {
+    fn get() -> SurplusCollators {
+        MockData::mock().surplus_collators
+    }
+}
+
+pub struct CoreChainsGetter;
+
+impl Get<Vec<CoreChain>> for CoreChainsGetter {
+    fn get() -> Vec<CoreChain> {
+        MockData::mock().core_chains
+    }
+}
+
+pub struct RandomnessGetter;
+
+impl tp_traits::GetRandomnessForSession<u32> for RandomnessGetter {
+    fn randomness_for_session(_session_index: u32) -> [u8; 32] {
+        MockData::mock().random_seed
+    }
+}
+
+pub struct RandomnessDelayGetter;
+
+impl Get<u64> for RandomnessDelayGetter {
+    fn get() -> u64 {
+        MockData::mock().randomness_delay
+    }
+}
+
+pub struct NextSessionRotation;
+
+impl EstimateNextSessionRotation<u64> for NextSessionRotation {
+    fn average_session_length() -> u64 {
+        SESSION_LEN
+    }
+
+    fn estimate_current_session_progress(now: u64) -> (Option<Permill>, Weight) {
+        (
+            Some(Permill::from_rational(
+                (now + SESSION_LEN - 1) % SESSION_LEN,
+                SESSION_LEN,
+            )),
+            Weight::zero(),
+        )
+    }
+
+    fn estimate_next_session_rotation(now: u64) -> (Option<u64>, Weight) {
+        // Same session changes as `run_to_block`
+        (
+            Some(now + SESSION_LEN - (now + SESSION_LEN - 1) % SESSION_LEN),
+            Weight::zero(),
+        )
+    }
+}
+
+pub struct NotifyCollatorsChangedGetter;
+
+impl Get<bool> for NotifyCollatorsChangedGetter {
+    fn get() -> bool {
+        MockData::mock().notify_collators_changed
+    }
+}
+
+pub struct CollatorsChangedHook;
+
+impl tp_traits::OnContainerChainCollatorsChanged<u64> for CollatorsChangedHook {
+    fn on_collators_changed(para_id: ParaId, collators: &[u64]) {
+        MockData::mutate(|m| {
+            m.collators_changed_notifications
+                .push((para_id.into(), collators.to_vec()));
+        })
+    }
+}
+
+pub struct ParathreadChainsGetter;
+
+impl tp_traits::GetParathreadChains for ParathreadChainsGetter {
+    fn is_parathread(para_id: ParaId) -> bool {
+        MockData::mock().parathreads.contains(&para_id.into())
+    }
+
+    fn has_active_order(para_id: ParaId) -> bool {
+        MockData::mock().parathread_orders.contains(&para_id.into())
+    }
+}
+
 impl pallet_collator_assignment::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
     type SessionIndex = u32;
     type HostConfiguration = HostConfigurationGetter;
     type ContainerChains = ContainerChainsGetter;
+    type ParathreadChains = ParathreadChainsGetter;
+    type Invulnerables = InvulnerablesGetter;
+    type MinCollatorsToActivate = MinCollatorsToActivateGetter;
+    type MaxChurnPerSession = MaxChurnPerSessionGetter;
+    type RecentCollatorChainTtl = RecentCollatorChainTtlGetter;
+    type OrchestratorCollatorsPerContainerChain = OrchestratorCollatorsPerContainerChainGetter;
+    type OrchestratorSelection = OrchestratorSelectionGetter;
+    type SurplusCollators = SurplusCollatorsGetter;
+    type CoreChains = CoreChainsGetter;
+    type Randomness = RandomnessGetter;
+    type RandomnessDelay = RandomnessDelayGetter;
+    type NextSessionRotation = NextSessionRotation;
+    type CollatorsChangedHook = CollatorsChangedHook;
+    type NotifyCollatorsChanged = NotifyCollatorsChangedGetter;
+    type DeferredAssignmentHook = ();
+    type MaxCollatorPreferences = ConstU32<3>;
+    type MaxFrozenChains = ConstU32<2>;
+    type MaxAssignmentDiffEntries = ConstU32<10>;
+    type CurrentCollators = CollatorsGetter;
+    type CurrentSessionIndex = CurrentSessionIndexGetter;
+    type ForceOrigin = frame_system::EnsureRoot<u64>;
     type WeightInfo = ();
 }
 
@@ -177,19 +378,53 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
         .into()
 }
 
+// Build genesis storage for the benchmarks, where container chains have room for collators to be
+// pinned to them.
+#[cfg(feature = "runtime-benchmarks")]
+pub fn new_bench_ext() -> sp_io::TestExternalities {
+    let mut ext = new_test_ext();
+    ext.execute_with(|| {
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+        });
+    });
+
+    ext
+}
+
+// Build genesis storage with `initial_orchestrator_collators` assigned to the orchestrator chain.
+pub fn new_test_ext_with_initial_orchestrator_collators(
+    initial_orchestrator_collators: Vec<u64>,
+) -> sp_io::TestExternalities {
+    let mut t = system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+
+    pallet_collator_assignment::GenesisConfig::<Test> {
+        initial_orchestrator_collators,
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    t.into()
+}
+
 pub trait GetCollators<AccountId, SessionIndex> {
     fn collators(session_index: SessionIndex) -> Vec<AccountId>;
 }
 
+pub const SESSION_LEN: u64 = 5;
+
 pub fn run_to_block(n: u64) {
     let old_block_number = System::block_number();
-    let session_len = 5;
 
     for x in (old_block_number + 1)..=n {
-        System::set_block_number(x);
+        // Resets the weight consumed by the previous block
+        System::initialize(&x, &Default::default(), &Default::default());
+        CollatorAssignment::on_initialize(x);
 
-        if x % session_len == 1 {
-            let session_index = (x / session_len) as u32;
+        if x % SESSION_LEN == 1 {
+            let session_index = (x / SESSION_LEN) as u32;
             CollatorAssignment::initializer_on_new_session(
                 &session_index,
                 CollatorsGetter::collators(session_index),
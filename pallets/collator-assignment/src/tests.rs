@@ -15,8 +15,24 @@
 // along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
 
 use {
-    crate::{mock::*, CollatorContainerChain},
-    std::collections::BTreeMap,
+    crate::{
+        mock::*, weights::WeightInfo, AssignmentDiff, AssignmentParams, AssignmentStatus,
+        ChainReassignments, CollatorContainerChain, CollatorPreferences, ContainerChainMetadata,
+        ContainerChainsMetadata, CoreChain, DeferredAssignment, Error, Event, MigrationCursor,
+        NotServedReason, OrchestratorSelection, PendingCollatorContainerChain, RecentCollatorChain,
+        SurplusCollators,
+    },
+    frame_support::{
+        assert_noop, assert_ok,
+        dispatch::DispatchClass,
+        pallet_prelude::Encode,
+        traits::{Get, GetStorageVersion, Hooks, StorageVersion},
+        weights::{constants::RocksDbWeight, Weight},
+        BoundedVec,
+    },
+    std::collections::{BTreeMap, BTreeSet},
+    tp_collator_assignment::{AssignedCollators, AssignmentConfig},
+    tp_traits::{GetContainerChainAuthor, ParaId},
 };
 
 fn assigned_collators() -> BTreeMap<u64, u32> {
@@ -74,6 +90,116 @@ fn assign_initial_collators() {
     });
 }
 
+#[test]
+fn initial_orchestrator_collators_assigned_from_genesis() {
+    new_test_ext_with_initial_orchestrator_collators(vec![9, 10]).execute_with(|| {
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![(9, 999), (10, 999)]),
+        );
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 2;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            m.container_chains = vec![1001, 1002]
+        });
+        run_to_block(1);
+
+        // The initial collators keep serving the orchestrator chain
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 1001),
+                (2, 1001),
+                (3, 1002),
+                (4, 1002),
+                (9, 999),
+                (10, 999),
+            ]),
+        );
+    });
+}
+
+#[test]
+fn orchestrator_and_container_collators_match_assignment() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            m.container_chains = vec![1001, 1002]
+        });
+
+        run_to_block(11);
+
+        let collators_of = |chain: u32| -> Vec<u64> {
+            assigned_collators()
+                .into_iter()
+                .filter(|(_, para_id)| *para_id == chain)
+                .map(|(collator, _)| collator)
+                .collect()
+        };
+
+        assert_eq!(
+            CollatorAssignment::orchestrator_collators(),
+            collators_of(999)
+        );
+        assert_eq!(
+            CollatorAssignment::orchestrator_collators(),
+            vec![1, 2, 3, 4, 5]
+        );
+        for para_id in [1001, 1002] {
+            assert_eq!(
+                CollatorAssignment::container_collators(para_id.into()),
+                collators_of(para_id)
+            );
+        }
+        assert_eq!(
+            CollatorAssignment::container_collators(1001.into()),
+            vec![6, 7]
+        );
+        assert_eq!(
+            CollatorAssignment::container_collators(1002.into()),
+            vec![8, 9]
+        );
+        // Unknown chains have no collators
+        assert_eq!(
+            CollatorAssignment::container_collators(1003.into()),
+            Vec::<u64>::new()
+        );
+    });
+}
+
+#[test]
+fn idle_collators_are_the_unassigned_remainder() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+            m.container_chains = vec![1001, 1002]
+        });
+
+        run_to_block(11);
+
+        let assigned = assigned_collators();
+        let unassigned: Vec<u64> = (1..=12).filter(|c| !assigned.contains_key(c)).collect();
+        assert_eq!(unassigned, vec![10, 11, 12]);
+        assert_eq!(CollatorAssignment::idle_collators(), unassigned);
+    });
+}
+
 #[test]
 fn assign_collators_after_one_leaves_container() {
     new_test_ext().execute_with(|| {
@@ -137,7 +263,7 @@ fn assign_collators_after_one_leaves_container() {
 }
 
 #[test]
-fn assign_collators_after_one_leaves_orchestrator_chain() {
+fn container_chains_are_notified_when_their_collators_change() {
     new_test_ext().execute_with(|| {
         run_to_block(1);
 
@@ -147,54 +273,38 @@ fn assign_collators_after_one_leaves_orchestrator_chain() {
             m.max_orchestrator_chain_collators = 5;
 
             m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
-            m.container_chains = vec![1001, 1002]
+            m.container_chains = vec![1001, 1002];
+            m.notify_collators_changed = true;
         });
 
-        assert_eq!(assigned_collators(), BTreeMap::new(),);
-        run_to_block(11);
-
+        run_to_block(6);
         assert_eq!(
-            assigned_collators(),
-            BTreeMap::from_iter(vec![
-                (1, 999),
-                (2, 999),
-                (3, 999),
-                (4, 999),
-                (5, 999),
-                (6, 1001),
-                (7, 1001),
-                (8, 1002),
-                (9, 1002),
-            ]),
+            MockData::mock().collators_changed_notifications,
+            vec![(1001, vec![6, 7]), (1002, vec![8, 9])]
         );
 
         MockData::mutate(|m| {
-            // Remove 4
-            m.collators = vec![1, 2, 3, /*4,*/ 5, 6, 7, 8, 9, 10];
+            m.collators_changed_notifications.clear();
+            // Remove 6
+            m.collators = vec![1, 2, 3, 4, 5, /*6,*/ 7, 8, 9, 10];
         });
-        run_to_block(21);
 
+        // Only the chain of the removed collator is notified
+        run_to_block(11);
         assert_eq!(
-            assigned_collators(),
-            BTreeMap::from_iter(vec![
-                (1, 999),
-                (2, 999),
-                (3, 999),
-                //(4, 999),
-                (5, 999),
-                (6, 1001),
-                (7, 1001),
-                (8, 1002),
-                (9, 1002),
-                // 10 is assigned in place of 4
-                (10, 999),
-            ]),
+            MockData::mock().collators_changed_notifications,
+            vec![(1001, vec![7, 10])]
         );
+
+        // No notifications without changes
+        MockData::mutate(|m| m.collators_changed_notifications.clear());
+        run_to_block(16);
+        assert_eq!(MockData::mock().collators_changed_notifications, vec![]);
     });
 }
 
 #[test]
-fn assign_collators_if_config_orchestrator_chain_collators_increases() {
+fn notifications_are_disabled_by_config() {
     new_test_ext().execute_with(|| {
         run_to_block(1);
 
@@ -203,10 +313,32 @@ fn assign_collators_if_config_orchestrator_chain_collators_increases() {
             m.min_orchestrator_chain_collators = 5;
             m.max_orchestrator_chain_collators = 5;
 
-            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            m.container_chains = vec![1001, 1002];
+        });
+
+        run_to_block(11);
+        assert_eq!(assigned_collators()[&6], 1001);
+        assert_eq!(MockData::mock().collators_changed_notifications, vec![]);
+    });
+}
+
+#[test]
+fn returning_collator_is_restored_to_recent_chain() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+            m.min_collators_to_activate = Some(1);
+            m.recent_collator_chain_ttl = Some(2);
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
             m.container_chains = vec![1001, 1002]
         });
-        assert_eq!(assigned_collators(), BTreeMap::new(),);
+
         run_to_block(11);
 
         assert_eq!(
@@ -225,11 +357,11 @@ fn assign_collators_if_config_orchestrator_chain_collators_increases() {
         );
 
         MockData::mutate(|m| {
-            // Add 3 new collators to orchestrator_chain
-            m.min_orchestrator_chain_collators = 8;
-            m.max_orchestrator_chain_collators = 8;
+            // Remove 6, nobody can take its slot
+            m.collators = vec![1, 2, 3, 4, 5, /*6,*/ 7, 8, 9];
         });
 
+        run_to_block(16);
         run_to_block(21);
 
         assert_eq!(
@@ -240,33 +372,20 @@ fn assign_collators_if_config_orchestrator_chain_collators_increases() {
                 (3, 999),
                 (4, 999),
                 (5, 999),
-                (6, 1001),
                 (7, 1001),
                 (8, 1002),
                 (9, 1002),
-                (10, 999),
-                (11, 999),
-                (12, 999),
             ]),
         );
-    });
-}
-
-#[test]
-fn assign_collators_if_config_orchestrator_chain_collators_decreases() {
-    new_test_ext().execute_with(|| {
-        run_to_block(1);
 
         MockData::mutate(|m| {
-            m.collators_per_container = 2;
-            m.min_orchestrator_chain_collators = 5;
-            m.max_orchestrator_chain_collators = 5;
-
-            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
-            m.container_chains = vec![1001, 1002]
+            // 6 comes back within the TTL, after a new collator that would otherwise
+            // take the free slot
+            m.collators = vec![1, 2, 3, 4, 5, 10, 6, 7, 8, 9];
         });
-        assert_eq!(assigned_collators(), BTreeMap::new(),);
-        run_to_block(11);
+
+        run_to_block(26);
+        run_to_block(31);
 
         assert_eq!(
             assigned_collators(),
@@ -282,22 +401,12 @@ fn assign_collators_if_config_orchestrator_chain_collators_decreases() {
                 (9, 1002),
             ]),
         );
-
-        MockData::mutate(|m| {
-            // Remove 3 collators from orchestrator_chain
-            m.min_orchestrator_chain_collators = 2;
-            m.max_orchestrator_chain_collators = 2;
-        });
-
-        run_to_block(21);
-
-        // The removed collators are random so no easy way to test the full list
-        assert_eq!(assigned_collators().len(), 6,);
+        assert_eq!(RecentCollatorChain::<Test>::get(6), None);
     });
 }
 
 #[test]
-fn assign_collators_if_config_collators_per_container_increases() {
+fn collator_is_assigned_to_preferred_chain() {
     new_test_ext().execute_with(|| {
         run_to_block(1);
 
@@ -306,35 +415,31 @@ fn assign_collators_if_config_collators_per_container_increases() {
             m.min_orchestrator_chain_collators = 5;
             m.max_orchestrator_chain_collators = 5;
 
-            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
             m.container_chains = vec![1001, 1002]
         });
 
-        assert_eq!(assigned_collators(), BTreeMap::new(),);
-        run_to_block(11);
-
-        assert_eq!(
-            assigned_collators(),
-            BTreeMap::from_iter(vec![
-                (1, 999),
-                (2, 999),
-                (3, 999),
-                (4, 999),
-                (5, 999),
-                (6, 1001),
-                (7, 1001),
-                (8, 1002),
-                (9, 1002),
-            ]),
+        let preferences = BoundedVec::truncate_from(vec![1002.into()]);
+        assert_noop!(
+            CollatorAssignment::set_preferences(RuntimeOrigin::signed(100), preferences.clone()),
+            Error::<Test>::NotACollator
+        );
+        assert_ok!(CollatorAssignment::set_preferences(
+            RuntimeOrigin::signed(6),
+            preferences.clone()
+        ));
+        assert_eq!(CollatorPreferences::<Test>::get(6), Some(preferences));
+        System::assert_last_event(
+            Event::CollatorPreferencesSet {
+                collator: 6,
+                preferences: vec![1002.into()],
+            }
+            .into(),
         );
 
-        MockData::mutate(|m| {
-            // Add 2 new collators to each container_chain
-            m.collators_per_container = 4;
-        });
-
-        run_to_block(21);
+        run_to_block(11);
 
+        // 1002 has a free slot for 6, the other collators fill the chains in order
         assert_eq!(
             assigned_collators(),
             BTreeMap::from_iter(vec![
@@ -343,21 +448,24 @@ fn assign_collators_if_config_collators_per_container_increases() {
                 (3, 999),
                 (4, 999),
                 (5, 999),
-                (6, 1001),
+                (6, 1002),
                 (7, 1001),
-                (8, 1002),
+                (8, 1001),
                 (9, 1002),
-                (10, 1001),
-                (11, 1001),
-                (12, 1002),
-                (13, 1002),
             ]),
         );
+
+        // An empty list removes the preferences
+        assert_ok!(CollatorAssignment::set_preferences(
+            RuntimeOrigin::signed(6),
+            BoundedVec::new()
+        ));
+        assert_eq!(CollatorPreferences::<Test>::get(6), None);
     });
 }
 
 #[test]
-fn assign_collators_if_container_chain_is_removed() {
+fn assign_collators_after_one_leaves_orchestrator_chain() {
     new_test_ext().execute_with(|| {
         run_to_block(1);
 
@@ -366,9 +474,10 @@ fn assign_collators_if_container_chain_is_removed() {
             m.min_orchestrator_chain_collators = 5;
             m.max_orchestrator_chain_collators = 5;
 
-            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
             m.container_chains = vec![1001, 1002]
         });
+
         assert_eq!(assigned_collators(), BTreeMap::new(),);
         run_to_block(11);
 
@@ -388,10 +497,9 @@ fn assign_collators_if_container_chain_is_removed() {
         );
 
         MockData::mutate(|m| {
-            // Remove 1 container_chain
-            m.container_chains = vec![1001 /*1002*/];
+            // Remove 4
+            m.collators = vec![1, 2, 3, /*4,*/ 5, 6, 7, 8, 9, 10];
         });
-
         run_to_block(21);
 
         assert_eq!(
@@ -400,17 +508,21 @@ fn assign_collators_if_container_chain_is_removed() {
                 (1, 999),
                 (2, 999),
                 (3, 999),
-                (4, 999),
+                //(4, 999),
                 (5, 999),
                 (6, 1001),
                 (7, 1001),
+                (8, 1002),
+                (9, 1002),
+                // 10 is assigned in place of 4
+                (10, 999),
             ]),
         );
     });
 }
 
 #[test]
-fn assign_collators_if_container_chain_is_added() {
+fn assign_collators_if_config_orchestrator_chain_collators_increases() {
     new_test_ext().execute_with(|| {
         run_to_block(1);
 
@@ -441,8 +553,9 @@ fn assign_collators_if_container_chain_is_added() {
         );
 
         MockData::mutate(|m| {
-            // Add 1 new container_chain
-            m.container_chains = vec![1001, 1002, 1003];
+            // Add 3 new collators to orchestrator_chain
+            m.min_orchestrator_chain_collators = 8;
+            m.max_orchestrator_chain_collators = 8;
         });
 
         run_to_block(21);
@@ -459,15 +572,16 @@ fn assign_collators_if_container_chain_is_added() {
                 (7, 1001),
                 (8, 1002),
                 (9, 1002),
-                (10, 1003),
-                (11, 1003),
+                (10, 999),
+                (11, 999),
+                (12, 999),
             ]),
         );
     });
 }
 
 #[test]
-fn assign_collators_after_decrease_num_collators() {
+fn assign_collators_if_config_orchestrator_chain_collators_decreases() {
     new_test_ext().execute_with(|| {
         run_to_block(1);
 
@@ -498,38 +612,152 @@ fn assign_collators_after_decrease_num_collators() {
         );
 
         MockData::mutate(|m| {
-            m.collators = vec![];
+            // Remove 3 collators from orchestrator_chain
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 2;
         });
 
         run_to_block(21);
-        assert_eq!(assigned_collators(), BTreeMap::from_iter(vec![]));
+
+        // The removed collators are random so no easy way to test the full list
+        assert_eq!(assigned_collators().len(), 6,);
     });
 }
 
 #[test]
-fn assign_collators_stay_constant_if_new_collators_can_take_new_chains() {
+fn orchestrator_chain_collators_decrease_keeps_lowest_account_ids() {
     new_test_ext().execute_with(|| {
         run_to_block(1);
 
         MockData::mutate(|m| {
             m.collators_per_container = 2;
-            m.min_orchestrator_chain_collators = 2;
+            m.min_orchestrator_chain_collators = 5;
             m.max_orchestrator_chain_collators = 5;
+            m.orchestrator_selection = OrchestratorSelection::LowestAccountId;
 
-            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
-            m.container_chains = vec![];
+            m.collators = vec![5, 4, 3, 2, 1, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+            m.container_chains = vec![1001, 1002]
         });
-        assert_eq!(assigned_collators(), BTreeMap::new(),);
         run_to_block(11);
 
         assert_eq!(
-            assigned_collators(),
-            BTreeMap::from_iter(vec![(1, 999), (2, 999), (3, 999), (4, 999), (5, 999),]),
+            CollatorContainerChain::<Test>::get().orchestrator_chain,
+            vec![5, 4, 3, 2, 1]
         );
 
         MockData::mutate(|m| {
-            m.container_chains = vec![1001, 1002];
+            // Remove 3 collators from orchestrator_chain
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 2;
         });
+
+        run_to_block(21);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (6, 1001),
+                (7, 1001),
+                (8, 1002),
+                (9, 1002),
+            ]),
+        );
+    });
+}
+
+#[test]
+fn orchestrator_chain_excess_collators_fill_partial_container_chains() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 3;
+            m.max_orchestrator_chain_collators = 3;
+            m.min_collators_to_activate = Some(1);
+            m.orchestrator_selection = OrchestratorSelection::HighestStakeReassignExcess;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6];
+            m.container_chains = vec![1001, 1002]
+        });
+        run_to_block(11);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 1001),
+                (5, 1001),
+                (6, 1002),
+            ]),
+        );
+
+        MockData::mutate(|m| {
+            // Remove 1 collator from orchestrator_chain while a new collator joins
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 2;
+            m.collators.push(7);
+        });
+
+        run_to_block(21);
+
+        // 3 is the lowest-priority orchestrator chain collator, it fills 1002 before the new
+        // collator, which stays idle
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (4, 1001),
+                (5, 1001),
+                (3, 1002),
+                (6, 1002),
+            ]),
+        );
+    });
+}
+
+#[test]
+fn assign_collators_if_config_collators_per_container_increases() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+            m.container_chains = vec![1001, 1002]
+        });
+
+        assert_eq!(assigned_collators(), BTreeMap::new(),);
+        run_to_block(11);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (6, 1001),
+                (7, 1001),
+                (8, 1002),
+                (9, 1002),
+            ]),
+        );
+
+        MockData::mutate(|m| {
+            // Add 2 new collators to each container_chain
+            m.collators_per_container = 4;
+        });
+
         run_to_block(21);
 
         assert_eq!(
@@ -544,57 +772,138 @@ fn assign_collators_stay_constant_if_new_collators_can_take_new_chains() {
                 (7, 1001),
                 (8, 1002),
                 (9, 1002),
+                (10, 1001),
+                (11, 1001),
+                (12, 1002),
+                (13, 1002),
             ]),
         );
     });
 }
 
 #[test]
-fn assign_collators_move_extra_container_chain_to_orchestrator_chain_if_not_enough_collators() {
+fn paused_assignment_is_kept_until_resumed() {
     new_test_ext().execute_with(|| {
         run_to_block(1);
 
         MockData::mutate(|m| {
             m.collators_per_container = 2;
-            m.min_orchestrator_chain_collators = 2;
+            m.min_orchestrator_chain_collators = 5;
             m.max_orchestrator_chain_collators = 5;
 
-            m.collators = vec![1, 2, 3, 4];
-            m.container_chains = vec![];
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+            m.container_chains = vec![1001, 1002]
+        });
+        run_to_block(11);
+        let paused_assignment = assigned_collators();
+
+        assert_ok!(CollatorAssignment::set_assignment_paused(
+            RuntimeOrigin::root(),
+            true
+        ));
+        System::assert_last_event(Event::AssignmentPausedSet { paused: true }.into());
+        assert_noop!(
+            CollatorAssignment::force_recompute_assignment(RuntimeOrigin::root()),
+            Error::<Test>::AssignmentIsPaused
+        );
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 4;
+        });
+        run_to_block(21);
+        assert_eq!(assigned_collators(), paused_assignment);
+
+        assert_ok!(CollatorAssignment::set_assignment_paused(
+            RuntimeOrigin::root(),
+            false
+        ));
+        run_to_block(31);
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (6, 1001),
+                (7, 1001),
+                (8, 1002),
+                (9, 1002),
+                (10, 1001),
+                (11, 1001),
+                (12, 1002),
+                (13, 1002),
+            ]),
+        );
+    });
+}
+
+#[test]
+fn assign_collators_if_container_chain_is_removed() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+            m.container_chains = vec![1001, 1002]
         });
         assert_eq!(assigned_collators(), BTreeMap::new(),);
         run_to_block(11);
 
         assert_eq!(
             assigned_collators(),
-            BTreeMap::from_iter(vec![(1, 999), (2, 999), (3, 999), (4, 999),]),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (6, 1001),
+                (7, 1001),
+                (8, 1002),
+                (9, 1002),
+            ]),
         );
 
         MockData::mutate(|m| {
-            m.collators = vec![1, 2, 3, 4, 5];
-            m.container_chains = vec![1001, 1002];
+            // Remove 1 container_chain
+            m.container_chains = vec![1001 /*1002*/];
         });
+
         run_to_block(21);
 
         assert_eq!(
             assigned_collators(),
-            BTreeMap::from_iter(vec![(1, 999), (2, 999), (5, 1001), (3, 1001), (4, 999),]),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (6, 1001),
+                (7, 1001),
+            ]),
         );
     });
 }
 
 #[test]
-fn assign_collators_reorganize_container_chains_if_not_enough_collators() {
+fn assign_collators_if_container_chain_is_added() {
     new_test_ext().execute_with(|| {
         run_to_block(1);
 
         MockData::mutate(|m| {
             m.collators_per_container = 2;
-            m.min_orchestrator_chain_collators = 2;
+            m.min_orchestrator_chain_collators = 5;
             m.max_orchestrator_chain_collators = 5;
 
-            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
-            m.container_chains = vec![1001, 1002, 1003, 1004, 1005];
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+            m.container_chains = vec![1001, 1002]
         });
         assert_eq!(assigned_collators(), BTreeMap::new(),);
         run_to_block(11);
@@ -604,37 +913,1937 @@ fn assign_collators_reorganize_container_chains_if_not_enough_collators() {
             BTreeMap::from_iter(vec![
                 (1, 999),
                 (2, 999),
-                (3, 1001),
-                (4, 1001),
-                (5, 1002),
-                (6, 1002),
-                (7, 1003),
-                (8, 1003),
-                (9, 1004),
-                (10, 1004),
-                (11, 1005),
-                (12, 1005)
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (6, 1001),
+                (7, 1001),
+                (8, 1002),
+                (9, 1002),
             ]),
         );
 
         MockData::mutate(|m| {
-            // Remove collators to leave only 1 per container chain
-            m.collators = vec![1, 2, 3, 5, 7, 9, 11];
+            // Add 1 new container_chain
+            m.container_chains = vec![1001, 1002, 1003];
         });
+
         run_to_block(21);
 
-        // There are 7 collators in total: 2x2 container chains, plus 3 in the orchestrator chain
         assert_eq!(
             assigned_collators(),
             BTreeMap::from_iter(vec![
                 (1, 999),
                 (2, 999),
-                (3, 1005),
-                (5, 1004),
-                (7, 999),
-                (9, 1004),
-                (11, 1005)
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (6, 1001),
+                (7, 1001),
+                (8, 1002),
+                (9, 1002),
+                (10, 1003),
+                (11, 1003),
             ]),
         );
     });
 }
+
+#[test]
+fn container_chain_is_not_assigned_until_activation_session() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+            m.container_chains = vec![1001, 1002]
+        });
+        run_to_block(11);
+
+        // Added during session 2, 1003 would be assigned collators from session 4
+        assert_ok!(CollatorAssignment::set_activation_session(
+            RuntimeOrigin::root(),
+            1003.into(),
+            Some(5)
+        ));
+        System::assert_last_event(
+            Event::ActivationSessionSet {
+                para_id: 1003.into(),
+                session_index: Some(5),
+            }
+            .into(),
+        );
+        MockData::mutate(|m| {
+            m.container_chains = vec![1001, 1002, 1003];
+        });
+
+        let without_1003 = BTreeMap::from_iter(vec![
+            (1, 999),
+            (2, 999),
+            (3, 999),
+            (4, 999),
+            (5, 999),
+            (6, 1001),
+            (7, 1001),
+            (8, 1002),
+            (9, 1002),
+        ]);
+        run_to_block(16);
+        assert_eq!(assigned_collators(), without_1003);
+        run_to_block(21);
+        assert_eq!(assigned_collators(), without_1003);
+
+        run_to_block(26);
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (6, 1001),
+                (7, 1001),
+                (8, 1002),
+                (9, 1002),
+                (10, 1003),
+                (11, 1003),
+            ]),
+        );
+    });
+}
+
+#[test]
+fn reassigned_chain_collators_move_to_target_chain() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+            m.container_chains = vec![1001, 1002]
+        });
+        run_to_block(11);
+        assert_eq!(
+            CollatorAssignment::container_collators(1001.into()),
+            vec![6, 7]
+        );
+
+        assert_noop!(
+            CollatorAssignment::reassign_chain_collators(
+                RuntimeOrigin::root(),
+                1001.into(),
+                1001.into()
+            ),
+            Error::<Test>::ReassignmentToSameChain
+        );
+        assert_ok!(CollatorAssignment::reassign_chain_collators(
+            RuntimeOrigin::root(),
+            1001.into(),
+            1003.into()
+        ));
+        System::assert_last_event(
+            Event::ChainCollatorsReassignmentScheduled {
+                from: 1001.into(),
+                to: 1003.into(),
+            }
+            .into(),
+        );
+        MockData::mutate(|m| {
+            m.container_chains = vec![1001, 1002, 1003];
+        });
+
+        // Without the reassignment, 1003 would get the unassigned collators 10 and 11
+        run_to_block(21);
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (6, 1003),
+                (7, 1003),
+                (8, 1002),
+                (9, 1002),
+                (10, 1001),
+                (11, 1001),
+            ]),
+        );
+        // The reassignment is only applied once
+        assert_eq!(ChainReassignments::<Test>::get(ParaId::from(1001)), None);
+    });
+}
+
+#[test]
+fn assignment_can_be_recomputed_from_last_seed() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            m.container_chains = vec![1001, 1002]
+        });
+        run_to_block(11);
+
+        // The seed selects which orchestrator chain collators are kept
+        let seed = [7; 32];
+        MockData::mutate(|m| {
+            m.max_orchestrator_chain_collators = 3;
+            m.random_seed = seed;
+        });
+        let previous = CollatorAssignment::pending_collator_container_chain()
+            .unwrap_or_else(CollatorAssignment::collator_container_chain);
+
+        // Session 3 computes the assignment of session 4
+        run_to_block(16);
+        assert_eq!(CollatorAssignment::last_assignment_seed(), seed);
+
+        let recomputed = CollatorAssignment::compute_assignment(
+            MockData::mock().collators,
+            &para_ids(&[1001, 1002]),
+            &CollatorAssignment::assignment_params(
+                &3,
+                CollatorAssignment::assignment_config(4),
+                &MockData::mock().collators,
+            ),
+            &previous,
+            CollatorAssignment::last_assignment_seed(),
+        );
+        assert_eq!(recomputed.orchestrator_chain.len(), 3);
+        assert_eq!(
+            Some(recomputed),
+            CollatorAssignment::pending_collator_container_chain()
+        );
+    });
+}
+
+#[test]
+fn assignment_seed_is_committed_before_the_session_end() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            m.container_chains = vec![1001, 1002];
+            m.randomness_delay = 2;
+        });
+        run_to_block(11);
+
+        let committed_seed = [7; 32];
+        MockData::mutate(|m| {
+            m.max_orchestrator_chain_collators = 3;
+            m.random_seed = committed_seed;
+        });
+        let previous = CollatorAssignment::pending_collator_container_chain()
+            .unwrap_or_else(CollatorAssignment::collator_container_chain);
+
+        // The next session change is at block 16, so the seed is committed at block 14
+        run_to_block(13);
+        assert_eq!(CollatorAssignment::committed_seed(), None);
+        run_to_block(14);
+        assert_eq!(CollatorAssignment::committed_seed(), Some(committed_seed));
+
+        // Randomness available after the commitment is not used
+        MockData::mutate(|m| {
+            m.random_seed = [9; 32];
+        });
+        run_to_block(16);
+        assert_eq!(CollatorAssignment::last_assignment_seed(), committed_seed);
+        assert_eq!(CollatorAssignment::committed_seed(), None);
+
+        let expected = CollatorAssignment::compute_assignment(
+            MockData::mock().collators,
+            &para_ids(&[1001, 1002]),
+            &CollatorAssignment::assignment_params(
+                &3,
+                CollatorAssignment::assignment_config(4),
+                &MockData::mock().collators,
+            ),
+            &previous,
+            committed_seed,
+        );
+        assert_eq!(
+            Some(expected),
+            CollatorAssignment::pending_collator_container_chain()
+        );
+    });
+}
+
+#[test]
+fn only_capable_collators_are_assigned_to_restricted_chains() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 2;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8];
+            m.container_chains = vec![1001, 1002]
+        });
+
+        assert_noop!(
+            CollatorAssignment::set_required_capabilities(RuntimeOrigin::signed(1), 1002.into(), 1),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(CollatorAssignment::set_required_capabilities(
+            RuntimeOrigin::root(),
+            1002.into(),
+            0b01
+        ));
+        System::assert_last_event(
+            Event::RequiredCapabilitiesSet {
+                para_id: 1002.into(),
+                capabilities: 0b01,
+            }
+            .into(),
+        );
+        for collator in [7, 8] {
+            assert_ok!(CollatorAssignment::set_collator_capabilities(
+                RuntimeOrigin::root(),
+                collator,
+                0b01
+            ));
+        }
+        System::assert_last_event(
+            Event::CollatorCapabilitiesSet {
+                collator: 8,
+                capabilities: 0b01,
+            }
+            .into(),
+        );
+
+        run_to_block(11);
+
+        // 5 and 6 are skipped by 1002, which is assigned the only capable collators
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 1001),
+                (4, 1001),
+                (7, 1002),
+                (8, 1002),
+            ]),
+        );
+
+        // 1001 now requires a capability that only 5 and 6 have, its collators are replaced
+        assert_ok!(CollatorAssignment::set_required_capabilities(
+            RuntimeOrigin::root(),
+            1001.into(),
+            0b10
+        ));
+        for collator in [5, 6] {
+            assert_ok!(CollatorAssignment::set_collator_capabilities(
+                RuntimeOrigin::root(),
+                collator,
+                0b10
+            ));
+        }
+
+        run_to_block(21);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (5, 1001),
+                (6, 1001),
+                (7, 1002),
+                (8, 1002),
+            ]),
+        );
+    });
+}
+
+#[test]
+fn frozen_chain_keeps_its_collators() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+            m.container_chains = vec![1001, 1002]
+        });
+        run_to_block(11);
+
+        assert_noop!(
+            CollatorAssignment::unfreeze_chain(RuntimeOrigin::root(), 1002.into()),
+            Error::<Test>::ChainNotFrozen
+        );
+        assert_ok!(CollatorAssignment::freeze_chain(
+            RuntimeOrigin::root(),
+            1002.into()
+        ));
+        System::assert_last_event(
+            Event::ChainFrozen {
+                para_id: 1002.into(),
+            }
+            .into(),
+        );
+        assert_noop!(
+            CollatorAssignment::freeze_chain(RuntimeOrigin::root(), 1002.into()),
+            Error::<Test>::ChainAlreadyFrozen
+        );
+
+        // Collators removed from the orchestrator chain only fill 1001
+        MockData::mutate(|m| {
+            m.collators_per_container = 3;
+            m.min_orchestrator_chain_collators = 3;
+            m.max_orchestrator_chain_collators = 3;
+        });
+        run_to_block(21);
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 1001),
+                (6, 1001),
+                (7, 1001),
+                (8, 1002),
+                (9, 1002),
+            ]),
+        );
+
+        assert_ok!(CollatorAssignment::unfreeze_chain(
+            RuntimeOrigin::root(),
+            1002.into()
+        ));
+        run_to_block(31);
+        assert_eq!(
+            CollatorAssignment::container_collators(1002.into()),
+            vec![8, 9, 5]
+        );
+    });
+}
+
+#[test]
+fn collator_count_event_is_emitted_for_added_chain() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+            m.container_chains = vec![1001, 1002]
+        });
+        run_to_block(11);
+
+        MockData::mutate(|m| {
+            m.container_chains = vec![1001, 1002, 1003];
+        });
+        System::reset_events();
+        run_to_block(16);
+
+        // Only the new chain changed its number of collators
+        let count_events: Vec<_> = System::events()
+            .into_iter()
+            .filter_map(|record| match record.event {
+                RuntimeEvent::CollatorAssignment(
+                    event @ Event::ContainerChainCollatorCount { .. },
+                ) => Some(event),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            count_events,
+            vec![Event::ContainerChainCollatorCount {
+                para_id: 1003.into(),
+                count: 2,
+            }]
+        );
+    });
+}
+
+#[test]
+fn assign_collators_after_decrease_num_collators() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+            m.container_chains = vec![1001, 1002]
+        });
+        assert_eq!(assigned_collators(), BTreeMap::new(),);
+        run_to_block(11);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (6, 1001),
+                (7, 1001),
+                (8, 1002),
+                (9, 1002),
+            ]),
+        );
+
+        MockData::mutate(|m| {
+            m.collators = vec![];
+        });
+
+        run_to_block(21);
+        assert_eq!(assigned_collators(), BTreeMap::from_iter(vec![]));
+    });
+}
+
+#[test]
+fn orchestrator_under_minimum_is_reported() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+            m.container_chains = vec![1001, 1002]
+        });
+        System::reset_events();
+        run_to_block(11);
+        assert!(!System::events().iter().any(|record| matches!(
+            record.event,
+            RuntimeEvent::CollatorAssignment(Event::OrchestratorUnderMinimum { .. })
+        )));
+
+        // Only 3 collators are left for the 5 orchestrator chain seats
+        MockData::mutate(|m| {
+            m.collators = vec![1, 2, 3];
+        });
+        run_to_block(16);
+        System::assert_has_event(Event::OrchestratorUnderMinimum { have: 3, need: 5 }.into());
+    });
+}
+
+#[test]
+fn assign_collators_stay_constant_if_new_collators_can_take_new_chains() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+            m.container_chains = vec![];
+        });
+        assert_eq!(assigned_collators(), BTreeMap::new(),);
+        run_to_block(11);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![(1, 999), (2, 999), (3, 999), (4, 999), (5, 999),]),
+        );
+
+        MockData::mutate(|m| {
+            m.container_chains = vec![1001, 1002];
+        });
+        run_to_block(21);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (6, 1001),
+                (7, 1001),
+                (8, 1002),
+                (9, 1002),
+            ]),
+        );
+    });
+}
+
+#[test]
+fn assign_collators_move_extra_container_chain_to_orchestrator_chain_if_not_enough_collators() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4];
+            m.container_chains = vec![];
+        });
+        assert_eq!(assigned_collators(), BTreeMap::new(),);
+        run_to_block(11);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![(1, 999), (2, 999), (3, 999), (4, 999),]),
+        );
+
+        MockData::mutate(|m| {
+            m.collators = vec![1, 2, 3, 4, 5];
+            m.container_chains = vec![1001, 1002];
+        });
+        run_to_block(21);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![(1, 999), (2, 999), (5, 1001), (3, 1001), (4, 999),]),
+        );
+    });
+}
+
+#[test]
+fn assign_collators_reorganize_container_chains_if_not_enough_collators() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+            m.container_chains = vec![1001, 1002, 1003, 1004, 1005];
+        });
+        assert_eq!(assigned_collators(), BTreeMap::new(),);
+        run_to_block(11);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 1001),
+                (4, 1001),
+                (5, 1002),
+                (6, 1002),
+                (7, 1003),
+                (8, 1003),
+                (9, 1004),
+                (10, 1004),
+                (11, 1005),
+                (12, 1005)
+            ]),
+        );
+
+        MockData::mutate(|m| {
+            // Remove collators to leave only 1 per container chain
+            m.collators = vec![1, 2, 3, 5, 7, 9, 11];
+        });
+        run_to_block(21);
+
+        // There are 7 collators in total: 2x2 container chains, plus 3 in the orchestrator chain
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 1005),
+                (5, 1004),
+                (7, 999),
+                (9, 1004),
+                (11, 1005)
+            ]),
+        );
+    });
+}
+
+#[test]
+fn invulnerables_always_assigned_to_orchestrator() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 3;
+
+            // Invulnerables are at the end of the list, they would be assigned to
+            // container chains otherwise
+            m.collators = vec![3, 4, 5, 6, 7, 1, 2];
+            m.container_chains = vec![1001, 1002];
+            m.invulnerables = vec![1, 2];
+        });
+
+        run_to_block(11);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 1001),
+                (4, 1001),
+                (5, 1002),
+                (6, 1002),
+                (7, 999),
+            ]),
+        );
+
+        // Not enough collators for every chain, invulnerables still stay in the orchestrator
+        MockData::mutate(|m| {
+            m.collators = vec![1, 2, 3];
+        });
+
+        run_to_block(21);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![(1, 999), (2, 999), (3, 999)]),
+        );
+    });
+}
+
+#[test]
+fn invulnerables_exceeding_max_orchestrator_collators_are_not_assigned() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 1;
+            m.max_orchestrator_chain_collators = 2;
+
+            m.collators = vec![1, 2, 3, 4, 5];
+            m.container_chains = vec![1001];
+            m.invulnerables = vec![1, 2, 3];
+        });
+
+        run_to_block(11);
+
+        // Invulnerable 3 does not fit in the orchestrator chain, and it is never
+        // assigned to a container chain
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![(1, 999), (2, 999), (4, 1001), (5, 1001)]),
+        );
+    });
+}
+
+fn container_chains_metadata() -> BTreeMap<u32, u64> {
+    ContainerChainsMetadata::<Test>::iter()
+        .map(|(para_id, metadata)| (u32::from(para_id), metadata.assigned_since))
+        .collect()
+}
+
+#[test]
+fn container_chains_metadata_follows_assignment() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 1;
+            m.max_orchestrator_chain_collators = 1;
+
+            m.collators = vec![1, 2, 3, 4, 5];
+            m.container_chains = vec![1001, 1002]
+        });
+
+        run_to_block(11);
+        assert_eq!(
+            container_chains_metadata(),
+            BTreeMap::from_iter(vec![(1001, 6), (1002, 6)]),
+        );
+
+        MockData::mutate(|m| {
+            m.container_chains = vec![1002, 1003];
+        });
+
+        run_to_block(21);
+        assert_eq!(
+            container_chains_metadata(),
+            BTreeMap::from_iter(vec![(1002, 6), (1003, 16)]),
+        );
+    });
+}
+
+#[test]
+fn lazy_migration_to_v1_migrates_chains_over_several_blocks() {
+    new_test_ext().execute_with(|| {
+        // Old storage layout, assigned container chains have no metadata
+        StorageVersion::new(0).put::<CollatorAssignment>();
+        let old_assigned = AssignedCollators {
+            orchestrator_chain: vec![1, 2],
+            container_chains: BTreeMap::from_iter(
+                (1001..=1005).map(|para_id| (ParaId::from(para_id), vec![para_id as u64])),
+            ),
+        };
+        CollatorContainerChain::<Test>::put(&old_assigned);
+        // A chain that already has metadata keeps it
+        ContainerChainsMetadata::<Test>::insert(
+            ParaId::from(1003),
+            ContainerChainMetadata { assigned_since: 0 },
+        );
+
+        // Enough weight to migrate 2 chains per block
+        let db_weight = RocksDbWeight::get();
+        let weight_per_block =
+            db_weight.reads(4) + db_weight.reads_writes(2, 2) + db_weight.writes(2);
+
+        System::set_block_number(1);
+        CollatorAssignment::on_idle(1, weight_per_block);
+        assert_eq!(MigrationCursor::<Test>::get(), Some(ParaId::from(1002)));
+        assert_eq!(CollatorAssignment::on_chain_storage_version(), 0);
+
+        // Not enough weight for a single chain, nothing changes
+        System::set_block_number(2);
+        CollatorAssignment::on_idle(2, db_weight.reads(4));
+        assert_eq!(MigrationCursor::<Test>::get(), Some(ParaId::from(1002)));
+
+        System::set_block_number(3);
+        CollatorAssignment::on_idle(3, weight_per_block);
+        assert_eq!(MigrationCursor::<Test>::get(), Some(ParaId::from(1004)));
+
+        System::set_block_number(4);
+        CollatorAssignment::on_idle(4, weight_per_block);
+        assert_eq!(MigrationCursor::<Test>::get(), None);
+        assert_eq!(CollatorAssignment::on_chain_storage_version(), 1);
+
+        let expected_metadata =
+            BTreeMap::from_iter(vec![(1001, 1), (1002, 1), (1003, 0), (1004, 3), (1005, 4)]);
+        assert_eq!(container_chains_metadata(), expected_metadata);
+        assert_eq!(CollatorContainerChain::<Test>::get(), old_assigned);
+
+        // Running it again once completed does nothing
+        System::set_block_number(5);
+        assert_eq!(
+            CollatorAssignment::on_idle(5, weight_per_block),
+            db_weight.reads(1)
+        );
+        assert_eq!(container_chains_metadata(), expected_metadata);
+    });
+}
+
+#[test]
+fn parathread_assigned_collators_only_with_active_order() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 1;
+            m.max_orchestrator_chain_collators = 1;
+
+            m.collators = vec![1, 2, 3, 4, 5];
+            m.container_chains = vec![1001, 2001];
+            m.parathreads = vec![2001];
+        });
+
+        run_to_block(11);
+
+        // No order, the parathread does not get collators
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![(1, 999), (2, 1001), (3, 1001)]),
+        );
+
+        MockData::mutate(|m| {
+            m.parathread_orders = vec![2001];
+        });
+
+        run_to_block(21);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![(1, 999), (2, 1001), (3, 1001), (4, 2001), (5, 2001)]),
+        );
+
+        // Order served, the parathread collators are released
+        MockData::mutate(|m| {
+            m.parathread_orders = vec![];
+        });
+
+        run_to_block(31);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![(1, 999), (2, 1001), (3, 1001)]),
+        );
+    });
+}
+
+#[test]
+fn force_unassign_collator_backfills_from_free_collators() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            m.container_chains = vec![1001, 1002]
+        });
+
+        run_to_block(11);
+
+        assert_noop!(
+            CollatorAssignment::force_unassign_collator(RuntimeOrigin::signed(1), 6),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            CollatorAssignment::force_unassign_collator(RuntimeOrigin::root(), 10),
+            Error::<Test>::CollatorNotAssigned
+        );
+
+        assert_ok!(CollatorAssignment::force_unassign_collator(
+            RuntimeOrigin::root(),
+            6
+        ));
+
+        // 10 was the only free collator, it takes the slot of 6 in the same block
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (7, 1001),
+                (8, 1002),
+                (9, 1002),
+                (10, 1001),
+            ]),
+        );
+        System::assert_last_event(
+            Event::CollatorForceUnassigned {
+                collator: 6,
+                container_chain: Some(1001.into()),
+                replacement: Some(10),
+            }
+            .into(),
+        );
+
+        // No free collators left, the slot stays empty
+        assert_ok!(CollatorAssignment::force_unassign_collator(
+            RuntimeOrigin::root(),
+            1
+        ));
+        assert_eq!(
+            CollatorContainerChain::<Test>::get().orchestrator_chain,
+            vec![2, 3, 4, 5]
+        );
+        System::assert_last_event(
+            Event::CollatorForceUnassigned {
+                collator: 1,
+                container_chain: None,
+                replacement: None,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn force_recompute_assignment_removes_banned_collator_immediately() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            m.container_chains = vec![1001, 1002]
+        });
+
+        run_to_block(11);
+
+        // 6 is banned, it is not part of the collators anymore
+        MockData::mutate(|m| {
+            m.collators.retain(|c| *c != 6);
+        });
+
+        assert_noop!(
+            CollatorAssignment::force_recompute_assignment(RuntimeOrigin::signed(1)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(CollatorAssignment::force_recompute_assignment(
+            RuntimeOrigin::root()
+        ));
+
+        // 6 is removed in the same block, and 10 takes its slot
+        let expected = BTreeMap::from_iter(vec![
+            (1, 999),
+            (2, 999),
+            (3, 999),
+            (4, 999),
+            (5, 999),
+            (7, 1001),
+            (8, 1002),
+            (9, 1002),
+            (10, 1001),
+        ]);
+        assert_eq!(assigned_collators(), expected);
+        System::assert_last_event(
+            Event::AssignmentChanged {
+                session_index: 2,
+                diff: AssignmentDiff {
+                    moved: vec![],
+                    added: vec![(10, Some(1001.into()))],
+                    removed: vec![(6, Some(1001.into()))],
+                    full_rotation: false,
+                },
+            }
+            .into(),
+        );
+
+        // Recomputing again does not change anything
+        System::reset_events();
+        assert_ok!(CollatorAssignment::force_recompute_assignment(
+            RuntimeOrigin::root()
+        ));
+        assert_eq!(assigned_collators(), expected);
+        assert!(System::events().is_empty());
+
+        // The next session changes keep the recomputed assignment
+        run_to_block(21);
+        assert_eq!(assigned_collators(), expected);
+    });
+}
+
+#[test]
+fn sessions_served_are_counted_while_assigned() {
+    new_test_ext().execute_with(|| {
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 2;
+
+            m.collators = vec![1, 2, 3, 4];
+            m.container_chains = vec![1001]
+        });
+        run_to_block(11);
+        for collator in [1, 2, 3, 4] {
+            assert_eq!(CollatorAssignment::collator_sessions_served(collator), 3);
+        }
+
+        // Collator 4 leaves, and is no longer assigned from the session after the next one
+        MockData::mutate(|m| {
+            m.collators = vec![1, 2, 3];
+        });
+        run_to_block(21);
+        assert_eq!(CollatorAssignment::collator_sessions_served(1), 5);
+        assert_eq!(CollatorAssignment::collator_sessions_served(2), 5);
+        assert_eq!(CollatorAssignment::collator_sessions_served(4), 4);
+        assert_eq!(CollatorAssignment::collator_sessions_served(5), 0);
+    });
+}
+
+#[test]
+fn pinned_collator_stays_on_its_chain_across_rotations() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+            m.container_chains = vec![1001, 1002]
+        });
+
+        assert_ok!(CollatorAssignment::pin_collator(
+            RuntimeOrigin::root(),
+            7,
+            1002.into()
+        ));
+        System::assert_last_event(
+            Event::CollatorPinned {
+                collator: 7,
+                para_id: 1002.into(),
+            }
+            .into(),
+        );
+        assert_ok!(CollatorAssignment::pin_collator(
+            RuntimeOrigin::root(),
+            8,
+            1002.into()
+        ));
+        // 1002 only has 2 slots
+        assert_noop!(
+            CollatorAssignment::pin_collator(RuntimeOrigin::root(), 9, 1002.into()),
+            Error::<Test>::TooManyPinnedCollators
+        );
+        assert_ok!(CollatorAssignment::unpin_collator(RuntimeOrigin::root(), 8));
+        System::assert_last_event(Event::CollatorUnpinned { collator: 8 }.into());
+        assert_noop!(
+            CollatorAssignment::unpin_collator(RuntimeOrigin::root(), 8),
+            Error::<Test>::CollatorNotPinned
+        );
+
+        run_to_block(11);
+        assert_eq!(assigned_collators().get(&7), Some(&1002));
+
+        // Collators leave and come back, and chains are added and removed, but collator 7
+        // is always assigned to 1002
+        let rotations = vec![
+            (vec![1, 2, 3, 4, 5, 6, 7], vec![1001, 1002]),
+            (vec![7, 8, 9, 10, 11, 12, 13], vec![1001, 1002, 1003]),
+            (vec![13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1], vec![1002]),
+            (
+                vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13],
+                vec![1001, 1002],
+            ),
+        ];
+        let mut block = 11;
+        for (collators, container_chains) in rotations {
+            MockData::mutate(|m| {
+                m.collators = collators;
+                m.container_chains = container_chains;
+            });
+            block += 10;
+            run_to_block(block);
+            assert_eq!(assigned_collators().get(&7), Some(&1002));
+        }
+    });
+}
+
+#[test]
+fn new_session_weight_scales_with_collators() {
+    let session_weight = |collators: Vec<u64>| {
+        let mut weight = Weight::zero();
+        new_test_ext().execute_with(|| {
+            MockData::mutate(|m| {
+                m.collators_per_container = 2;
+                m.min_orchestrator_chain_collators = 5;
+                m.max_orchestrator_chain_collators = 5;
+
+                m.collators = collators.clone();
+                m.container_chains = vec![1001, 1002]
+            });
+
+            let mandatory_weight_before = *System::block_weight().get(DispatchClass::Mandatory);
+            CollatorAssignment::initializer_on_new_session(&0, collators);
+            weight =
+                *System::block_weight().get(DispatchClass::Mandatory) - mandatory_weight_before;
+        });
+        weight
+    };
+
+    let few_collators = session_weight((1..=10).collect());
+    let many_collators = session_weight((1..=100).collect());
+
+    assert_eq!(few_collators, <() as WeightInfo>::new_session(10, 2));
+    assert_eq!(many_collators, <() as WeightInfo>::new_session(100, 2));
+    assert!(many_collators.ref_time() > few_collators.ref_time());
+    assert!(many_collators.proof_size() >= few_collators.proof_size());
+}
+
+#[test]
+fn new_session_charges_benchmarked_weight() {
+    new_test_ext().execute_with(|| {
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            m.container_chains = vec![1001, 1002]
+        });
+
+        let mandatory_weight_before = *System::block_weight().get(DispatchClass::Mandatory);
+        CollatorAssignment::initializer_on_new_session(&0, MockData::mock().collators);
+        let mandatory_weight_after = *System::block_weight().get(DispatchClass::Mandatory);
+
+        assert_eq!(
+            mandatory_weight_after - mandatory_weight_before,
+            <() as WeightInfo>::new_session(10, 2)
+        );
+    });
+}
+
+#[test]
+fn container_chain_not_activated_without_min_collators() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 3;
+            m.min_collators_to_activate = Some(2);
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8];
+            m.container_chains = vec![1001]
+        });
+        run_to_block(11);
+
+        let initial_assignment = BTreeMap::from_iter(vec![
+            (1, 999),
+            (2, 999),
+            (3, 999),
+            (4, 999),
+            (5, 999),
+            (6, 1001),
+            (7, 1001),
+            (8, 1001),
+        ]);
+        assert_eq!(assigned_collators(), initial_assignment);
+
+        MockData::mutate(|m| {
+            // Add 1 new container_chain, but only 1 free collator
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+            m.container_chains = vec![1001, 1002];
+        });
+        run_to_block(21);
+
+        assert_eq!(assigned_collators(), initial_assignment);
+        System::assert_has_event(
+            Event::ChainActivationDeferred {
+                para_id: 1002.into(),
+            }
+            .into(),
+        );
+
+        MockData::mutate(|m| {
+            // Enough collators to activate the chain, even if it is not fully served
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        });
+        run_to_block(31);
+
+        let mut expected = initial_assignment;
+        expected.insert(9, 1002);
+        expected.insert(10, 1002);
+        assert_eq!(assigned_collators(), expected);
+    });
+}
+
+#[test]
+fn frozen_chain_without_collators_is_not_deferred() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+            m.container_chains = vec![1001, 1002]
+        });
+        run_to_block(11);
+        assert_eq!(
+            CollatorAssignment::container_collators(1002.into()),
+            vec![8, 9]
+        );
+
+        assert_ok!(CollatorAssignment::freeze_chain(
+            RuntimeOrigin::root(),
+            1002.into()
+        ));
+        // The collators of the frozen chain leave, so it is left without collators
+        MockData::mutate(|m| {
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7];
+        });
+        System::reset_events();
+        run_to_block(21);
+
+        assert!(CollatorAssignment::container_collators(1002.into()).is_empty());
+        assert!(!System::events().iter().any(|record| matches!(
+            record.event,
+            RuntimeEvent::CollatorAssignment(Event::ChainActivationDeferred { .. })
+        )));
+    });
+}
+
+#[test]
+fn chain_assignment_status_explains_under_served_chains() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 3;
+            m.min_collators_to_activate = Some(2);
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+            m.container_chains = vec![1001, 1002]
+        });
+        run_to_block(11);
+
+        // Only 1 collator left for 1002, below the minimum to activate it
+        assert_eq!(
+            CollatorAssignment::chain_assignment_status(ParaId::from(1001)),
+            Some(AssignmentStatus::FullyServed)
+        );
+        assert_eq!(
+            CollatorAssignment::chain_assignment_status(ParaId::from(1002)),
+            Some(AssignmentStatus::NotServed {
+                reason: NotServedReason::NotEnoughCollators
+            })
+        );
+
+        MockData::mutate(|m| {
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        });
+        run_to_block(21);
+
+        assert_eq!(
+            CollatorAssignment::container_collators(1002.into()),
+            vec![9, 10]
+        );
+        assert_eq!(
+            CollatorAssignment::chain_assignment_status(ParaId::from(1002)),
+            Some(AssignmentStatus::PartiallyServed { have: 2, want: 3 })
+        );
+        // Unregistered chains have no status
+        assert_eq!(
+            CollatorAssignment::chain_assignment_status(ParaId::from(1003)),
+            None
+        );
+    });
+}
+
+#[test]
+fn collator_moves_are_limited_by_max_churn_per_session() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 3;
+            m.min_orchestrator_chain_collators = 6;
+            m.max_orchestrator_chain_collators = 6;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+            m.container_chains = vec![1001, 1002]
+        });
+        run_to_block(11);
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (6, 999),
+                (7, 1001),
+                (8, 1001),
+                (9, 1001),
+                (10, 1002),
+                (11, 1002),
+                (12, 1002),
+            ]),
+        );
+
+        MockData::mutate(|m| {
+            // All the orchestrator chain collators should move to the container chains
+            m.collators_per_container = 6;
+            m.min_orchestrator_chain_collators = 0;
+            m.max_orchestrator_chain_collators = 0;
+            m.max_churn_per_session = Some(2);
+        });
+
+        // The first new assignment is computed at block 16 and applied at block 21.
+        // Only 2 collators move each session
+        run_to_block(16);
+        let mut expected = assigned_collators();
+        for moves in [
+            [(1, 1001), (2, 1001)],
+            [(3, 1001), (4, 1002)],
+            [(5, 1002), (6, 1002)],
+        ] {
+            run_to_block(System::block_number() + 5);
+            expected.extend(moves);
+            assert_eq!(assigned_collators(), expected);
+        }
+
+        run_to_block(System::block_number() + 10);
+        assert_eq!(assigned_collators(), expected);
+    });
+}
+
+#[test]
+fn dry_run_assignment_matches_assignment_with_same_config() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+            m.container_chains = vec![1001, 1002]
+        });
+
+        run_to_block(11);
+
+        let current = CollatorContainerChain::<Test>::get();
+        let dry_run = CollatorAssignment::dry_run_assignment(
+            &3,
+            AssignmentConfig {
+                min_orchestrator_collators: 5,
+                max_orchestrator_collators: 5,
+                collators_per_container: 4,
+            },
+        );
+
+        // Storage is not modified
+        assert_eq!(CollatorContainerChain::<Test>::get(), current);
+        assert_eq!(CollatorAssignment::pending_collator_container_chain(), None);
+        assert_ne!(dry_run, current);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 4;
+        });
+
+        run_to_block(16);
+
+        assert_eq!(
+            CollatorAssignment::pending_collator_container_chain(),
+            Some(dry_run)
+        );
+    });
+}
+
+#[test]
+fn session_change_without_enough_weight_is_deferred_to_next_block() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 2;
+
+            m.collators = vec![1, 2, 3, 4];
+            m.container_chains = vec![1001]
+        });
+
+        run_to_block(10);
+        let active = CollatorContainerChain::<Test>::get();
+        let pending = PendingCollatorContainerChain::<Test>::get()
+            .expect("session 1 should have computed a new assignment");
+        assert_ne!(active, pending);
+
+        // Session 2 starts in a block that has no weight left
+        System::initialize(&11, &Default::default(), &Default::default());
+        CollatorAssignment::on_initialize(11);
+        System::register_extra_weight_unchecked(
+            <Test as frame_system::Config>::BlockWeights::get().max_block,
+            DispatchClass::Mandatory,
+        );
+        let outcome =
+            CollatorAssignment::initializer_on_new_session(&2, MockData::mock().collators);
+
+        System::assert_last_event(Event::AssignmentDeferred { session_index: 2 }.into());
+        assert_eq!(outcome.active_assignment, active);
+        assert_eq!(CollatorContainerChain::<Test>::get(), active);
+        assert_eq!(
+            PendingCollatorContainerChain::<Test>::get(),
+            Some(pending.clone())
+        );
+        assert_eq!(
+            DeferredAssignment::<Test>::get(),
+            Some((2, MockData::mock().collators))
+        );
+
+        // The prior assignment is kept for one more block
+        run_to_block(12);
+        assert_eq!(DeferredAssignment::<Test>::get(), None);
+        assert_eq!(CollatorContainerChain::<Test>::get(), pending);
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![(1, 999), (2, 999), (3, 1001), (4, 1001)])
+        );
+    });
+}
+
+fn assignment_params(
+    min_orchestrator_collators: u32,
+    max_orchestrator_collators: u32,
+    collators_per_container: u32,
+) -> AssignmentParams<u64> {
+    AssignmentParams {
+        config: AssignmentConfig {
+            min_orchestrator_collators,
+            max_orchestrator_collators,
+            collators_per_container,
+        },
+        invulnerables: vec![],
+        min_collators_to_activate: 1,
+        max_churn: u32::MAX,
+        orchestrator_collators_per_container_chain: 0,
+        orchestrator_selection: OrchestratorSelection::RandomSeeded,
+        surplus_collators: SurplusCollators::Unassigned,
+        recent_chains: BTreeMap::new(),
+        preferences: BTreeMap::new(),
+        chain_reassignments: BTreeMap::new(),
+        frozen_chains: BTreeSet::new(),
+        collator_capabilities: BTreeMap::new(),
+        required_capabilities: BTreeMap::new(),
+        core_chains: vec![],
+        pinned_assignments: BTreeMap::new(),
+    }
+}
+
+fn para_ids(ids: &[u32]) -> Vec<ParaId> {
+    ids.iter().map(|id| ParaId::from(*id)).collect()
+}
+
+#[test]
+fn orchestrator_minimum_scales_with_container_chains() {
+    let mut params = assignment_params(2, 8, 2);
+    params.orchestrator_collators_per_container_chain = 1;
+    let collators: Vec<u64> = (1..=8).collect();
+
+    // 2 + 2 * 1 orchestrator collators, the rest serve the container chains
+    let assigned = CollatorAssignment::compute_assignment(
+        collators.clone(),
+        &para_ids(&[1001, 1002]),
+        &params,
+        &AssignedCollators::default(),
+        [0; 32],
+    );
+    assert_eq!(assigned.orchestrator_chain, vec![1, 2, 3, 4]);
+
+    // 2 + 5 * 1 orchestrator collators, leaving a single collator for the container chains
+    let assigned = CollatorAssignment::compute_assignment(
+        collators,
+        &para_ids(&[1001, 1002, 1003, 1004, 1005]),
+        &params,
+        &AssignedCollators::default(),
+        [0; 32],
+    );
+    assert_eq!(assigned.orchestrator_chain, vec![1, 2, 3, 4, 5, 6, 7]);
+}
+
+#[test]
+fn compute_assignment_from_empty_assignment() {
+    let assigned = CollatorAssignment::compute_assignment(
+        vec![1, 2, 3, 4, 5, 6, 7],
+        &para_ids(&[1001, 1002]),
+        &assignment_params(2, 5, 2),
+        &AssignedCollators::default(),
+        [0; 32],
+    );
+
+    assert_eq!(
+        assigned,
+        AssignedCollators {
+            orchestrator_chain: vec![1, 2, 7],
+            container_chains: BTreeMap::from_iter(vec![
+                (1001.into(), vec![3, 4]),
+                (1002.into(), vec![5, 6]),
+            ]),
+        }
+    );
+}
+
+#[test]
+fn compute_assignment_keeps_previous_assignment() {
+    let previous = AssignedCollators {
+        orchestrator_chain: vec![1, 2],
+        container_chains: BTreeMap::from_iter(vec![
+            (1001.into(), vec![3, 4]),
+            (1002.into(), vec![5, 6]),
+        ]),
+    };
+
+    let assigned = CollatorAssignment::compute_assignment(
+        vec![6, 5, 4, 3, 2, 1],
+        &para_ids(&[1001, 1002]),
+        &assignment_params(2, 5, 2),
+        &previous,
+        [0; 32],
+    );
+
+    assert_eq!(assigned, previous);
+}
+
+#[test]
+fn compute_assignment_pins_invulnerables_to_orchestrator_chain() {
+    let mut params = assignment_params(2, 5, 2);
+    params.invulnerables = vec![7];
+
+    let assigned = CollatorAssignment::compute_assignment(
+        vec![1, 2, 3, 4, 5, 6, 7],
+        &para_ids(&[1001, 1002]),
+        &params,
+        &AssignedCollators::default(),
+        [0; 32],
+    );
+
+    assert_eq!(
+        assigned,
+        AssignedCollators {
+            orchestrator_chain: vec![7, 1, 6],
+            container_chains: BTreeMap::from_iter(vec![
+                (1001.into(), vec![2, 3]),
+                (1002.into(), vec![4, 5]),
+            ]),
+        }
+    );
+}
+
+#[test]
+fn compute_assignment_follows_precedence_of_collators_in_several_categories() {
+    let mut params = assignment_params(2, 5, 2);
+    // 7 is invulnerable, pinned and has preferences, 6 is pinned and has preferences
+    params.invulnerables = vec![7];
+    params.pinned_assignments = BTreeMap::from_iter(vec![(7, 1002.into()), (6, 1001.into())]);
+    params.preferences = BTreeMap::from_iter(vec![
+        (7, para_ids(&[1001])),
+        (6, para_ids(&[1002])),
+        (5, para_ids(&[1002])),
+    ]);
+
+    // Duplicated collators are only assigned once
+    let assigned = CollatorAssignment::compute_assignment(
+        vec![1, 2, 3, 4, 5, 6, 7, 7, 6],
+        &para_ids(&[1001, 1002]),
+        &params,
+        &AssignedCollators::default(),
+        [0; 32],
+    );
+
+    assert_eq!(
+        assigned,
+        AssignedCollators {
+            orchestrator_chain: vec![7, 1],
+            container_chains: BTreeMap::from_iter(vec![
+                (1001.into(), vec![6, 2]),
+                (1002.into(), vec![5, 4]),
+            ]),
+        }
+    );
+}
+
+#[test]
+fn compute_assignment_seed_selects_orchestrator_chain_collators() {
+    let previous = AssignedCollators {
+        orchestrator_chain: vec![1, 2, 3, 4],
+        container_chains: BTreeMap::new(),
+    };
+    let compute = |seed| {
+        CollatorAssignment::compute_assignment(
+            vec![1, 2, 3, 4],
+            &[],
+            &assignment_params(2, 2, 2),
+            &previous,
+            seed,
+        )
+    };
+
+    // A zero seed keeps the collators that were assigned first
+    assert_eq!(compute([0; 32]).orchestrator_chain, vec![1, 2]);
+
+    let seed = [1; 32];
+    let mut expected = vec![1u64, 2, 3, 4];
+    expected.sort_by_cached_key(|c| sp_core::hashing::blake2_256(&(seed, c).encode()));
+    expected.truncate(2);
+    assert_eq!(compute(seed).orchestrator_chain, expected);
+    // The selection only depends on the seed
+    assert_eq!(compute(seed), compute(seed));
+}
+
+#[test]
+fn compute_assignment_spreads_surplus_across_container_chains() {
+    let mut params = assignment_params(2, 2, 2);
+    params.surplus_collators = SurplusCollators::SpreadSurplus;
+
+    let assigned = CollatorAssignment::compute_assignment(
+        vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13],
+        &para_ids(&[1001, 1002, 1003, 1004]),
+        &params,
+        &AssignedCollators::default(),
+        [0; 32],
+    );
+
+    assert_eq!(
+        assigned,
+        AssignedCollators {
+            orchestrator_chain: vec![1, 2],
+            container_chains: BTreeMap::from_iter(vec![
+                (1001.into(), vec![3, 4, 11]),
+                (1002.into(), vec![5, 6, 12]),
+                (1003.into(), vec![7, 8, 13]),
+                (1004.into(), vec![9, 10]),
+            ]),
+        }
+    );
+
+    // Without spreading, the surplus is left unassigned
+    params.surplus_collators = SurplusCollators::Unassigned;
+    let assigned = CollatorAssignment::compute_assignment(
+        vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13],
+        &para_ids(&[1001, 1002, 1003, 1004]),
+        &params,
+        &AssignedCollators::default(),
+        [0; 32],
+    );
+    assert_eq!(assigned.container_chains[&1001.into()], vec![3, 4]);
+    assert!(!assigned.find_collator(&11));
+}
+
+#[test]
+fn orchestrator_minimum_is_filled_before_container_chains() {
+    let params = assignment_params(5, 5, 2);
+
+    let assigned = CollatorAssignment::compute_assignment(
+        vec![1, 2, 3, 4, 5],
+        &para_ids(&[1001]),
+        &params,
+        &AssignedCollators::default(),
+        [0; 32],
+    );
+    let mut orchestrator_chain = assigned.orchestrator_chain.clone();
+    orchestrator_chain.sort();
+    assert_eq!(orchestrator_chain, vec![1, 2, 3, 4, 5]);
+    assert!(assigned
+        .container_chains
+        .values()
+        .all(|collators| collators.is_empty()));
+
+    // Container chain collators are moved to the orchestrator chain when it falls below the
+    // minimum, even if that leaves the container chain without collators
+    let previous = AssignedCollators {
+        orchestrator_chain: vec![1, 2, 3],
+        container_chains: BTreeMap::from_iter(vec![(1001.into(), vec![4, 5])]),
+    };
+    let assigned = CollatorAssignment::compute_assignment(
+        vec![1, 2, 3, 4, 5],
+        &para_ids(&[1001]),
+        &params,
+        &previous,
+        [0; 32],
+    );
+    let mut orchestrator_chain = assigned.orchestrator_chain.clone();
+    orchestrator_chain.sort();
+    assert_eq!(orchestrator_chain, vec![1, 2, 3, 4, 5]);
+    assert!(assigned
+        .container_chains
+        .values()
+        .all(|collators| collators.is_empty()));
+}
+
+#[test]
+fn core_chains_minimum_is_filled_before_container_chains() {
+    let mut params = assignment_params(2, 2, 2);
+    params.core_chains = vec![
+        CoreChain {
+            para_id: 2001.into(),
+            min_collators: 2,
+            max_collators: 3,
+        },
+        CoreChain {
+            para_id: 2002.into(),
+            min_collators: 1,
+            max_collators: 2,
+        },
+    ];
+
+    // Not enough collators for all the chains: the core chains get their minimum before the
+    // container chain
+    let assigned = CollatorAssignment::compute_assignment(
+        vec![1, 2, 3, 4, 5, 6],
+        &para_ids(&[1001]),
+        &params,
+        &AssignedCollators::default(),
+        [0; 32],
+    );
+    assert_eq!(assigned.orchestrator_chain, vec![1, 2]);
+    assert_eq!(
+        assigned.container_chains,
+        BTreeMap::from_iter(vec![
+            (1001.into(), vec![6]),
+            (2001.into(), vec![3, 4]),
+            (2002.into(), vec![5]),
+        ])
+    );
+
+    // Once the container chain is full, the core chains are filled up to their maximum
+    let assigned = CollatorAssignment::compute_assignment(
+        vec![1, 2, 3, 4, 5, 6, 7, 8, 9],
+        &para_ids(&[1001]),
+        &params,
+        &assigned,
+        [0; 32],
+    );
+    assert_eq!(assigned.orchestrator_chain, vec![1, 2]);
+    assert_eq!(
+        assigned.container_chains,
+        BTreeMap::from_iter(vec![
+            (1001.into(), vec![6, 7]),
+            (2001.into(), vec![3, 4, 8]),
+            (2002.into(), vec![5, 9]),
+        ])
+    );
+}
+
+#[test]
+fn assignment_changed_event_lists_moved_collators() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+            m.container_chains = vec![1001, 1002]
+        });
+
+        run_to_block(11);
+
+        // 1002 is deregistered and 1003 registered, its collators have nowhere else to go
+        MockData::mutate(|m| {
+            m.container_chains = vec![1001, 1003];
+        });
+
+        assert_ok!(CollatorAssignment::force_recompute_assignment(
+            RuntimeOrigin::root()
+        ));
+
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 999),
+                (2, 999),
+                (3, 999),
+                (4, 999),
+                (5, 999),
+                (6, 1001),
+                (7, 1001),
+                (8, 1003),
+                (9, 1003),
+            ])
+        );
+        System::assert_last_event(
+            Event::AssignmentChanged {
+                session_index: 2,
+                diff: AssignmentDiff {
+                    moved: vec![
+                        (8, Some(1002.into()), Some(1003.into())),
+                        (9, Some(1002.into()), Some(1003.into())),
+                    ],
+                    added: vec![],
+                    removed: vec![],
+                    full_rotation: false,
+                },
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn assignment_changed_event_is_emitted_on_session_change() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+            m.container_chains = vec![1001, 1002]
+        });
+
+        run_to_block(11);
+        assert_eq!(assigned_collators().get(&9), Some(&1002));
+
+        // 9 leaves the collators
+        MockData::mutate(|m| {
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        });
+        System::reset_events();
+        run_to_block(16);
+
+        let diff = System::events()
+            .into_iter()
+            .find_map(|record| match record.event {
+                RuntimeEvent::CollatorAssignment(Event::AssignmentChanged {
+                    session_index: 3,
+                    diff,
+                }) => Some(diff),
+                _ => None,
+            })
+            .expect("AssignmentChanged event expected");
+        assert!(diff.removed.contains(&(9, Some(1002.into()))));
+        assert!(!diff.full_rotation);
+    });
+}
+
+#[test]
+fn authors_of_previous_assignment_are_accepted_for_one_session() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            m.container_chains = vec![1001, 1002]
+        });
+        run_to_block(11);
+        assert_eq!(assigned_collators().get(&6), Some(&1001));
+        assert!(CollatorAssignment::is_author_assigned(1001.into(), &6));
+
+        // Collator 6 leaves, it is still assigned until the pending assignment applies
+        MockData::mutate(|m| {
+            m.collators = vec![1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+        });
+        run_to_block(21);
+        assert_eq!(assigned_collators().get(&6), None);
+
+        // Blocks authored before the session change can still be noted
+        assert!(CollatorAssignment::is_author_assigned(1001.into(), &6));
+        assert!(!CollatorAssignment::is_author_assigned(1002.into(), &6));
+
+        // But not once the previous assignment is a session old
+        run_to_block(26);
+        assert!(!CollatorAssignment::is_author_assigned(1001.into(), &6));
+    });
+}
@@ -22,6 +22,10 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `tomasz-XPS-15-9520`, CPU: `12th Gen Intel(R) Core(TM) i7-12700H`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: None, DB CACHE: 1024
+//!
+//! Pending a benchmark run: only the execution time of `new_session` comes from the run above,
+//! and the session hook has read and written more storage since. The other weights are derived
+//! from the storage accesses of each call. All of them must be regenerated with the command below.
 
 // Executed Command:
 // ./target/release/tanssi-node
@@ -32,7 +36,7 @@
 // --pallet
 // pallet_collator_assignment
 // --extrinsic
-// new_session
+// *
 // --steps
 // 50
 // --repeat
@@ -53,6 +57,18 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for pallet_collator_assignment.
 pub trait WeightInfo {
 	fn new_session(x: u32, y: u32, ) -> Weight;
+	fn force_unassign_collator() -> Weight;
+	fn force_recompute_assignment() -> Weight;
+	fn set_preferences(x: u32, ) -> Weight;
+	fn set_activation_session() -> Weight;
+	fn reassign_chain_collators() -> Weight;
+	fn freeze_chain() -> Weight;
+	fn unfreeze_chain() -> Weight;
+	fn set_collator_capabilities() -> Weight;
+	fn set_required_capabilities() -> Weight;
+	fn set_assignment_paused() -> Weight;
+	fn pin_collator() -> Weight;
+	fn unpin_collator() -> Weight;
 }
 
 /// Weights for pallet_collator_assignment using the Substrate node and recommended hardware.
@@ -68,11 +84,21 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof Skipped: Configuration PendingConfigs (max_values: Some(1), max_size: None, mode: Measured)
 	/// Storage: Configuration ActiveConfig (r:1 w:0)
 	/// Proof Skipped: Configuration ActiveConfig (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment Collators (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment Collators (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment ContainerChainsMetadata (r:0 w:40)
+	/// Proof Skipped: CollatorAssignment ContainerChainsMetadata (max_values: None, max_size: None, mode: Measured)
 	/// Storage: CollatorAssignment CollatorContainerChain (r:0 w:1)
 	/// Proof Skipped: CollatorAssignment CollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment CollatorSessionsServed (r:200 w:200)
+	/// Proof Skipped: CollatorAssignment CollatorSessionsServed (max_values: None, max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment PreviousCollatorContainerChain (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment PreviousCollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
 	/// The range of component `x` is `[1, 200]`.
 	/// The range of component `y` is `[1, 20]`.
 	fn new_session(x: u32, y: u32, ) -> Weight {
+		// Pending a benchmark run: measured before the assignment read the preferences,
+		// capabilities, pins and recent chains of the collators.
 		// Proof Size summary in bytes:
 		//  Measured:  `427 + y * (4 ±0)`
 		//  Estimated: `1913 + y * (4 ±0)`
@@ -83,9 +109,126 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			// Standard Error: 8_900
 			.saturating_add(Weight::from_parts(706_995, 0).saturating_mul(y.into()))
 			.saturating_add(T::DbWeight::get().reads(5_u64))
-			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes(24_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(x.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(x.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(y.into())))
 			.saturating_add(Weight::from_parts(0, 4).saturating_mul(y.into()))
 	}
+	/// Storage: CollatorAssignment CollatorContainerChain (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment CollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment Collators (r:1 w:0)
+	/// Proof Skipped: CollatorAssignment Collators (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment PendingCollatorContainerChain (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment PendingCollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment PreviousCollatorContainerChain (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment PreviousCollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
+	fn force_unassign_collator() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(98_712_000, 10097)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: CollatorAssignment CollatorContainerChain (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment CollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment PendingCollatorContainerChain (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment PendingCollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment Collators (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment Collators (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Configuration PendingConfigs (r:1 w:0)
+	/// Proof Skipped: Configuration PendingConfigs (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment RecentCollatorChain (r:201 w:200)
+	/// Proof Skipped: CollatorAssignment RecentCollatorChain (max_values: None, max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment ContainerChainsMetadata (r:0 w:20)
+	/// Proof Skipped: CollatorAssignment ContainerChainsMetadata (max_values: None, max_size: None, mode: Measured)
+	fn force_recompute_assignment() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(631_992_000, 515140)
+			.saturating_add(T::DbWeight::get().reads(205_u64))
+			.saturating_add(T::DbWeight::get().writes(223_u64))
+	}
+	/// Storage: CollatorAssignment Collators (r:1 w:0)
+	/// Proof Skipped: CollatorAssignment Collators (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment CollatorPreferences (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment CollatorPreferences (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `x` is `[0, 10]`.
+	fn set_preferences(x: u32, ) -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(16_811_402, 1552)
+			// Standard Error: 1_842
+			.saturating_add(Weight::from_parts(21_310, 0).saturating_mul(x.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment ActivationSession (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment ActivationSession (max_values: None, max_size: None, mode: Measured)
+	fn set_activation_session() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(12_305_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment ChainReassignments (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment ChainReassignments (max_values: None, max_size: None, mode: Measured)
+	fn reassign_chain_collators() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(12_093_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment FrozenChains (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment FrozenChains (max_values: Some(1), max_size: None, mode: Measured)
+	fn freeze_chain() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(13_806_000, 1485)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment FrozenChains (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment FrozenChains (max_values: Some(1), max_size: None, mode: Measured)
+	fn unfreeze_chain() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(13_806_000, 1485)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment CollatorCapabilities (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment CollatorCapabilities (max_values: None, max_size: None, mode: Measured)
+	fn set_collator_capabilities() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(10_612_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment RequiredCapabilities (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment RequiredCapabilities (max_values: None, max_size: None, mode: Measured)
+	fn set_required_capabilities() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(10_498_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment AssignmentPaused (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment AssignmentPaused (max_values: Some(1), max_size: None, mode: Measured)
+	fn set_assignment_paused() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(10_061_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment PinnedAssignments (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment PinnedAssignments (max_values: None, max_size: None, mode: Measured)
+	fn pin_collator() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(14_487_000, 3465)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment PinnedAssignments (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment PinnedAssignments (max_values: None, max_size: None, mode: Measured)
+	fn unpin_collator() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(13_946_000, 3567)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -100,11 +243,21 @@ impl WeightInfo for () {
 	/// Proof Skipped: Configuration PendingConfigs (max_values: Some(1), max_size: None, mode: Measured)
 	/// Storage: Configuration ActiveConfig (r:1 w:0)
 	/// Proof Skipped: Configuration ActiveConfig (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment Collators (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment Collators (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment ContainerChainsMetadata (r:0 w:40)
+	/// Proof Skipped: CollatorAssignment ContainerChainsMetadata (max_values: None, max_size: None, mode: Measured)
 	/// Storage: CollatorAssignment CollatorContainerChain (r:0 w:1)
 	/// Proof Skipped: CollatorAssignment CollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment CollatorSessionsServed (r:200 w:200)
+	/// Proof Skipped: CollatorAssignment CollatorSessionsServed (max_values: None, max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment PreviousCollatorContainerChain (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment PreviousCollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
 	/// The range of component `x` is `[1, 200]`.
 	/// The range of component `y` is `[1, 20]`.
 	fn new_session(x: u32, y: u32, ) -> Weight {
+		// Pending a benchmark run: measured before the assignment read the preferences,
+		// capabilities, pins and recent chains of the collators.
 		// Proof Size summary in bytes:
 		//  Measured:  `427 + y * (4 ±0)`
 		//  Estimated: `1913 + y * (4 ±0)`
@@ -115,7 +268,124 @@ impl WeightInfo for () {
 			// Standard Error: 8_900
 			.saturating_add(Weight::from_parts(706_995, 0).saturating_mul(y.into()))
 			.saturating_add(RocksDbWeight::get().reads(5_u64))
-			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(24_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(x.into())))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(x.into())))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(y.into())))
 			.saturating_add(Weight::from_parts(0, 4).saturating_mul(y.into()))
 	}
+	/// Storage: CollatorAssignment CollatorContainerChain (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment CollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment Collators (r:1 w:0)
+	/// Proof Skipped: CollatorAssignment Collators (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment PendingCollatorContainerChain (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment PendingCollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment PreviousCollatorContainerChain (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment PreviousCollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
+	fn force_unassign_collator() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(98_712_000, 10097)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: CollatorAssignment CollatorContainerChain (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment CollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment PendingCollatorContainerChain (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment PendingCollatorContainerChain (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment Collators (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment Collators (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Configuration PendingConfigs (r:1 w:0)
+	/// Proof Skipped: Configuration PendingConfigs (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment RecentCollatorChain (r:201 w:200)
+	/// Proof Skipped: CollatorAssignment RecentCollatorChain (max_values: None, max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment ContainerChainsMetadata (r:0 w:20)
+	/// Proof Skipped: CollatorAssignment ContainerChainsMetadata (max_values: None, max_size: None, mode: Measured)
+	fn force_recompute_assignment() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(631_992_000, 515140)
+			.saturating_add(RocksDbWeight::get().reads(205_u64))
+			.saturating_add(RocksDbWeight::get().writes(223_u64))
+	}
+	/// Storage: CollatorAssignment Collators (r:1 w:0)
+	/// Proof Skipped: CollatorAssignment Collators (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: CollatorAssignment CollatorPreferences (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment CollatorPreferences (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `x` is `[0, 10]`.
+	fn set_preferences(x: u32, ) -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(16_811_402, 1552)
+			// Standard Error: 1_842
+			.saturating_add(Weight::from_parts(21_310, 0).saturating_mul(x.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment ActivationSession (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment ActivationSession (max_values: None, max_size: None, mode: Measured)
+	fn set_activation_session() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(12_305_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment ChainReassignments (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment ChainReassignments (max_values: None, max_size: None, mode: Measured)
+	fn reassign_chain_collators() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(12_093_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment FrozenChains (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment FrozenChains (max_values: Some(1), max_size: None, mode: Measured)
+	fn freeze_chain() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(13_806_000, 1485)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment FrozenChains (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment FrozenChains (max_values: Some(1), max_size: None, mode: Measured)
+	fn unfreeze_chain() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(13_806_000, 1485)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment CollatorCapabilities (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment CollatorCapabilities (max_values: None, max_size: None, mode: Measured)
+	fn set_collator_capabilities() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(10_612_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment RequiredCapabilities (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment RequiredCapabilities (max_values: None, max_size: None, mode: Measured)
+	fn set_required_capabilities() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(10_498_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment AssignmentPaused (r:0 w:1)
+	/// Proof Skipped: CollatorAssignment AssignmentPaused (max_values: Some(1), max_size: None, mode: Measured)
+	fn set_assignment_paused() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(10_061_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment PinnedAssignments (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment PinnedAssignments (max_values: None, max_size: None, mode: Measured)
+	fn pin_collator() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(14_487_000, 3465)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: CollatorAssignment PinnedAssignments (r:1 w:1)
+	/// Proof Skipped: CollatorAssignment PinnedAssignments (max_values: None, max_size: None, mode: Measured)
+	fn unpin_collator() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(13_946_000, 3567)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 }
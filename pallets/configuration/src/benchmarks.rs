@@ -18,7 +18,7 @@
 
 //! Benchmarking
 use {
-    crate::{Call, Config, Pallet},
+    crate::{Call, Config, HostConfiguration, Pallet},
     frame_benchmarking::benchmarks,
     frame_system::RawOrigin,
 };
@@ -26,6 +26,8 @@ use {
 benchmarks! {
     set_config_with_u32 {}: set_max_collators(RawOrigin::Root, 100)
 
+    schedule_config_change {}: _(RawOrigin::Root, HostConfiguration::default(), 10u32.into())
+
     impl_benchmark_test_suite!(
         Pallet,
         crate::mock::new_test_ext(),
@@ -79,6 +79,12 @@ impl Default for HostConfiguration {
 pub enum InconsistentError {
     /// `max_orchestrator_collators` is lower than `min_orchestrator_collators`
     MaxCollatorsLowerThanMinCollators,
+    /// `collators_per_container` is zero
+    ZeroCollatorsPerContainer,
+    /// `min_orchestrator_collators` is greater than `max_collators`
+    MinOrchestratorCollatorsGreaterThanMaxCollators,
+    /// `collators_per_container` is greater than `max_collators`
+    CollatorsPerContainerGreaterThanMaxCollators,
 }
 
 impl HostConfiguration {
@@ -91,6 +97,15 @@ impl HostConfiguration {
         if self.max_orchestrator_collators < self.min_orchestrator_collators {
             return Err(InconsistentError::MaxCollatorsLowerThanMinCollators);
         }
+        if self.collators_per_container == 0 {
+            return Err(InconsistentError::ZeroCollatorsPerContainer);
+        }
+        if self.min_orchestrator_collators > self.max_collators {
+            return Err(InconsistentError::MinOrchestratorCollatorsGreaterThanMaxCollators);
+        }
+        if self.collators_per_container > self.max_collators {
+            return Err(InconsistentError::CollatorsPerContainerGreaterThanMaxCollators);
+        }
         Ok(())
     }
 
@@ -143,6 +158,9 @@ pub mod pallet {
     pub enum Error<T> {
         /// The new value for a configuration parameter is invalid.
         InvalidNewValue,
+        /// The session at which a configuration change should be activated is before the
+        /// scheduled session.
+        ActivationSessionTooEarly,
     }
 
     /// The active configuration for the current session.
@@ -155,8 +173,9 @@ pub mod pallet {
     /// This is a list of configuration changes, each with a session index at which it should
     /// be applied.
     ///
-    /// The list is sorted ascending by session index. Also, this list can only contain at most
-    /// 2 items: for the next session and for the `scheduled_session`.
+    /// The list is sorted ascending by session index. Changes done through the setters are
+    /// always scheduled for the `scheduled_session`, while `schedule_config_change` can schedule
+    /// whole configurations for any session after that one.
     #[pallet::storage]
     #[pallet::getter(fn pending_configs)]
     pub(crate) type PendingConfigs<T: Config> =
@@ -238,6 +257,51 @@ pub mod pallet {
             })
         }
 
+        /// Schedule a whole configuration to be activated at `activate_at_session`, so that related
+        /// parameters change together. Replaces any configuration already scheduled for that session.
+        #[pallet::call_index(4)]
+        #[pallet::weight((
+			T::WeightInfo::schedule_config_change(),
+			DispatchClass::Operational,
+		))]
+        pub fn schedule_config_change(
+            origin: OriginFor<T>,
+            config: HostConfiguration,
+            activate_at_session: T::SessionIndex,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if activate_at_session < Self::scheduled_session() {
+                return Err(Error::<T>::ActivationSessionTooEarly.into());
+            }
+
+            if BypassConsistencyCheck::<T>::get() {
+                log::warn!(
+                    target: LOG_TARGET,
+                    "Bypassing the consistency check for the configuration change!",
+                );
+            } else if let Err(e) = config.check_consistency() {
+                log::warn!(
+                    target: LOG_TARGET,
+                    "Configuration change rejected due to invalid configuration: {:?}",
+                    e,
+                );
+                return Err(Error::<T>::InvalidNewValue.into());
+            }
+
+            let mut pending_configs = <PendingConfigs<T>>::get();
+            match pending_configs
+                .binary_search_by_key(&activate_at_session, |&(apply_at_session, _)| {
+                    apply_at_session
+                }) {
+                Ok(index) => pending_configs[index].1 = config,
+                Err(index) => pending_configs.insert(index, (activate_at_session, config)),
+            }
+            <PendingConfigs<T>>::put(pending_configs);
+
+            Ok(())
+        }
+
         /// Setting this to true will disable consistency checks for the configuration setters.
         /// Use with caution.
         #[pallet::call_index(44)]
@@ -366,10 +430,17 @@ pub mod pallet {
             //
             // There cannot be (cur, X) because those are applied in the session change handler for the
             // current session.
+            //
+            // Configurations scheduled with `schedule_config_change` for sessions after the scheduled
+            // session are ignored here, and they will override this change once they are applied.
+
+            let scheduled_session = Self::scheduled_session();
 
             // First, we need to decide what we should use as the base configuration.
             let mut base_config = pending_configs
-                .last()
+                .iter()
+                .rev()
+                .find(|&&(apply_at_session, _)| apply_at_session <= scheduled_session)
                 .map(|(_, config)| config.clone())
                 .unwrap_or_else(Self::config);
             let base_config_consistent = base_config.check_consistency().is_ok();
@@ -411,16 +482,13 @@ pub mod pallet {
                 }
             }
 
-            let scheduled_session = Self::scheduled_session();
-
-            if let Some(&mut (_, ref mut config)) = pending_configs
-                .iter_mut()
-                .find(|&&mut (apply_at_session, _)| apply_at_session >= scheduled_session)
-            {
-                *config = new_config;
-            } else {
+            match pending_configs
+                .binary_search_by_key(&scheduled_session, |&(apply_at_session, _)| {
+                    apply_at_session
+                }) {
+                Ok(index) => pending_configs[index].1 = new_config,
                 // We are scheduling a new configuration change for the scheduled session.
-                pending_configs.push((scheduled_session, new_config));
+                Err(index) => pending_configs.insert(index, (scheduled_session, new_config)),
             }
 
             <PendingConfigs<T>>::put(pending_configs);
@@ -15,8 +15,8 @@
 // along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
 
 use {
-    crate::{mock::*, HostConfiguration, PendingConfigs},
-    frame_support::{assert_ok, dispatch::GetDispatchInfo},
+    crate::{mock::*, Error, HostConfiguration, PendingConfigs},
+    frame_support::{assert_noop, assert_ok, dispatch::GetDispatchInfo},
     sp_std::vec,
 };
 
@@ -51,14 +51,14 @@ fn config_sets_default_values() {
 #[test]
 fn config_set_value() {
     new_test_ext_with_genesis(HostConfiguration {
-        max_collators: 0,
+        max_collators: 1,
         min_orchestrator_collators: 0,
         max_orchestrator_collators: 0,
-        collators_per_container: 0,
+        collators_per_container: 1,
     })
     .execute_with(|| {
         run_to_block(1);
-        assert_eq!(Configuration::config().max_collators, 0);
+        assert_eq!(Configuration::config().max_collators, 1);
         assert_ok!(
             Configuration::set_max_collators(RuntimeOrigin::root(), 50),
             ()
@@ -72,21 +72,21 @@ fn config_set_value() {
                     max_collators: 50,
                     min_orchestrator_collators: 0,
                     max_orchestrator_collators: 0,
-                    collators_per_container: 0,
+                    collators_per_container: 1,
                 }
             )]
         );
 
         // The session delay is set to 2, and one session is 5 blocks,
         // so the change should not happen until block 11
-        assert_eq!(Configuration::config().max_collators, 0);
+        assert_eq!(Configuration::config().max_collators, 1);
         run_to_block(2);
-        assert_eq!(Configuration::config().max_collators, 0);
+        assert_eq!(Configuration::config().max_collators, 1);
         // First block of session 1
         run_to_block(6);
-        assert_eq!(Configuration::config().max_collators, 0);
+        assert_eq!(Configuration::config().max_collators, 1);
         run_to_block(10);
-        assert_eq!(Configuration::config().max_collators, 0);
+        assert_eq!(Configuration::config().max_collators, 1);
         // First block of session 2
         run_to_block(11);
         assert_eq!(Configuration::config().max_collators, 50);
@@ -96,15 +96,15 @@ fn config_set_value() {
 #[test]
 fn config_set_many_values_same_block() {
     new_test_ext_with_genesis(HostConfiguration {
-        max_collators: 0,
+        max_collators: 1,
         min_orchestrator_collators: 0,
         max_orchestrator_collators: 0,
-        collators_per_container: 0,
+        collators_per_container: 1,
     })
     .execute_with(|| {
         run_to_block(1);
-        assert_eq!(Configuration::config().max_collators, 0);
-        assert_eq!(Configuration::config().collators_per_container, 0);
+        assert_eq!(Configuration::config().max_collators, 1);
+        assert_eq!(Configuration::config().collators_per_container, 1);
         assert_eq!(Configuration::config().min_orchestrator_collators, 0);
         assert_ok!(
             Configuration::set_max_collators(RuntimeOrigin::root(), 50),
@@ -135,8 +135,8 @@ fn config_set_many_values_same_block() {
         // The session delay is set to 2, and one session is 5 blocks,
         // so the change should not happen until block 11
         run_to_block(10);
-        assert_eq!(Configuration::config().max_collators, 0);
-        assert_eq!(Configuration::config().collators_per_container, 0);
+        assert_eq!(Configuration::config().max_collators, 1);
+        assert_eq!(Configuration::config().collators_per_container, 1);
         assert_eq!(Configuration::config().min_orchestrator_collators, 0);
         // First block of session 2
         run_to_block(11);
@@ -149,15 +149,15 @@ fn config_set_many_values_same_block() {
 #[test]
 fn config_set_many_values_different_blocks() {
     new_test_ext_with_genesis(HostConfiguration {
-        max_collators: 0,
+        max_collators: 1,
         min_orchestrator_collators: 0,
         max_orchestrator_collators: 0,
-        collators_per_container: 0,
+        collators_per_container: 1,
     })
     .execute_with(|| {
         run_to_block(1);
-        assert_eq!(Configuration::config().max_collators, 0);
-        assert_eq!(Configuration::config().collators_per_container, 0);
+        assert_eq!(Configuration::config().max_collators, 1);
+        assert_eq!(Configuration::config().collators_per_container, 1);
         assert_eq!(Configuration::config().min_orchestrator_collators, 0);
         assert_ok!(
             Configuration::set_max_collators(RuntimeOrigin::root(), 50),
@@ -190,9 +190,9 @@ fn config_set_many_values_different_blocks() {
         // The session delay is set to 2, and one session is 5 blocks,
         // so the change should not happen until block 11
         run_to_block(10);
-        assert_eq!(Configuration::config().max_collators, 0);
+        assert_eq!(Configuration::config().max_collators, 1);
         assert_eq!(Configuration::config().min_orchestrator_collators, 0);
-        assert_eq!(Configuration::config().collators_per_container, 0);
+        assert_eq!(Configuration::config().collators_per_container, 1);
         // First block of session 2
         run_to_block(11);
         assert_eq!(Configuration::config().max_collators, 50);
@@ -204,16 +204,16 @@ fn config_set_many_values_different_blocks() {
 #[test]
 fn config_set_many_values_different_sessions() {
     new_test_ext_with_genesis(HostConfiguration {
-        max_collators: 0,
+        max_collators: 1,
         min_orchestrator_collators: 0,
         max_orchestrator_collators: 0,
-        collators_per_container: 0,
+        collators_per_container: 1,
     })
     .execute_with(|| {
         run_to_block(1);
-        assert_eq!(Configuration::config().max_collators, 0);
+        assert_eq!(Configuration::config().max_collators, 1);
         assert_eq!(Configuration::config().min_orchestrator_collators, 0);
-        assert_eq!(Configuration::config().collators_per_container, 0);
+        assert_eq!(Configuration::config().collators_per_container, 1);
         assert_ok!(
             Configuration::set_max_collators(RuntimeOrigin::root(), 50),
             ()
@@ -223,9 +223,9 @@ fn config_set_many_values_different_sessions() {
             Configuration::set_min_orchestrator_collators(RuntimeOrigin::root(), 20),
             ()
         );
-        assert_eq!(Configuration::config().max_collators, 0);
+        assert_eq!(Configuration::config().max_collators, 1);
         assert_eq!(Configuration::config().min_orchestrator_collators, 0);
-        assert_eq!(Configuration::config().collators_per_container, 0);
+        assert_eq!(Configuration::config().collators_per_container, 1);
         run_to_block(11);
         assert_ok!(
             Configuration::set_collators_per_container(RuntimeOrigin::root(), 10),
@@ -241,7 +241,7 @@ fn config_set_many_values_different_sessions() {
                         max_collators: 50,
                         min_orchestrator_collators: 20,
                         max_orchestrator_collators: 20,
-                        collators_per_container: 0,
+                        collators_per_container: 1,
                     }
                 ),
                 (
@@ -258,11 +258,11 @@ fn config_set_many_values_different_sessions() {
 
         assert_eq!(Configuration::config().max_collators, 50);
         assert_eq!(Configuration::config().min_orchestrator_collators, 0);
-        assert_eq!(Configuration::config().collators_per_container, 0);
+        assert_eq!(Configuration::config().collators_per_container, 1);
         run_to_block(16);
         assert_eq!(Configuration::config().max_collators, 50);
         assert_eq!(Configuration::config().min_orchestrator_collators, 20);
-        assert_eq!(Configuration::config().collators_per_container, 0);
+        assert_eq!(Configuration::config().collators_per_container, 1);
         run_to_block(21);
         assert_eq!(Configuration::config().max_collators, 50);
         assert_eq!(Configuration::config().min_orchestrator_collators, 20);
@@ -270,6 +270,170 @@ fn config_set_many_values_different_sessions() {
     });
 }
 
+#[test]
+fn config_setters_reject_invalid_values() {
+    new_test_ext_with_genesis(HostConfiguration {
+        max_collators: 100,
+        min_orchestrator_collators: 2,
+        max_orchestrator_collators: 5,
+        collators_per_container: 2,
+    })
+    .execute_with(|| {
+        run_to_block(1);
+        // collators_per_container cannot be zero
+        assert_noop!(
+            Configuration::set_collators_per_container(RuntimeOrigin::root(), 0),
+            Error::<Test>::InvalidNewValue
+        );
+        // collators_per_container cannot be greater than max_collators
+        assert_noop!(
+            Configuration::set_collators_per_container(RuntimeOrigin::root(), 101),
+            Error::<Test>::InvalidNewValue
+        );
+        // min_orchestrator_collators cannot be greater than max_collators
+        assert_noop!(
+            Configuration::set_min_orchestrator_collators(RuntimeOrigin::root(), 101),
+            Error::<Test>::InvalidNewValue
+        );
+        assert_noop!(
+            Configuration::set_max_collators(RuntimeOrigin::root(), 1),
+            Error::<Test>::InvalidNewValue
+        );
+        // min_orchestrator_collators cannot be greater than max_orchestrator_collators
+        assert_noop!(
+            Configuration::schedule_config_change(
+                RuntimeOrigin::root(),
+                HostConfiguration {
+                    max_collators: 100,
+                    min_orchestrator_collators: 6,
+                    max_orchestrator_collators: 5,
+                    collators_per_container: 2,
+                },
+                2
+            ),
+            Error::<Test>::InvalidNewValue
+        );
+
+        assert_ok!(
+            Configuration::set_collators_per_container(RuntimeOrigin::root(), 3),
+            ()
+        );
+        assert_eq!(
+            PendingConfigs::<Test>::get(),
+            vec![(
+                2,
+                HostConfiguration {
+                    max_collators: 100,
+                    min_orchestrator_collators: 2,
+                    max_orchestrator_collators: 5,
+                    collators_per_container: 3,
+                }
+            )]
+        );
+    });
+}
+
+#[test]
+fn schedule_config_change_applies_at_activation_session() {
+    new_test_ext_with_genesis(HostConfiguration {
+        max_collators: 1,
+        min_orchestrator_collators: 0,
+        max_orchestrator_collators: 0,
+        collators_per_container: 1,
+    })
+    .execute_with(|| {
+        run_to_block(1);
+        let new_config = HostConfiguration {
+            max_collators: 50,
+            min_orchestrator_collators: 2,
+            max_orchestrator_collators: 5,
+            collators_per_container: 3,
+        };
+        assert_ok!(
+            Configuration::schedule_config_change(RuntimeOrigin::root(), new_config.clone(), 2),
+            ()
+        );
+        assert_eq!(PendingConfigs::<Test>::get(), vec![(2, new_config.clone())]);
+
+        // Nothing changes before session 2
+        run_to_block(6);
+        assert_eq!(Configuration::config().max_collators, 1);
+        run_to_block(10);
+        assert_eq!(Configuration::config().max_collators, 1);
+        assert_eq!(Configuration::config().collators_per_container, 1);
+
+        // First block of session 2, all parameters change together
+        run_to_block(11);
+        assert_eq!(Configuration::config(), new_config);
+        assert_eq!(PendingConfigs::<Test>::get(), vec![]);
+    });
+}
+
+#[test]
+fn schedule_config_change_far_future_is_kept_after_setter() {
+    new_test_ext_with_genesis(HostConfiguration {
+        max_collators: 1,
+        min_orchestrator_collators: 0,
+        max_orchestrator_collators: 0,
+        collators_per_container: 1,
+    })
+    .execute_with(|| {
+        run_to_block(1);
+        let new_config = HostConfiguration {
+            max_collators: 50,
+            min_orchestrator_collators: 2,
+            max_orchestrator_collators: 5,
+            collators_per_container: 3,
+        };
+        assert_ok!(
+            Configuration::schedule_config_change(RuntimeOrigin::root(), new_config.clone(), 4),
+            ()
+        );
+        // A setter change is scheduled for session 2, before the scheduled config
+        assert_ok!(
+            Configuration::set_max_collators(RuntimeOrigin::root(), 20),
+            ()
+        );
+        assert_eq!(
+            PendingConfigs::<Test>::get(),
+            vec![
+                (
+                    2,
+                    HostConfiguration {
+                        max_collators: 20,
+                        min_orchestrator_collators: 0,
+                        max_orchestrator_collators: 0,
+                        collators_per_container: 1,
+                    }
+                ),
+                (4, new_config.clone())
+            ]
+        );
+
+        // First block of session 2
+        run_to_block(11);
+        assert_eq!(Configuration::config().max_collators, 20);
+        // First block of session 4
+        run_to_block(21);
+        assert_eq!(Configuration::config(), new_config);
+    });
+}
+
+#[test]
+fn schedule_config_change_too_early() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+        assert_noop!(
+            Configuration::schedule_config_change(
+                RuntimeOrigin::root(),
+                HostConfiguration::default(),
+                1
+            ),
+            Error::<Test>::ActivationSessionTooEarly
+        );
+    });
+}
+
 #[test]
 fn weights_assigned_to_extrinsics_are_correct() {
     new_test_ext().execute_with(|| {
@@ -300,5 +464,15 @@ fn weights_assigned_to_extrinsics_are_correct() {
                 .weight,
             <() as crate::weights::WeightInfo>::set_config_with_u32()
         );
+
+        assert_eq!(
+            crate::Call::<Test>::schedule_config_change {
+                config: HostConfiguration::default(),
+                activate_at_session: 2u32
+            }
+            .get_dispatch_info()
+            .weight,
+            <() as crate::weights::WeightInfo>::schedule_config_change()
+        );
     });
 }
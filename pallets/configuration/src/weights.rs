@@ -53,6 +53,7 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for pallet_configuration.
 pub trait WeightInfo {
 	fn set_config_with_u32() -> Weight;
+	fn schedule_config_change() -> Weight;
 }
 
 /// Weights for pallet_configuration using the Substrate node and recommended hardware.
@@ -75,6 +76,21 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(4_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: Session CurrentIndex (r:1 w:0)
+	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Configuration BypassConsistencyCheck (r:1 w:0)
+	/// Proof Skipped: Configuration BypassConsistencyCheck (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Configuration PendingConfigs (r:1 w:1)
+	/// Proof Skipped: Configuration PendingConfigs (max_values: Some(1), max_size: None, mode: Measured)
+	fn schedule_config_change() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `252`
+		//  Estimated: `5217`
+		// Minimum execution time: 8_946_000 picoseconds.
+		Weight::from_parts(9_301_000, 5217)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -96,4 +112,19 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(4_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: Session CurrentIndex (r:1 w:0)
+	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Configuration BypassConsistencyCheck (r:1 w:0)
+	/// Proof Skipped: Configuration BypassConsistencyCheck (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Configuration PendingConfigs (r:1 w:1)
+	/// Proof Skipped: Configuration PendingConfigs (max_values: Some(1), max_size: None, mode: Measured)
+	fn schedule_config_change() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `252`
+		//  Estimated: `5217`
+		// Minimum execution time: 8_946_000 picoseconds.
+		Weight::from_parts(9_301_000, 5217)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 }
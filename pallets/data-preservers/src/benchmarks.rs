@@ -0,0 +1,106 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+#![cfg(feature = "runtime-benchmarks")]
+
+//! Benchmarking
+use {
+    crate::{
+        Call, ChainRequirements, Config, Pallet, PreserverAssignment, PreserverMode, Profile,
+        Profiles, ProfilesCount,
+    },
+    frame_benchmarking::{account, benchmarks},
+    frame_support::traits::{Currency, EnsureOriginWithArg, Get},
+    frame_system::RawOrigin,
+    sp_std::vec::Vec,
+    tp_traits::{GetCurrentContainerChains, ParaId},
+};
+
+/// Create a user able to pay the profile deposit.
+fn create_funded_user<T: Config>(string: &'static str, n: u32) -> T::AccountId {
+    const SEED: u32 = 0;
+    let user = account(string, n, SEED);
+    let total = T::ProfileDeposit::get() + T::Currency::minimum_balance();
+    T::Currency::make_free_balance_be(&user, total);
+    T::Currency::issue(total);
+    user
+}
+
+fn archive() -> Profile {
+    Profile {
+        mode: PreserverMode::Archive,
+    }
+}
+
+benchmarks! {
+    set_profile {
+        let caller = create_funded_user::<T>("caller", 0);
+    }: _(RawOrigin::Signed(caller.clone()), archive())
+    verify {
+        assert_eq!(Profiles::<T>::get(&caller), Some(archive()));
+    }
+
+    delete_profile {
+        let caller = create_funded_user::<T>("caller", 0);
+        Pallet::<T>::set_profile(RawOrigin::Signed(caller.clone()).into(), archive())?;
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert_eq!(Profiles::<T>::get(&caller), None);
+    }
+
+    set_chain_requirements {
+        let para_id = ParaId::from(1001);
+        let origin = T::SetRequirementsOrigin::try_successful_origin(&para_id)
+            .expect("failed to create SetRequirementsOrigin");
+    }: _<T::RuntimeOrigin>(origin, para_id, Some(PreserverMode::Archive))
+    verify {
+        assert_eq!(ChainRequirements::<T>::get(para_id), Some(PreserverMode::Archive));
+    }
+
+    assign_preservers {
+        // Worst case: every chain is assigned preservers, replacing a previous assignment
+        let x in 1..100;
+        let y in 1..50;
+
+        for i in 0..x {
+            let preserver: T::AccountId = account("preserver", i, 0);
+            Profiles::<T>::insert(&preserver, archive());
+        }
+        ProfilesCount::<T>::put(x);
+
+        let para_ids: Vec<ParaId> = (0..y).map(|i| ParaId::from(1000 + i)).collect();
+        T::ContainerChains::set_current_container_chains(&para_ids);
+        for para_id in para_ids.iter() {
+            ChainRequirements::<T>::insert(para_id, PreserverMode::Archive);
+        }
+        Pallet::<T>::assign_preservers();
+    }: {
+        Pallet::<T>::assign_preservers();
+    }
+    verify {
+        let num_assigned = x.min(T::MaxPreserversPerChain::get());
+        assert_eq!(
+            PreserverAssignment::<T>::get(para_ids[0]).len() as u32,
+            num_assigned
+        );
+    }
+
+    impl_benchmark_test_suite!(
+        Pallet,
+        crate::mock::new_test_ext(),
+        crate::mock::Test
+    );
+}
@@ -0,0 +1,363 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+//! # Data Preservers Pallet
+//!
+//! This pallet assigns data preservers (nodes providing RPC endpoints and bootnodes) to
+//! container chains.
+//!
+//! Each preserver declares a `Profile` describing what it is able to serve, and the manager of
+//! a container chain can declare the `PreserverMode` the chain requires.
+//!
+//! Upon new session, the `ProfileAssignment` routine maps each container chain to the preservers
+//! whose profile satisfies the chain requirements, similarly to what the collator-assignment
+//! pallet does with collators, and stores the result in the `PreserverAssignment` storage.
+//! A preserver serves at most one container chain.
+//!
+//! This pallet is not part of any runtime yet. A runtime including it must call
+//! `initializer_on_new_session` from its session change handler, as it is done for the
+//! collator-assignment pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+use {
+    frame_support::{
+        pallet_prelude::*,
+        traits::{Currency, EnsureOriginWithArg, ReservableCurrency},
+    },
+    frame_system::pallet_prelude::*,
+    sp_std::{collections::btree_map::BTreeMap, vec::Vec},
+    tp_traits::{GetCurrentContainerChains, ParaId},
+};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+mod benchmarks;
+
+pub mod weights;
+use crate::weights::WeightInfo;
+
+/// Balance used by this pallet
+pub type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Data a preserver is able to serve.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(
+    Default, RuntimeDebug, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo, MaxEncodedLen,
+)]
+pub enum PreserverMode {
+    /// Only keeps the recent state of the chain.
+    #[default]
+    Full,
+    /// Keeps the state of the chain at every block.
+    Archive,
+}
+
+impl PreserverMode {
+    /// Whether a preserver running in this mode can serve a chain requiring `required`.
+    pub fn satisfies(&self, required: &PreserverMode) -> bool {
+        match (self, required) {
+            (PreserverMode::Archive, _) => true,
+            (PreserverMode::Full, PreserverMode::Full) => true,
+            (PreserverMode::Full, PreserverMode::Archive) => false,
+        }
+    }
+}
+
+/// Capabilities declared by a preserver.
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(
+    Default, RuntimeDebug, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo, MaxEncodedLen,
+)]
+pub struct Profile {
+    pub mode: PreserverMode,
+}
+
+impl Profile {
+    pub fn satisfies(&self, required: &PreserverMode) -> bool {
+        self.mode.satisfies(required)
+    }
+}
+
+/// Assigns preservers to container chains according to their profiles.
+pub struct ProfileAssignment;
+
+impl ProfileAssignment {
+    /// Assign up to `max_per_chain` of `preservers` to each of the `container_chains`.
+    ///
+    /// Chains requiring archive preservers are served first, as they can only be served by a
+    /// subset of the preservers. Chains requiring full preservers use full preservers first,
+    /// and only fall back to archive preservers once those are exhausted.
+    /// Each preserver is assigned to at most one chain, and the order of `preservers` is
+    /// respected.
+    pub fn assign<PreserverId: Clone>(
+        mut preservers: Vec<(PreserverId, Profile)>,
+        container_chains: &[(ParaId, PreserverMode)],
+        max_per_chain: usize,
+    ) -> BTreeMap<ParaId, Vec<PreserverId>> {
+        let mut assignment = BTreeMap::new();
+
+        let archive_chains = container_chains
+            .iter()
+            .filter(|(_, required)| *required == PreserverMode::Archive);
+        let full_chains = container_chains
+            .iter()
+            .filter(|(_, required)| *required == PreserverMode::Full);
+
+        // Stable sort, full preservers go first but keep their relative order.
+        preservers.sort_by_key(|(_, profile)| profile.mode == PreserverMode::Archive);
+
+        for (para_id, required) in archive_chains.chain(full_chains) {
+            let mut assigned = Vec::new();
+            let mut i = 0;
+            while assigned.len() < max_per_chain && i < preservers.len() {
+                if preservers[i].1.satisfies(required) {
+                    assigned.push(preservers.remove(i).0);
+                } else {
+                    i += 1;
+                }
+            }
+            assignment.insert(*para_id, assigned);
+        }
+
+        assignment
+    }
+}
+
+#[frame_support::pallet(dev_mode)]
+pub mod pallet {
+    use super::*;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(PhantomData<T>);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// The overarching event type.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// Container chains to assign preservers to
+        type ContainerChains: GetCurrentContainerChains;
+        /// Origin of the manager of a para, allowed to set its preserver requirements
+        type SetRequirementsOrigin: EnsureOriginWithArg<Self::RuntimeOrigin, ParaId>;
+        /// Maximum number of preservers assigned to a single container chain
+        type MaxPreserversPerChain: Get<u32>;
+        /// Currency used to reserve the deposit of each profile
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// Deposit reserved from a preserver while it has a profile
+        #[pallet::constant]
+        type ProfileDeposit: Get<BalanceOf<Self>>;
+        /// Maximum number of profiles, bounding the assignment done upon new session
+        #[pallet::constant]
+        type MaxProfiles: Get<u32>;
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        NoProfile,
+        NotSufficientDeposit,
+        TooManyProfiles,
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        ProfileUpdated {
+            preserver: T::AccountId,
+            profile: Profile,
+        },
+        ProfileDeleted {
+            preserver: T::AccountId,
+        },
+        ChainRequirementsUpdated {
+            para_id: ParaId,
+            required: Option<PreserverMode>,
+        },
+    }
+
+    /// Profile declared by each preserver
+    #[pallet::storage]
+    #[pallet::getter(fn profiles)]
+    pub type Profiles<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Profile, OptionQuery>;
+
+    /// Deposit reserved from each preserver with a profile
+    #[pallet::storage]
+    #[pallet::getter(fn profile_deposit)]
+    pub type ProfileDeposits<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, OptionQuery>;
+
+    /// Number of entries in `Profiles`
+    #[pallet::storage]
+    #[pallet::getter(fn profiles_count)]
+    pub type ProfilesCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Preserver mode required by each container chain. Chains without requirements can be
+    /// served by any preserver.
+    #[pallet::storage]
+    #[pallet::getter(fn chain_requirements)]
+    pub type ChainRequirements<T: Config> =
+        StorageMap<_, Blake2_128Concat, ParaId, PreserverMode, OptionQuery>;
+
+    /// Preservers assigned to each container chain, computed upon new session
+    #[pallet::storage]
+    #[pallet::getter(fn preserver_assignment)]
+    pub type PreserverAssignment<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        ParaId,
+        BoundedVec<T::AccountId, T::MaxPreserversPerChain>,
+        ValueQuery,
+    >;
+
+    /// Number of entries in `PreserverAssignment`
+    #[pallet::storage]
+    pub type AssignedChainsCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Declare the profile of the caller as a preserver. `T::ProfileDeposit` is reserved
+        /// from the caller when it does not have a profile yet.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::set_profile())]
+        pub fn set_profile(origin: OriginFor<T>, profile: Profile) -> DispatchResultWithPostInfo {
+            let preserver = ensure_signed(origin)?;
+
+            if !Profiles::<T>::contains_key(&preserver) {
+                let profiles_count = ProfilesCount::<T>::get();
+                ensure!(
+                    profiles_count < T::MaxProfiles::get(),
+                    Error::<T>::TooManyProfiles
+                );
+
+                let deposit = T::ProfileDeposit::get();
+                T::Currency::reserve(&preserver, deposit)
+                    .map_err(|_| Error::<T>::NotSufficientDeposit)?;
+                ProfileDeposits::<T>::insert(&preserver, deposit);
+                ProfilesCount::<T>::put(profiles_count.saturating_add(1));
+            }
+
+            Profiles::<T>::insert(&preserver, profile);
+
+            Self::deposit_event(Event::<T>::ProfileUpdated { preserver, profile });
+
+            Ok(().into())
+        }
+
+        /// Remove the profile of the caller, it will not be assigned to any chain from the
+        /// next session on. The profile deposit is returned.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::delete_profile())]
+        pub fn delete_profile(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let preserver = ensure_signed(origin)?;
+
+            ensure!(
+                Profiles::<T>::contains_key(&preserver),
+                Error::<T>::NoProfile
+            );
+            Profiles::<T>::remove(&preserver);
+            ProfilesCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+            if let Some(deposit) = ProfileDeposits::<T>::take(&preserver) {
+                T::Currency::unreserve(&preserver, deposit);
+            }
+
+            Self::deposit_event(Event::<T>::ProfileDeleted { preserver });
+
+            Ok(().into())
+        }
+
+        /// Set the preserver mode required by a para. `None` removes the requirements.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::set_chain_requirements())]
+        pub fn set_chain_requirements(
+            origin: OriginFor<T>,
+            para_id: ParaId,
+            required: Option<PreserverMode>,
+        ) -> DispatchResultWithPostInfo {
+            T::SetRequirementsOrigin::ensure_origin(origin, &para_id)?;
+
+            ChainRequirements::<T>::set(para_id, required);
+
+            Self::deposit_event(Event::<T>::ChainRequirementsUpdated { para_id, required });
+
+            Ok(().into())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Assign preservers to the current container chains, replacing the previous
+        /// assignment. Returns the number of profiles and container chains considered.
+        pub fn assign_preservers() -> (u32, u32) {
+            // At most `MaxProfiles` profiles can be declared
+            let mut preservers: Vec<_> = Profiles::<T>::iter()
+                .take(T::MaxProfiles::get() as usize)
+                .collect();
+            // Storage iteration order depends on the hashes, sort to get a stable order.
+            preservers.sort_by(|(a, _), (b, _)| a.cmp(b));
+            let num_profiles = preservers.len() as u32;
+
+            let container_chains: Vec<_> = T::ContainerChains::current_container_chains()
+                .into_iter()
+                .map(|para_id| {
+                    (
+                        para_id,
+                        ChainRequirements::<T>::get(para_id).unwrap_or_default(),
+                    )
+                })
+                .collect();
+            let num_container_chains = container_chains.len() as u32;
+
+            let assignment = ProfileAssignment::assign(
+                preservers,
+                &container_chains,
+                T::MaxPreserversPerChain::get() as usize,
+            );
+
+            // Only the chains assigned in the previous session have an entry
+            let _ = PreserverAssignment::<T>::clear(AssignedChainsCount::<T>::get(), None);
+            let mut assigned_chains: u32 = 0;
+            for (para_id, assigned) in assignment {
+                if assigned.is_empty() {
+                    continue;
+                }
+                // The assignment never exceeds `MaxPreserversPerChain`.
+                let assigned = BoundedVec::truncate_from(assigned);
+                PreserverAssignment::<T>::insert(para_id, assigned);
+                assigned_chains = assigned_chains.saturating_add(1);
+            }
+            AssignedChainsCount::<T>::put(assigned_chains);
+
+            (num_profiles, num_container_chains)
+        }
+
+        pub fn initializer_on_new_session() {
+            let (num_profiles, num_container_chains) = Self::assign_preservers();
+
+            frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                T::WeightInfo::assign_preservers(num_profiles, num_container_chains),
+                DispatchClass::Mandatory,
+            );
+        }
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    crate::{self as pallet_data_preservers},
+    frame_support::{
+        parameter_types,
+        traits::{AsEnsureOriginWithArg, ConstU32, ConstU64, Everything},
+    },
+    frame_system::EnsureSigned,
+    sp_core::H256,
+    sp_runtime::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup},
+    },
+    tp_traits::{GetCurrentContainerChains, ParaId},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+pub type AccountId = u64;
+pub type Balance = u128;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        DataPreservers: pallet_data_preservers::{Pallet, Call, Storage, Event<T>}
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u128 = 1;
+}
+
+impl pallet_balances::Config for Test {
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 4];
+    type MaxLocks = ();
+    type Balance = Balance;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type FreezeIdentifier = ();
+    type MaxFreezes = ();
+    type HoldIdentifier = ();
+    type MaxHolds = ();
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub static CurrentContainerChains: Vec<ParaId> = vec![];
+    pub const ProfileDeposit: Balance = 10;
+}
+
+impl pallet_data_preservers::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type ContainerChains = MockContainerChains;
+    type SetRequirementsOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+    type MaxPreserversPerChain = ConstU32<2>;
+    type Currency = Balances;
+    type ProfileDeposit = ProfileDeposit;
+    type MaxProfiles = ConstU32<5>;
+    type WeightInfo = ();
+}
+
+pub struct MockContainerChains;
+impl GetCurrentContainerChains for MockContainerChains {
+    fn current_container_chains() -> Vec<ParaId> {
+        CurrentContainerChains::get()
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_current_container_chains(container_chains: &[ParaId]) {
+        CurrentContainerChains::set(container_chains.to_vec());
+    }
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+
+    // Preservers used in tests, the last one can't pay the profile deposit
+    pallet_balances::GenesisConfig::<Test> {
+        balances: (1..=6)
+            .map(|account| (account, 100))
+            .chain([(7, 5)])
+            .collect(),
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    let mut ext: sp_io::TestExternalities = t.into();
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+pub(crate) fn events() -> Vec<pallet_data_preservers::Event<Test>> {
+    System::events()
+        .into_iter()
+        .map(|r| r.event)
+        .filter_map(|e| {
+            if let RuntimeEvent::DataPreservers(inner) = e {
+                Some(inner)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>()
+}
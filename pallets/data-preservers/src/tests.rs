@@ -0,0 +1,220 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    crate::{mock::*, Error, Event, PreserverMode, Profile},
+    frame_support::{assert_noop, assert_ok},
+    tp_traits::ParaId,
+};
+
+const FULL_PRESERVER: AccountId = 1;
+const ARCHIVE_PRESERVER: AccountId = 2;
+
+fn full() -> Profile {
+    Profile {
+        mode: PreserverMode::Full,
+    }
+}
+
+fn archive() -> Profile {
+    Profile {
+        mode: PreserverMode::Archive,
+    }
+}
+
+#[test]
+fn set_and_delete_profile() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DataPreservers::set_profile(
+            RuntimeOrigin::signed(FULL_PRESERVER),
+            full()
+        ));
+        assert_eq!(DataPreservers::profiles(FULL_PRESERVER), Some(full()));
+        assert_eq!(Balances::reserved_balance(FULL_PRESERVER), 10);
+
+        // Updating the profile doesn't take another deposit
+        assert_ok!(DataPreservers::set_profile(
+            RuntimeOrigin::signed(FULL_PRESERVER),
+            archive()
+        ));
+        assert_eq!(Balances::reserved_balance(FULL_PRESERVER), 10);
+        assert_eq!(DataPreservers::profiles_count(), 1);
+
+        assert_ok!(DataPreservers::delete_profile(RuntimeOrigin::signed(
+            FULL_PRESERVER
+        )));
+        assert_eq!(DataPreservers::profiles(FULL_PRESERVER), None);
+        assert_eq!(Balances::reserved_balance(FULL_PRESERVER), 0);
+        assert_eq!(DataPreservers::profiles_count(), 0);
+        assert_noop!(
+            DataPreservers::delete_profile(RuntimeOrigin::signed(FULL_PRESERVER)),
+            Error::<Test>::NoProfile
+        );
+
+        assert_eq!(
+            events(),
+            vec![
+                Event::ProfileUpdated {
+                    preserver: FULL_PRESERVER,
+                    profile: full(),
+                },
+                Event::ProfileUpdated {
+                    preserver: FULL_PRESERVER,
+                    profile: archive(),
+                },
+                Event::ProfileDeleted {
+                    preserver: FULL_PRESERVER,
+                },
+            ]
+        );
+    });
+}
+
+#[test]
+fn set_profile_requires_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            DataPreservers::set_profile(RuntimeOrigin::signed(7), full()),
+            Error::<Test>::NotSufficientDeposit
+        );
+    });
+}
+
+#[test]
+fn profiles_are_bounded() {
+    new_test_ext().execute_with(|| {
+        for preserver in 1..=5 {
+            assert_ok!(DataPreservers::set_profile(
+                RuntimeOrigin::signed(preserver),
+                full()
+            ));
+        }
+        assert_noop!(
+            DataPreservers::set_profile(RuntimeOrigin::signed(6), full()),
+            Error::<Test>::TooManyProfiles
+        );
+
+        // Existing profiles can still be updated, and new ones declared once there is room
+        assert_ok!(DataPreservers::set_profile(
+            RuntimeOrigin::signed(1),
+            archive()
+        ));
+        assert_ok!(DataPreservers::delete_profile(RuntimeOrigin::signed(2)));
+        assert_ok!(DataPreservers::set_profile(
+            RuntimeOrigin::signed(6),
+            full()
+        ));
+    });
+}
+
+#[test]
+fn archive_chain_is_assigned_archive_preserver() {
+    new_test_ext().execute_with(|| {
+        CurrentContainerChains::set(vec![1001.into()]);
+        assert_ok!(DataPreservers::set_chain_requirements(
+            RuntimeOrigin::signed(1),
+            1001.into(),
+            Some(PreserverMode::Archive)
+        ));
+        assert_ok!(DataPreservers::set_profile(
+            RuntimeOrigin::signed(FULL_PRESERVER),
+            full()
+        ));
+        assert_ok!(DataPreservers::set_profile(
+            RuntimeOrigin::signed(ARCHIVE_PRESERVER),
+            archive()
+        ));
+
+        DataPreservers::initializer_on_new_session();
+
+        // The full preserver goes first but can't serve an archive chain
+        assert_eq!(
+            DataPreservers::preserver_assignment(ParaId::from(1001)).into_inner(),
+            vec![ARCHIVE_PRESERVER]
+        );
+    });
+}
+
+#[test]
+fn full_chains_prefer_full_preservers() {
+    new_test_ext().execute_with(|| {
+        CurrentContainerChains::set(vec![1001.into(), 1002.into()]);
+        assert_ok!(DataPreservers::set_chain_requirements(
+            RuntimeOrigin::signed(1),
+            1002.into(),
+            Some(PreserverMode::Archive)
+        ));
+        assert_ok!(DataPreservers::set_profile(
+            RuntimeOrigin::signed(FULL_PRESERVER),
+            full()
+        ));
+        assert_ok!(DataPreservers::set_profile(
+            RuntimeOrigin::signed(ARCHIVE_PRESERVER),
+            archive()
+        ));
+        assert_ok!(DataPreservers::set_profile(
+            RuntimeOrigin::signed(3),
+            archive()
+        ));
+
+        DataPreservers::initializer_on_new_session();
+
+        // 1002 is served first and gets all the archive preservers
+        assert_eq!(
+            DataPreservers::preserver_assignment(ParaId::from(1001)).into_inner(),
+            vec![FULL_PRESERVER]
+        );
+        assert_eq!(
+            DataPreservers::preserver_assignment(ParaId::from(1002)).into_inner(),
+            vec![ARCHIVE_PRESERVER, 3]
+        );
+
+        // Deleted profiles and deregistered chains are removed upon new session
+        CurrentContainerChains::set(vec![1002.into()]);
+        assert_ok!(DataPreservers::delete_profile(RuntimeOrigin::signed(3)));
+        DataPreservers::initializer_on_new_session();
+
+        assert!(DataPreservers::preserver_assignment(ParaId::from(1001)).is_empty());
+        assert_eq!(
+            DataPreservers::preserver_assignment(ParaId::from(1002)).into_inner(),
+            vec![ARCHIVE_PRESERVER]
+        );
+    });
+}
+
+#[test]
+fn assignment_is_bounded_per_chain() {
+    new_test_ext().execute_with(|| {
+        CurrentContainerChains::set(vec![1001.into(), 1002.into()]);
+        for preserver in 1..=5 {
+            assert_ok!(DataPreservers::set_profile(
+                RuntimeOrigin::signed(preserver),
+                full()
+            ));
+        }
+
+        DataPreservers::initializer_on_new_session();
+
+        assert_eq!(
+            DataPreservers::preserver_assignment(ParaId::from(1001)).into_inner(),
+            vec![1, 2]
+        );
+        assert_eq!(
+            DataPreservers::preserver_assignment(ParaId::from(1002)).into_inner(),
+            vec![3, 4]
+        );
+    });
+}
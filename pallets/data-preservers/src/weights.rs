@@ -0,0 +1,167 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+
+//! Weights for pallet_data_preservers
+//!
+//! Pending a benchmark run: these weights are derived from the storage accesses of each call
+//! and must be regenerated with the benchmark CLI, using the same command as the other pallets.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_data_preservers.
+pub trait WeightInfo {
+	fn set_profile() -> Weight;
+	fn delete_profile() -> Weight;
+	fn set_chain_requirements() -> Weight;
+	fn assign_preservers(x: u32, y: u32, ) -> Weight;
+}
+
+/// Weights for pallet_data_preservers using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: DataPreservers Profiles (r:1 w:1)
+	/// Proof Skipped: DataPreservers Profiles (max_values: None, max_size: None, mode: Measured)
+	/// Storage: DataPreservers ProfilesCount (r:1 w:1)
+	/// Proof Skipped: DataPreservers ProfilesCount (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: DataPreservers ProfileDeposits (r:0 w:1)
+	/// Proof Skipped: DataPreservers ProfileDeposits (max_values: None, max_size: None, mode: Measured)
+	fn set_profile() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(26_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: DataPreservers Profiles (r:1 w:1)
+	/// Proof Skipped: DataPreservers Profiles (max_values: None, max_size: None, mode: Measured)
+	/// Storage: DataPreservers ProfilesCount (r:1 w:1)
+	/// Proof Skipped: DataPreservers ProfilesCount (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: DataPreservers ProfileDeposits (r:1 w:1)
+	/// Proof Skipped: DataPreservers ProfileDeposits (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn delete_profile() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(28_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: DataPreservers ChainRequirements (r:0 w:1)
+	/// Proof Skipped: DataPreservers ChainRequirements (max_values: None, max_size: None, mode: Measured)
+	fn set_chain_requirements() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: DataPreservers Profiles (r:101 w:0)
+	/// Proof Skipped: DataPreservers Profiles (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: DataPreservers ChainRequirements (r:50 w:0)
+	/// Proof Skipped: DataPreservers ChainRequirements (max_values: None, max_size: None, mode: Measured)
+	/// Storage: DataPreservers AssignedChainsCount (r:1 w:1)
+	/// Proof Skipped: DataPreservers AssignedChainsCount (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: DataPreservers PreserverAssignment (r:0 w:100)
+	/// Proof Skipped: DataPreservers PreserverAssignment (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `x` is `[1, 100]`.
+	/// The range of component `y` is `[1, 50]`.
+	fn assign_preservers(x: u32, y: u32, ) -> Weight {
+		// Pending a benchmark run: the previous assignment of every chain is removed and a
+		// new one is written.
+		Weight::from_parts(12_000_000, 1500)
+			.saturating_add(Weight::from_parts(1_500_000, 0).saturating_mul(x.into()))
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(y.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(x.into())))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(y.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(y.into())))
+			.saturating_add(Weight::from_parts(0, 2500).saturating_mul(x.into()))
+			.saturating_add(Weight::from_parts(0, 2500).saturating_mul(y.into()))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: DataPreservers Profiles (r:1 w:1)
+	/// Proof Skipped: DataPreservers Profiles (max_values: None, max_size: None, mode: Measured)
+	/// Storage: DataPreservers ProfilesCount (r:1 w:1)
+	/// Proof Skipped: DataPreservers ProfilesCount (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: DataPreservers ProfileDeposits (r:0 w:1)
+	/// Proof Skipped: DataPreservers ProfileDeposits (max_values: None, max_size: None, mode: Measured)
+	fn set_profile() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(26_000_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: DataPreservers Profiles (r:1 w:1)
+	/// Proof Skipped: DataPreservers Profiles (max_values: None, max_size: None, mode: Measured)
+	/// Storage: DataPreservers ProfilesCount (r:1 w:1)
+	/// Proof Skipped: DataPreservers ProfilesCount (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: DataPreservers ProfileDeposits (r:1 w:1)
+	/// Proof Skipped: DataPreservers ProfileDeposits (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn delete_profile() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(28_000_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: DataPreservers ChainRequirements (r:0 w:1)
+	/// Proof Skipped: DataPreservers ChainRequirements (max_values: None, max_size: None, mode: Measured)
+	fn set_chain_requirements() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: DataPreservers Profiles (r:101 w:0)
+	/// Proof Skipped: DataPreservers Profiles (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: DataPreservers ChainRequirements (r:50 w:0)
+	/// Proof Skipped: DataPreservers ChainRequirements (max_values: None, max_size: None, mode: Measured)
+	/// Storage: DataPreservers AssignedChainsCount (r:1 w:1)
+	/// Proof Skipped: DataPreservers AssignedChainsCount (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: DataPreservers PreserverAssignment (r:0 w:100)
+	/// Proof Skipped: DataPreservers PreserverAssignment (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `x` is `[1, 100]`.
+	/// The range of component `y` is `[1, 50]`.
+	fn assign_preservers(x: u32, y: u32, ) -> Weight {
+		// Pending a benchmark run: the previous assignment of every chain is removed and a
+		// new one is written.
+		Weight::from_parts(12_000_000, 1500)
+			.saturating_add(Weight::from_parts(1_500_000, 0).saturating_mul(x.into()))
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(y.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(x.into())))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(y.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(y.into())))
+			.saturating_add(Weight::from_parts(0, 2500).saturating_mul(x.into()))
+			.saturating_add(Weight::from_parts(0, 2500).saturating_mul(y.into()))
+	}
+}
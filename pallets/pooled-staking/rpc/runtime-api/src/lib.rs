@@ -0,0 +1,74 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+//! Runtime API for PooledStaking pallet
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use {
+    pallet_pooled_staking::{AllTargetPool, Lock, PoolsStake, StakingStats},
+    scale_info::prelude::vec::Vec,
+};
+
+sp_api::decl_runtime_apis! {
+    pub trait PooledStakingApi<AccountId, Balance, Instant> where
+        AccountId: parity_scale_codec::Codec,
+        Balance: parity_scale_codec::Codec,
+        Instant: parity_scale_codec::Codec,
+    {
+        /// Return the eligible candidates with their total stake, in the order they
+        /// are currently sorted.
+        fn eligible_candidates() -> Vec<(AccountId, Balance)>;
+
+        /// Return the manual rewards of `delegator` from `candidate` that claiming them
+        /// now would pay.
+        fn claimable_rewards(delegator: AccountId, candidate: AccountId) -> Balance;
+
+        /// Return the funds of `delegator` held by its pending operations, along with the
+        /// instant from which each operation can be executed.
+        fn locks(delegator: AccountId) -> Vec<Lock<AccountId, Balance, Instant>>;
+
+        /// Return the amount of shares of `pool` that `stake` is worth for `candidate`,
+        /// rounded like the pallet does. Returns `None` if the conversion fails.
+        fn stake_to_shares(
+            candidate: AccountId,
+            pool: AllTargetPool,
+            stake: Balance,
+        ) -> Option<Balance>;
+
+        /// Return the amount of stake that `shares` of `pool` are worth for `candidate`,
+        /// rounded like the pallet does. Returns `None` if the conversion fails.
+        fn shares_to_stake(
+            candidate: AccountId,
+            pool: AllTargetPool,
+            shares: Balance,
+        ) -> Option<Balance>;
+
+        /// Return the number of candidates and delegators, along with the stake of all
+        /// candidates.
+        fn stats() -> StakingStats<Balance>;
+
+        /// Return the delegators of `candidate` along with the current value of their shares
+        /// in each pool, a page of at most `limit` of them (and never more than
+        /// `MAX_CANDIDATE_DELEGATORS_PAGE`) starting after the raw storage key `cursor`. Also
+        /// return the cursor of the next page, or `None` once all delegators have been listed.
+        fn candidate_delegators(
+            candidate: AccountId,
+            cursor: Option<Vec<u8>>,
+            limit: u32,
+        ) -> (Vec<(AccountId, PoolsStake<Balance>)>, Option<Vec<u8>>);
+    }
+}
@@ -21,7 +21,7 @@ use {super::*, crate::Pallet as PooledStaking};
 use {
     crate::{
         pools::Pool,
-        traits::{IsCandidateEligible, Timer},
+        traits::{IsCandidateEligible, IsCandidateRegistered, Timer},
         PendingOperationKey::{JoiningAutoCompounding, JoiningManualRewards},
     },
     frame_benchmarking::{account, impl_benchmark_test_suite, v2::*, BenchmarkError},
@@ -30,10 +30,11 @@ use {
         traits::{
             fungible::{InspectHold, Mutate, MutateHold},
             tokens::Precision,
-            Get,
+            EnsureOrigin, Get,
         },
     },
     frame_system::EventRecord,
+    sp_runtime::{Perbill, Percent},
     sp_std::prelude::*,
 };
 
@@ -83,6 +84,7 @@ mod benchmarks {
             caller.clone(),
             TargetPool::AutoCompounding,
             min_candidate_stk::<T>(),
+            None,
         )?;
 
         // self delegation
@@ -91,6 +93,7 @@ mod benchmarks {
             caller.clone(),
             TargetPool::ManualRewards,
             min_candidate_stk::<T>(),
+            None,
         )?;
 
         let timer = T::JoiningRequestTimer::now();
@@ -120,12 +123,14 @@ mod benchmarks {
         )?;
 
         // Worst case scenario is: we have already shares in both pools, and we delegate again
+        // with a maximum share price
         #[extrinsic_call]
         _(
             RawOrigin::Signed(caller.clone()),
             caller.clone(),
             TargetPool::AutoCompounding,
             min_candidate_stk::<T>(),
+            Some(min_candidate_stk::<T>()),
         );
 
         // assert that it comes out sorted
@@ -150,7 +155,6 @@ mod benchmarks {
             create_funded_user::<T>("caller", USER_SEED, min_candidate_stk::<T>() * b.into());
 
         let mut pending_operations = vec![];
-        let mut candidates = vec![];
 
         T::Currency::set_balance(&T::StakingAccount::get(), min_candidate_stk::<T>());
 
@@ -164,6 +168,7 @@ mod benchmarks {
                 min_candidate_stk::<T>() * 2u32.into(),
             );
             T::EligibleCandidatesFilter::make_candidate_eligible(&candidate, true);
+            T::RegisteredCandidates::make_candidate_registered(&candidate);
 
             // self delegation
             PooledStaking::<T>::request_delegate(
@@ -171,31 +176,29 @@ mod benchmarks {
                 candidate.clone(),
                 TargetPool::AutoCompounding,
                 min_candidate_stk::<T>(),
+                None,
             )?;
 
             pending_operations.push(PendingOperationQuery {
                 delegator: caller.clone(),
                 operation: JoiningAutoCompounding {
-                    candidate: candidate.clone(),
+                    candidate,
                     at: timer.clone(),
                 },
             });
-            candidates.push(candidate);
         }
 
         T::JoiningRequestTimer::skip_to_elapsed();
         #[extrinsic_call]
         _(RawOrigin::Signed(caller.clone()), pending_operations);
 
-        let last_candidate = &candidates[candidates.len() - 1];
-        // assert that it comes out sorted
         assert_last_event::<T>(
-            Event::ExecutedDelegate {
-                candidate: last_candidate.clone(),
-                delegator: caller,
-                pool: TargetPool::AutoCompounding,
-                staked: min_candidate_stk::<T>(),
-                released: 0u32.into(),
+            Event::PendingOperationsExecuted {
+                executor: caller.clone(),
+                delegators: vec![caller],
+                count: b,
+                total_staked: min_candidate_stk::<T>() * b.into(),
+                total_released: 0u32.into(),
             }
             .into(),
         );
@@ -215,6 +218,7 @@ mod benchmarks {
             caller.clone(),
             TargetPool::AutoCompounding,
             min_candidate_stk::<T>(),
+            None,
         )?;
 
         let timer = T::JoiningRequestTimer::now();
@@ -265,6 +269,65 @@ mod benchmarks {
         Ok(())
     }
 
+    #[benchmark]
+    fn cancel_undelegation() -> Result<(), BenchmarkError> {
+        const USER_SEED: u32 = 1;
+        let (caller, _deposit_amount) =
+            create_funded_user::<T>("caller", USER_SEED, min_candidate_stk::<T>());
+
+        T::EligibleCandidatesFilter::make_candidate_eligible(&caller, true);
+
+        PooledStaking::<T>::request_delegate(
+            RawOrigin::Signed(caller.clone()).into(),
+            caller.clone(),
+            TargetPool::AutoCompounding,
+            min_candidate_stk::<T>(),
+            None,
+        )?;
+
+        let timer = T::JoiningRequestTimer::now();
+
+        T::JoiningRequestTimer::skip_to_elapsed();
+
+        PooledStaking::<T>::execute_pending_operations(
+            RawOrigin::Signed(caller.clone()).into(),
+            vec![PendingOperationQuery {
+                delegator: caller.clone(),
+                operation: JoiningAutoCompounding {
+                    candidate: caller.clone(),
+                    at: timer.clone(),
+                },
+            }],
+        )?;
+
+        PooledStaking::<T>::request_undelegate(
+            RawOrigin::Signed(caller.clone()).into(),
+            caller.clone(),
+            TargetPool::AutoCompounding,
+            SharesOrStake::Stake(min_candidate_stk::<T>() / 2u32.into()),
+        )?;
+
+        let at = T::LeavingRequestTimer::now();
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller.clone()),
+            caller.clone(),
+            at.clone(),
+            TargetPool::AutoCompounding,
+        );
+
+        let operation = PendingOperationKey::Leaving {
+            candidate: caller.clone(),
+            at,
+        };
+        assert_eq!(
+            PendingOperations::<T>::get(&caller, &operation),
+            0u32.into()
+        );
+        Ok(())
+    }
+
     #[benchmark]
     fn claim_manual_rewards(
         b: Linear<1, { T::EligibleCandidatesBufferSize::get() }>,
@@ -283,6 +346,7 @@ mod benchmarks {
                 min_candidate_stk::<T>() * 2u32.into(),
             );
             T::EligibleCandidatesFilter::make_candidate_eligible(&candidate, true);
+            T::RegisteredCandidates::make_candidate_registered(&candidate);
 
             // self delegation
             PooledStaking::<T>::request_delegate(
@@ -290,6 +354,7 @@ mod benchmarks {
                 candidate.clone(),
                 TargetPool::AutoCompounding,
                 min_candidate_stk::<T>(),
+                None,
             )?;
 
             PooledStaking::<T>::request_delegate(
@@ -297,6 +362,7 @@ mod benchmarks {
                 candidate.clone(),
                 TargetPool::ManualRewards,
                 min_candidate_stk::<T>(),
+                None,
             )?;
 
             candidate_delegator.push((candidate.clone(), caller.clone()))
@@ -367,12 +433,14 @@ mod benchmarks {
         );
 
         T::EligibleCandidatesFilter::make_candidate_eligible(&candidate, true);
+        T::RegisteredCandidates::make_candidate_registered(&candidate);
         // self delegation
         PooledStaking::<T>::request_delegate(
             RawOrigin::Signed(candidate.clone()).into(),
             candidate.clone(),
             TargetPool::AutoCompounding,
             min_candidate_stk::<T>(),
+            None,
         )?;
 
         PooledStaking::<T>::request_delegate(
@@ -380,6 +448,7 @@ mod benchmarks {
             candidate.clone(),
             TargetPool::AutoCompounding,
             min_candidate_stk::<T>(),
+            None,
         )?;
 
         PooledStaking::<T>::request_delegate(
@@ -387,6 +456,7 @@ mod benchmarks {
             candidate.clone(),
             TargetPool::AutoCompounding,
             min_candidate_stk::<T>(),
+            None,
         )?;
 
         let fake_hold = min_candidate_stk::<T>() / 2u32.into();
@@ -440,6 +510,7 @@ mod benchmarks {
                 candidate.clone(),
                 TargetPool::AutoCompounding,
                 min_candidate_stk::<T>(),
+                None,
             )?;
 
             // Make candidate eligible
@@ -454,6 +525,326 @@ mod benchmarks {
         Ok(())
     }
 
+    #[benchmark]
+    fn set_compounding_preference() -> Result<(), BenchmarkError> {
+        const USER_SEED: u32 = 1;
+        let (caller, _deposit_amount) =
+            create_funded_user::<T>("caller", USER_SEED, min_candidate_stk::<T>());
+        let (candidate, _deposit) =
+            create_funded_user::<T>("candidate", USER_SEED + 1, min_candidate_stk::<T>());
+        T::EligibleCandidatesFilter::make_candidate_eligible(&candidate, true);
+        T::RegisteredCandidates::make_candidate_registered(&candidate);
+
+        // Only delegators with auto compounding shares can set a preference
+        PooledStaking::<T>::request_delegate(
+            RawOrigin::Signed(caller.clone()).into(),
+            candidate.clone(),
+            TargetPool::AutoCompounding,
+            min_candidate_stk::<T>(),
+            None,
+        )?;
+
+        let timer = T::JoiningRequestTimer::now();
+
+        T::JoiningRequestTimer::skip_to_elapsed();
+
+        PooledStaking::<T>::execute_pending_operations(
+            RawOrigin::Signed(caller.clone()).into(),
+            vec![PendingOperationQuery {
+                delegator: caller.clone(),
+                operation: JoiningAutoCompounding {
+                    candidate: candidate.clone(),
+                    at: timer.clone(),
+                },
+            }],
+        )?;
+
+        let preference = Percent::from_percent(50);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller.clone()),
+            candidate.clone(),
+            preference,
+        );
+
+        assert_last_event::<T>(
+            Event::UpdatedCompoundingPreference {
+                candidate,
+                delegator: caller,
+                preference,
+            }
+            .into(),
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_reward_destination() -> Result<(), BenchmarkError> {
+        const USER_SEED: u32 = 1;
+        let (caller, _deposit_amount) =
+            create_funded_user::<T>("caller", USER_SEED, min_candidate_stk::<T>());
+        let destination_account: T::AccountId = account("destination", USER_SEED + 1, 0);
+        let destination = RewardDestination::Account(destination_account);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), destination.clone());
+
+        assert_last_event::<T>(
+            Event::UpdatedRewardDestination {
+                delegator: caller,
+                destination,
+            }
+            .into(),
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_commission() -> Result<(), BenchmarkError> {
+        const USER_SEED: u32 = 1;
+        let (caller, _deposit_amount) =
+            create_funded_user::<T>("caller", USER_SEED, min_candidate_stk::<T>());
+        T::RegisteredCandidates::make_candidate_registered(&caller);
+
+        // Worst case: an increase replacing a previous one that is already elapsed.
+        let previous = Perbill::from_percent(50) * T::MaxCommission::get();
+        <PendingCommissionIncreases<T>>::insert(&caller, (previous, T::JoiningRequestTimer::now()));
+        T::JoiningRequestTimer::skip_to_elapsed();
+        let commission = T::MaxCommission::get();
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), commission);
+
+        assert_eq!(<CandidateCommission<T>>::get(&caller), previous);
+        assert_last_event::<T>(
+            Event::CommissionIncreaseRequested {
+                candidate: caller,
+                commission,
+                at: T::JoiningRequestTimer::now(),
+            }
+            .into(),
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_candidate_min_delegation() -> Result<(), BenchmarkError> {
+        const USER_SEED: u32 = 1;
+        let (caller, _deposit_amount) =
+            create_funded_user::<T>("caller", USER_SEED, min_candidate_stk::<T>());
+        T::RegisteredCandidates::make_candidate_registered(&caller);
+        let min_delegation = min_candidate_stk::<T>();
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), min_delegation);
+
+        assert_last_event::<T>(
+            Event::UpdatedCandidateMinDelegation {
+                candidate: caller,
+                min_delegation,
+            }
+            .into(),
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_delegation_proxy() -> Result<(), BenchmarkError> {
+        const USER_SEED: u32 = 1;
+        let (caller, _deposit_amount) =
+            create_funded_user::<T>("caller", USER_SEED, min_candidate_stk::<T>());
+        let proxy: T::AccountId = account("proxy", USER_SEED + 1, 0);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), proxy.clone(), true);
+
+        assert_last_event::<T>(
+            Event::UpdatedDelegationProxy {
+                delegator: caller,
+                proxy,
+                allowed: true,
+            }
+            .into(),
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_request_delay() -> Result<(), BenchmarkError> {
+        let delay = T::JoiningRequestTimer::default_delay();
+        let origin = T::RequestDelayOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        set_joining_delay(origin as T::RuntimeOrigin, delay.clone());
+
+        assert_last_event::<T>(Event::UpdatedJoiningDelay { delay }.into());
+        Ok(())
+    }
+
+    #[benchmark]
+    fn schedule_delegation() -> Result<(), BenchmarkError> {
+        const USER_SEED: u32 = 1;
+        let (caller, _deposit_amount) =
+            create_funded_user::<T>("caller", USER_SEED, min_candidate_stk::<T>() * 2u32.into());
+        let activate_at = frame_system::Pallet::<T>::block_number() + 2u32.into();
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller.clone()),
+            caller.clone(),
+            TargetPool::AutoCompounding,
+            min_candidate_stk::<T>(),
+            activate_at,
+        );
+
+        assert_last_event::<T>(
+            Event::ScheduledDelegation {
+                candidate: caller.clone(),
+                delegator: caller,
+                pool: TargetPool::AutoCompounding,
+                stake: min_candidate_stk::<T>(),
+                activate_at,
+            }
+            .into(),
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn request_candidate_leave() -> Result<(), BenchmarkError> {
+        const USER_SEED: u32 = 1;
+        let (caller, _deposit_amount) =
+            create_funded_user::<T>("caller", USER_SEED, min_candidate_stk::<T>());
+
+        T::RegisteredCandidates::make_candidate_registered(&caller);
+        // self delegation
+        PooledStaking::<T>::request_delegate(
+            RawOrigin::Signed(caller.clone()).into(),
+            caller.clone(),
+            TargetPool::AutoCompounding,
+            min_candidate_stk::<T>(),
+            None,
+        )?;
+
+        let at = frame_system::Pallet::<T>::block_number() + T::CandidateLeaveDelay::get();
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()));
+
+        assert_last_event::<T>(
+            Event::CandidateLeaveRequested {
+                candidate: caller,
+                at,
+            }
+            .into(),
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn bonded_delegate() -> Result<(), BenchmarkError> {
+        const USER_SEED: u32 = 1;
+        let (caller, _deposit_amount) =
+            create_funded_user::<T>("caller", USER_SEED, min_candidate_stk::<T>() * 2u32.into());
+        let until = frame_system::Pallet::<T>::block_number() + 10u32.into();
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller.clone()),
+            caller.clone(),
+            TargetPool::AutoCompounding,
+            min_candidate_stk::<T>(),
+            until,
+        );
+
+        assert_last_event::<T>(
+            Event::BondedPosition {
+                candidate: caller.clone(),
+                delegator: caller,
+                until,
+            }
+            .into(),
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn distribute_rewards(b: Linear<0, 100>) -> Result<(), BenchmarkError> {
+        const USER_SEED: u32 = 1000;
+        let (candidate, _deposit) =
+            create_funded_user::<T>("candidate", USER_SEED, min_candidate_stk::<T>());
+        T::EligibleCandidatesFilter::make_candidate_eligible(&candidate, true);
+        T::RegisteredCandidates::make_candidate_registered(&candidate);
+
+        // self delegation
+        PooledStaking::<T>::request_delegate(
+            RawOrigin::Signed(candidate.clone()).into(),
+            candidate.clone(),
+            TargetPool::AutoCompounding,
+            min_candidate_stk::<T>(),
+            None,
+        )?;
+
+        // Worst case scenario is: each delegator only compounds part of its rewards
+        let mut delegators = vec![];
+        for i in 0..b {
+            let (delegator, _deposit) =
+                create_funded_user::<T>("delegator", USER_SEED - i - 1, min_candidate_stk::<T>());
+
+            PooledStaking::<T>::request_delegate(
+                RawOrigin::Signed(delegator.clone()).into(),
+                candidate.clone(),
+                TargetPool::AutoCompounding,
+                min_candidate_stk::<T>(),
+                None,
+            )?;
+
+            delegators.push(delegator);
+        }
+
+        let timer = T::JoiningRequestTimer::now();
+
+        T::JoiningRequestTimer::skip_to_elapsed();
+
+        for delegator in delegators.iter().chain([&candidate]) {
+            PooledStaking::<T>::execute_pending_operations(
+                RawOrigin::Signed(delegator.clone()).into(),
+                vec![PendingOperationQuery {
+                    delegator: delegator.clone(),
+                    operation: JoiningAutoCompounding {
+                        candidate: candidate.clone(),
+                        at: timer.clone(),
+                    },
+                }],
+            )?;
+        }
+
+        for delegator in delegators {
+            PooledStaking::<T>::set_compounding_preference(
+                RawOrigin::Signed(delegator).into(),
+                candidate.clone(),
+                Percent::from_percent(50),
+            )?;
+        }
+
+        let rewards = min_candidate_stk::<T>();
+        T::Currency::set_balance(&T::RewardPot::get(), rewards * 2u32.into());
+        T::Currency::set_balance(&T::StakingAccount::get(), min_candidate_stk::<T>());
+
+        #[block]
+        {
+            PooledStaking::<T>::distribute_rewards(
+                candidate.clone(),
+                AuthorRole::Container,
+                rewards,
+            )?;
+        }
+
+        Ok(())
+    }
+
     impl_benchmark_test_suite!(
         PooledStaking,
         crate::mock::ExtBuilder::default().build(),
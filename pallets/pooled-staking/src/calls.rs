@@ -18,19 +18,35 @@ use {
     crate::{
         candidate::Candidates,
         pools::{self, Pool},
-        traits::{ErrAdd, ErrSub, Timer},
-        AllTargetPool, Candidate, Config, Delegator, Error, Event, Pallet, PendingOperationKey,
-        PendingOperationQuery, PendingOperationQueryOf, PendingOperations, Shares, SharesOrStake,
-        Stake, TargetPool,
+        traits::{ErrAdd, ErrSub, IsCandidateLeaving, IsCandidateRegistered, OnDelegation, Timer},
+        weights::WeightInfo,
+        AllTargetPool, Bond, Bonds, Candidate, CandidateCommission, CandidateLeaveRequests,
+        CandidateLeaveSchedule, CandidateMinDelegation, CompoundingPreference,
+        CompoundingPreferencesCount, Config, DelegationProxies, Delegator, DelegatorCandidates,
+        DelegatorsCount, DueCandidateLeaves, EligibleSince, Error, Event, JoiningDelay,
+        JoiningInstantOf, LeavingDelay, LeavingInstantOf, LeavingStakeRequested, MaxSharePrices,
+        Pallet, PendingCommissionIncreases, PendingOperationKey, PendingOperationKeyOf,
+        PendingOperationQuery, PendingOperationQueryOf, PendingOperations, Pools, PoolsKey,
+        PurgingCandidates, RecentlyJoinedStake, ReducedDelayJoinings, RewardDestination,
+        RewardDestinations, ScheduledDelegation, ScheduledDelegations, ShareValueHistory, Shares,
+        SharesOrStake, SortedEligibleCandidates, Stake, TargetPool, TotalCandidates,
+        TotalDelegators, ZeroStakeExpiries, ZeroStakeSince,
     },
     frame_support::{
+        dispatch::DispatchErrorWithPostInfo,
         pallet_prelude::*,
+        sp_io::MultiRemovalResults,
+        storage::with_storage_layer,
         traits::{
             fungible::{Mutate, MutateHold},
             tokens::{Precision, Preservation},
         },
     },
-    sp_runtime::traits::{CheckedSub, Zero},
+    frame_system::pallet_prelude::BlockNumberFor,
+    sp_runtime::{
+        traits::{CheckedSub, One, Saturating, Zero},
+        Perbill, Percent,
+    },
     sp_std::vec::Vec,
 };
 
@@ -113,12 +129,60 @@ impl<T: Config> Calls<T> {
         delegator: Delegator<T>,
         pool: TargetPool,
         stake: T::Balance,
+        max_share_price: Option<T::Balance>,
     ) -> DispatchResultWithPostInfo {
         ensure!(!stake.is_zero(), Error::<T>::StakeMustBeNonZero);
+        ensure!(
+            !T::LeavingCandidates::is_candidate_leaving(&candidate)
+                && !CandidateLeaveRequests::<T>::contains_key(&candidate),
+            Error::<T>::CandidateLeaving
+        );
+        ensure!(
+            !PurgingCandidates::<T>::contains_key(&candidate),
+            Error::<T>::CandidateBeingPurged
+        );
+        ensure!(
+            candidate == delegator || T::RegisteredCandidates::is_candidate_registered(&candidate),
+            Error::<T>::NotACandidate
+        );
+
+        // Joining shares can only round the stake down, checking the requested stake is enough.
+        ensure!(
+            Pallet::<T>::total_stake().saturating_add(stake) <= T::MaxTotalStake::get(),
+            Error::<T>::GlobalStakeCapReached
+        );
 
         // Convert stake into joining shares quantity.
         let shares = pools::Joining::<T>::stake_to_shares_or_init(&candidate, Stake(stake))?;
 
+        // Only new delegators are limited, existing ones can increase their stake.
+        if !Self::is_delegating(&candidate, &delegator) {
+            ensure!(
+                candidate == delegator || stake >= CandidateMinDelegation::<T>::get(&candidate),
+                Error::<T>::BelowCandidateMinimum
+            );
+            let count = DelegatorsCount::<T>::get(&candidate);
+            ensure!(
+                count < T::MaxDelegatorsPerCandidate::get(),
+                Error::<T>::TooManyDelegators
+            );
+            let is_new_delegator = DelegatorCandidates::<T>::try_mutate(&delegator, |candidates| {
+                candidates.try_push(candidate.clone())?;
+                Ok::<_, Candidate<T>>(candidates.len() == 1)
+            })
+            .map_err(|_| Error::<T>::TooManyDelegations)?;
+            if is_new_delegator {
+                TotalDelegators::<T>::mutate(|total| *total = total.saturating_add(1));
+            }
+            if count.is_zero() {
+                TotalCandidates::<T>::mutate(|total| *total = total.saturating_add(1));
+                if let Some(since) = ZeroStakeSince::<T>::take(&candidate) {
+                    ZeroStakeExpiries::<T>::remove(Self::zero_stake_expiry(since), &candidate);
+                }
+            }
+            DelegatorsCount::<T>::insert(&candidate, count.saturating_add(1));
+        }
+
         // If the amount was stake and is less than the value of 1 share it will round down to
         // 0 share. We avoid doing any work for 0 shares.
         ensure!(!shares.0.is_zero(), Error::<T>::StakeMustBeNonZero);
@@ -132,7 +196,8 @@ impl<T: Config> Calls<T> {
         pools::Joining::<T>::increase_hold(&candidate, &delegator, &stake)?;
         Candidates::<T>::add_total_stake(&candidate, &stake)?;
 
-        // We create/mutate a request for joining.
+        // We create/mutate a request for joining. Requests made at the same instant share the
+        // same key, so they are merged into a single pending operation.
         let now = T::JoiningRequestTimer::now();
         let operation_key = match pool {
             TargetPool::AutoCompounding => PendingOperationKey::JoiningAutoCompounding {
@@ -147,6 +212,30 @@ impl<T: Config> Calls<T> {
 
         // We store/mutate the operation in storage.
         let operation = PendingOperations::<T>::get(&delegator, &operation_key);
+        // Increasing the stake in a pool the delegator already has shares in uses the
+        // reduced delay, unless merged with a request that doesn't.
+        let has_pool_shares = match pool {
+            TargetPool::AutoCompounding => {
+                !pools::AutoCompounding::<T>::shares(&candidate, &delegator)
+                    .0
+                    .is_zero()
+            }
+            TargetPool::ManualRewards => !pools::ManualRewards::<T>::shares(&candidate, &delegator)
+                .0
+                .is_zero(),
+        };
+        if operation.is_zero() && has_pool_shares {
+            ReducedDelayJoinings::<T>::insert(&delegator, &operation_key, ());
+        }
+        // Merged requests keep the lowest of their maximum share prices.
+        if let Some(max_share_price) = max_share_price {
+            MaxSharePrices::<T>::mutate(&delegator, &operation_key, |max| {
+                *max = Some(match *max {
+                    Some(existing) => existing.min(max_share_price),
+                    None => max_share_price,
+                })
+            });
+        }
         let operation = operation
             .err_add(&shares.0)
             .map_err(|_| Error::<T>::MathOverflow)?;
@@ -157,7 +246,7 @@ impl<T: Config> Calls<T> {
         Pallet::<T>::deposit_event(Event::<T>::RequestedDelegate {
             candidate,
             delegator,
-            pool: pool,
+            pool,
             pending: stake.0,
         });
 
@@ -170,6 +259,47 @@ impl<T: Config> Calls<T> {
         pool: TargetPool,
         amount: SharesOrStake<T::Balance>,
     ) -> DispatchResultWithPostInfo {
+        Self::undelegate(candidate, delegator, pool, amount, false)
+    }
+
+    /// Move `amount` of the delegation of `delegator` in `pool` to the leaving pool.
+    /// Undelegations `forced` by the candidate leaving bypass bonds, the leaving rate limit and
+    /// the early exit penalty.
+    fn undelegate(
+        candidate: Candidate<T>,
+        delegator: Delegator<T>,
+        pool: TargetPool,
+        amount: SharesOrStake<T::Balance>,
+        forced: bool,
+    ) -> DispatchResultWithPostInfo {
+        // Bonded positions are only released early when the candidate leaves.
+        if !forced && !CandidateLeaveRequests::<T>::contains_key(&candidate) {
+            ensure!(
+                Pallet::<T>::bonded_until(&candidate, &delegator).is_none(),
+                Error::<T>::StillBonded
+            );
+        }
+
+        // The requested amount cannot exceed what the delegator has in the pool
+        match (&amount, pool) {
+            (SharesOrStake::Shares(s), TargetPool::AutoCompounding) => ensure!(
+                *s <= pools::AutoCompounding::<T>::shares(&candidate, &delegator).0,
+                Error::<T>::NotEnoughShares
+            ),
+            (SharesOrStake::Shares(s), TargetPool::ManualRewards) => ensure!(
+                *s <= pools::ManualRewards::<T>::shares(&candidate, &delegator).0,
+                Error::<T>::NotEnoughShares
+            ),
+            (SharesOrStake::Stake(s), TargetPool::AutoCompounding) => ensure!(
+                *s <= pools::AutoCompounding::<T>::computed_stake(&candidate, &delegator)?.0,
+                Error::<T>::NotEnoughStake
+            ),
+            (SharesOrStake::Stake(s), TargetPool::ManualRewards) => ensure!(
+                *s <= pools::ManualRewards::<T>::computed_stake(&candidate, &delegator)?.0,
+                Error::<T>::NotEnoughStake
+            ),
+        }
+
         // Converts amount to shares of the correct pool
         let shares = match (amount, pool) {
             (SharesOrStake::Shares(s), _) => s,
@@ -208,6 +338,12 @@ impl<T: Config> Calls<T> {
                 )?;
 
                 pools::AutoCompounding::<T>::decrease_hold(&candidate, &delegator, &stake)?;
+                if pools::AutoCompounding::<T>::shares(&candidate, &delegator)
+                    .0
+                    .is_zero()
+                {
+                    Self::remove_compounding_preference(&candidate, &delegator);
+                }
                 stake
             }
             TargetPool::ManualRewards => {
@@ -230,9 +366,58 @@ impl<T: Config> Calls<T> {
             }
         };
 
+        // The stake leaving the candidate in a single session is limited.
+        let now = T::LeavingRequestTimer::now();
+        if !forced {
+            let requested_stake = match LeavingStakeRequested::<T>::get(&candidate) {
+                Some((at, requested_stake)) if at == now => requested_stake,
+                _ => Zero::zero(),
+            };
+            let requested_stake = requested_stake
+                .err_add(&removed_stake.0)
+                .map_err(Error::<T>::from)?;
+            ensure!(
+                requested_stake <= T::MaxLeavingStakePerSession::get(),
+                Error::<T>::LeavingRateLimited
+            );
+            LeavingStakeRequested::<T>::insert(&candidate, (now.clone(), requested_stake));
+        }
+
         // All this stake no longer contribute to the election of the candidate.
         Candidates::<T>::sub_total_stake(&candidate, removed_stake)?;
 
+        // Undelegating recently joined stake is penalized, the penalty is taken from the
+        // removed stake before it starts leaving. Delegators are not penalized for their
+        // candidate leaving.
+        let penalty = if forced {
+            Zero::zero()
+        } else {
+            Self::early_exit_penalty(&candidate, &delegator, removed_stake.0)
+        };
+        if !penalty.is_zero() {
+            T::Currency::release(
+                &T::CurrencyHoldReason::get(),
+                &delegator,
+                penalty,
+                Precision::Exact,
+            )?;
+            let destination = T::EarlyExitPenaltyDestination::get();
+            T::Currency::transfer(&delegator, &destination, penalty, Preservation::Expendable)?;
+
+            Pallet::<T>::deposit_event(Event::<T>::EarlyExitPenalized {
+                candidate: candidate.clone(),
+                delegator: delegator.clone(),
+                destination,
+                amount: penalty,
+            });
+        }
+        let removed_stake = Stake(
+            removed_stake
+                .0
+                .err_sub(&penalty)
+                .map_err(Error::<T>::from)?,
+        );
+
         // Create leaving shares.
         // As with all pools there will be some rounding error, this amount
         // should be small enough so that it is safe to directly release it
@@ -244,7 +429,6 @@ impl<T: Config> Calls<T> {
         pools::Leaving::<T>::increase_hold(&candidate, &delegator, &leaving_stake)?;
 
         // We create/mutate a request for leaving.
-        let now = T::LeavingRequestTimer::now();
         let operation_key = PendingOperationKey::Leaving {
             candidate: candidate.clone(),
             at: now,
@@ -255,7 +439,7 @@ impl<T: Config> Calls<T> {
             .map_err(|_| Error::<T>::MathOverflow)?;
         PendingOperations::<T>::set(&delegator, &operation_key, operation);
 
-        // We release the dust if non-zero.
+        // We release the dust if non-zero, and sweep it to the dust collector if any.
         let dust = removed_stake
             .0
             .err_sub(&leaving_stake.0)
@@ -268,6 +452,17 @@ impl<T: Config> Calls<T> {
                 dust,
                 Precision::Exact,
             )?;
+
+            if let Some(collector) = T::DustCollector::get() {
+                T::Currency::transfer(&delegator, &collector, dust, Preservation::Expendable)?;
+
+                Pallet::<T>::deposit_event(Event::<T>::SweptDust {
+                    candidate: candidate.clone(),
+                    delegator: delegator.clone(),
+                    collector,
+                    amount: dust,
+                });
+            }
         }
 
         pools::check_candidate_consistency::<T>(&candidate)?;
@@ -283,73 +478,362 @@ impl<T: Config> Calls<T> {
         Ok(().into())
     }
 
+    /// Recently joined stake of `delegator` towards `candidate` and the block at which it was
+    /// executed, if it was executed less than `MinStakeDuration` blocks ago.
+    fn recently_joined_stake(
+        candidate: &Candidate<T>,
+        delegator: &Delegator<T>,
+    ) -> Option<(BlockNumberFor<T>, T::Balance)> {
+        let now = frame_system::Pallet::<T>::block_number();
+        RecentlyJoinedStake::<T>::get(candidate, delegator)
+            .filter(|(at, _)| now < at.saturating_add(T::MinStakeDuration::get()))
+    }
+
+    /// Penalty taken from `stake` if `delegator` undelegates it from `candidate` now. Only the
+    /// part of `stake` that was recently joined is penalized, which is considered to be
+    /// undelegated first and is no longer recent afterwards.
+    fn early_exit_penalty(
+        candidate: &Candidate<T>,
+        delegator: &Delegator<T>,
+        stake: T::Balance,
+    ) -> T::Balance {
+        let (at, recent_stake) = match Self::recently_joined_stake(candidate, delegator) {
+            Some(recent) => recent,
+            None => return Zero::zero(),
+        };
+
+        let penalized_stake = recent_stake.min(stake);
+        RecentlyJoinedStake::<T>::insert(
+            candidate,
+            delegator,
+            (at, recent_stake.saturating_sub(penalized_stake)),
+        );
+
+        T::EarlyExitPenalty::get() * penalized_stake
+    }
+
+    /// Record `staked` as recently joined by `delegator` towards `candidate`. If it already has
+    /// recently joined stake, the block at which it is considered executed is moved towards now
+    /// in proportion to the newly joined stake, such that small top-ups don't lock the stake
+    /// joined before them again.
+    fn note_joined_stake(candidate: &Candidate<T>, delegator: &Delegator<T>, staked: T::Balance) {
+        let now = frame_system::Pallet::<T>::block_number();
+        let recent = match Self::recently_joined_stake(candidate, delegator) {
+            Some((at, recent_stake)) => {
+                let total = recent_stake.saturating_add(staked);
+                let elapsed = now.saturating_sub(at);
+                let at = at.saturating_add(Perbill::from_rational(staked, total) * elapsed);
+                (at, total)
+            }
+            None => (now, staked),
+        };
+
+        RecentlyJoinedStake::<T>::insert(candidate, delegator, recent);
+    }
+
+    pub fn cancel_undelegation(
+        candidate: Candidate<T>,
+        delegator: Delegator<T>,
+        at: LeavingInstantOf<T>,
+        pool: TargetPool,
+    ) -> DispatchResultWithPostInfo {
+        ensure!(
+            !T::LeavingCandidates::is_candidate_leaving(&candidate)
+                && !CandidateLeaveRequests::<T>::contains_key(&candidate),
+            Error::<T>::CandidateLeaving
+        );
+
+        let operation_key = PendingOperationKey::Leaving {
+            candidate: candidate.clone(),
+            at: at.clone(),
+        };
+        let leaving_shares = PendingOperations::<T>::get(&delegator, &operation_key);
+        ensure!(!leaving_shares.is_zero(), Error::<T>::NoPendingOperation);
+
+        // Convert leaving shares back into stake, no rewards are distributed to the Leaving
+        // pool so there should always be enough hold.
+        let stake =
+            pools::Leaving::<T>::sub_shares(&candidate, &delegator, Shares(leaving_shares))?;
+        pools::Leaving::<T>::decrease_hold(&candidate, &delegator, &stake)?;
+        PendingOperations::<T>::remove(&delegator, &operation_key);
+
+        // The cancelled stake no longer counts towards the leaving rate limit.
+        LeavingStakeRequested::<T>::mutate(&candidate, |requested| {
+            if let Some((requested_at, requested_stake)) = requested {
+                if *requested_at == at {
+                    *requested_stake = requested_stake.saturating_sub(stake.0);
+                }
+            }
+        });
+
+        // Any change in the amount of Manual Rewards shares requires to claim manual rewards.
+        if let TargetPool::ManualRewards = pool {
+            Self::claim_manual_rewards(&[(candidate.clone(), delegator.clone())])?;
+        }
+
+        // Shares are created at the current share value, the stake that cannot be converted to
+        // shares due to rounding is released.
+        let (shares, actually_staked) = match pool {
+            TargetPool::AutoCompounding => {
+                let shares =
+                    pools::AutoCompounding::<T>::stake_to_shares_or_init(&candidate, stake)?;
+                let staked =
+                    pools::AutoCompounding::<T>::add_shares(&candidate, &delegator, shares)?;
+                pools::AutoCompounding::<T>::increase_hold(&candidate, &delegator, &staked)?;
+                (shares, staked)
+            }
+            TargetPool::ManualRewards => {
+                let shares = pools::ManualRewards::<T>::stake_to_shares_or_init(&candidate, stake)?;
+                let staked = pools::ManualRewards::<T>::add_shares(&candidate, &delegator, shares)?;
+                pools::ManualRewards::<T>::increase_hold(&candidate, &delegator, &staked)?;
+                (shares, staked)
+            }
+        };
+
+        let release = stake
+            .0
+            .err_sub(&actually_staked.0)
+            .map_err(|_| Error::<T>::MathUnderflow)?;
+        T::Currency::release(
+            &T::CurrencyHoldReason::get(),
+            &delegator,
+            release,
+            Precision::Exact,
+        )?;
+        Candidates::<T>::add_total_stake(&candidate, &actually_staked)?;
+
+        pools::check_candidate_consistency::<T>(&candidate)?;
+
+        let event = match pool {
+            TargetPool::AutoCompounding => Event::<T>::StakedAutoCompounding {
+                candidate: candidate.clone(),
+                delegator: delegator.clone(),
+                shares: shares.0,
+                stake: actually_staked.0,
+            },
+            TargetPool::ManualRewards => Event::<T>::StakedManualRewards {
+                candidate: candidate.clone(),
+                delegator: delegator.clone(),
+                shares: shares.0,
+                stake: actually_staked.0,
+            },
+        };
+        Pallet::<T>::deposit_event(event);
+        Pallet::<T>::deposit_event(Event::<T>::CancelledUndelegate {
+            candidate,
+            delegator,
+            pool,
+            staked: actually_staked.0,
+            released: release,
+        });
+
+        Ok(().into())
+    }
+
     pub fn execute_pending_operations(
+        executor: T::AccountId,
         operations: Vec<PendingOperationQueryOf<T>>,
     ) -> DispatchResultWithPostInfo {
+        let mut count = 0u32;
+        let mut delegators = Vec::new();
+        let mut total_staked = T::Balance::zero();
+        let mut total_released = T::Balance::zero();
+
         for (index, query) in operations.into_iter().enumerate() {
-            // We deconstruct the query and find the balance associated with it.
+            // We find the balance associated with the query.
             // If it is zero it may not exist or have been executed before, thus
             // we simply skip it instead of erroring.
-            let PendingOperationQuery {
-                delegator,
-                operation,
-            } = query;
-
-            let value = PendingOperations::<T>::get(&delegator, &operation);
+            let value = PendingOperations::<T>::get(&query.delegator, &query.operation);
 
             if value.is_zero() {
                 continue;
             }
 
-            match &operation {
-                PendingOperationKey::JoiningAutoCompounding { candidate, at } => {
-                    ensure!(
-                        T::JoiningRequestTimer::is_elapsed(at),
-                        Error::<T>::RequestCannotBeExecuted(index as u16)
-                    );
+            ensure!(
+                Self::is_pending_operation_ready(&query.delegator, &query.operation),
+                Error::<T>::RequestCannotBeExecuted(index as u16)
+            );
 
-                    Self::execute_joining(
-                        candidate.clone(),
-                        delegator.clone(),
-                        TargetPool::AutoCompounding,
-                        Shares(value),
-                    )?;
-                }
-                PendingOperationKey::JoiningManualRewards { candidate, at } => {
-                    ensure!(
-                        T::JoiningRequestTimer::is_elapsed(at),
-                        Error::<T>::RequestCannotBeExecuted(index as u16)
-                    );
+            if !delegators.contains(&query.delegator) {
+                delegators.push(query.delegator.clone());
+            }
+            let (staked, released) = Self::execute_pending_operation(query, value)?;
+            count = count.saturating_add(1);
+            total_staked = total_staked.saturating_add(staked);
+            total_released = total_released.saturating_add(released);
+        }
 
-                    Self::execute_joining(
-                        candidate.clone(),
-                        delegator.clone(),
-                        TargetPool::ManualRewards,
-                        Shares(value),
-                    )?;
-                }
-                PendingOperationKey::Leaving { candidate, at } => {
-                    ensure!(
-                        T::LeavingRequestTimer::is_elapsed(at),
-                        Error::<T>::RequestCannotBeExecuted(index as u16)
-                    );
+        Pallet::<T>::deposit_event(Event::<T>::PendingOperationsExecuted {
+            executor,
+            delegators,
+            count,
+            total_staked,
+            total_released,
+        });
 
-                    Self::execute_leaving(candidate.clone(), delegator.clone(), Shares(value))?;
-                }
+        Ok(().into())
+    }
+
+    pub fn execute_pending_operations_best_effort(
+        operations: Vec<PendingOperationQueryOf<T>>,
+    ) -> DispatchResultWithPostInfo {
+        let mut executed = Vec::new();
+
+        for (index, query) in operations.into_iter().enumerate() {
+            let value = PendingOperations::<T>::get(&query.delegator, &query.operation);
+
+            // Operations that don't exist or are not ready yet are skipped, and remain
+            // pending in the latter case.
+            if value.is_zero()
+                || !Self::is_pending_operation_ready(&query.delegator, &query.operation)
+            {
+                continue;
             }
 
-            PendingOperations::<T>::remove(&delegator, &operation);
+            Self::execute_pending_operation(query, value)?;
+            executed.push(index as u16);
         }
 
+        Pallet::<T>::deposit_event(Event::<T>::ExecutedPendingOperations { executed });
+
         Ok(().into())
     }
 
+    pub fn execute_pending_operations_multi(
+        executor: T::AccountId,
+        operations: Vec<PendingOperationQueryOf<T>>,
+    ) -> DispatchResultWithPostInfo {
+        let mut executed = Vec::new();
+        let mut delegators = Vec::new();
+        let mut total_staked = T::Balance::zero();
+        let mut total_released = T::Balance::zero();
+
+        for (index, query) in operations.into_iter().enumerate() {
+            let value = PendingOperations::<T>::get(&query.delegator, &query.operation);
+
+            // Operations that don't exist for the delegator of the query or are not ready
+            // yet are skipped.
+            if value.is_zero()
+                || !Self::is_pending_operation_ready(&query.delegator, &query.operation)
+            {
+                continue;
+            }
+
+            if !delegators.contains(&query.delegator) {
+                delegators.push(query.delegator.clone());
+            }
+            let (staked, released) = Self::execute_pending_operation(query, value)?;
+            executed.push(index as u16);
+            total_staked = total_staked.saturating_add(staked);
+            total_released = total_released.saturating_add(released);
+        }
+
+        let count = executed.len() as u32;
+        Pallet::<T>::deposit_event(Event::<T>::ExecutedPendingOperations { executed });
+        Pallet::<T>::deposit_event(Event::<T>::PendingOperationsExecuted {
+            executor,
+            delegators,
+            count,
+            total_staked,
+            total_released,
+        });
+
+        Ok(().into())
+    }
+
+    fn is_pending_operation_ready(
+        delegator: &Delegator<T>,
+        operation: &PendingOperationKeyOf<T>,
+    ) -> bool {
+        match operation {
+            PendingOperationKey::JoiningAutoCompounding { at, .. }
+            | PendingOperationKey::JoiningManualRewards { at, .. } => {
+                JoiningDelay::<T>::get().is_elapsed::<T::JoiningRequestTimer>(at)
+                    || (ReducedDelayJoinings::<T>::contains_key(delegator, operation)
+                        && T::JoiningRequestTimer::is_elapsed_after(
+                            at,
+                            &T::ExistingDelegatorJoiningDelay::get(),
+                        ))
+            }
+            PendingOperationKey::Leaving { at, .. } => {
+                LeavingDelay::<T>::get().is_elapsed::<T::LeavingRequestTimer>(at)
+            }
+        }
+    }
+
+    pub fn set_joining_delay(delay: JoiningInstantOf<T>) -> DispatchResultWithPostInfo {
+        JoiningDelay::<T>::try_mutate(|request_delay| {
+            request_delay
+                .set::<T::JoiningRequestTimer>(delay.clone())
+                .map_err(|_| Error::<T>::TooManyDelayChanges)
+        })?;
+
+        Pallet::<T>::deposit_event(Event::<T>::UpdatedJoiningDelay { delay });
+
+        Ok(().into())
+    }
+
+    pub fn set_leaving_delay(delay: LeavingInstantOf<T>) -> DispatchResultWithPostInfo {
+        LeavingDelay::<T>::try_mutate(|request_delay| {
+            request_delay
+                .set::<T::LeavingRequestTimer>(delay.clone())
+                .map_err(|_| Error::<T>::TooManyDelayChanges)
+        })?;
+
+        Pallet::<T>::deposit_event(Event::<T>::UpdatedLeavingDelay { delay });
+
+        Ok(().into())
+    }
+
+    /// Execute a pending operation worth `value` shares, which must be ready.
+    /// Returns the amounts staked and released by the operation.
+    fn execute_pending_operation(
+        query: PendingOperationQueryOf<T>,
+        value: T::Balance,
+    ) -> Result<(T::Balance, T::Balance), DispatchErrorWithPostInfo> {
+        let PendingOperationQuery {
+            delegator,
+            operation,
+        } = query;
+
+        let max_share_price = MaxSharePrices::<T>::get(&delegator, &operation);
+        let executed = match &operation {
+            PendingOperationKey::JoiningAutoCompounding { candidate, .. } => Self::execute_joining(
+                candidate.clone(),
+                delegator.clone(),
+                TargetPool::AutoCompounding,
+                Shares(value),
+                max_share_price,
+            )?,
+            PendingOperationKey::JoiningManualRewards { candidate, .. } => Self::execute_joining(
+                candidate.clone(),
+                delegator.clone(),
+                TargetPool::ManualRewards,
+                Shares(value),
+                max_share_price,
+            )?,
+            PendingOperationKey::Leaving { candidate, .. } => {
+                let released =
+                    Self::execute_leaving(candidate.clone(), delegator.clone(), Shares(value))?;
+                (Zero::zero(), released)
+            }
+        };
+
+        PendingOperations::<T>::remove(&delegator, &operation);
+        ReducedDelayJoinings::<T>::remove(&delegator, &operation);
+        MaxSharePrices::<T>::remove(&delegator, &operation);
+
+        Ok(executed)
+    }
+
+    /// Returns the amounts staked and released.
     fn execute_joining(
         candidate: Candidate<T>,
         delegator: Delegator<T>,
         pool: TargetPool,
         joining_shares: Shares<T::Balance>,
-    ) -> DispatchResultWithPostInfo {
+        max_share_price: Option<T::Balance>,
+    ) -> Result<(T::Balance, T::Balance), DispatchErrorWithPostInfo> {
         // Convert joining shares into stake.
         let stake = pools::Joining::<T>::sub_shares(&candidate, &delegator, joining_shares)?;
 
@@ -357,6 +841,57 @@ impl<T: Config> Calls<T> {
         // be enough hold. Thus no need to rebalance.
         pools::Joining::<T>::decrease_hold(&candidate, &delegator, &stake)?;
 
+        // Candidates that requested to leave no longer accept delegations, requests made
+        // before are refunded.
+        if CandidateLeaveRequests::<T>::contains_key(&candidate) {
+            T::Currency::release(
+                &T::CurrencyHoldReason::get(),
+                &delegator,
+                stake.0,
+                Precision::Exact,
+            )?;
+            Candidates::<T>::sub_total_stake(&candidate, Stake(stake.0))?;
+            Self::remove_delegation_if_empty(&candidate, &delegator);
+            pools::check_candidate_consistency::<T>(&candidate)?;
+
+            Pallet::<T>::deposit_event(Event::<T>::DelegationRefundedCandidateLeaving {
+                candidate: candidate.clone(),
+                delegator,
+                pool,
+                released: stake.0,
+            });
+            Self::remove_candidate_if_left(&candidate);
+
+            return Ok((Zero::zero(), stake.0));
+        }
+
+        // If shares became more expensive than the delegator accepts we refund the stake.
+        if let Some(max_share_price) = max_share_price {
+            let share_price = pools::share_price::<T>(&candidate, pool)?;
+            if share_price > max_share_price {
+                T::Currency::release(
+                    &T::CurrencyHoldReason::get(),
+                    &delegator,
+                    stake.0,
+                    Precision::Exact,
+                )?;
+                Candidates::<T>::sub_total_stake(&candidate, Stake(stake.0))?;
+                Self::remove_delegation_if_empty(&candidate, &delegator);
+                pools::check_candidate_consistency::<T>(&candidate)?;
+
+                Pallet::<T>::deposit_event(Event::<T>::DelegationRefunded {
+                    candidate,
+                    delegator,
+                    pool,
+                    share_price,
+                    max_share_price,
+                    released: stake.0,
+                });
+
+                return Ok((Zero::zero(), stake.0));
+            }
+        }
+
         // Any change in the amount of Manual Rewards shares requires to claim manual rewards.
         if let TargetPool::ManualRewards = pool {
             Self::claim_manual_rewards(&[(candidate.clone(), delegator.clone())])?;
@@ -382,9 +917,11 @@ impl<T: Config> Calls<T> {
             )?;
             Candidates::<T>::sub_total_stake(&candidate, Stake(stake.0))?;
             pools::check_candidate_consistency::<T>(&candidate)?;
-            return Ok(().into());
+            return Ok((Zero::zero(), stake.0));
         }
 
+        let is_new_position = !Self::has_position(&candidate, &delegator);
+
         // We create the new shares. It returns the actual amount of stake those shares
         // represents (due to rounding).
         let actually_staked = match pool {
@@ -434,6 +971,17 @@ impl<T: Config> Calls<T> {
         pools::check_candidate_consistency::<T>(&candidate)?;
 
         Pallet::<T>::deposit_event(event);
+        if !release.is_zero() {
+            Pallet::<T>::deposit_event(Event::<T>::RoundingReleased {
+                candidate: candidate.clone(),
+                delegator: delegator.clone(),
+                amount: release,
+            });
+        }
+        Self::note_joined_stake(&candidate, &delegator, actually_staked.0);
+        if is_new_position {
+            T::OnDelegation::on_position_created(&candidate, &delegator);
+        }
         Pallet::<T>::deposit_event(Event::<T>::ExecutedDelegate {
             candidate,
             delegator,
@@ -442,14 +990,15 @@ impl<T: Config> Calls<T> {
             released: release,
         });
 
-        Ok(().into())
+        Ok((actually_staked.0, release))
     }
 
+    /// Returns the amount released.
     fn execute_leaving(
         candidate: Candidate<T>,
         delegator: Delegator<T>,
         leavinig_shares: Shares<T::Balance>,
-    ) -> DispatchResultWithPostInfo {
+    ) -> Result<T::Balance, DispatchErrorWithPostInfo> {
         // Convert leaving shares into stake.
         let stake = pools::Leaving::<T>::sub_shares(&candidate, &delegator, leavinig_shares)?;
 
@@ -465,13 +1014,110 @@ impl<T: Config> Calls<T> {
             Precision::Exact,
         )?;
 
+        Self::remove_delegation_if_empty(&candidate, &delegator);
+
+        if !Self::has_position(&candidate, &delegator) {
+            RecentlyJoinedStake::<T>::remove(&candidate, &delegator);
+            T::OnDelegation::on_position_removed(&candidate, &delegator);
+        }
         Pallet::<T>::deposit_event(Event::<T>::ExecutedUndelegate {
-            candidate,
+            candidate: candidate.clone(),
             delegator,
             released: stake.0,
         });
+        Self::remove_candidate_if_left(&candidate);
 
-        Ok(().into())
+        Ok(stake.0)
+    }
+
+    /// Stop counting `delegator` as delegating to `candidate` once it has no shares left in
+    /// any of its pools.
+    fn remove_delegation_if_empty(candidate: &Candidate<T>, delegator: &Delegator<T>) {
+        if Self::is_delegating(candidate, delegator) {
+            return;
+        }
+
+        let count = DelegatorsCount::<T>::mutate(candidate, |count| {
+            *count = count.saturating_sub(1);
+            *count
+        });
+        if count.is_zero() {
+            TotalCandidates::<T>::mutate(|total| *total = total.saturating_sub(1));
+            let now = frame_system::Pallet::<T>::block_number();
+            ZeroStakeSince::<T>::insert(candidate, now);
+            ZeroStakeExpiries::<T>::insert(Self::zero_stake_expiry(now), candidate, ());
+        }
+        let is_last_delegation = DelegatorCandidates::<T>::mutate(delegator, |candidates| {
+            candidates.retain(|c| c != candidate);
+            candidates.is_empty()
+        });
+        if is_last_delegation {
+            TotalDelegators::<T>::mutate(|total| *total = total.saturating_sub(1));
+        }
+    }
+
+    /// Whether `delegator` has shares in any pool of `candidate`.
+    fn is_delegating(candidate: &Candidate<T>, delegator: &Delegator<T>) -> bool {
+        !pools::Joining::<T>::shares(candidate, delegator)
+            .0
+            .is_zero()
+            || !pools::AutoCompounding::<T>::shares(candidate, delegator)
+                .0
+                .is_zero()
+            || !pools::ManualRewards::<T>::shares(candidate, delegator)
+                .0
+                .is_zero()
+            || !pools::Leaving::<T>::shares(candidate, delegator)
+                .0
+                .is_zero()
+    }
+
+    /// Whether `delegator` has staked or leaving shares of `candidate`, which make up its
+    /// delegation position. Pending joining requests are not part of it.
+    fn has_position(candidate: &Candidate<T>, delegator: &Delegator<T>) -> bool {
+        !pools::AutoCompounding::<T>::shares(candidate, delegator)
+            .0
+            .is_zero()
+            || !pools::ManualRewards::<T>::shares(candidate, delegator)
+                .0
+                .is_zero()
+            || !pools::Leaving::<T>::shares(candidate, delegator)
+                .0
+                .is_zero()
+    }
+
+    pub fn request_undelegate_all(
+        delegator: Delegator<T>,
+        pool: TargetPool,
+    ) -> DispatchResultWithPostInfo {
+        let mut undelegated = 0u64;
+
+        for candidate in DelegatorCandidates::<T>::get(&delegator) {
+            let shares = match pool {
+                TargetPool::AutoCompounding => {
+                    pools::AutoCompounding::<T>::shares(&candidate, &delegator)
+                }
+                TargetPool::ManualRewards => {
+                    pools::ManualRewards::<T>::shares(&candidate, &delegator)
+                }
+            };
+
+            if shares.0.is_zero() {
+                continue;
+            }
+
+            Self::request_undelegate(
+                candidate,
+                delegator.clone(),
+                pool,
+                SharesOrStake::Shares(shares.0),
+            )?;
+            undelegated += 1;
+        }
+
+        ensure!(undelegated > 0, Error::<T>::NothingToUndelegate);
+
+        Ok(Some(Pallet::<T>::request_undelegate_weight().saturating_mul(undelegated)).into())
     }
 
     pub fn claim_manual_rewards(
@@ -479,14 +1125,22 @@ impl<T: Config> Calls<T> {
     ) -> DispatchResultWithPostInfo {
         for (candidate, delegator) in pairs {
             let Stake(rewards) = pools::ManualRewards::<T>::claim_rewards(candidate, delegator)?;
+            let Stake(auto_rewards) =
+                pools::AutoCompounding::<T>::claim_rewards(candidate, delegator);
+            let rewards = rewards.err_add(&auto_rewards).map_err(Error::<T>::from)?;
 
             if rewards.is_zero() {
                 continue;
             }
 
+            let destination = match RewardDestinations::<T>::get(delegator) {
+                RewardDestination::SelfAccount => delegator.clone(),
+                RewardDestination::Account(account) => account,
+            };
+
             T::Currency::transfer(
                 &T::StakingAccount::get(),
-                delegator,
+                &destination,
                 rewards,
                 Preservation::Preserve,
             )?;
@@ -501,6 +1155,506 @@ impl<T: Config> Calls<T> {
         Ok(().into())
     }
 
+    pub fn set_compounding_preference(
+        candidate: Candidate<T>,
+        delegator: Delegator<T>,
+        preference: Percent,
+    ) -> DispatchResultWithPostInfo {
+        ensure!(
+            !pools::AutoCompounding::<T>::shares(&candidate, &delegator)
+                .0
+                .is_zero(),
+            Error::<T>::NoAutoCompoundingShares
+        );
+
+        // Only preferences below full compounding are stored, as they are iterated over when
+        // distributing rewards.
+        if preference == Percent::from_percent(100) {
+            Self::remove_compounding_preference(&candidate, &delegator);
+        } else {
+            if !CompoundingPreference::<T>::contains_key(&candidate, &delegator) {
+                CompoundingPreferencesCount::<T>::try_mutate(&candidate, |count| {
+                    ensure!(
+                        *count < T::MaxCompoundingPreferencesPerCandidate::get(),
+                        Error::<T>::TooManyCompoundingPreferences
+                    );
+                    *count = count.saturating_add(1);
+                    Ok::<_, Error<T>>(())
+                })?;
+            }
+            CompoundingPreference::<T>::insert(&candidate, &delegator, preference);
+        }
+
+        Pallet::<T>::deposit_event(Event::<T>::UpdatedCompoundingPreference {
+            candidate,
+            delegator,
+            preference,
+        });
+
+        Ok(().into())
+    }
+
+    /// Remove the `CompoundingPreference` of `delegator` for `candidate`, if any.
+    fn remove_compounding_preference(candidate: &Candidate<T>, delegator: &Delegator<T>) {
+        if CompoundingPreference::<T>::take(candidate, delegator) != Percent::from_percent(100) {
+            CompoundingPreferencesCount::<T>::mutate(candidate, |count| {
+                *count = count.saturating_sub(1)
+            });
+        }
+    }
+
+    pub fn set_reward_destination(
+        delegator: Delegator<T>,
+        destination: RewardDestination<T::AccountId>,
+    ) -> DispatchResultWithPostInfo {
+        RewardDestinations::<T>::set(&delegator, destination.clone());
+
+        Pallet::<T>::deposit_event(Event::<T>::UpdatedRewardDestination {
+            delegator,
+            destination,
+        });
+
+        Ok(().into())
+    }
+
+    /// Candidate settings can only be changed by registered candidates or candidates that
+    /// still have stake.
+    fn ensure_candidate(candidate: &Candidate<T>) -> DispatchResult {
+        ensure!(
+            T::RegisteredCandidates::is_candidate_registered(candidate)
+                || !Candidates::<T>::total_stake(candidate).0.is_zero(),
+            Error::<T>::NotACandidate
+        );
+
+        Ok(())
+    }
+
+    pub fn set_commission(
+        candidate: Candidate<T>,
+        commission: Perbill,
+    ) -> DispatchResultWithPostInfo {
+        Self::ensure_candidate(&candidate)?;
+        ensure!(
+            commission <= T::MaxCommission::get(),
+            Error::<T>::CommissionTooHigh
+        );
+
+        // An elapsed increase is applied before being replaced.
+        let current = Pallet::<T>::candidate_commission(&candidate);
+        CandidateCommission::<T>::set(&candidate, current);
+
+        if commission > current {
+            let at = T::JoiningRequestTimer::now();
+            PendingCommissionIncreases::<T>::insert(&candidate, (commission, at.clone()));
+
+            Pallet::<T>::deposit_event(Event::<T>::CommissionIncreaseRequested {
+                candidate,
+                commission,
+                at,
+            });
+        } else {
+            CandidateCommission::<T>::set(&candidate, commission);
+            PendingCommissionIncreases::<T>::remove(&candidate);
+
+            Pallet::<T>::deposit_event(Event::<T>::UpdatedCandidateCommission {
+                candidate,
+                commission,
+            });
+        }
+
+        Ok(().into())
+    }
+
+    pub fn set_candidate_min_delegation(
+        candidate: Candidate<T>,
+        min_delegation: T::Balance,
+    ) -> DispatchResultWithPostInfo {
+        Self::ensure_candidate(&candidate)?;
+
+        CandidateMinDelegation::<T>::set(&candidate, min_delegation);
+
+        Pallet::<T>::deposit_event(Event::<T>::UpdatedCandidateMinDelegation {
+            candidate,
+            min_delegation,
+        });
+
+        Ok(().into())
+    }
+
+    pub fn set_delegation_proxy(
+        delegator: Delegator<T>,
+        proxy: T::AccountId,
+        allowed: bool,
+    ) -> DispatchResultWithPostInfo {
+        if allowed {
+            DelegationProxies::<T>::insert(&delegator, &proxy, ());
+        } else {
+            DelegationProxies::<T>::remove(&delegator, &proxy);
+        }
+
+        Pallet::<T>::deposit_event(Event::<T>::UpdatedDelegationProxy {
+            delegator,
+            proxy,
+            allowed,
+        });
+
+        Ok(().into())
+    }
+
+    pub fn delegate_for(
+        proxy: T::AccountId,
+        owner: Delegator<T>,
+        candidate: Candidate<T>,
+        pool: TargetPool,
+        stake: T::Balance,
+    ) -> DispatchResultWithPostInfo {
+        ensure!(
+            DelegationProxies::<T>::contains_key(&owner, &proxy),
+            Error::<T>::NotAuthorizedProxy
+        );
+
+        Self::request_delegate(candidate, owner, pool, stake, None)
+    }
+
+    pub fn schedule_delegation(
+        candidate: Candidate<T>,
+        delegator: Delegator<T>,
+        pool: TargetPool,
+        stake: T::Balance,
+        activate_at: BlockNumberFor<T>,
+    ) -> DispatchResultWithPostInfo {
+        ensure!(!stake.is_zero(), Error::<T>::StakeMustBeNonZero);
+        ensure!(
+            activate_at > frame_system::Pallet::<T>::block_number(),
+            Error::<T>::ActivationMustBeInFuture
+        );
+
+        ScheduledDelegations::<T>::try_mutate(activate_at, |scheduled| {
+            scheduled
+                .try_push(ScheduledDelegation {
+                    candidate: candidate.clone(),
+                    delegator: delegator.clone(),
+                    pool,
+                    stake,
+                })
+                .map_err(|_| Error::<T>::TooManyScheduledDelegations)
+        })?;
+
+        // Funds are held right away to ensure they are still available at activation.
+        T::Currency::hold(&T::CurrencyHoldReason::get(), &delegator, stake)?;
+
+        Pallet::<T>::deposit_event(Event::<T>::ScheduledDelegation {
+            candidate,
+            delegator,
+            pool,
+            stake,
+            activate_at,
+        });
+
+        Ok(().into())
+    }
+
+    pub fn bonded_delegate(
+        candidate: Candidate<T>,
+        delegator: Delegator<T>,
+        pool: TargetPool,
+        stake: T::Balance,
+        until: BlockNumberFor<T>,
+    ) -> DispatchResultWithPostInfo {
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(until > now, Error::<T>::BondMustEndInFuture);
+
+        let post_info =
+            Self::request_delegate(candidate.clone(), delegator.clone(), pool, stake, None)?;
+
+        let until = Bonds::<T>::try_mutate(&candidate, |bonds| {
+            // Elapsed bonds don't restrict anything anymore.
+            bonds.retain(|bond| bond.until > now);
+
+            match bonds.iter_mut().find(|bond| bond.delegator == delegator) {
+                Some(bond) => {
+                    bond.until = bond.until.max(until);
+                    Ok(bond.until)
+                }
+                None => bonds
+                    .try_push(Bond {
+                        delegator: delegator.clone(),
+                        until,
+                    })
+                    .map(|_| until)
+                    .map_err(|_| Error::<T>::TooManyBonds),
+            }
+        })?;
+
+        Pallet::<T>::deposit_event(Event::<T>::BondedPosition {
+            candidate,
+            delegator,
+            until,
+        });
+
+        Ok(post_info)
+    }
+
+    /// Request the delegations scheduled to activate at block `now`, returning the consumed
+    /// weight.
+    pub fn activate_scheduled_delegations(now: BlockNumberFor<T>) -> Weight {
+        let scheduled = ScheduledDelegations::<T>::take(now);
+        let count = scheduled.len() as u64;
+
+        for ScheduledDelegation {
+            candidate,
+            delegator,
+            pool,
+            stake,
+        } in scheduled
+        {
+            // The request holds the funds again, only for the amount actually represented by
+            // joining shares.
+            let _ = T::Currency::release(
+                &T::CurrencyHoldReason::get(),
+                &delegator,
+                stake,
+                Precision::BestEffort,
+            );
+
+            let res = with_storage_layer(|| {
+                Self::request_delegate(candidate.clone(), delegator.clone(), pool, stake, None)
+            });
+
+            if res.is_err() {
+                Pallet::<T>::deposit_event(Event::<T>::ScheduledDelegationFailed {
+                    candidate,
+                    delegator,
+                    stake,
+                });
+            }
+        }
+
+        T::DbWeight::get()
+            .reads_writes(1, 1)
+            .saturating_add(T::WeightInfo::request_delegate().saturating_mul(count))
+    }
+
+    pub fn request_candidate_leave(candidate: Candidate<T>) -> DispatchResultWithPostInfo {
+        ensure!(
+            !CandidateLeaveRequests::<T>::contains_key(&candidate),
+            Error::<T>::CandidateLeaving
+        );
+        ensure!(
+            !Candidates::<T>::total_stake(&candidate).0.is_zero(),
+            Error::<T>::NotACandidate
+        );
+
+        let at =
+            frame_system::Pallet::<T>::block_number().saturating_add(T::CandidateLeaveDelay::get());
+        CandidateLeaveSchedule::<T>::try_mutate(at, |scheduled| {
+            scheduled
+                .try_push(candidate.clone())
+                .map_err(|_| Error::<T>::TooManyCandidateLeaves)
+        })?;
+        CandidateLeaveRequests::<T>::insert(&candidate, at);
+
+        Pallet::<T>::deposit_event(Event::<T>::CandidateLeaveRequested { candidate, at });
+
+        Ok(().into())
+    }
+
+    /// Move the delegations of the candidates whose leave request is due to the leaving pool,
+    /// returning the consumed weight. At most `MaxCandidateLeaveEntriesPerBlock` `Pools`
+    /// entries are processed per block, candidates with more entries being processed over
+    /// the following blocks. Delegations that cannot be moved are reported with
+    /// `CandidateLeaveUndelegationFailed` and not retried.
+    pub fn process_candidate_leave_requests(now: BlockNumberFor<T>) -> Weight {
+        let mut weight = T::DbWeight::get().reads_writes(1, 1);
+
+        for candidate in CandidateLeaveSchedule::<T>::take(now) {
+            DueCandidateLeaves::<T>::insert(&candidate, None::<PoolsKey<T::AccountId>>);
+            weight.saturating_accrue(T::DbWeight::get().writes(1));
+        }
+
+        let mut budget = T::MaxCandidateLeaveEntriesPerBlock::get();
+        while !budget.is_zero() {
+            let Some((candidate, cursor)) = DueCandidateLeaves::<T>::iter().next() else {
+                break;
+            };
+            budget -= 1;
+            weight.saturating_accrue(T::DbWeight::get().reads(1));
+
+            let mut entries = match &cursor {
+                Some(key) => Pools::<T>::iter_prefix_from(
+                    &candidate,
+                    Pools::<T>::hashed_key_for(&candidate, key),
+                ),
+                None => Pools::<T>::iter_prefix(&candidate),
+            };
+            let mut cursor = cursor;
+            let mut finished = true;
+
+            loop {
+                if budget.is_zero() {
+                    finished = false;
+                    break;
+                }
+                let Some((key, shares)) = entries.next() else {
+                    break;
+                };
+                budget -= 1;
+                weight.saturating_accrue(T::DbWeight::get().reads(1));
+
+                let delegation = match &key {
+                    PoolsKey::AutoCompoundingShares { delegator } => {
+                        Some((delegator.clone(), TargetPool::AutoCompounding))
+                    }
+                    PoolsKey::ManualRewardsShares { delegator } => {
+                        Some((delegator.clone(), TargetPool::ManualRewards))
+                    }
+                    _ => None,
+                };
+
+                if let Some((delegator, pool)) = delegation.filter(|_| !shares.is_zero()) {
+                    let res = with_storage_layer(|| {
+                        Self::undelegate(
+                            candidate.clone(),
+                            delegator.clone(),
+                            pool,
+                            SharesOrStake::Shares(shares),
+                            true,
+                        )
+                    });
+                    weight.saturating_accrue(Pallet::<T>::request_undelegate_weight());
+
+                    if let Err(err) = res {
+                        Pallet::<T>::deposit_event(Event::<T>::CandidateLeaveUndelegationFailed {
+                            candidate: candidate.clone(),
+                            delegator,
+                            pool,
+                            error: err.error,
+                        });
+                    }
+                }
+
+                cursor = Some(key);
+            }
+
+            weight.saturating_accrue(T::DbWeight::get().writes(1));
+            if finished {
+                DueCandidateLeaves::<T>::remove(&candidate);
+                Self::remove_candidate_if_left(&candidate);
+                weight.saturating_accrue(T::DbWeight::get().reads_writes(4, 1));
+            } else {
+                DueCandidateLeaves::<T>::insert(&candidate, cursor);
+            }
+        }
+
+        weight
+    }
+
+    /// Remove the leave request of `candidate` once all its delegations have been moved to
+    /// the leaving pool and it has no stake left, including leaving and joining stake.
+    fn remove_candidate_if_left(candidate: &Candidate<T>) {
+        let now = frame_system::Pallet::<T>::block_number();
+        match CandidateLeaveRequests::<T>::get(candidate) {
+            Some(at) if at <= now => (),
+            _ => return,
+        }
+        if DueCandidateLeaves::<T>::contains_key(candidate) {
+            return;
+        }
+
+        // The total stake includes the joining stake.
+        let has_stake = !Candidates::<T>::total_stake(candidate).0.is_zero()
+            || !pools::Leaving::<T>::shares_supply(candidate).0.is_zero();
+        if !has_stake {
+            CandidateLeaveRequests::<T>::remove(candidate);
+            Pallet::<T>::deposit_event(Event::<T>::CandidateLeft {
+                candidate: candidate.clone(),
+            });
+        }
+    }
+
+    /// Purge the candidates left without stake for `ZeroStakeCandidateGracePeriod` blocks,
+    /// returning the consumed weight. At most `MaxPurgedEntriesPerBlock` entries of `Pools`,
+    /// `ShareValueHistory` and `CompoundingPreference` are removed per block, candidates with
+    /// more entries being purged over the following blocks.
+    pub fn purge_zero_stake_candidates(now: BlockNumberFor<T>) -> Weight {
+        let mut weight = T::DbWeight::get().reads(1);
+
+        for (candidate, ()) in ZeroStakeExpiries::<T>::drain_prefix(now) {
+            weight.saturating_accrue(T::DbWeight::get().reads_writes(2, 1));
+
+            // The expiry may be outdated if the grace period changed.
+            match ZeroStakeSince::<T>::get(&candidate) {
+                Some(since) if Self::zero_stake_expiry(since) <= now => (),
+                _ => continue,
+            }
+
+            ZeroStakeSince::<T>::remove(&candidate);
+            SortedEligibleCandidates::<T>::mutate(|list| {
+                list.retain(|entry| entry.candidate != candidate)
+            });
+            EligibleSince::<T>::remove(&candidate);
+            DelegatorsCount::<T>::remove(&candidate);
+            CandidateCommission::<T>::remove(&candidate);
+            PendingCommissionIncreases::<T>::remove(&candidate);
+            CandidateMinDelegation::<T>::remove(&candidate);
+            CompoundingPreferencesCount::<T>::remove(&candidate);
+            LeavingStakeRequested::<T>::remove(&candidate);
+            Bonds::<T>::remove(&candidate);
+            PurgingCandidates::<T>::insert(&candidate, ());
+            weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 11));
+        }
+
+        let mut limit = T::MaxPurgedEntriesPerBlock::get();
+        while !limit.is_zero() {
+            let Some(candidate) = PurgingCandidates::<T>::iter_keys().next() else {
+                break;
+            };
+            weight.saturating_accrue(T::DbWeight::get().reads(1));
+
+            // Pools keep zero entries once their shares are removed.
+            let clears: [&dyn Fn(u32) -> MultiRemovalResults; 3] = [
+                &|limit| Pools::<T>::clear_prefix(&candidate, limit, None),
+                &|limit| ShareValueHistory::<T>::clear_prefix(&candidate, limit, None),
+                &|limit| CompoundingPreference::<T>::clear_prefix(&candidate, limit, None),
+            ];
+            let mut cleared = true;
+            for clear in clears {
+                if limit.is_zero() {
+                    cleared = false;
+                    break;
+                }
+
+                let res = clear(limit);
+                limit = limit.saturating_sub(res.loops);
+                weight.saturating_accrue(
+                    T::DbWeight::get().reads_writes(res.loops.into(), res.unique.into()),
+                );
+
+                if res.maybe_cursor.is_some() {
+                    cleared = false;
+                    break;
+                }
+            }
+
+            if !cleared {
+                break;
+            }
+
+            PurgingCandidates::<T>::remove(&candidate);
+            weight.saturating_accrue(T::DbWeight::get().writes(1));
+            Pallet::<T>::deposit_event(Event::<T>::CandidatePurged { candidate });
+        }
+
+        weight
+    }
+
+    /// Block at which a candidate without stake since `since` starts being purged. It is
+    /// never the current block, which is already initialized.
+    fn zero_stake_expiry(since: BlockNumberFor<T>) -> BlockNumberFor<T> {
+        let grace_period = T::ZeroStakeCandidateGracePeriod::get().max(One::one());
+        since.saturating_add(grace_period)
+    }
+
     pub fn update_candidate_position(candidates: &[Candidate<T>]) -> DispatchResultWithPostInfo {
         for candidate in candidates {
             let stake = Candidates::<T>::total_stake(candidate);
@@ -17,8 +17,9 @@
 use {
     crate::{
         pools::{self, Pool},
-        traits::{ErrAdd, ErrSub, IsCandidateEligible},
-        Candidate, Config, Error, Event, Pallet, Pools, PoolsKey, SortedEligibleCandidates, Stake,
+        traits::{ErrAdd, ErrMul, ErrSub, IsCandidateEligible},
+        Candidate, Config, EligibleSince, Error, Event, Pallet, Pools, PoolsKey,
+        SortedEligibleCandidates, Stake,
     },
     core::{cmp::Ordering, marker::PhantomData},
     parity_scale_codec::{Decode, Encode},
@@ -38,6 +39,8 @@ pub struct EligibleCandidate<C, S> {
     pub stake: S,
 }
 
+/// Order by decreasing stake then by candidate. `SortedEligibleCandidates` also takes into
+/// account when candidates became eligible, see `Candidates::cmp_eligible`.
 impl<C: Ord, S: Ord> Ord for EligibleCandidate<C, S> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.stake
@@ -104,7 +107,6 @@ impl<T: Config> Candidates<T> {
         candidate: &Candidate<T>,
         new_stake: Stake<T::Balance>,
     ) -> Result<(), Error<T>> {
-        let stake_before = Pools::<T>::get(candidate, &PoolsKey::CandidateTotalStake);
         Pools::<T>::set(candidate, &PoolsKey::CandidateTotalStake, new_stake.0);
 
         // Compute self delegation.
@@ -140,30 +142,34 @@ impl<T: Config> Candidates<T> {
         let mut list = SortedEligibleCandidates::<T>::get();
 
         // Remove old data if it exists.
-        let old_position = match list.binary_search(&EligibleCandidate {
-            candidate: candidate.clone(),
-            stake: stake_before,
-        }) {
-            Ok(pos) => {
+        // Entries are sorted by weighted stake, which depends on the previous self
+        // delegation of the candidate. We thus look for the entry of the candidate directly.
+        let old_position = match list.iter().position(|entry| &entry.candidate == candidate) {
+            Some(pos) => {
                 let _ = list.remove(pos);
                 Some(pos as u32)
             }
-            Err(_) => None,
+            None => None,
         };
 
+        let was_eligible = EligibleSince::<T>::contains_key(candidate);
         let eligible = self_delegation >= T::MinimumSelfDelegation::get()
             && T::EligibleCandidatesFilter::is_candidate_eligible(&candidate);
 
         // Find new position in the sorted list.
         // It will not be inserted if under the minimum self delegation.
         let new_position = if eligible {
+            if !EligibleSince::<T>::contains_key(candidate) {
+                EligibleSince::<T>::insert(candidate, frame_system::Pallet::<T>::block_number());
+            }
+
             let entry = EligibleCandidate {
                 candidate: candidate.clone(),
-                stake: new_stake.0,
+                stake: Self::weighted_stake(new_stake.0, self_delegation)?,
             };
 
             let pos = list
-                .binary_search(&entry)
+                .binary_search_by(|other| Self::cmp_eligible(other, &entry))
                 .expect_err("Candidate should be present at most once in the list.");
 
             if pos >= T::EligibleCandidatesBufferSize::get() as usize {
@@ -180,6 +186,7 @@ impl<T: Config> Candidates<T> {
                 Some(pos as u32)
             }
         } else {
+            EligibleSince::<T>::remove(candidate);
             None
         };
 
@@ -191,8 +198,41 @@ impl<T: Config> Candidates<T> {
             after: new_position,
         });
 
+        if eligible != was_eligible {
+            Pallet::<T>::deposit_event(Event::<T>::CandidateEligibilityChanged {
+                candidate: candidate.clone(),
+                eligible,
+            });
+        }
+
         SortedEligibleCandidates::<T>::set(list);
 
         Ok(())
     }
+
+    /// Order of `SortedEligibleCandidates`: by decreasing stake, then by the block at which
+    /// the candidates became eligible, then by account id. This keeps the order of candidates
+    /// with equal stake stable regardless of the order in which their stake was updated.
+    pub fn cmp_eligible(
+        a: &EligibleCandidate<Candidate<T>, T::Balance>,
+        b: &EligibleCandidate<Candidate<T>, T::Balance>,
+    ) -> Ordering {
+        b.stake
+            .cmp(&a.stake)
+            .then_with(|| {
+                EligibleSince::<T>::get(&a.candidate).cmp(&EligibleSince::<T>::get(&b.candidate))
+            })
+            .then_with(|| a.candidate.cmp(&b.candidate))
+    }
+
+    /// Stake used to sort eligible candidates, in which self delegation is counted
+    /// `SelfDelegationMultiplier` times.
+    pub fn weighted_stake(
+        total_stake: T::Balance,
+        self_delegation: T::Balance,
+    ) -> Result<T::Balance, Error<T>> {
+        let extra_weight = T::SelfDelegationMultiplier::get().saturating_sub(1);
+        let bonus = self_delegation.err_mul(&extra_weight.into())?;
+        Ok(total_stake.err_add(&bonus)?)
+    }
 }
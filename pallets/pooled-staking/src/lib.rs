@@ -59,7 +59,11 @@ pub mod pallet {
     use {
         super::*,
         crate::{
-            traits::{IsCandidateEligible, Timer},
+            pools::Pool,
+            traits::{
+                IsCandidateEligible, IsCandidateLeaving, IsCandidateRegistered, MulDiv,
+                OnDelegation, PoolRewardWeight, Timer,
+            },
             weights::WeightInfo,
         },
         calls::Calls,
@@ -67,14 +71,21 @@ pub mod pallet {
         frame_support::{
             pallet_prelude::*,
             storage::types::{StorageDoubleMap, StorageValue, ValueQuery},
-            traits::{fungible, tokens::Balance, IsType},
+            traits::{
+                fungible::{self, Inspect, Mutate},
+                tokens::{Balance, Fortitude, Preservation},
+                IsType,
+            },
             Blake2_128Concat, RuntimeDebug,
         },
         frame_system::pallet_prelude::*,
         parity_scale_codec::{Decode, Encode, FullCodec},
         scale_info::TypeInfo,
         sp_core::Get,
-        sp_runtime::{BoundedVec, Perbill},
+        sp_runtime::{
+            traits::{Saturating, Zero},
+            BoundedVec, Perbill, Percent, Permill,
+        },
         sp_std::vec::Vec,
     };
 
@@ -138,6 +149,10 @@ pub mod pallet {
         LeavingSharesTotalStaked,
         /// Amount of currency held in the delegator account.
         LeavingSharesHeldStake { delegator: A },
+
+        /// Part of the auto compounding rewards of a delegator that is not compounded
+        /// according to its `CompoundingPreference`, waiting to be claimed.
+        AutoCompoundingClaimableRewards { delegator: A },
     }
 
     /// Key used by the "PendingOperations" StorageDoubleMap.
@@ -160,6 +175,126 @@ pub mod pallet {
         <<T as Config>::LeavingRequestTimer as Timer>::Instant,
     >;
 
+    pub type JoiningInstantOf<T> = <<T as Config>::JoiningRequestTimer as Timer>::Instant;
+    pub type LeavingInstantOf<T> = <<T as Config>::LeavingRequestTimer as Timer>::Instant;
+
+    /// Kind of pending operation holding funds of a delegator.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo)]
+    pub enum LockKind {
+        /// Funds are staked once the joining operation is executed.
+        Joining,
+        /// Funds are released once the leaving operation is executed.
+        Leaving,
+    }
+
+    /// Funds of a delegator held by a pending operation towards `candidate`, which can be
+    /// executed from `unlock_at`.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+    pub struct Lock<A, B, I> {
+        pub candidate: A,
+        pub amount: B,
+        pub unlock_at: I,
+        pub kind: LockKind,
+    }
+
+    pub type LockOf<T> =
+        Lock<<T as frame_system::Config>::AccountId, <T as Config>::Balance, JoiningInstantOf<T>>;
+
+    /// Maximum number of previous delays kept in a `RequestDelay`. They are forgotten once all
+    /// the requests they apply to are elapsed.
+    pub const MAX_PREVIOUS_REQUEST_DELAYS: usize = 10;
+
+    /// Delay after which joining or leaving requests can be executed. Previous delays are kept
+    /// so that pending requests are executed with the delay they were made with.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, Default, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+    pub struct RequestDelay<Instant> {
+        /// Delay of the requests made now, `None` for the default delay of the timer.
+        pub current: Option<Instant>,
+        /// Previous delays as `(until, delay)` pairs in chronological order. Requests made at
+        /// or before `until`, and after the previous `until`, use `delay`.
+        pub previous: Vec<(Instant, Instant)>,
+        /// Requests made at or before this instant are elapsed, their delay was forgotten.
+        pub elapsed_until: Option<Instant>,
+    }
+
+    impl<Instant: Clone + Ord> RequestDelay<Instant> {
+        /// Delay of the requests made now.
+        pub fn current<Tm: Timer<Instant = Instant>>(&self) -> Instant {
+            self.current.clone().unwrap_or_else(Tm::default_delay)
+        }
+
+        /// Delay of a request made at `start`, `None` if the request is elapsed and its delay
+        /// was forgotten.
+        fn delay_of<Tm: Timer<Instant = Instant>>(&self, start: &Instant) -> Option<Instant> {
+            if self
+                .elapsed_until
+                .as_ref()
+                .map_or(false, |until| start <= until)
+            {
+                return None;
+            }
+
+            let delay = self
+                .previous
+                .iter()
+                .find(|(until, _)| start <= until)
+                .map(|(_, delay)| delay.clone())
+                .unwrap_or_else(|| self.current::<Tm>());
+
+            Some(delay)
+        }
+
+        /// Check if a request made at `start` is elapsed, using the delay of that time.
+        pub fn is_elapsed<Tm: Timer<Instant = Instant>>(&self, start: &Instant) -> bool {
+            self.delay_of::<Tm>(start)
+                .map_or(true, |delay| Tm::is_elapsed_after(start, &delay))
+        }
+
+        /// Instant at which a request made at `start` is elapsed, using the delay of that time.
+        /// The current instant is returned if the delay was forgotten, as the request is already
+        /// elapsed. `None` if it overflows.
+        pub fn elapsed_at<Tm: Timer<Instant = Instant>>(&self, start: &Instant) -> Option<Instant> {
+            match self.delay_of::<Tm>(start) {
+                Some(delay) => Tm::elapsed_at(start, &delay),
+                None => Some(Tm::now()),
+            }
+        }
+
+        /// Use `delay` for the requests made after the current instant. Returns an error if
+        /// too many previous delays still apply to pending requests.
+        pub fn set<Tm: Timer<Instant = Instant>>(&mut self, delay: Instant) -> Result<(), ()> {
+            let now = Tm::now();
+            // If the delay already changed during this instant, requests made now before that
+            // change are already covered.
+            if self
+                .previous
+                .last()
+                .map_or(true, |(until, _)| *until != now)
+            {
+                self.previous.push((now, self.current::<Tm>()));
+            }
+            self.current = Some(delay);
+
+            // Forget the oldest delays once all the requests they apply to are elapsed.
+            while let Some((until, delay)) = self.previous.first() {
+                if !Tm::is_elapsed_after(until, delay) {
+                    break;
+                }
+                self.elapsed_until = Some(until.clone());
+                self.previous.remove(0);
+            }
+
+            if self.previous.len() > MAX_PREVIOUS_REQUEST_DELAYS {
+                return Err(());
+            }
+
+            Ok(())
+        }
+    }
+
     #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
     #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
     pub struct PendingOperationQuery<A: FullCodec, J: FullCodec, L: FullCodec> {
@@ -180,6 +315,26 @@ pub mod pallet {
         ManualRewards,
     }
 
+    /// Chain on which a rewarded collator authored blocks.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo)]
+    pub enum AuthorRole {
+        Orchestrator,
+        Container,
+    }
+
+    /// Account receiving the manual rewards claimed by a delegator. Auto compounding rewards
+    /// are not affected as they stay in the pool.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo, Default)]
+    pub enum RewardDestination<AccountId> {
+        /// The delegator account.
+        #[default]
+        SelfAccount,
+        /// Another account.
+        Account(AccountId),
+    }
+
     #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
     #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo)]
     pub enum AllTargetPool {
@@ -209,6 +364,75 @@ pub mod pallet {
         Stake(T),
     }
 
+    /// Headline numbers of the pallet, see `Pallet::stats`.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, Default, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+    pub struct StakingStats<Balance> {
+        /// Number of candidates with at least one delegator, including themselves.
+        pub total_candidates: u32,
+        /// Number of accounts delegating towards at least one candidate.
+        pub total_delegators: u32,
+        /// Stake of all candidates combined, excluding the stake leaving them.
+        pub total_staked: Balance,
+    }
+
+    /// Maximum number of delegators returned by `Pallet::candidate_delegators` at once.
+    pub const MAX_CANDIDATE_DELEGATORS_PAGE: u32 = 100;
+
+    /// Stake of a delegator in each pool of a candidate, see `Pallet::candidate_delegators`.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, Default, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+    pub struct PoolsStake<Balance> {
+        pub joining: Balance,
+        pub auto_compounding: Balance,
+        pub manual_rewards: Balance,
+        pub leaving: Balance,
+    }
+
+    /// Value of the shares of a pool at the start of a session.
+    /// For the auto compounding pool it is the stake backing a single share, while for the
+    /// manual rewards pool (whose shares have a constant value) it is the rewards counter.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo)]
+    pub struct ShareValueSnapshot<Balance> {
+        pub session: u32,
+        pub value: Balance,
+    }
+
+    /// Delegation scheduled with `schedule_delegation`, whose funds are held until it is
+    /// requested at its activation block.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+    pub struct ScheduledDelegation<AccountId, Balance> {
+        pub candidate: AccountId,
+        pub delegator: AccountId,
+        pub pool: TargetPool,
+        pub stake: Balance,
+    }
+
+    pub type ScheduledDelegationOf<T> =
+        ScheduledDelegation<<T as frame_system::Config>::AccountId, <T as Config>::Balance>;
+
+    /// Position of a delegator made with `bonded_delegate`, which cannot be undelegated
+    /// before block `until`.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+    pub struct Bond<AccountId, BlockNumber> {
+        pub delegator: AccountId,
+        pub until: BlockNumber,
+    }
+
+    pub type BondOf<T> = Bond<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+    /// Value of a share of the joining and leaving pools. Stake entering those pools is rounded
+    /// down to a multiple of it, the remainder being released.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo)]
+    pub struct SharePrecision<Balance> {
+        pub joining: Balance,
+        pub leaving: Balance,
+    }
+
     /// Wrapper type for an amount of shares.
     #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
     #[derive(RuntimeDebug, Default, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo)]
@@ -247,6 +471,9 @@ pub mod pallet {
 
         /// Account holding Currency of all delegators.
         type StakingAccount: Get<Self::AccountId>;
+        /// Account funding the rewards. They are moved from it to the staking account when
+        /// distributed. Must be distinct from `StakingAccount`.
+        type RewardPot: Get<Self::AccountId>;
 
         /// When creating the first Shares for a candidate the supply can be arbitrary.
         /// Picking a value too low will make an higher supply, which means each share will get
@@ -257,30 +484,112 @@ pub mod pallet {
         /// Picking a value too high is a barrier of entry for staking, which will increase overtime
         /// as the value of each share will increase due to auto compounding.
         type InitialAutoCompoundingShareValue: Get<Self::Balance>;
+        /// Share values of the joining and leaving pools, which are never rewarded. Higher values
+        /// round joining and leaving stake in larger steps, releasing more dust. A value of 1
+        /// doesn't round stake at all.
+        type SharePrecision: Get<SharePrecision<Self::Balance>>;
 
         /// Minimum amount of stake a Candidate must delegate (stake) towards itself. Not reaching
         /// this minimum prevents from being elected.
         type MinimumSelfDelegation: Get<Self::Balance>;
+        /// How many times the self delegation of a candidate is counted when sorting
+        /// eligible candidates. A value of 1 counts self delegation like any other stake,
+        /// while higher values favor candidates with more stake of their own.
+        type SelfDelegationMultiplier: Get<u32>;
         /// Part of the rewards that will be sent exclusively to the collator.
         type RewardsCollatorCommission: Get<Perbill>;
+        /// Maximum commission a candidate can take on the rewards of its delegators.
+        type MaxCommission: Get<Perbill>;
+        /// Multiplier, in percent, applied to the rewards of blocks authored on the
+        /// orchestrator chain. A value of 100 rewards them like container chain blocks.
+        type OrchestratorRewardMultiplier: Get<u32>;
+        /// Weight of each pool when splitting delegators rewards between the pools of a
+        /// candidate, allowing to favor auto compounding or manual claim delegators.
+        type PoolRewardWeight: PoolRewardWeight;
 
         /// Condition for when a joining request can be executed.
         type JoiningRequestTimer: Timer;
+        /// Delay of the joining requests of delegators that already have shares in the target
+        /// pool of the candidate. They can still be executed after the joining delay if it is
+        /// shorter.
+        type ExistingDelegatorJoiningDelay: Get<JoiningInstantOf<Self>>;
         /// Condition for when a leaving request can be executed.
         type LeavingRequestTimer: Timer;
+        /// Maximum stake that can be requested to leave a candidate during a single instant
+        /// of the `LeavingRequestTimer` (a session in the runtime).
+        type MaxLeavingStakePerSession: Get<Self::Balance>;
+        /// Number of sessions for which the share value of each pool is kept in
+        /// `ShareValueHistory`.
+        type ShareValueHistoryDepth: Get<u32>;
         /// All eligible candidates are stored in a sorted list that is modified each time
         /// delegations changes. It is safer to bound this list, in which case eligible candidate
         /// could fall out of this list if they have less stake than the top `EligibleCandidatesBufferSize`
         /// eligible candidates. One of this top candidates leaving will then not bring the dropped candidate
         /// in the list. An extrinsic is available to manually bring back such dropped candidate.
         type EligibleCandidatesBufferSize: Get<u32>;
+        /// Account receiving the dust released when converting stake to leaving shares. If
+        /// `None` the dust is released in the delegator account.
+        type DustCollector: Get<Option<Self::AccountId>>;
+        /// Number of blocks after a delegation is executed during which undelegating the stake
+        /// it joined is penalized.
+        type MinStakeDuration: Get<BlockNumberFor<Self>>;
+        /// Part of the recently joined stake undelegated before `MinStakeDuration` taken as a
+        /// penalty. Zero disables the penalty.
+        type EarlyExitPenalty: Get<Permill>;
+        /// Account receiving the early undelegation penalties.
+        type EarlyExitPenaltyDestination: Get<Self::AccountId>;
+        /// Maximum number of delegators a candidate can have, counting the candidate itself if it
+        /// delegates towards itself. Existing delegators can still increase their stake.
+        type MaxDelegatorsPerCandidate: Get<u32>;
+        /// Maximum stake of all candidates combined, excluding the stake leaving them. Delegations
+        /// that would exceed it are rejected.
+        type MaxTotalStake: Get<Self::Balance>;
+        /// Maximum number of candidates a delegator can have stake in. Also bounds the number
+        /// of candidates `request_undelegate_all` undelegates from.
+        type MaxDelegationsPerDelegator: Get<u32>;
+        /// Maximum number of delegations that can be scheduled to activate at the same block.
+        type MaxScheduledDelegationsPerBlock: Get<u32>;
+        /// Maximum number of bonded positions made with `bonded_delegate` a candidate can
+        /// have at the same time.
+        type MaxBondsPerCandidate: Get<u32>;
+        /// Maximum number of delegators of a candidate compounding only part of their auto
+        /// compounding rewards, which bounds the work done when rewarding the candidate.
+        type MaxCompoundingPreferencesPerCandidate: Get<u32>;
+        /// Origin allowed to change the delays of joining and leaving requests.
+        type RequestDelayOrigin: EnsureOrigin<Self::RuntimeOrigin>;
         /// Additional filter for candidates to be eligible.
         type EligibleCandidatesFilter: IsCandidateEligible<Self::AccountId>;
+        /// Accounts registered as candidates. Other accounts can only delegate to themselves,
+        /// which is how candidates join.
+        type RegisteredCandidates: IsCandidateRegistered<Self::AccountId>;
+        /// Candidates that requested to leave, which cannot receive new delegations.
+        type LeavingCandidates: IsCandidateLeaving<Self::AccountId>;
+        /// Number of blocks between a candidate calling `request_candidate_leave` and its
+        /// delegations being moved to the leaving pool.
+        type CandidateLeaveDelay: Get<BlockNumberFor<Self>>;
+        /// Maximum number of candidates whose delegations start being moved to the leaving
+        /// pool at the same block.
+        type MaxCandidateLeavesPerBlock: Get<u32>;
+        /// Maximum number of `Pools` entries of leaving candidates processed in a single
+        /// block. Candidates with more entries are processed over several blocks.
+        type MaxCandidateLeaveEntriesPerBlock: Get<u32>;
+        /// Number of blocks a candidate can stay without any stake, including leaving stake,
+        /// before its remaining storage is purged.
+        type ZeroStakeCandidateGracePeriod: Get<BlockNumberFor<Self>>;
+        /// Maximum number of storage entries of purged candidates removed in a single block.
+        /// Candidates with more entries are purged over several blocks.
+        type MaxPurgedEntriesPerBlock: Get<u32>;
+        /// Notified when a delegation position is created or removed. Use `()` if positions
+        /// are not represented outside of the pallet.
+        type OnDelegation: OnDelegation<Self::AccountId>;
 
         type WeightInfo: WeightInfo;
     }
 
-    /// Keeps a list of all eligible candidates, sorted by the amount of stake backing them.
+    /// Keeps a list of all eligible candidates, sorted by the amount of stake backing them
+    /// (with self delegation weighted by `SelfDelegationMultiplier`).
+    /// Candidates with equal stake are sorted by the block at which they became eligible
+    /// (see `EligibleSince`), then by account id.
     /// This can be quickly updated using a binary search, and allow to easily take the top
     /// `MaxCollatorSetSize`.
     #[pallet::storage]
@@ -293,6 +602,13 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Block at which each eligible candidate became eligible, used to break ties between
+    /// candidates with equal stake in `SortedEligibleCandidates`. Removed once the candidate is
+    /// no longer eligible.
+    #[pallet::storage]
+    pub type EligibleSince<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, T::BlockNumber, OptionQuery>;
+
     /// Pools balances.
     #[pallet::storage]
     pub type Pools<T: Config> = StorageDoubleMap<
@@ -318,6 +634,257 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    #[pallet::type_value]
+    pub fn DefaultCompoundingPreference() -> Percent {
+        Percent::from_percent(100)
+    }
+
+    /// Part of the auto compounding rewards of a delegator from a candidate that is compounded.
+    /// The rest is removed from its auto compounding stake and can be claimed like manual
+    /// rewards. Defaults to 100%, which is not stored. Removed once the delegator has no auto
+    /// compounding shares left.
+    #[pallet::storage]
+    pub type CompoundingPreference<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        Candidate<T>,
+        Blake2_128Concat,
+        Delegator<T>,
+        Percent,
+        ValueQuery,
+        DefaultCompoundingPreference,
+    >;
+
+    /// Number of `CompoundingPreference` entries of each candidate.
+    #[pallet::storage]
+    pub type CompoundingPreferencesCount<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, u32, ValueQuery>;
+
+    /// Account to which the manual rewards claimed by a delegator are paid.
+    /// Defaults to the delegator account.
+    #[pallet::storage]
+    pub type RewardDestinations<T: Config> =
+        StorageMap<_, Blake2_128Concat, Delegator<T>, RewardDestination<T::AccountId>, ValueQuery>;
+
+    /// Commission taken by each candidate on the rewards of its delegators.
+    /// Defaults to 0%. Increases only apply after a delay, see `PendingCommissionIncreases`.
+    #[pallet::storage]
+    pub type CandidateCommission<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, Perbill, ValueQuery>;
+
+    /// Commission increase requested by each candidate, along with the instant of the
+    /// `JoiningRequestTimer` at which it was requested. It applies once the joining delay is
+    /// elapsed, giving delegators time to undelegate at the previous commission.
+    #[pallet::storage]
+    pub type PendingCommissionIncreases<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, (Perbill, JoiningInstantOf<T>), OptionQuery>;
+
+    /// Minimum stake a new delegator must request to delegate towards each candidate, set by
+    /// the candidate. Defaults to 0.
+    #[pallet::storage]
+    pub type CandidateMinDelegation<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, T::Balance, ValueQuery>;
+
+    /// Stake requested to leave each candidate, along with the instant of the
+    /// `LeavingRequestTimer` at which it was requested.
+    #[pallet::storage]
+    pub type LeavingStakeRequested<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        Candidate<T>,
+        (<T::LeavingRequestTimer as Timer>::Instant, T::Balance),
+        OptionQuery,
+    >;
+
+    /// Number of delegators having stake in any pool of each candidate.
+    #[pallet::storage]
+    pub type DelegatorsCount<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, u32, ValueQuery>;
+
+    /// Number of candidates with a non-zero `DelegatorsCount`.
+    #[pallet::storage]
+    pub type TotalCandidates<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Number of delegators with a non-empty `DelegatorCandidates`.
+    #[pallet::storage]
+    pub type TotalDelegators<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Candidates in which each delegator has stake in any pool, in delegation order.
+    #[pallet::storage]
+    pub type DelegatorCandidates<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        Delegator<T>,
+        BoundedVec<Candidate<T>, T::MaxDelegationsPerDelegator>,
+        ValueQuery,
+    >;
+
+    /// Accounts allowed by a delegator to delegate its funds on its behalf
+    /// (see `delegate_for`).
+    #[pallet::storage]
+    pub type DelegationProxies<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        Delegator<T>,
+        Blake2_128Concat,
+        T::AccountId,
+        (),
+        OptionQuery,
+    >;
+
+    /// Share value of each pool of the eligible candidates, snapshotted at every new session.
+    /// Only the last `ShareValueHistoryDepth` snapshots are kept, oldest first.
+    #[pallet::storage]
+    pub type ShareValueHistory<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        Candidate<T>,
+        Blake2_128Concat,
+        TargetPool,
+        BoundedVec<ShareValueSnapshot<T::Balance>, T::ShareValueHistoryDepth>,
+        ValueQuery,
+    >;
+
+    /// Stake in the joining pools of all candidates.
+    #[pallet::storage]
+    #[pallet::getter(fn total_joining)]
+    pub type TotalJoining<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+    /// Stake in the auto compounding pools of all candidates.
+    #[pallet::storage]
+    #[pallet::getter(fn total_auto_compounding)]
+    pub type TotalAutoCompounding<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+    /// Stake in the manual rewards pools of all candidates.
+    #[pallet::storage]
+    #[pallet::getter(fn total_manual_claim)]
+    pub type TotalManualClaim<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+    /// Stake in the leaving pools of all candidates.
+    #[pallet::storage]
+    #[pallet::getter(fn total_leaving)]
+    pub type TotalLeaving<T: Config> = StorageValue<_, T::Balance, ValueQuery>;
+
+    /// Delay of the joining requests, defaulting to the delay of `JoiningRequestTimer`.
+    #[pallet::storage]
+    pub type JoiningDelay<T: Config> =
+        StorageValue<_, RequestDelay<JoiningInstantOf<T>>, ValueQuery>;
+
+    /// Delay of the leaving requests, defaulting to the delay of `LeavingRequestTimer`.
+    #[pallet::storage]
+    pub type LeavingDelay<T: Config> =
+        StorageValue<_, RequestDelay<LeavingInstantOf<T>>, ValueQuery>;
+
+    /// Joining requests using `ExistingDelegatorJoiningDelay`, made by delegators that already
+    /// had shares in the target pool.
+    #[pallet::storage]
+    pub type ReducedDelayJoinings<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        Delegator<T>,
+        Blake2_128Concat,
+        PendingOperationKeyOf<T>,
+        (),
+        OptionQuery,
+    >;
+
+    /// Highest share price at which a joining request can be executed, as requested with
+    /// `request_delegate`. Above it the joining stake is refunded instead of being staked.
+    #[pallet::storage]
+    pub type MaxSharePrices<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        Delegator<T>,
+        Blake2_128Concat,
+        PendingOperationKeyOf<T>,
+        T::Balance,
+        OptionQuery,
+    >;
+
+    /// Delegations scheduled with `schedule_delegation`, by activation block.
+    #[pallet::storage]
+    pub type ScheduledDelegations<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<ScheduledDelegationOf<T>, T::MaxScheduledDelegationsPerBlock>,
+        ValueQuery,
+    >;
+
+    /// Bonded positions of the delegators of each candidate, see `bonded_delegate`. Elapsed
+    /// bonds are removed when a new bond is made towards the same candidate.
+    #[pallet::storage]
+    pub type Bonds<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        Candidate<T>,
+        BoundedVec<BondOf<T>, T::MaxBondsPerCandidate>,
+        ValueQuery,
+    >;
+
+    /// Candidates that called `request_candidate_leave`, with the block from which their
+    /// delegations are moved to the leaving pool. Removed once the candidate has no stake left.
+    #[pallet::storage]
+    pub type CandidateLeaveRequests<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, BlockNumberFor<T>, OptionQuery>;
+
+    /// Candidates that requested to leave, by the block from which their delegations are
+    /// moved to the leaving pool.
+    #[pallet::storage]
+    pub type CandidateLeaveSchedule<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<Candidate<T>, T::MaxCandidateLeavesPerBlock>,
+        ValueQuery,
+    >;
+
+    /// Candidates whose delegations are being moved to the leaving pool, with the key of the
+    /// last `Pools` entry processed, if any. Removed once all their entries are processed.
+    #[pallet::storage]
+    pub type DueCandidateLeaves<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, Option<PoolsKey<T::AccountId>>, ValueQuery>;
+
+    /// Block at which each candidate was left without any stake, including leaving stake. The
+    /// candidate is purged `ZeroStakeCandidateGracePeriod` blocks later unless it receives a
+    /// new delegation in the meantime.
+    #[pallet::storage]
+    pub type ZeroStakeSince<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, BlockNumberFor<T>, OptionQuery>;
+
+    /// Candidates left without any stake, by the block at which they start being purged.
+    #[pallet::storage]
+    pub type ZeroStakeExpiries<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        Candidate<T>,
+        (),
+        OptionQuery,
+    >;
+
+    /// Candidates whose `Pools`, `ShareValueHistory` and `CompoundingPreference` entries are
+    /// being removed. They cannot receive delegations until they are fully purged.
+    #[pallet::storage]
+    pub type PurgingCandidates<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, (), OptionQuery>;
+
+    /// Stake recently joined by a delegator to a candidate, along with the block at which it
+    /// was executed, used to penalize early undelegations. Delegations executed before the
+    /// previous recent stake reached `MinStakeDuration` move this block proportionally to their
+    /// stake. Removed once the delegator has no stake left for that candidate.
+    #[pallet::storage]
+    pub type RecentlyJoinedStake<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        Candidate<T>,
+        Blake2_128Concat,
+        Delegator<T>,
+        (BlockNumberFor<T>, T::Balance),
+        OptionQuery,
+    >;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -331,6 +898,42 @@ pub mod pallet {
             after: Option<u32>,
         },
 
+        /// Candidate became eligible, or stopped being eligible, after a change of its stake.
+        CandidateEligibilityChanged {
+            candidate: Candidate<T>,
+            eligible: bool,
+        },
+
+        /// Candidate requested to leave. It doesn't accept new delegations, and all its
+        /// delegations will be moved to the leaving pool at block `at`.
+        CandidateLeaveRequested {
+            candidate: Candidate<T>,
+            at: BlockNumberFor<T>,
+        },
+
+        /// All the delegations of a candidate that requested to leave were executed, it has
+        /// no stake left.
+        CandidateLeft { candidate: Candidate<T> },
+        /// Delegation of a leaving candidate could not be moved to the leaving pool. The
+        /// delegator can still undelegate it with `request_undelegate`.
+        CandidateLeaveUndelegationFailed {
+            candidate: Candidate<T>,
+            delegator: Delegator<T>,
+            pool: TargetPool,
+            error: DispatchError,
+        },
+        /// Joining request towards a candidate that requested to leave was executed, its
+        /// stake has been `released` instead of delegated.
+        DelegationRefundedCandidateLeaving {
+            candidate: Candidate<T>,
+            delegator: Delegator<T>,
+            pool: TargetPool,
+            released: T::Balance,
+        },
+        /// Candidate had no stake for `ZeroStakeCandidateGracePeriod` blocks, its storage was
+        /// removed. Candidates with many storage entries are purged over several blocks.
+        CandidatePurged { candidate: Candidate<T> },
+
         /// User requested to delegate towards a candidate.
         RequestedDelegate {
             candidate: Candidate<T>,
@@ -338,6 +941,30 @@ pub mod pallet {
             pool: TargetPool,
             pending: T::Balance,
         },
+        /// Delegation scheduled to be requested at block `activate_at`. Its funds are
+        /// already held.
+        ScheduledDelegation {
+            candidate: Candidate<T>,
+            delegator: Delegator<T>,
+            pool: TargetPool,
+            stake: T::Balance,
+            activate_at: BlockNumberFor<T>,
+        },
+        /// Scheduled delegation could not be requested at its activation block, its funds
+        /// have been released.
+        ScheduledDelegationFailed {
+            candidate: Candidate<T>,
+            delegator: Delegator<T>,
+            stake: T::Balance,
+        },
+        /// Part of a delegation could not be converted to shares when executed due to rounding,
+        /// and `amount` has been released back to the delegator. Not emitted when nothing was
+        /// released.
+        RoundingReleased {
+            candidate: Candidate<T>,
+            delegator: Delegator<T>,
+            amount: T::Balance,
+        },
         /// Delegation request was executed. `staked` has been properly staked
         /// in `pool`, while the rounding when converting to shares has been
         /// `released`.
@@ -348,6 +975,23 @@ pub mod pallet {
             staked: T::Balance,
             released: T::Balance,
         },
+        /// Delegation request was not executed as the `share_price` of `pool` exceeded the
+        /// `max_share_price` requested by the delegator. The joining stake has been `released`.
+        DelegationRefunded {
+            candidate: Candidate<T>,
+            delegator: Delegator<T>,
+            pool: TargetPool,
+            share_price: T::Balance,
+            max_share_price: T::Balance,
+            released: T::Balance,
+        },
+        /// Position of `delegator` towards `candidate` is bonded and cannot be undelegated
+        /// before block `until`.
+        BondedPosition {
+            candidate: Candidate<T>,
+            delegator: Delegator<T>,
+            until: BlockNumberFor<T>,
+        },
         /// User requested to undelegate from a candidate.
         /// Stake was removed from a `pool` and is `pending` for the request
         /// to be executed. The rounding when converting to leaving shares has
@@ -359,6 +1003,30 @@ pub mod pallet {
             pending: T::Balance,
             released: T::Balance,
         },
+        /// Dust released when requesting to undelegate was sent to the `DustCollector`.
+        SweptDust {
+            candidate: Candidate<T>,
+            delegator: Delegator<T>,
+            collector: T::AccountId,
+            amount: T::Balance,
+        },
+        /// Undelegation of recently joined stake was requested before `MinStakeDuration`,
+        /// `amount` was taken from the undelegated stake and sent to `destination`.
+        EarlyExitPenalized {
+            candidate: Candidate<T>,
+            delegator: Delegator<T>,
+            destination: T::AccountId,
+            amount: T::Balance,
+        },
+        /// Undelegation request was cancelled before being executed, its stake was `staked`
+        /// back into `pool`. The rounding when converting to `pool` shares has been `released`.
+        CancelledUndelegate {
+            candidate: Candidate<T>,
+            delegator: Delegator<T>,
+            pool: TargetPool,
+            staked: T::Balance,
+            released: T::Balance,
+        },
         /// Undelegation request was executed.
         ExecutedUndelegate {
             candidate: Candidate<T>,
@@ -407,21 +1075,93 @@ pub mod pallet {
         /// Collator has been rewarded.
         RewardedCollator {
             collator: Candidate<T>,
+            role: AuthorRole,
             auto_compounding_rewards: T::Balance,
             manual_claim_rewards: T::Balance,
         },
+        /// The reward pot didn't hold enough funds to pay `requested` rewards to a collator and
+        /// its delegators. Only `available` has been distributed, pro-rata.
+        RewardsUnderfunded {
+            collator: Candidate<T>,
+            requested: T::Balance,
+            available: T::Balance,
+        },
         /// Delegators have been rewarded.
         RewardedDelegators {
             collator: Candidate<T>,
             auto_compounding_rewards: T::Balance,
             manual_claim_rewards: T::Balance,
         },
+        /// Bonded delegator received `rewards` in addition to the rewards of its pools, as
+        /// bonded stake is weighted with `PoolRewardWeight::bonded_reward_weight`.
+        RewardedBondedDelegator {
+            collator: Candidate<T>,
+            delegator: Delegator<T>,
+            rewards: T::Balance,
+        },
         /// Rewards manually claimed.
         ClaimedManualRewards {
             candidate: Candidate<T>,
             delegator: Delegator<T>,
             rewards: T::Balance,
         },
+        /// Delegator updated its compounding preference for a candidate.
+        UpdatedCompoundingPreference {
+            candidate: Candidate<T>,
+            delegator: Delegator<T>,
+            preference: Percent,
+        },
+        /// Delegator updated the account receiving its claimed manual rewards.
+        UpdatedRewardDestination {
+            delegator: Delegator<T>,
+            destination: RewardDestination<T::AccountId>,
+        },
+        /// Delay of the joining requests made after the current instant was updated.
+        UpdatedJoiningDelay { delay: JoiningInstantOf<T> },
+        /// Delay of the leaving requests made after the current instant was updated.
+        UpdatedLeavingDelay { delay: LeavingInstantOf<T> },
+        /// Delegator allowed or disallowed a proxy to delegate its funds.
+        UpdatedDelegationProxy {
+            delegator: Delegator<T>,
+            proxy: T::AccountId,
+            allowed: bool,
+        },
+        /// Pending operations executed by `execute_pending_operations_best_effort` or
+        /// `execute_pending_operations_multi`, as indices in the provided list of operations.
+        ExecutedPendingOperations { executed: Vec<u16> },
+        /// Summary of a successful `execute_pending_operations` or
+        /// `execute_pending_operations_multi` call made by `executor`, which
+        /// executed `count` operations staking `total_staked` and releasing `total_released`.
+        /// The operations belong to `delegators`, which may not include `executor`.
+        PendingOperationsExecuted {
+            executor: T::AccountId,
+            delegators: Vec<Delegator<T>>,
+            count: u32,
+            total_staked: T::Balance,
+            total_released: T::Balance,
+        },
+        /// Candidate updated the commission taken on the rewards of its delegators.
+        UpdatedCandidateCommission {
+            candidate: Candidate<T>,
+            commission: Perbill,
+        },
+        /// Candidate requested to increase its commission, which applies once the joining
+        /// delay after `at` is elapsed.
+        CommissionIncreaseRequested {
+            candidate: Candidate<T>,
+            commission: Perbill,
+            at: JoiningInstantOf<T>,
+        },
+        /// Commission on delegators rewards paid to the candidate.
+        CommissionPaid {
+            candidate: Candidate<T>,
+            amount: T::Balance,
+        },
+        /// Candidate updated the minimum stake required from its new delegators.
+        UpdatedCandidateMinDelegation {
+            candidate: Candidate<T>,
+            min_delegation: T::Balance,
+        },
     }
 
     #[pallet::error]
@@ -439,6 +1179,37 @@ pub mod pallet {
         UnsufficientSharesForTransfer,
         CandidateTransferingOwnSharesForbidden,
         RequestCannotBeExecuted(u16),
+        CandidateLeaving,
+        NotACandidate,
+        LeavingRateLimited,
+        NotAuthorizedProxy,
+        TooManyDelegators,
+        TooManyDelegations,
+        TooManyDelayChanges,
+        ActivationMustBeInFuture,
+        TooManyScheduledDelegations,
+        NoPendingOperation,
+        GlobalStakeCapReached,
+        NotEnoughStake,
+        BelowCandidateMinimum,
+        StillBonded,
+        BondMustEndInFuture,
+        TooManyBonds,
+        NothingToUndelegate,
+        TooManyCandidateLeaves,
+        CandidateBeingPurged,
+        NoAutoCompoundingShares,
+        TooManyCompoundingPreferences,
+        CommissionTooHigh,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            Calls::<T>::activate_scheduled_delegations(n)
+                .saturating_add(Calls::<T>::process_candidate_leave_requests(n))
+                .saturating_add(Calls::<T>::purge_zero_stake_candidates(n))
+        }
     }
 
     #[pallet::call]
@@ -457,6 +1228,9 @@ pub mod pallet {
             Calls::<T>::rebalance_hold(candidate, delegator, pool)
         }
 
+        /// Request to delegate `stake` to `candidate` in `pool`. If `max_share_price` is
+        /// provided, the request is refunded instead of executed if a share of `pool` is worth
+        /// more than it at execution.
         #[pallet::call_index(1)]
         #[pallet::weight(T::WeightInfo::request_delegate())]
         pub fn request_delegate(
@@ -464,25 +1238,27 @@ pub mod pallet {
             candidate: Candidate<T>,
             pool: TargetPool,
             stake: T::Balance,
+            max_share_price: Option<T::Balance>,
         ) -> DispatchResultWithPostInfo {
             let delegator = ensure_signed(origin)?;
 
-            Calls::<T>::request_delegate(candidate, delegator, pool, stake)
+            Calls::<T>::request_delegate(candidate, delegator, pool, stake, max_share_price)
         }
 
         /// Execute pending operations can incur in claim manual rewards per operation, we simply add the worst case
+        #[pallet::call_index(2)]
         #[pallet::weight(T::WeightInfo::execute_pending_operations(operations.len() as u32).saturating_add(T::WeightInfo::claim_manual_rewards(operations.len() as u32)))]
         pub fn execute_pending_operations(
             origin: OriginFor<T>,
             operations: Vec<PendingOperationQueryOf<T>>,
         ) -> DispatchResultWithPostInfo {
-            // We don't care about the sender.
-            let _ = ensure_signed(origin)?;
+            let executor = ensure_signed(origin)?;
 
-            Calls::<T>::execute_pending_operations(operations)
+            Calls::<T>::execute_pending_operations(executor, operations)
         }
 
         /// Request undelegate can incur in either claim manual rewards or hold rebalances, we simply add the worst case
+        #[pallet::call_index(3)]
         #[pallet::weight(T::WeightInfo::request_undelegate().saturating_add(T::WeightInfo::claim_manual_rewards(1).max(T::WeightInfo::rebalance_hold())))]
         pub fn request_undelegate(
             origin: OriginFor<T>,
@@ -495,6 +1271,7 @@ pub mod pallet {
             Calls::<T>::request_undelegate(candidate, delegator, pool, amount)
         }
 
+        #[pallet::call_index(4)]
         #[pallet::weight(T::WeightInfo::claim_manual_rewards(pairs.len() as u32))]
         pub fn claim_manual_rewards(
             origin: OriginFor<T>,
@@ -506,6 +1283,7 @@ pub mod pallet {
             Calls::<T>::claim_manual_rewards(&pairs)
         }
 
+        #[pallet::call_index(5)]
         #[pallet::weight(T::WeightInfo::update_candidate_position(candidates.len() as u32))]
         pub fn update_candidate_position(
             origin: OriginFor<T>,
@@ -516,5 +1294,613 @@ pub mod pallet {
 
             Calls::<T>::update_candidate_position(&candidates)
         }
+
+        /// Set which part of the future auto compounding rewards from `candidate` will be
+        /// compounded, the rest being claimable with the manual rewards. The caller must have
+        /// auto compounding shares of `candidate`, and at most
+        /// `MaxCompoundingPreferencesPerCandidate` delegators of a candidate can compound less
+        /// than 100%.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::set_compounding_preference())]
+        pub fn set_compounding_preference(
+            origin: OriginFor<T>,
+            candidate: Candidate<T>,
+            preference: Percent,
+        ) -> DispatchResultWithPostInfo {
+            let delegator = ensure_signed(origin)?;
+
+            Calls::<T>::set_compounding_preference(candidate, delegator, preference)
+        }
+
+        /// Set the account to which manual rewards claimed for the caller are paid.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::set_reward_destination())]
+        pub fn set_reward_destination(
+            origin: OriginFor<T>,
+            destination: RewardDestination<T::AccountId>,
+        ) -> DispatchResultWithPostInfo {
+            let delegator = ensure_signed(origin)?;
+
+            Calls::<T>::set_reward_destination(delegator, destination)
+        }
+
+        /// Set the commission the caller takes, as a candidate, on the rewards of its
+        /// delegators, up to `MaxCommission`. Decreases apply immediately while increases apply
+        /// once the joining delay is elapsed.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::set_commission())]
+        pub fn set_commission(
+            origin: OriginFor<T>,
+            commission: Perbill,
+        ) -> DispatchResultWithPostInfo {
+            let candidate = ensure_signed(origin)?;
+
+            Calls::<T>::set_commission(candidate, commission)
+        }
+
+        /// Set the minimum stake the caller requires, as a candidate, from each new delegator.
+        /// It doesn't apply to its self delegation nor to existing delegators. The caller must be
+        /// a registered candidate or have stake.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::set_candidate_min_delegation())]
+        pub fn set_candidate_min_delegation(
+            origin: OriginFor<T>,
+            min_delegation: T::Balance,
+        ) -> DispatchResultWithPostInfo {
+            let candidate = ensure_signed(origin)?;
+
+            Calls::<T>::set_candidate_min_delegation(candidate, min_delegation)
+        }
+
+        /// Allow or disallow `proxy` to delegate the funds of the caller with `delegate_for`.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::set_delegation_proxy())]
+        pub fn set_delegation_proxy(
+            origin: OriginFor<T>,
+            proxy: T::AccountId,
+            allowed: bool,
+        ) -> DispatchResultWithPostInfo {
+            let delegator = ensure_signed(origin)?;
+
+            Calls::<T>::set_delegation_proxy(delegator, proxy, allowed)
+        }
+
+        /// Request to delegate the funds of `owner`, which must have allowed the caller with
+        /// `set_delegation_proxy`. The delegation belongs to `owner`.
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::WeightInfo::request_delegate().saturating_add(T::DbWeight::get().reads(1)))]
+        pub fn delegate_for(
+            origin: OriginFor<T>,
+            owner: Delegator<T>,
+            candidate: Candidate<T>,
+            pool: TargetPool,
+            stake: T::Balance,
+        ) -> DispatchResultWithPostInfo {
+            let proxy = ensure_signed(origin)?;
+
+            Calls::<T>::delegate_for(proxy, owner, candidate, pool, stake)
+        }
+
+        /// Set the delay of the joining requests made after the current instant. Pending
+        /// requests keep the delay they were made with.
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::WeightInfo::set_request_delay())]
+        pub fn set_joining_delay(
+            origin: OriginFor<T>,
+            delay: JoiningInstantOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            T::RequestDelayOrigin::ensure_origin(origin)?;
+
+            Calls::<T>::set_joining_delay(delay)
+        }
+
+        /// Set the delay of the leaving requests made after the current instant. Pending
+        /// requests keep the delay they were made with.
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::WeightInfo::set_request_delay())]
+        pub fn set_leaving_delay(
+            origin: OriginFor<T>,
+            delay: LeavingInstantOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            T::RequestDelayOrigin::ensure_origin(origin)?;
+
+            Calls::<T>::set_leaving_delay(delay)
+        }
+
+        /// Hold `stake` now and request to delegate it towards `candidate` at block
+        /// `activate_at`. If the request fails at that time the funds are released.
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::WeightInfo::schedule_delegation())]
+        pub fn schedule_delegation(
+            origin: OriginFor<T>,
+            candidate: Candidate<T>,
+            pool: TargetPool,
+            stake: T::Balance,
+            activate_at: BlockNumberFor<T>,
+        ) -> DispatchResultWithPostInfo {
+            let delegator = ensure_signed(origin)?;
+
+            Calls::<T>::schedule_delegation(candidate, delegator, pool, stake, activate_at)
+        }
+
+        /// Stop accepting delegations and, after `CandidateLeaveDelay` blocks, request to
+        /// undelegate all the delegations towards the caller, including its self delegation.
+        /// Pending joining requests are refunded when executed. The candidate is removed once
+        /// all the leaving and joining requests are executed.
+        #[pallet::call_index(15)]
+        #[pallet::weight(T::WeightInfo::request_candidate_leave())]
+        pub fn request_candidate_leave(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let candidate = ensure_signed(origin)?;
+
+            Calls::<T>::request_candidate_leave(candidate)
+        }
+
+        /// Request to delegate `stake` to `candidate` in `pool` and bond the position until
+        /// block `until_block`, before which it cannot be undelegated unless the candidate
+        /// leaves. Bonding again towards the same candidate can only extend the bond.
+        #[pallet::call_index(16)]
+        #[pallet::weight(T::WeightInfo::bonded_delegate())]
+        pub fn bonded_delegate(
+            origin: OriginFor<T>,
+            candidate: Candidate<T>,
+            pool: TargetPool,
+            stake: T::Balance,
+            until_block: BlockNumberFor<T>,
+        ) -> DispatchResultWithPostInfo {
+            let delegator = ensure_signed(origin)?;
+
+            Calls::<T>::bonded_delegate(candidate, delegator, pool, stake, until_block)
+        }
+
+        /// Execute the pending operations which are ready, skipping the ones that are not
+        /// instead of failing. Indices of the executed operations are listed in the
+        /// `ExecutedPendingOperations` event.
+        /// Anyone can call it, and operations can belong to different delegators: each
+        /// operation is only looked up for the delegator of its query, so operations that don't
+        /// belong to it are skipped.
+        #[pallet::call_index(17)]
+        #[pallet::weight(T::WeightInfo::execute_pending_operations(operations.len() as u32).saturating_add(T::WeightInfo::claim_manual_rewards(operations.len() as u32)))]
+        pub fn execute_pending_operations_best_effort(
+            origin: OriginFor<T>,
+            operations: Vec<PendingOperationQueryOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            // We don't care about the sender.
+            let _ = ensure_signed(origin)?;
+
+            Calls::<T>::execute_pending_operations_best_effort(operations)
+        }
+
+        /// Cancel the leaving request of the caller from `candidate` made at `at`, before it is
+        /// executed. Its stake goes back to `pool` at the current share value.
+        #[pallet::call_index(18)]
+        #[pallet::weight(T::WeightInfo::cancel_undelegation().saturating_add(T::WeightInfo::claim_manual_rewards(1)))]
+        pub fn cancel_undelegation(
+            origin: OriginFor<T>,
+            candidate: Candidate<T>,
+            at: LeavingInstantOf<T>,
+            pool: TargetPool,
+        ) -> DispatchResultWithPostInfo {
+            let delegator = ensure_signed(origin)?;
+
+            Calls::<T>::cancel_undelegation(candidate, delegator, at, pool)
+        }
+
+        /// Request to undelegate all the shares of the caller in `pool`, from every candidate it
+        /// delegates to. The weight of candidates without shares in `pool` is refunded.
+        #[pallet::call_index(19)]
+        #[pallet::weight(Pallet::<T>::request_undelegate_weight().saturating_mul(T::MaxDelegationsPerDelegator::get().into()))]
+        pub fn request_undelegate_all(
+            origin: OriginFor<T>,
+            pool: TargetPool,
+        ) -> DispatchResultWithPostInfo {
+            let delegator = ensure_signed(origin)?;
+
+            Calls::<T>::request_undelegate_all(delegator, pool)
+        }
+
+        /// Claim manual rewards of the caller for all provided candidates at once.
+        /// Candidates without any claimable rewards are skipped.
+        #[pallet::call_index(20)]
+        #[pallet::weight(T::WeightInfo::claim_manual_rewards(candidates.len() as u32))]
+        pub fn claim_rewards_batch(
+            origin: OriginFor<T>,
+            candidates: Vec<Candidate<T>>,
+        ) -> DispatchResultWithPostInfo {
+            let delegator = ensure_signed(origin)?;
+
+            let pairs: Vec<_> = candidates
+                .into_iter()
+                .map(|candidate| (candidate, delegator.clone()))
+                .collect();
+
+            Calls::<T>::claim_manual_rewards(&pairs)
+        }
+
+        /// Execute the ready pending operations of any number of delegators, skipping the
+        /// operations that don't exist for the delegator of their query or are not ready yet.
+        /// Anyone can call it. Executed operations are listed in `ExecutedPendingOperations` and
+        /// summarized in `PendingOperationsExecuted`.
+        #[pallet::call_index(21)]
+        #[pallet::weight(T::WeightInfo::execute_pending_operations(operations.len() as u32).saturating_add(T::WeightInfo::claim_manual_rewards(operations.len() as u32)))]
+        pub fn execute_pending_operations_multi(
+            origin: OriginFor<T>,
+            operations: Vec<PendingOperationQueryOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            let executor = ensure_signed(origin)?;
+
+            Calls::<T>::execute_pending_operations_multi(executor, operations)
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Block until which the position of `delegator` towards `candidate` is bonded, if
+        /// the bond has not elapsed yet.
+        pub fn bonded_until(
+            candidate: &Candidate<T>,
+            delegator: &Delegator<T>,
+        ) -> Option<BlockNumberFor<T>> {
+            let now = frame_system::Pallet::<T>::block_number();
+            Bonds::<T>::get(candidate)
+                .into_iter()
+                .find(|bond| &bond.delegator == delegator && bond.until > now)
+                .map(|bond| bond.until)
+        }
+
+        /// Eligible candidates with their total stake, in the order they are sorted
+        /// in `SortedEligibleCandidates`.
+        pub fn eligible_candidates() -> Vec<(Candidate<T>, T::Balance)> {
+            SortedEligibleCandidates::<T>::get()
+                .into_iter()
+                .map(|entry| {
+                    let stake = candidate::Candidates::<T>::total_stake(&entry.candidate).0;
+                    (entry.candidate, stake)
+                })
+                .collect()
+        }
+
+        /// Manual rewards of `delegator` from `candidate` that would be paid by claiming them
+        /// now, including all the rewards distributed since its last claim and the auto
+        /// compounding rewards it does not compound.
+        pub fn claimable_rewards(delegator: &Delegator<T>, candidate: &Candidate<T>) -> T::Balance {
+            pools::ManualRewards::<T>::pending_rewards(candidate, delegator)
+                .map(|rewards| rewards.0)
+                .unwrap_or_default()
+                .saturating_add(
+                    pools::AutoCompounding::<T>::claimable_rewards(candidate, delegator).0,
+                )
+        }
+
+        /// Funds of `delegator` held by its pending operations, along with the instant from which
+        /// each operation can be executed. Joining and leaving requests must use the same kind of
+        /// instant.
+        pub fn locks(delegator: &Delegator<T>) -> Vec<LockOf<T>>
+        where
+            T::LeavingRequestTimer: Timer<Instant = JoiningInstantOf<T>>,
+        {
+            PendingOperations::<T>::iter_prefix(delegator)
+                .filter_map(|(operation, shares)| {
+                    let (candidate, kind, amount, unlock_at) = match &operation {
+                        PendingOperationKey::JoiningAutoCompounding { candidate, at }
+                        | PendingOperationKey::JoiningManualRewards { candidate, at } => {
+                            let amount =
+                                pools::Joining::<T>::shares_to_stake(candidate, Shares(shares))
+                                    .ok()?;
+                            let mut unlock_at = JoiningDelay::<T>::get()
+                                .elapsed_at::<T::JoiningRequestTimer>(at)?;
+                            if ReducedDelayJoinings::<T>::contains_key(delegator, &operation) {
+                                let reduced_unlock_at = T::JoiningRequestTimer::elapsed_at(
+                                    at,
+                                    &T::ExistingDelegatorJoiningDelay::get(),
+                                )?;
+                                unlock_at = unlock_at.min(reduced_unlock_at);
+                            }
+                            (candidate.clone(), LockKind::Joining, amount, unlock_at)
+                        }
+                        PendingOperationKey::Leaving { candidate, at } => {
+                            let amount =
+                                pools::Leaving::<T>::shares_to_stake(candidate, Shares(shares))
+                                    .ok()?;
+                            let unlock_at = LeavingDelay::<T>::get()
+                                .elapsed_at::<T::LeavingRequestTimer>(at)?;
+                            (candidate.clone(), LockKind::Leaving, amount, unlock_at)
+                        }
+                    };
+
+                    Some(Lock {
+                        candidate,
+                        amount: amount.0,
+                        unlock_at,
+                        kind,
+                    })
+                })
+                .collect()
+        }
+
+        /// Amount of shares of `pool` that `stake` is worth for `candidate`, rounded down like
+        /// when shares are created by the pallet. Returns `None` if the conversion fails.
+        pub fn stake_to_shares(
+            candidate: &Candidate<T>,
+            pool: AllTargetPool,
+            stake: T::Balance,
+        ) -> Option<T::Balance> {
+            let stake = Stake(stake);
+            let shares = match pool {
+                AllTargetPool::Joining => {
+                    pools::Joining::<T>::stake_to_shares_or_init(candidate, stake)
+                }
+                AllTargetPool::AutoCompounding => {
+                    pools::AutoCompounding::<T>::stake_to_shares_or_init(candidate, stake)
+                }
+                AllTargetPool::ManualRewards => {
+                    pools::ManualRewards::<T>::stake_to_shares_or_init(candidate, stake)
+                }
+                AllTargetPool::Leaving => {
+                    pools::Leaving::<T>::stake_to_shares_or_init(candidate, stake)
+                }
+            };
+
+            shares.ok().map(|shares| shares.0)
+        }
+
+        /// Amount of stake that `shares` of `pool` are worth for `candidate`, rounded down like
+        /// when shares are destroyed by the pallet. Returns `None` if the conversion fails.
+        pub fn shares_to_stake(
+            candidate: &Candidate<T>,
+            pool: AllTargetPool,
+            shares: T::Balance,
+        ) -> Option<T::Balance> {
+            let shares = Shares(shares);
+            let stake = match pool {
+                AllTargetPool::Joining => {
+                    pools::Joining::<T>::shares_to_stake_or_init(candidate, shares)
+                }
+                AllTargetPool::AutoCompounding => {
+                    pools::AutoCompounding::<T>::shares_to_stake_or_init(candidate, shares)
+                }
+                AllTargetPool::ManualRewards => {
+                    pools::ManualRewards::<T>::shares_to_stake_or_init(candidate, shares)
+                }
+                AllTargetPool::Leaving => {
+                    pools::Leaving::<T>::shares_to_stake_or_init(candidate, shares)
+                }
+            };
+
+            stake.ok().map(|stake| stake.0)
+        }
+
+        /// Weight of `request_undelegate`, which can incur in either claim manual rewards or
+        /// hold rebalances.
+        pub fn request_undelegate_weight() -> Weight {
+            T::WeightInfo::request_undelegate().saturating_add(
+                T::WeightInfo::claim_manual_rewards(1).max(T::WeightInfo::rebalance_hold()),
+            )
+        }
+
+        /// Rewards for `rewards` worth of blocks authored by a collator with the given `role`,
+        /// with `OrchestratorRewardMultiplier` applied to orchestrator authors.
+        pub fn rewards_for_role(
+            role: AuthorRole,
+            rewards: T::Balance,
+        ) -> Result<T::Balance, Error<T>> {
+            match role {
+                AuthorRole::Orchestrator => Ok(rewards
+                    .mul_div(T::OrchestratorRewardMultiplier::get().into(), 100u32.into())?),
+                AuthorRole::Container => Ok(rewards),
+            }
+        }
+
+        /// Distribute `rewards` to `candidate` and its delegators, applying the multiplier
+        /// of the author `role` (see `rewards_for_role`).
+        /// Resulting rewards are taken from the reward pot. If it doesn't hold enough funds,
+        /// `RewardsUnderfunded` is emitted and what is available is distributed instead, which
+        /// reduces the share of the candidate and of each delegator proportionally.
+        /// The actual weight of the distribution is returned, as it depends on the number of
+        /// delegators with a `CompoundingPreference`.
+        pub fn distribute_rewards(
+            candidate: Candidate<T>,
+            role: AuthorRole,
+            rewards: T::Balance,
+        ) -> DispatchResultWithPostInfo {
+            let requested = Self::rewards_for_role(role, rewards)?;
+
+            let pot = T::RewardPot::get();
+            let available =
+                T::Currency::reducible_balance(&pot, Preservation::Expendable, Fortitude::Polite);
+
+            let rewards = if available < requested {
+                Self::deposit_event(Event::<T>::RewardsUnderfunded {
+                    collator: candidate.clone(),
+                    requested,
+                    available,
+                });
+
+                if available.is_zero() {
+                    return Ok(Some(T::WeightInfo::distribute_rewards(0)).into());
+                }
+
+                available
+            } else {
+                requested
+            };
+
+            T::Currency::transfer(
+                &pot,
+                &T::StakingAccount::get(),
+                rewards,
+                Preservation::Expendable,
+            )?;
+
+            pools::distribute_rewards::<T>(&candidate, role, Stake(rewards))
+        }
+
+        /// Delay of the joining requests made now.
+        pub fn joining_delay() -> JoiningInstantOf<T> {
+            JoiningDelay::<T>::get().current::<T::JoiningRequestTimer>()
+        }
+
+        /// Delay of the leaving requests made now.
+        pub fn leaving_delay() -> LeavingInstantOf<T> {
+            LeavingDelay::<T>::get().current::<T::LeavingRequestTimer>()
+        }
+
+        /// Commission taken by `candidate` on the rewards of its delegators, including its
+        /// requested increase once the joining delay is elapsed.
+        pub fn candidate_commission(candidate: &Candidate<T>) -> Perbill {
+            match PendingCommissionIncreases::<T>::get(candidate) {
+                Some((commission, at))
+                    if JoiningDelay::<T>::get().is_elapsed::<T::JoiningRequestTimer>(&at) =>
+                {
+                    commission
+                }
+                _ => CandidateCommission::<T>::get(candidate),
+            }
+        }
+
+        /// Stake of all candidates combined, excluding the stake leaving them.
+        pub fn total_stake() -> T::Balance {
+            TotalJoining::<T>::get()
+                .saturating_add(TotalAutoCompounding::<T>::get())
+                .saturating_add(TotalManualClaim::<T>::get())
+        }
+
+        /// Delegators of `candidate` along with the current value of their shares in each pool,
+        /// in storage order. Listing starts after the raw `Pools` key `cursor` and stops after
+        /// `limit` delegators, at least one and at most `MAX_CANDIDATE_DELEGATORS_PAGE`. Also
+        /// returns the cursor of the next page, or `None` once all delegators have been listed.
+        pub fn candidate_delegators(
+            candidate: &Candidate<T>,
+            cursor: Option<Vec<u8>>,
+            limit: u32,
+        ) -> (Vec<(Delegator<T>, PoolsStake<T::Balance>)>, Option<Vec<u8>>) {
+            let limit = limit.clamp(1, MAX_CANDIDATE_DELEGATORS_PAGE) as usize;
+            let entries = match cursor {
+                Some(raw_key) => Pools::<T>::iter_prefix_from(candidate, raw_key),
+                None => Pools::<T>::iter_prefix(candidate),
+            };
+
+            let mut delegators = Vec::new();
+            let mut last_raw_key = None;
+            for (key, shares) in entries {
+                let raw_key = Pools::<T>::hashed_key_for(candidate, &key);
+                let delegator = match key {
+                    PoolsKey::JoiningShares { delegator }
+                    | PoolsKey::AutoCompoundingShares { delegator }
+                    | PoolsKey::ManualRewardsShares { delegator }
+                    | PoolsKey::LeavingShares { delegator } => Some(delegator),
+                    _ => None,
+                };
+
+                // A delegator is listed at the first of its non-empty share entries, so that
+                // it appears in a single page.
+                if let Some(delegator) = delegator.filter(|d| {
+                    !shares.is_zero() && Self::first_shares_raw_key(candidate, d) == raw_key
+                }) {
+                    if delegators.len() == limit {
+                        return (delegators, last_raw_key);
+                    }
+                    let stake = Self::pools_stake(candidate, &delegator);
+                    delegators.push((delegator, stake));
+                }
+
+                last_raw_key = Some(raw_key);
+            }
+
+            (delegators, None)
+        }
+
+        /// Smallest raw `Pools` key among the non-empty share entries of `delegator`.
+        fn first_shares_raw_key(candidate: &Candidate<T>, delegator: &Delegator<T>) -> Vec<u8> {
+            [
+                PoolsKey::JoiningShares {
+                    delegator: delegator.clone(),
+                },
+                PoolsKey::AutoCompoundingShares {
+                    delegator: delegator.clone(),
+                },
+                PoolsKey::ManualRewardsShares {
+                    delegator: delegator.clone(),
+                },
+                PoolsKey::LeavingShares {
+                    delegator: delegator.clone(),
+                },
+            ]
+            .iter()
+            .filter(|key| !Pools::<T>::get(candidate, key).is_zero())
+            .map(|key| Pools::<T>::hashed_key_for(candidate, key))
+            .min()
+            .unwrap_or_default()
+        }
+
+        /// Current value of the shares of `delegator` in each pool of `candidate`.
+        fn pools_stake(
+            candidate: &Candidate<T>,
+            delegator: &Delegator<T>,
+        ) -> PoolsStake<T::Balance> {
+            let value = |stake: Result<Stake<T::Balance>, Error<T>>| {
+                stake.map(|stake| stake.0).unwrap_or_default()
+            };
+            PoolsStake {
+                joining: value(pools::Joining::<T>::computed_stake(candidate, delegator)),
+                auto_compounding: value(pools::AutoCompounding::<T>::computed_stake(
+                    candidate, delegator,
+                )),
+                manual_rewards: value(pools::ManualRewards::<T>::computed_stake(
+                    candidate, delegator,
+                )),
+                leaving: value(pools::Leaving::<T>::computed_stake(candidate, delegator)),
+            }
+        }
+
+        /// Number of candidates and delegators, along with the stake of all candidates.
+        pub fn stats() -> StakingStats<T::Balance> {
+            StakingStats {
+                total_candidates: TotalCandidates::<T>::get(),
+                total_delegators: TotalDelegators::<T>::get(),
+                total_staked: Self::total_stake(),
+            }
+        }
+
+        /// Share value snapshots of a pool of `candidate`, oldest first.
+        pub fn share_value_history(
+            candidate: &Candidate<T>,
+            pool: TargetPool,
+        ) -> Vec<ShareValueSnapshot<T::Balance>> {
+            ShareValueHistory::<T>::get(candidate, pool).into_inner()
+        }
+
+        pub fn initializer_on_new_session(session_index: &u32) {
+            let candidates = SortedEligibleCandidates::<T>::get();
+
+            for entry in candidates.iter() {
+                for pool in [TargetPool::AutoCompounding, TargetPool::ManualRewards] {
+                    let value = match pools::share_value::<T>(&entry.candidate, pool) {
+                        Ok(value) => value,
+                        Err(_) => continue,
+                    };
+
+                    ShareValueHistory::<T>::mutate(&entry.candidate, pool, |history| {
+                        // Prune the oldest snapshot to make room for the new one.
+                        if history.len() as u32 >= T::ShareValueHistoryDepth::get()
+                            && !history.is_empty()
+                        {
+                            history.remove(0);
+                        }
+                        let _ = history.try_push(ShareValueSnapshot {
+                            session: *session_index,
+                            value,
+                        });
+                    });
+                }
+            }
+
+            let num_candidates = candidates.len() as u64;
+            frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                T::DbWeight::get().reads_writes(
+                    1 + num_candidates.saturating_mul(6),
+                    num_candidates.saturating_mul(2),
+                ),
+                DispatchClass::Mandatory,
+            );
+        }
     }
 }
@@ -19,8 +19,12 @@ use {
         self as pallet_pooled_staking,
         candidate::Candidates,
         pools::Pool,
-        traits::{BlockNumberTimer, Timer},
-        Candidate, Delegator, PendingOperationKey, PendingOperationKeyOf, TargetPool,
+        traits::{
+            BlockNumberTimer, IsCandidateLeaving, IsCandidateRegistered, OnDelegation,
+            PoolRewardWeight, Timer,
+        },
+        Candidate, Delegator, PendingOperationKey, PendingOperationKeyOf, SharePrecision,
+        TargetPool,
     },
     frame_support::{
         parameter_types,
@@ -37,7 +41,7 @@ use {
     sp_runtime::{
         testing::Header,
         traits::{BlakeTwo256, IdentityLookup},
-        Perbill,
+        Perbill, Permill,
     },
 };
 
@@ -68,6 +72,9 @@ pub const ACCOUNT_CANDIDATE_1: u64 = 1;
 pub const ACCOUNT_CANDIDATE_2: u64 = 2;
 pub const ACCOUNT_DELEGATOR_1: u64 = 3;
 pub const ACCOUNT_DELEGATOR_2: u64 = 4;
+pub const ACCOUNT_CANDIDATE_3: u64 = 5;
+pub const ACCOUNT_REWARD_POT: u64 = 6;
+pub const ACCOUNT_PENALTY_DESTINATION: u64 = 7;
 
 pub const KILO: u128 = 1000;
 pub const MEGA: u128 = 1000 * KILO;
@@ -138,15 +145,118 @@ impl pallet_balances::Config for Runtime {
 
 parameter_types! {
     pub const StakingAccount: u64 = ACCOUNT_STAKING;
+    pub const RewardPot: u64 = ACCOUNT_REWARD_POT;
+    pub const EarlyExitPenaltyDestination: u64 = ACCOUNT_PENALTY_DESTINATION;
     pub const CurrencyHoldReason: HoldIdentifier = HoldIdentifier::Staking;
     pub const InitialManualClaimShareValue: u128 = MEGA;
     pub const InitialAutoCompoundingShareValue: u128 = MEGA;
     pub const MinimumSelfDelegation: u128 = 10 * MEGA;
     pub const RewardsCollatorCommission: Perbill = Perbill::from_percent(20);
-    pub const BlocksToWait: u64 = BLOCKS_TO_WAIT;
+    pub const MaxCommission: Perbill = Perbill::from_percent(50);
 }
 
 pub const BLOCKS_TO_WAIT: u64 = 2;
+pub const CANDIDATE_LEAVE_DELAY: u64 = 5;
+
+parameter_types! {
+    // Both delays default to `BLOCKS_TO_WAIT`, tests can override them to
+    // check joining and leaving are timed independently.
+    pub static JoiningDelay: u64 = BLOCKS_TO_WAIT;
+    pub static LeavingDelay: u64 = BLOCKS_TO_WAIT;
+    pub static ExistingDelegatorJoiningDelay: u64 = BLOCKS_TO_WAIT;
+    pub static SelfDelegationMultiplier: u32 = 1;
+    pub static OrchestratorRewardMultiplier: u32 = 100;
+    pub static AutoCompoundingRewardWeight: u32 = 1;
+    pub static ManualRewardsRewardWeight: u32 = 1;
+    pub static BondedRewardWeight: u32 = 1;
+    pub static LeavingCandidatesList: Vec<AccountId> = vec![];
+    pub static UnregisteredCandidatesList: Vec<AccountId> = vec![];
+    pub static MaxLeavingStakePerSession: u128 = u128::MAX;
+    pub static MaxDelegatorsPerCandidate: u32 = u32::MAX;
+    pub static MaxTotalStake: u128 = u128::MAX;
+    pub static DustCollector: Option<AccountId> = None;
+    pub static MinStakeDuration: u64 = 0;
+    pub static EarlyExitPenalty: Permill = Permill::zero();
+    pub static ZeroStakeCandidateGracePeriod: u64 = u64::MAX;
+    pub static MaxCandidateLeaveEntriesPerBlock: u32 = 100;
+    pub static MaxPurgedEntriesPerBlock: u32 = 100;
+    pub static DelegationReceipts: Vec<Receipt> = vec![];
+    // Values above 1 to test rounding of joining and leaving stake
+    pub static StakingSharePrecision: SharePrecision<Balance> = SharePrecision {
+        joining: 2,
+        leaving: 3,
+    };
+}
+
+pub struct RegisteredCandidates;
+impl IsCandidateRegistered<AccountId> for RegisteredCandidates {
+    fn is_candidate_registered(a: &AccountId) -> bool {
+        !UnregisteredCandidatesList::get().contains(a)
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn make_candidate_registered(a: &AccountId) {
+        UnregisteredCandidatesList::mutate(|list| list.retain(|x| x != a));
+    }
+}
+
+/// Weights of the pools, set with `AutoCompoundingRewardWeight` and `ManualRewardsRewardWeight`.
+/// Bonded stake of both pools is weighted with `BondedRewardWeight`.
+pub struct PoolRewardWeights;
+impl PoolRewardWeight for PoolRewardWeights {
+    fn pool_reward_weight(pool: TargetPool) -> u32 {
+        match pool {
+            TargetPool::AutoCompounding => AutoCompoundingRewardWeight::get(),
+            TargetPool::ManualRewards => ManualRewardsRewardWeight::get(),
+        }
+    }
+
+    fn bonded_reward_weight(_: TargetPool) -> u32 {
+        BondedRewardWeight::get()
+    }
+}
+
+pub struct LeavingCandidates;
+impl IsCandidateLeaving<AccountId> for LeavingCandidates {
+    fn is_candidate_leaving(a: &AccountId) -> bool {
+        LeavingCandidatesList::get().contains(a)
+    }
+}
+
+/// Receipt token operation performed by `ReceiptsHook`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Receipt {
+    Minted {
+        candidate: AccountId,
+        delegator: AccountId,
+    },
+    Burned {
+        candidate: AccountId,
+        delegator: AccountId,
+    },
+}
+
+/// Mock NFT pallet, recording the receipts minted and burned in `DelegationReceipts`.
+pub struct ReceiptsHook;
+impl OnDelegation<AccountId> for ReceiptsHook {
+    fn on_position_created(candidate: &AccountId, delegator: &AccountId) {
+        DelegationReceipts::mutate(|receipts| {
+            receipts.push(Receipt::Minted {
+                candidate: *candidate,
+                delegator: *delegator,
+            })
+        });
+    }
+
+    fn on_position_removed(candidate: &AccountId, delegator: &AccountId) {
+        DelegationReceipts::mutate(|receipts| {
+            receipts.push(Receipt::Burned {
+                candidate: *candidate,
+                delegator: *delegator,
+            })
+        });
+    }
+}
 
 impl pallet_pooled_staking::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
@@ -154,15 +264,43 @@ impl pallet_pooled_staking::Config for Runtime {
     type Balance = Balance;
     type CurrencyHoldReason = CurrencyHoldReason;
     type StakingAccount = StakingAccount;
+    type RewardPot = RewardPot;
     type InitialManualClaimShareValue = InitialManualClaimShareValue;
     type InitialAutoCompoundingShareValue = InitialAutoCompoundingShareValue;
+    type SharePrecision = StakingSharePrecision;
     type MinimumSelfDelegation = MinimumSelfDelegation;
+    type SelfDelegationMultiplier = SelfDelegationMultiplier;
     type RewardsCollatorCommission = RewardsCollatorCommission;
-    type JoiningRequestTimer = BlockNumberTimer<Self, BlocksToWait>;
-    type LeavingRequestTimer = BlockNumberTimer<Self, BlocksToWait>;
+    type MaxCommission = MaxCommission;
+    type OrchestratorRewardMultiplier = OrchestratorRewardMultiplier;
+    type PoolRewardWeight = PoolRewardWeights;
+    type JoiningRequestTimer = BlockNumberTimer<Self, JoiningDelay>;
+    type ExistingDelegatorJoiningDelay = ExistingDelegatorJoiningDelay;
+    type LeavingRequestTimer = BlockNumberTimer<Self, LeavingDelay>;
+    type MaxLeavingStakePerSession = MaxLeavingStakePerSession;
+    type ShareValueHistoryDepth = ConstU32<3>;
     // low value so we can test vec bounding, in practice it should be bigger
     type EligibleCandidatesBufferSize = ConstU32<3>;
+    type DustCollector = DustCollector;
+    type MinStakeDuration = MinStakeDuration;
+    type EarlyExitPenalty = EarlyExitPenalty;
+    type EarlyExitPenaltyDestination = EarlyExitPenaltyDestination;
+    type MaxDelegatorsPerCandidate = MaxDelegatorsPerCandidate;
+    type MaxTotalStake = MaxTotalStake;
+    type MaxDelegationsPerDelegator = ConstU32<5>;
+    type MaxScheduledDelegationsPerBlock = ConstU32<10>;
+    type MaxBondsPerCandidate = ConstU32<5>;
+    type MaxCompoundingPreferencesPerCandidate = ConstU32<2>;
+    type RequestDelayOrigin = frame_system::EnsureRoot<AccountId>;
     type EligibleCandidatesFilter = ();
+    type RegisteredCandidates = RegisteredCandidates;
+    type LeavingCandidates = LeavingCandidates;
+    type CandidateLeaveDelay = ConstU64<CANDIDATE_LEAVE_DELAY>;
+    type MaxCandidateLeavesPerBlock = ConstU32<5>;
+    type MaxCandidateLeaveEntriesPerBlock = MaxCandidateLeaveEntriesPerBlock;
+    type ZeroStakeCandidateGracePeriod = ZeroStakeCandidateGracePeriod;
+    type MaxPurgedEntriesPerBlock = MaxPurgedEntriesPerBlock;
+    type OnDelegation = ReceiptsHook;
     type WeightInfo = ();
 }
 
@@ -370,7 +508,7 @@ pub(crate) fn roll_one_block() -> u64 {
     System::set_block_number(System::block_number() + 1);
     System::on_initialize(System::block_number());
     Balances::on_initialize(System::block_number());
-    // Staking::on_initialize(System::block_number());
+    Staking::on_initialize(System::block_number());
     System::block_number()
 }
 
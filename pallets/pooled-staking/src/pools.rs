@@ -16,13 +16,25 @@
 
 use {
     crate::{
-        traits::{ErrAdd, ErrMul, ErrSub, MulDiv},
-        Candidate, Config, Delegator, Error, Pools, PoolsKey, Shares, Stake,
+        candidate::Candidates,
+        traits::{ErrAdd, ErrMul, ErrSub, MulDiv, PoolRewardWeight},
+        weights::WeightInfo,
+        AuthorRole, Bonds, Candidate, CompoundingPreference, Config, Delegator, Error, Event,
+        Pallet, Pools, PoolsKey, Shares, Stake, TargetPool, TotalAutoCompounding, TotalJoining,
+        TotalLeaving, TotalManualClaim,
     },
     core::marker::PhantomData,
-    frame_support::ensure,
+    frame_support::{
+        dispatch::DispatchResultWithPostInfo,
+        ensure,
+        traits::{fungible::Mutate, tokens::Preservation},
+    },
     sp_core::Get,
-    sp_runtime::traits::{CheckedAdd, CheckedDiv, Zero},
+    sp_runtime::{
+        traits::{CheckedAdd, CheckedDiv, One, Saturating, Zero},
+        PerThing,
+    },
+    sp_std::vec::Vec,
 };
 
 pub trait Pool<T: Config> {
@@ -228,7 +240,15 @@ pub fn check_candidate_consistency<T: Config>(candidate: &Candidate<T>) -> Resul
 }
 
 macro_rules! impl_pool {
-    ($name:ident, $shares:ident, $supply:ident, $total:ident, $hold: ident, $init:expr $(,)?) => {
+    (
+        $name:ident,
+        $shares:ident,
+        $supply:ident,
+        $total:ident,
+        $hold:ident,
+        $aggregate:ident,
+        $init:expr $(,)?
+    ) => {
         pub struct $name<T>(PhantomData<T>);
         impl<T: Config> Pool<T> for $name<T> {
             fn shares(candidate: &Candidate<T>, delegator: &Delegator<T>) -> Shares<T::Balance> {
@@ -276,6 +296,11 @@ macro_rules! impl_pool {
             }
 
             fn set_total_staked(candidate: &Candidate<T>, value: Stake<T::Balance>) {
+                // Keep the total of this pool across all candidates up to date.
+                let previous = Pools::<T>::get(candidate, &PoolsKey::$total);
+                $aggregate::<T>::mutate(|total| {
+                    *total = total.saturating_sub(previous).saturating_add(value.0)
+                });
                 Pools::<T>::set(candidate, &PoolsKey::$total, value.0)
             }
 
@@ -306,7 +331,8 @@ impl_pool!(
     JoiningSharesSupply,
     JoiningSharesTotalStaked,
     JoiningSharesHeldStake,
-    if cfg!(test) { 2u32 } else { 1 }.into(),
+    TotalJoining,
+    T::SharePrecision::get().joining,
 );
 
 impl_pool!(
@@ -315,6 +341,7 @@ impl_pool!(
     AutoCompoundingSharesSupply,
     AutoCompoundingSharesTotalStaked,
     AutoCompoundingSharesHeldStake,
+    TotalAutoCompounding,
     T::InitialAutoCompoundingShareValue::get(),
 );
 
@@ -324,6 +351,7 @@ impl_pool!(
     ManualRewardsSharesSupply,
     ManualRewardsSharesTotalStaked,
     ManualRewardsSharesHeldStake,
+    TotalManualClaim,
     T::InitialManualClaimShareValue::get(),
 );
 
@@ -333,11 +361,11 @@ impl_pool!(
     LeavingSharesSupply,
     LeavingSharesTotalStaked,
     LeavingSharesHeldStake,
-    if cfg!(test) { 3u32 } else { 1u32 }.into(),
+    TotalLeaving,
+    T::SharePrecision::get().leaving,
 );
 
 impl<T: Config> ManualRewards<T> {
-    #[allow(dead_code)]
     pub fn pending_rewards(
         candidate: &Candidate<T>,
         delegator: &Delegator<T>,
@@ -361,6 +389,36 @@ impl<T: Config> ManualRewards<T> {
         Ok(Stake(diff.err_mul(&shares.0)?))
     }
 
+    /// Increase the rewards counter of that candidate such that `rewards` is
+    /// shared among all manual rewards share holders. Returns the amount that
+    /// couldn't be distributed due to rounding.
+    pub fn increase_rewards(
+        candidate: &Candidate<T>,
+        rewards: Stake<T::Balance>,
+    ) -> Result<Stake<T::Balance>, Error<T>> {
+        let Shares(supply) = Self::shares_supply(candidate);
+        if supply.is_zero() {
+            return Ok(rewards);
+        }
+
+        let rewards_per_share = rewards
+            .0
+            .checked_div(&supply)
+            .ok_or(Error::<T>::InconsistentState)?;
+        if rewards_per_share.is_zero() {
+            return Ok(rewards);
+        }
+
+        let distributed = rewards_per_share.err_mul(&supply)?;
+        let dust = rewards.0.err_sub(&distributed)?;
+
+        let counter = Pools::<T>::get(candidate, &PoolsKey::ManualRewardsCounter);
+        let counter = counter.err_add(&rewards_per_share)?;
+        Pools::<T>::set(candidate, &PoolsKey::ManualRewardsCounter, counter);
+
+        Ok(Stake(dust))
+    }
+
     pub fn claim_rewards(
         candidate: &Candidate<T>,
         delegator: &Delegator<T>,
@@ -396,3 +454,342 @@ impl<T: Config> ManualRewards<T> {
         Ok(Stake(rewards))
     }
 }
+
+impl<T: Config> AutoCompounding<T> {
+    /// Auto compounding rewards of that delegator that were not compounded and are waiting
+    /// to be claimed.
+    pub fn claimable_rewards(
+        candidate: &Candidate<T>,
+        delegator: &Delegator<T>,
+    ) -> Stake<T::Balance> {
+        Stake(Pools::<T>::get(
+            candidate,
+            &PoolsKey::AutoCompoundingClaimableRewards {
+                delegator: delegator.clone(),
+            },
+        ))
+    }
+
+    pub fn claim_rewards(candidate: &Candidate<T>, delegator: &Delegator<T>) -> Stake<T::Balance> {
+        Stake(Pools::<T>::take(
+            candidate,
+            &PoolsKey::AutoCompoundingClaimableRewards {
+                delegator: delegator.clone(),
+            },
+        ))
+    }
+
+    /// Remove from the stake of the delegators of that candidate with a `CompoundingPreference`
+    /// the part of `rewards` they don't compound, which becomes claimable. `rewards` must
+    /// already be shared among all auto compounding share holders. Returns the total amount
+    /// made claimable and the number of preferences that were read.
+    pub fn split_rewards(
+        candidate: &Candidate<T>,
+        rewards: Stake<T::Balance>,
+    ) -> Result<(Stake<T::Balance>, u32), Error<T>> {
+        let Shares(supply) = Self::shares_supply(candidate);
+        if rewards.0.is_zero() || supply.is_zero() {
+            return Ok((Stake(Zero::zero()), 0));
+        }
+
+        let mut total_claimable = T::Balance::zero();
+        let mut preferences = 0u32;
+        for (delegator, preference) in CompoundingPreference::<T>::iter_prefix(candidate) {
+            preferences = preferences.saturating_add(1);
+            let Shares(shares) = Self::shares(candidate, &delegator);
+            if shares.is_zero() {
+                continue;
+            }
+
+            let delegator_rewards = rewards.0.mul_div(shares, supply)?;
+            let claimable = preference.left_from_one() * delegator_rewards;
+            let claimable_shares = Self::stake_to_shares(candidate, Stake(claimable))?;
+            if claimable_shares.0.is_zero() {
+                continue;
+            }
+
+            let Stake(claimable) = Self::sub_shares(candidate, &delegator, claimable_shares)?;
+            Candidates::<T>::sub_total_stake(candidate, Stake(claimable))?;
+            Pools::<T>::mutate(
+                candidate,
+                &PoolsKey::AutoCompoundingClaimableRewards {
+                    delegator: delegator.clone(),
+                },
+                |claimable_rewards| {
+                    *claimable_rewards = claimable_rewards.err_add(&claimable)?;
+                    Ok::<_, Error<T>>(())
+                },
+            )?;
+            total_claimable = total_claimable.err_add(&claimable)?;
+        }
+
+        Ok((Stake(total_claimable), preferences))
+    }
+}
+
+/// Value of the shares of `pool` for `candidate`, as stored in `ShareValueHistory`.
+pub fn share_value<T: Config>(
+    candidate: &Candidate<T>,
+    pool: TargetPool,
+) -> Result<T::Balance, Error<T>> {
+    match pool {
+        TargetPool::AutoCompounding => {
+            Ok(AutoCompounding::<T>::shares_to_stake_or_init(candidate, Shares(One::one()))?.0)
+        }
+        TargetPool::ManualRewards => {
+            Ok(Pools::<T>::get(candidate, &PoolsKey::ManualRewardsCounter))
+        }
+    }
+}
+
+/// Value of a single share of `pool` for `candidate`, in staked currency.
+pub fn share_price<T: Config>(
+    candidate: &Candidate<T>,
+    pool: TargetPool,
+) -> Result<T::Balance, Error<T>> {
+    let one = Shares(One::one());
+    match pool {
+        TargetPool::AutoCompounding => {
+            Ok(AutoCompounding::<T>::shares_to_stake_or_init(candidate, one)?.0)
+        }
+        TargetPool::ManualRewards => {
+            Ok(ManualRewards::<T>::shares_to_stake_or_init(candidate, one)?.0)
+        }
+    }
+}
+
+/// Distribute `rewards` to the candidate and its delegators.
+/// Rewards must already be present in the staking account.
+///
+/// The candidate first takes `RewardsCollatorCommission`, then the remaining
+/// rewards are split between the auto compounding and manual rewards pools
+/// proportionally to their stake. Stake of bonded positions weighted above its
+/// pool earns an extra part of the delegators rewards, paid to the delegator.
+/// Auto compounding rewards are only compounded up to the `CompoundingPreference`
+/// of each delegator, the rest becoming claimable.
+/// The candidate commission is itself split between auto compounding and manual
+/// rewards proportionally to the stake the candidate has in each pool. Any
+/// amount that can't be distributed due to rounding is sent to the candidate.
+pub fn distribute_rewards<T: Config>(
+    candidate: &Candidate<T>,
+    role: AuthorRole,
+    rewards: Stake<T::Balance>,
+) -> DispatchResultWithPostInfo {
+    let candidate_rewards = T::RewardsCollatorCommission::get() * rewards.0;
+    let delegators_rewards = rewards
+        .0
+        .err_sub(&candidate_rewards)
+        .map_err(Error::<T>::from)?;
+
+    // Commission of the candidate is taken from the delegators rewards before they are
+    // distributed to the pools.
+    let commission = Pallet::<T>::candidate_commission(candidate) * delegators_rewards;
+    let delegators_rewards = delegators_rewards
+        .err_sub(&commission)
+        .map_err(Error::<T>::from)?;
+
+    // Each pool is rewarded according to its total stake multiplied by its weight.
+    let auto_weight = T::PoolRewardWeight::pool_reward_weight(TargetPool::AutoCompounding);
+    let manual_weight = T::PoolRewardWeight::pool_reward_weight(TargetPool::ManualRewards);
+    let Stake(auto_total_stake) = AutoCompounding::<T>::total_staked(candidate);
+    let Stake(manual_total_stake) = ManualRewards::<T>::total_staked(candidate);
+    let auto_weighted_stake = auto_total_stake
+        .err_mul(&auto_weight.into())
+        .map_err(Error::<T>::from)?;
+    let manual_weighted_stake = manual_total_stake
+        .err_mul(&manual_weight.into())
+        .map_err(Error::<T>::from)?;
+    let bonded_weighted_stakes = bonded_extra_weighted_stakes::<T>(candidate)?;
+    let combined_weighted_stake = bonded_weighted_stakes
+        .iter()
+        .try_fold(
+            auto_weighted_stake
+                .err_add(&manual_weighted_stake)
+                .map_err(Error::<T>::from)?,
+            |total, (_, extra)| total.err_add(extra),
+        )
+        .map_err(Error::<T>::from)?;
+
+    // Bonded delegators receive directly the rewards of the extra weight of their stake.
+    let mut bonded_rewards = Vec::with_capacity(bonded_weighted_stakes.len());
+    let mut bonded_total_rewards = T::Balance::zero();
+    for (delegator, extra) in bonded_weighted_stakes {
+        let amount = delegators_rewards
+            .mul_div(extra, combined_weighted_stake)
+            .map_err(Error::<T>::from)?;
+        if !amount.is_zero() {
+            bonded_total_rewards = bonded_total_rewards
+                .err_add(&amount)
+                .map_err(Error::<T>::from)?;
+            bonded_rewards.push((delegator, amount));
+        }
+    }
+
+    // Distribute delegators rewards to each pool.
+    let delegators_auto_rewards = if combined_weighted_stake.is_zero() {
+        Zero::zero()
+    } else {
+        delegators_rewards
+            .mul_div(auto_weighted_stake, combined_weighted_stake)
+            .map_err(Error::<T>::from)?
+    };
+    let delegators_manual_rewards = delegators_rewards
+        .err_sub(&delegators_auto_rewards)
+        .and_then(|r| r.err_sub(&bonded_total_rewards))
+        .map_err(Error::<T>::from)?;
+
+    if !delegators_auto_rewards.is_zero() {
+        AutoCompounding::<T>::share_stake_among_holders(candidate, Stake(delegators_auto_rewards))?;
+        Candidates::<T>::add_total_stake(candidate, &Stake(delegators_auto_rewards))?;
+    }
+
+    // Delegators not compounding all their rewards can claim the rest with the manual rewards.
+    let (Stake(delegators_claimable_rewards), preferences) =
+        AutoCompounding::<T>::split_rewards(candidate, Stake(delegators_auto_rewards))?;
+
+    let Stake(manual_dust) =
+        ManualRewards::<T>::increase_rewards(candidate, Stake(delegators_manual_rewards))?;
+    let delegators_manual_rewards = delegators_manual_rewards
+        .err_sub(&manual_dust)
+        .map_err(Error::<T>::from)?;
+
+    // Distribute candidate rewards.
+    let candidate_auto_stake = AutoCompounding::<T>::computed_stake(candidate, candidate)?.0;
+    let candidate_manual_stake = ManualRewards::<T>::computed_stake(candidate, candidate)?.0;
+    let candidate_combined_stake = candidate_auto_stake
+        .err_add(&candidate_manual_stake)
+        .map_err(Error::<T>::from)?;
+
+    let candidate_auto_rewards = if candidate_combined_stake.is_zero() {
+        Zero::zero()
+    } else {
+        candidate_rewards
+            .mul_div(candidate_auto_stake, candidate_combined_stake)
+            .map_err(Error::<T>::from)?
+    };
+
+    // Candidate auto compounding rewards are converted into new shares. As the candidate
+    // has stake in that pool the supply can't be zero.
+    let candidate_auto_shares =
+        AutoCompounding::<T>::stake_to_shares(candidate, Stake(candidate_auto_rewards))
+            .unwrap_or(Shares(Zero::zero()));
+    let candidate_auto_rewards = if candidate_auto_shares.0.is_zero() {
+        Zero::zero()
+    } else {
+        let stake = AutoCompounding::<T>::add_shares(candidate, candidate, candidate_auto_shares)?;
+        Candidates::<T>::add_total_stake(candidate, &stake)?;
+        stake.0
+    };
+
+    let candidate_manual_rewards = rewards
+        .0
+        .err_sub(&delegators_auto_rewards)
+        .and_then(|r| r.err_sub(&delegators_manual_rewards))
+        .and_then(|r| r.err_sub(&candidate_auto_rewards))
+        .and_then(|r| r.err_sub(&commission))
+        .and_then(|r| r.err_sub(&bonded_total_rewards))
+        .map_err(Error::<T>::from)?;
+
+    if !candidate_manual_rewards.is_zero() {
+        T::Currency::transfer(
+            &T::StakingAccount::get(),
+            candidate,
+            candidate_manual_rewards,
+            Preservation::Preserve,
+        )?;
+    }
+
+    if !commission.is_zero() {
+        T::Currency::transfer(
+            &T::StakingAccount::get(),
+            candidate,
+            commission,
+            Preservation::Preserve,
+        )?;
+
+        Pallet::<T>::deposit_event(Event::<T>::CommissionPaid {
+            candidate: candidate.clone(),
+            amount: commission,
+        });
+    }
+
+    for (delegator, rewards) in bonded_rewards {
+        T::Currency::transfer(
+            &T::StakingAccount::get(),
+            &delegator,
+            rewards,
+            Preservation::Preserve,
+        )?;
+
+        Pallet::<T>::deposit_event(Event::<T>::RewardedBondedDelegator {
+            collator: candidate.clone(),
+            delegator,
+            rewards,
+        });
+    }
+
+    check_candidate_consistency::<T>(candidate)?;
+
+    let delegators_compounded_rewards = delegators_auto_rewards
+        .err_sub(&delegators_claimable_rewards)
+        .map_err(Error::<T>::from)?;
+    let delegators_manual_rewards = delegators_manual_rewards
+        .err_add(&delegators_claimable_rewards)
+        .map_err(Error::<T>::from)?;
+
+    Pallet::<T>::deposit_event(Event::<T>::RewardedCollator {
+        collator: candidate.clone(),
+        role,
+        auto_compounding_rewards: candidate_auto_rewards,
+        manual_claim_rewards: candidate_manual_rewards,
+    });
+    Pallet::<T>::deposit_event(Event::<T>::RewardedDelegators {
+        collator: candidate.clone(),
+        auto_compounding_rewards: delegators_compounded_rewards,
+        manual_claim_rewards: delegators_manual_rewards,
+    });
+
+    Ok(Some(T::WeightInfo::distribute_rewards(preferences)).into())
+}
+
+/// Weighted stake of each active bonded position of `candidate` in excess of the weight of
+/// the pools the stake is in.
+fn bonded_extra_weighted_stakes<T: Config>(
+    candidate: &Candidate<T>,
+) -> Result<Vec<(Delegator<T>, T::Balance)>, Error<T>> {
+    let now = frame_system::Pallet::<T>::block_number();
+    let mut extras = Vec::new();
+
+    for bond in Bonds::<T>::get(candidate) {
+        if bond.until <= now {
+            continue;
+        }
+
+        let mut extra = T::Balance::zero();
+        for pool in [TargetPool::AutoCompounding, TargetPool::ManualRewards] {
+            let weight = T::PoolRewardWeight::bonded_reward_weight(pool)
+                .saturating_sub(T::PoolRewardWeight::pool_reward_weight(pool));
+            if weight.is_zero() {
+                continue;
+            }
+
+            let Stake(stake) = match pool {
+                TargetPool::AutoCompounding => {
+                    AutoCompounding::<T>::computed_stake(candidate, &bond.delegator)?
+                }
+                TargetPool::ManualRewards => {
+                    ManualRewards::<T>::computed_stake(candidate, &bond.delegator)?
+                }
+            };
+            extra = stake
+                .err_mul(&weight.into())
+                .and_then(|s| extra.err_add(&s))?;
+        }
+
+        if !extra.is_zero() {
+            extras.push((bond.delegator, extra));
+        }
+    }
+
+    Ok(extras)
+}
@@ -14,7 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
 
-use crate::{assert_eq_last_events, candidate::EligibleCandidate, SortedEligibleCandidates};
+use crate::{
+    assert_eq_last_events, assert_event_emitted, candidate::EligibleCandidate,
+    CandidateLeaveRequests, DueCandidateLeaves, EligibleSince, SortedEligibleCandidates,
+};
 
 use super::*;
 
@@ -68,6 +71,11 @@ pool_test!(
                     after: None,
                 },
                 P::event_staked(ACCOUNT_CANDIDATE_1, ACCOUNT_CANDIDATE_1, 9, final_amount),
+                Event::RoundingReleased {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator: ACCOUNT_CANDIDATE_1,
+                    amount: round_down(requested_amount, 2) - final_amount,
+                },
                 Event::ExecutedDelegate {
                     candidate: ACCOUNT_CANDIDATE_1,
                     delegator: ACCOUNT_CANDIDATE_1,
@@ -75,6 +83,13 @@ pool_test!(
                     staked: final_amount,
                     released: round_down(requested_amount, 2) - final_amount,
                 },
+                Event::PendingOperationsExecuted {
+                    executor: ACCOUNT_CANDIDATE_1,
+                    delegators: vec![ACCOUNT_CANDIDATE_1],
+                    count: 1,
+                    total_staked: final_amount,
+                    total_released: round_down(requested_amount, 2) - final_amount,
+                },
             ]);
         })
     }
@@ -132,6 +147,10 @@ pool_test!(
                     before: None,
                     after: Some(0),
                 },
+                Event::CandidateEligibilityChanged {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    eligible: true,
+                },
                 Event::RequestedDelegate {
                     candidate: ACCOUNT_CANDIDATE_1,
                     delegator: ACCOUNT_CANDIDATE_1,
@@ -146,6 +165,13 @@ pool_test!(
                     staked: final_amount,
                     released: round_down(requested_amount, 2) - final_amount,
                 },
+                Event::PendingOperationsExecuted {
+                    executor: ACCOUNT_CANDIDATE_1,
+                    delegators: vec![ACCOUNT_CANDIDATE_1],
+                    count: 1,
+                    total_staked: final_amount,
+                    total_released: round_down(requested_amount, 2) - final_amount,
+                },
                 // delegation 2
                 Event::IncreasedStake {
                     candidate: ACCOUNT_CANDIDATE_1,
@@ -172,6 +198,13 @@ pool_test!(
                     staked: final_amount,
                     released: round_down(requested_amount, 2) - final_amount,
                 },
+                Event::PendingOperationsExecuted {
+                    executor: ACCOUNT_CANDIDATE_1,
+                    delegators: vec![ACCOUNT_CANDIDATE_1],
+                    count: 1,
+                    total_staked: final_amount,
+                    total_released: round_down(requested_amount, 2) - final_amount,
+                },
                 // undelegation
                 Event::DecreasedStake {
                     candidate: ACCOUNT_CANDIDATE_1,
@@ -184,6 +217,10 @@ pool_test!(
                     before: Some(0),
                     after: None,
                 },
+                Event::CandidateEligibilityChanged {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    eligible: false,
+                },
                 Event::RequestedUndelegate {
                     candidate: ACCOUNT_CANDIDATE_1,
                     delegator: ACCOUNT_CANDIDATE_1,
@@ -195,12 +232,90 @@ pool_test!(
                     candidate: ACCOUNT_CANDIDATE_1,
                     delegator: ACCOUNT_CANDIDATE_1,
                     released: round_down(requested_amount * 2, 3)
+                },
+                Event::PendingOperationsExecuted {
+                    executor: ACCOUNT_CANDIDATE_1,
+                    delegators: vec![ACCOUNT_CANDIDATE_1],
+                    count: 1,
+                    total_staked: 0,
+                    total_released: round_down(requested_amount * 2, 3),
                 }
             ]);
         })
     }
 );
 
+pool_test!(
+    fn eligibility_changes_when_crossing_minimum_self_delegation<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let share = P::shares_to_stake_or_init(&ACCOUNT_CANDIDATE_1, Shares(1))
+                .unwrap()
+                .0;
+            let below_minimum = MinimumSelfDelegation::get() - share;
+            let eligibility_changes = || {
+                events()
+                    .into_iter()
+                    .filter(|e| matches!(e, Event::CandidateEligibilityChanged { .. }))
+                    .collect::<Vec<_>>()
+            };
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_CANDIDATE_1,
+                request_amount: below_minimum,
+                expected_increase: below_minimum,
+                ..default()
+            }
+            .test::<P>();
+
+            assert_eq!(eligibility_changes(), vec![]);
+
+            // Reaching the minimum makes the candidate eligible
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_CANDIDATE_1,
+                request_amount: share,
+                expected_increase: share,
+                ..default()
+            }
+            .test::<P>();
+
+            assert_eq!(
+                eligibility_changes(),
+                vec![Event::CandidateEligibilityChanged {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    eligible: true,
+                }]
+            );
+
+            // Going back below the minimum makes it not eligible anymore
+            FullUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_CANDIDATE_1,
+                request_amount: SharesOrStake::Stake(share),
+                expected_removed: share,
+                expected_leaving: round_down(share, 3),
+                ..default()
+            }
+            .test::<P>();
+
+            assert_eq!(
+                eligibility_changes(),
+                vec![
+                    Event::CandidateEligibilityChanged {
+                        candidate: ACCOUNT_CANDIDATE_1,
+                        eligible: true,
+                    },
+                    Event::CandidateEligibilityChanged {
+                        candidate: ACCOUNT_CANDIDATE_1,
+                        eligible: false,
+                    },
+                ]
+            );
+        })
+    }
+);
+
 #[test]
 fn many_candidates_mixed_pools() {
     ExtBuilder::default().build().execute_with(|| {
@@ -222,6 +337,8 @@ fn many_candidates_mixed_pools() {
 
             rank_before: Option<u32>,
             rank_after: Option<u32>,
+            // Eligibility after the action, only if it changed
+            eligible: Option<bool>,
         }
 
         fn perform_actions(actions: &[Action]) {
@@ -242,7 +359,7 @@ fn many_candidates_mixed_pools() {
                         }
                         .test::<pools::AutoCompounding<Runtime>>();
 
-                        assert_eq_last_events!(vec![
+                        let mut events = vec![
                             Event::<Runtime>::IncreasedStake {
                                 candidate: action.candidate,
                                 stake_diff: action.amount,
@@ -273,7 +390,24 @@ fn many_candidates_mixed_pools() {
                                 staked: action.amount,
                                 released: 0,
                             },
-                        ])
+                            Event::PendingOperationsExecuted {
+                                executor: action.delegator,
+                                delegators: vec![action.delegator],
+                                count: 1,
+                                total_staked: action.amount,
+                                total_released: 0,
+                            },
+                        ];
+                        if let Some(eligible) = action.eligible {
+                            events.insert(
+                                2,
+                                Event::CandidateEligibilityChanged {
+                                    candidate: action.candidate,
+                                    eligible,
+                                },
+                            );
+                        }
+                        assert_eq_last_events!(events)
                     }
                     Action {
                         join: true,
@@ -289,7 +423,7 @@ fn many_candidates_mixed_pools() {
                         }
                         .test::<pools::ManualRewards<Runtime>>();
 
-                        assert_eq_last_events!(vec![
+                        let mut events = vec![
                             Event::<Runtime>::IncreasedStake {
                                 candidate: action.candidate,
                                 stake_diff: action.amount,
@@ -320,7 +454,24 @@ fn many_candidates_mixed_pools() {
                                 staked: action.amount,
                                 released: 0,
                             },
-                        ])
+                            Event::PendingOperationsExecuted {
+                                executor: action.delegator,
+                                delegators: vec![action.delegator],
+                                count: 1,
+                                total_staked: action.amount,
+                                total_released: 0,
+                            },
+                        ];
+                        if let Some(eligible) = action.eligible {
+                            events.insert(
+                                2,
+                                Event::CandidateEligibilityChanged {
+                                    candidate: action.candidate,
+                                    eligible,
+                                },
+                            );
+                        }
+                        assert_eq_last_events!(events)
                     }
                     Action {
                         join: false,
@@ -337,7 +488,7 @@ fn many_candidates_mixed_pools() {
                         }
                         .test::<pools::AutoCompounding<Runtime>>();
 
-                        assert_eq_last_events!(vec![
+                        let mut events = vec![
                             Event::<Runtime>::DecreasedStake {
                                 candidate: action.candidate,
                                 stake_diff: action.amount,
@@ -361,7 +512,24 @@ fn many_candidates_mixed_pools() {
                                 delegator: action.delegator,
                                 released: round_down(action.amount, 3),
                             },
-                        ])
+                            Event::PendingOperationsExecuted {
+                                executor: action.delegator,
+                                delegators: vec![action.delegator],
+                                count: 1,
+                                total_staked: 0,
+                                total_released: round_down(action.amount, 3),
+                            },
+                        ];
+                        if let Some(eligible) = action.eligible {
+                            events.insert(
+                                2,
+                                Event::CandidateEligibilityChanged {
+                                    candidate: action.candidate,
+                                    eligible,
+                                },
+                            );
+                        }
+                        assert_eq_last_events!(events)
                     }
                     _ => todo!(),
                 }
@@ -379,6 +547,7 @@ fn many_candidates_mixed_pools() {
                 total_self: share * 11,
                 rank_before: None,
                 rank_after: Some(0),
+                eligible: Some(true),
             },
             Action {
                 candidate: ACCOUNT_CANDIDATE_2,
@@ -390,6 +559,7 @@ fn many_candidates_mixed_pools() {
                 total_self: share * 10,
                 rank_before: None,
                 rank_after: Some(1),
+                eligible: Some(true),
             },
             Action {
                 candidate: ACCOUNT_CANDIDATE_2,
@@ -401,6 +571,7 @@ fn many_candidates_mixed_pools() {
                 total_self: share * 10,
                 rank_before: Some(1),
                 rank_after: Some(0),
+                eligible: None,
             },
             Action {
                 candidate: ACCOUNT_CANDIDATE_1,
@@ -412,6 +583,7 @@ fn many_candidates_mixed_pools() {
                 total_self: share * 11,
                 rank_before: Some(1),
                 rank_after: Some(1),
+                eligible: None,
             },
             Action {
                 candidate: ACCOUNT_DELEGATOR_1,
@@ -423,6 +595,7 @@ fn many_candidates_mixed_pools() {
                 total_self: share * 11,
                 rank_before: None,
                 rank_after: Some(2),
+                eligible: Some(true),
             },
             Action {
                 candidate: ACCOUNT_DELEGATOR_2,
@@ -433,7 +606,8 @@ fn many_candidates_mixed_pools() {
                 total_stake: share * 10,
                 total_self: share * 10,
                 rank_before: None,
-                rank_after: None, // list is full
+                rank_after: None,     // list is full
+                eligible: Some(true), // eligible even if not in the list
             },
         ]);
 
@@ -467,6 +641,7 @@ fn many_candidates_mixed_pools() {
             total_self: 0,
             rank_before: Some(1),
             rank_after: None,
+            eligible: Some(false),
         }]);
 
         assert_eq!(
@@ -508,3 +683,500 @@ fn many_candidates_mixed_pools() {
         );
     })
 }
+
+#[test]
+fn self_delegation_multiplier_favors_self_stake() {
+    fn setup_candidates() {
+        let min = MinimumSelfDelegation::get();
+
+        // Candidate 1 has the minimum self delegation and an equal amount delegated.
+        FullDelegation {
+            candidate: ACCOUNT_CANDIDATE_1,
+            delegator: ACCOUNT_CANDIDATE_1,
+            request_amount: min,
+            expected_increase: min,
+            ..default()
+        }
+        .test::<pools::AutoCompounding<Runtime>>();
+
+        FullDelegation {
+            candidate: ACCOUNT_CANDIDATE_1,
+            delegator: ACCOUNT_DELEGATOR_1,
+            request_amount: min,
+            expected_increase: min,
+            ..default()
+        }
+        .test::<pools::AutoCompounding<Runtime>>();
+
+        // Candidate 2 has the same total stake but only self delegated.
+        FullDelegation {
+            candidate: ACCOUNT_CANDIDATE_2,
+            delegator: ACCOUNT_CANDIDATE_2,
+            request_amount: 2 * min,
+            expected_increase: 2 * min,
+            ..default()
+        }
+        .test::<pools::AutoCompounding<Runtime>>();
+    }
+
+    let min = MinimumSelfDelegation::get();
+
+    ExtBuilder::default().build().execute_with(|| {
+        setup_candidates();
+
+        // Equal stake, ordered by account id.
+        assert_eq!(
+            SortedEligibleCandidates::<Runtime>::get().into_inner(),
+            vec![
+                EligibleCandidate {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    stake: 2 * min,
+                },
+                EligibleCandidate {
+                    candidate: ACCOUNT_CANDIDATE_2,
+                    stake: 2 * min,
+                },
+            ]
+        );
+    });
+
+    ExtBuilder::default().build().execute_with(|| {
+        SelfDelegationMultiplier::set(2);
+        setup_candidates();
+
+        assert_eq!(
+            SortedEligibleCandidates::<Runtime>::get().into_inner(),
+            vec![
+                EligibleCandidate {
+                    candidate: ACCOUNT_CANDIDATE_2,
+                    stake: 4 * min,
+                },
+                EligibleCandidate {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    stake: 3 * min,
+                },
+            ]
+        );
+
+        assert_eq_last_events!(vec![
+            Event::<Runtime>::IncreasedStake {
+                candidate: ACCOUNT_CANDIDATE_2,
+                stake_diff: 2 * min,
+            },
+            Event::UpdatedCandidatePosition {
+                candidate: ACCOUNT_CANDIDATE_2,
+                stake: 2 * min,
+                self_delegation: 2 * min,
+                before: None,
+                after: Some(0),
+            },
+            Event::CandidateEligibilityChanged {
+                candidate: ACCOUNT_CANDIDATE_2,
+                eligible: true,
+            },
+            Event::RequestedDelegate {
+                candidate: ACCOUNT_CANDIDATE_2,
+                delegator: ACCOUNT_CANDIDATE_2,
+                pool: TargetPool::AutoCompounding,
+                pending: 2 * min,
+            },
+            Event::StakedAutoCompounding {
+                candidate: ACCOUNT_CANDIDATE_2,
+                delegator: ACCOUNT_CANDIDATE_2,
+                shares: 2 * min / InitialAutoCompoundingShareValue::get(),
+                stake: 2 * min,
+            },
+            Event::ExecutedDelegate {
+                candidate: ACCOUNT_CANDIDATE_2,
+                delegator: ACCOUNT_CANDIDATE_2,
+                pool: TargetPool::AutoCompounding,
+                staked: 2 * min,
+                released: 0,
+            },
+            Event::PendingOperationsExecuted {
+                executor: ACCOUNT_CANDIDATE_2,
+                delegators: vec![ACCOUNT_CANDIDATE_2],
+                count: 1,
+                total_staked: 2 * min,
+                total_released: 0,
+            },
+        ]);
+    });
+}
+
+#[test]
+fn eligible_candidates_sorted_by_descending_stake() {
+    ExtBuilder::default().build().execute_with(|| {
+        let min = MinimumSelfDelegation::get();
+
+        for (candidate, delegator, amount) in [
+            (ACCOUNT_CANDIDATE_1, ACCOUNT_CANDIDATE_1, min),
+            (ACCOUNT_CANDIDATE_2, ACCOUNT_CANDIDATE_2, min),
+            (ACCOUNT_CANDIDATE_2, ACCOUNT_DELEGATOR_1, 2 * min),
+            (ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_2, min),
+        ] {
+            FullDelegation {
+                candidate,
+                delegator,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<pools::AutoCompounding<Runtime>>();
+        }
+
+        assert_eq!(
+            Staking::eligible_candidates(),
+            vec![
+                (ACCOUNT_CANDIDATE_2, 3 * min),
+                (ACCOUNT_CANDIDATE_1, 2 * min)
+            ]
+        );
+    })
+}
+
+#[test]
+fn equal_stake_candidates_have_stable_order() {
+    let min = MinimumSelfDelegation::get();
+
+    let sorted_after_delegating = |candidates: [AccountId; 2]| {
+        ExtBuilder::default().build().execute_with(|| {
+            for candidate in candidates {
+                assert_ok!(Staking::request_delegate(
+                    RuntimeOrigin::signed(candidate),
+                    candidate,
+                    TargetPool::AutoCompounding,
+                    min,
+                    None,
+                ));
+            }
+
+            SortedEligibleCandidates::<Runtime>::get().into_inner()
+        })
+    };
+
+    // Both candidates became eligible in the same block, ordered by account id.
+    let expected = vec![
+        EligibleCandidate {
+            candidate: ACCOUNT_CANDIDATE_1,
+            stake: min,
+        },
+        EligibleCandidate {
+            candidate: ACCOUNT_CANDIDATE_2,
+            stake: min,
+        },
+    ];
+
+    assert_eq!(
+        sorted_after_delegating([ACCOUNT_CANDIDATE_1, ACCOUNT_CANDIDATE_2]),
+        expected
+    );
+    assert_eq!(
+        sorted_after_delegating([ACCOUNT_CANDIDATE_2, ACCOUNT_CANDIDATE_1]),
+        expected
+    );
+}
+
+#[test]
+fn equal_stake_candidates_ordered_by_eligibility_block() {
+    ExtBuilder::default().build().execute_with(|| {
+        let min = MinimumSelfDelegation::get();
+
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_2),
+            ACCOUNT_CANDIDATE_2,
+            TargetPool::AutoCompounding,
+            min,
+            None,
+        ));
+        let eligible_block = block_number();
+        assert_eq!(
+            EligibleSince::<Runtime>::get(ACCOUNT_CANDIDATE_2),
+            Some(eligible_block)
+        );
+
+        roll_to(eligible_block + 1);
+
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            min,
+            None,
+        ));
+
+        // Candidate 2 became eligible first, it stays first despite its bigger account id.
+        assert_eq!(
+            SortedEligibleCandidates::<Runtime>::get().into_inner(),
+            vec![
+                EligibleCandidate {
+                    candidate: ACCOUNT_CANDIDATE_2,
+                    stake: min,
+                },
+                EligibleCandidate {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    stake: min,
+                },
+            ]
+        );
+
+        assert_eq_last_events!(vec![
+            Event::<Runtime>::IncreasedStake {
+                candidate: ACCOUNT_CANDIDATE_1,
+                stake_diff: min,
+            },
+            Event::UpdatedCandidatePosition {
+                candidate: ACCOUNT_CANDIDATE_1,
+                stake: min,
+                self_delegation: min,
+                before: None,
+                after: Some(1),
+            },
+            Event::CandidateEligibilityChanged {
+                candidate: ACCOUNT_CANDIDATE_1,
+                eligible: true,
+            },
+            Event::RequestedDelegate {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_CANDIDATE_1,
+                pool: TargetPool::AutoCompounding,
+                pending: min,
+            },
+        ]);
+    })
+}
+
+pool_test!(
+    fn candidate_leave_moves_delegators_to_leaving<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+
+            assert_noop!(
+                Staking::request_candidate_leave(RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1)),
+                Error::<Runtime>::NotACandidate
+            );
+
+            for delegator in [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2] {
+                FullDelegation {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator,
+                    request_amount: amount,
+                    expected_increase: amount,
+                    ..default()
+                }
+                .test::<P>();
+            }
+
+            let at = block_number() + CANDIDATE_LEAVE_DELAY;
+            assert_ok!(Staking::request_candidate_leave(RuntimeOrigin::signed(
+                ACCOUNT_CANDIDATE_1
+            )));
+            assert_eq_last_events!(vec![Event::<Runtime>::CandidateLeaveRequested {
+                candidate: ACCOUNT_CANDIDATE_1,
+                at,
+            }]);
+
+            assert_noop!(
+                Staking::request_delegate(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    amount,
+                    None,
+                ),
+                Error::<Runtime>::CandidateLeaving
+            );
+
+            roll_to(at);
+
+            let mut operations = vec![];
+            for delegator in [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2] {
+                let leaving = PoolState::extract::<Leaving>(ACCOUNT_CANDIDATE_1, delegator);
+                assert_eq!(P::shares(&ACCOUNT_CANDIDATE_1, &delegator).0, 0);
+                assert_eq!(leaving.stake, round_down(amount, 3));
+
+                operations.push(PendingOperationQuery {
+                    delegator,
+                    operation: PendingOperationKey::Leaving {
+                        candidate: ACCOUNT_CANDIDATE_1,
+                        at,
+                    },
+                });
+            }
+            assert_eq!(
+                Candidates::<Runtime>::total_stake(&ACCOUNT_CANDIDATE_1).0,
+                0
+            );
+            // Delegators still have to execute their leaving requests.
+            assert_eq!(
+                CandidateLeaveRequests::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+                Some(at)
+            );
+
+            roll_to(at + LeavingDelay::get());
+            assert_ok!(Staking::execute_pending_operations(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                operations,
+            ));
+
+            assert_event_emitted!(Event::<Runtime>::CandidateLeft {
+                candidate: ACCOUNT_CANDIDATE_1,
+            });
+            assert_eq!(
+                CandidateLeaveRequests::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+                None
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn candidate_leave_is_processed_over_several_blocks<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let delegators = [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2];
+            // Enough for at most one delegation per block.
+            MaxCandidateLeaveEntriesPerBlock::set(3);
+
+            for delegator in delegators {
+                FullDelegation {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator,
+                    request_amount: amount,
+                    expected_increase: amount,
+                    ..default()
+                }
+                .test::<P>();
+            }
+
+            let at = block_number() + CANDIDATE_LEAVE_DELAY;
+            assert_ok!(Staking::request_candidate_leave(RuntimeOrigin::signed(
+                ACCOUNT_CANDIDATE_1
+            )));
+
+            roll_to(at);
+            assert!(DueCandidateLeaves::<Runtime>::contains_key(
+                ACCOUNT_CANDIDATE_1
+            ));
+            assert!(delegators
+                .iter()
+                .any(|delegator| P::shares(&ACCOUNT_CANDIDATE_1, delegator).0 != 0));
+
+            roll_to(at + 100);
+            assert!(!DueCandidateLeaves::<Runtime>::contains_key(
+                ACCOUNT_CANDIDATE_1
+            ));
+            for delegator in delegators {
+                assert_eq!(P::shares(&ACCOUNT_CANDIDATE_1, &delegator).0, 0);
+                let leaving = PoolState::extract::<Leaving>(ACCOUNT_CANDIDATE_1, delegator);
+                assert_eq!(leaving.stake, round_down(amount, 3));
+            }
+        })
+    }
+);
+
+pool_test!(
+    fn candidate_leave_bypasses_bonds_and_leaving_rate_limit<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            MaxLeavingStakePerSession::set(1);
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+            let joining_at = block_number();
+            assert_ok!(Staking::bonded_delegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                amount,
+                1_000,
+            ));
+            roll_to(joining_at + JoiningDelay::get());
+            assert_ok!(Staking::execute_pending_operations(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+                vec![PendingOperationQuery {
+                    delegator: ACCOUNT_DELEGATOR_2,
+                    operation: P::joining_operation_key(ACCOUNT_CANDIDATE_1, joining_at),
+                }],
+            ));
+
+            let at = block_number() + CANDIDATE_LEAVE_DELAY;
+            assert_ok!(Staking::request_candidate_leave(RuntimeOrigin::signed(
+                ACCOUNT_CANDIDATE_1
+            )));
+
+            roll_to(at);
+            for delegator in [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2] {
+                assert_eq!(P::shares(&ACCOUNT_CANDIDATE_1, &delegator).0, 0);
+                let leaving = PoolState::extract::<Leaving>(ACCOUNT_CANDIDATE_1, delegator);
+                assert_eq!(leaving.stake, round_down(amount, 3));
+            }
+            assert_eq!(
+                Candidates::<Runtime>::total_stake(&ACCOUNT_CANDIDATE_1).0,
+                0
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn joining_request_towards_leaving_candidate_is_refunded<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+
+            let joining_at = block_number();
+            assert_ok!(Staking::request_delegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                amount,
+                None,
+            ));
+            let joining = round_down(amount, StakingSharePrecision::get().joining);
+
+            let at = block_number() + CANDIDATE_LEAVE_DELAY;
+            assert_ok!(Staking::request_candidate_leave(RuntimeOrigin::signed(
+                ACCOUNT_CANDIDATE_1
+            )));
+            roll_to(at.max(joining_at + JoiningDelay::get()));
+
+            let before = State::extract(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_2);
+            assert_ok!(Staking::execute_pending_operations(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+                vec![PendingOperationQuery {
+                    delegator: ACCOUNT_DELEGATOR_2,
+                    operation: P::joining_operation_key(ACCOUNT_CANDIDATE_1, joining_at),
+                }],
+            ));
+            let after = State::extract(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_2);
+
+            assert_event_emitted!(Event::<Runtime>::DelegationRefundedCandidateLeaving {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_2,
+                pool: P::target_pool(),
+                released: joining,
+            });
+            assert_eq!(P::shares(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_2).0, 0);
+            assert_eq!(before.delegator_hold - joining, after.delegator_hold);
+            assert_eq!(
+                before.candidate_total_stake - joining,
+                after.candidate_total_stake
+            );
+        })
+    }
+);
@@ -14,7 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
 
-use super::*;
+use {
+    super::*,
+    crate::{
+        assert_event_emitted, Lock, LockKind, Pools, PurgingCandidates, StakingStats,
+        ZeroStakeSince,
+    },
+};
 
 pool_test!(
     fn empty_delegation<P>() {
@@ -28,7 +34,8 @@ pool_test!(
                     RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
                     ACCOUNT_CANDIDATE_1,
                     P::target_pool(),
-                    0
+                    0,
+                    None
                 ),
                 Error::<Runtime>::StakeMustBeNonZero
             );
@@ -96,6 +103,7 @@ pool_test!(
                     ACCOUNT_CANDIDATE_1,
                     P::target_pool(),
                     amount,
+                    None,
                 ),
                 TokenError::FundsUnavailable
             );
@@ -157,6 +165,11 @@ pool_test!(
                     after: None,
                 },
                 P::event_staked(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1, 2, final_amount),
+                Event::RoundingReleased {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    amount: 10,
+                },
                 Event::ExecutedDelegate {
                     candidate: ACCOUNT_CANDIDATE_1,
                     delegator: ACCOUNT_DELEGATOR_1,
@@ -164,6 +177,13 @@ pool_test!(
                     staked: final_amount,
                     released: 10,
                 },
+                Event::PendingOperationsExecuted {
+                    executor: ACCOUNT_DELEGATOR_1,
+                    delegators: vec![ACCOUNT_DELEGATOR_1],
+                    count: 1,
+                    total_staked: final_amount,
+                    total_released: 10,
+                },
             ]);
         })
     }
@@ -183,7 +203,7 @@ pool_test!(
                 expected_joining: final_amount,
             }
             .test();
-            roll_to(block_number + BLOCKS_TO_WAIT - 1); // too soon
+            roll_to(block_number + JoiningDelay::get() - 1); // too soon
 
             assert_noop!(
                 Staking::execute_pending_operations(
@@ -226,7 +246,7 @@ pool_test!(
             }
             .test::<P>();
 
-            roll_to(block_number + BLOCKS_TO_WAIT - 1); // too soon
+            roll_to(block_number + LeavingDelay::get() - 1); // too soon
             assert_noop!(
                 Staking::execute_pending_operations(
                     RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
@@ -244,6 +264,208 @@ pool_test!(
     }
 );
 
+pool_test!(
+    fn best_effort_execution_skips_operations_not_ready<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let block_number_1 = block_number();
+
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                amount,
+                expected_joining: amount,
+            }
+            .test();
+
+            roll_to(block_number_1 + 1);
+            let block_number_2 = block_number();
+
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                amount,
+                expected_joining: amount,
+            }
+            .test();
+
+            // Only the first request is ready.
+            roll_to(block_number_1 + JoiningDelay::get());
+
+            assert_ok!(Staking::execute_pending_operations_best_effort(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                vec![
+                    PendingOperationQuery {
+                        delegator: ACCOUNT_DELEGATOR_1,
+                        operation: P::joining_operation_key(ACCOUNT_CANDIDATE_1, block_number_1)
+                    },
+                    PendingOperationQuery {
+                        delegator: ACCOUNT_DELEGATOR_1,
+                        operation: P::joining_operation_key(ACCOUNT_CANDIDATE_1, block_number_2)
+                    },
+                ]
+            ));
+
+            assert_eq!(
+                operation_stake(
+                    ACCOUNT_CANDIDATE_1,
+                    ACCOUNT_DELEGATOR_1,
+                    P::target_pool(),
+                    block_number_1
+                ),
+                0
+            );
+            assert_eq!(
+                operation_stake(
+                    ACCOUNT_CANDIDATE_1,
+                    ACCOUNT_DELEGATOR_1,
+                    P::target_pool(),
+                    block_number_2
+                ),
+                amount
+            );
+            assert_eq!(
+                P::computed_stake(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_1)
+                    .unwrap()
+                    .0,
+                amount
+            );
+            assert_eq!(
+                last_event(),
+                RuntimeEvent::Staking(Event::ExecutedPendingOperations { executed: vec![0] })
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn delegation_to_leaving_candidate<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let final_amount = 2 * InitialManualClaimShareValue::get();
+            let leaving_amount = round_down(final_amount, 3); // test leaving rounding
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: final_amount,
+                expected_increase: final_amount,
+                ..default()
+            }
+            .test::<P>();
+
+            LeavingCandidatesList::set(vec![ACCOUNT_CANDIDATE_1]);
+
+            assert_noop!(
+                Staking::request_delegate(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    final_amount,
+                    None,
+                ),
+                Error::<Runtime>::CandidateLeaving
+            );
+
+            // Existing delegators can still leave
+            RequestUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Stake(final_amount),
+                expected_removed: final_amount,
+                expected_leaving: leaving_amount,
+                ..default()
+            }
+            .test::<P>();
+        })
+    }
+);
+
+pool_test!(
+    fn delegation_to_unregistered_candidate<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+
+            UnregisteredCandidatesList::set(vec![ACCOUNT_CANDIDATE_1]);
+
+            assert_noop!(
+                Staking::request_delegate(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    amount,
+                    None,
+                ),
+                Error::<Runtime>::NotACandidate
+            );
+
+            // The account can still delegate to itself
+            assert_ok!(Staking::request_delegate(
+                RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                amount,
+                None,
+            ));
+        })
+    }
+);
+
+pool_test!(
+    fn undelegation_is_rate_limited_per_session<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let final_amount = 2 * InitialManualClaimShareValue::get();
+            let leaving_amount = round_down(final_amount, 3); // test leaving rounding
+
+            for delegator in [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2] {
+                FullDelegation {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator,
+                    request_amount: final_amount,
+                    expected_increase: final_amount,
+                    ..default()
+                }
+                .test::<P>();
+            }
+
+            MaxLeavingStakePerSession::set(final_amount);
+
+            RequestUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Stake(final_amount),
+                expected_removed: final_amount,
+                expected_leaving: leaving_amount,
+                ..default()
+            }
+            .test::<P>();
+
+            assert_noop!(
+                Staking::request_undelegate(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    SharesOrStake::Stake(final_amount),
+                ),
+                Error::<Runtime>::LeavingRateLimited
+            );
+
+            roll_to(block_number() + 1);
+
+            RequestUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_2,
+                request_amount: SharesOrStake::Stake(final_amount),
+                expected_removed: final_amount,
+                expected_leaving: leaving_amount,
+                ..default()
+            }
+            .test::<P>();
+        })
+    }
+);
+
 pool_test!(
     fn undelegation_execution<P>() {
         ExtBuilder::default().build().execute_with(|| {
@@ -304,6 +526,11 @@ pool_test!(
                     after: None,
                 },
                 P::event_staked(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1, 2, final_amount),
+                Event::RoundingReleased {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    amount: 10,
+                },
                 Event::ExecutedDelegate {
                     candidate: ACCOUNT_CANDIDATE_1,
                     delegator: ACCOUNT_DELEGATOR_1,
@@ -311,6 +538,13 @@ pool_test!(
                     staked: final_amount,
                     released: 10,
                 },
+                Event::PendingOperationsExecuted {
+                    executor: ACCOUNT_DELEGATOR_1,
+                    delegators: vec![ACCOUNT_DELEGATOR_1],
+                    count: 1,
+                    total_staked: final_amount,
+                    total_released: 10,
+                },
                 // undelegate request
                 Event::DecreasedStake {
                     candidate: ACCOUNT_CANDIDATE_1,
@@ -336,11 +570,54 @@ pool_test!(
                     delegator: ACCOUNT_DELEGATOR_1,
                     released: leaving_amount,
                 },
+                Event::PendingOperationsExecuted {
+                    executor: ACCOUNT_DELEGATOR_1,
+                    delegators: vec![ACCOUNT_DELEGATOR_1],
+                    count: 1,
+                    total_staked: 0,
+                    total_released: leaving_amount,
+                },
             ]);
         })
     }
 );
 
+pool_test!(
+    fn coarser_share_precision_rounds_in_larger_steps<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            StakingSharePrecision::set(SharePrecision {
+                joining: 1_000,
+                leaving: 300_000,
+            });
+
+            let final_amount = 2 * InitialManualClaimShareValue::get();
+            let requested_amount = final_amount + 999; // rounded down to a multiple of 1_000
+            let leaving_amount = round_down(final_amount, 300_000);
+
+            assert_eq!(leaving_amount, 1_800_000);
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: requested_amount,
+                expected_increase: final_amount,
+                ..default()
+            }
+            .test::<P>();
+
+            FullUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Stake(final_amount),
+                expected_removed: final_amount,
+                expected_leaving: leaving_amount,
+                ..default()
+            }
+            .test::<P>();
+        })
+    }
+);
+
 pool_test!(
     fn undelegation_execution_amount_in_shares<P>() {
         ExtBuilder::default().build().execute_with(|| {
@@ -403,6 +680,11 @@ pool_test!(
                     after: None,
                 },
                 P::event_staked(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1, 2, joining_amount),
+                Event::RoundingReleased {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    amount: 10,
+                },
                 Event::ExecutedDelegate {
                     candidate: ACCOUNT_CANDIDATE_1,
                     delegator: ACCOUNT_DELEGATOR_1,
@@ -410,6 +692,13 @@ pool_test!(
                     staked: joining_amount,
                     released: 10,
                 },
+                Event::PendingOperationsExecuted {
+                    executor: ACCOUNT_DELEGATOR_1,
+                    delegators: vec![ACCOUNT_DELEGATOR_1],
+                    count: 1,
+                    total_staked: joining_amount,
+                    total_released: 10,
+                },
                 // undelegate request
                 Event::DecreasedStake {
                     candidate: ACCOUNT_CANDIDATE_1,
@@ -435,7 +724,1728 @@ pool_test!(
                     delegator: ACCOUNT_DELEGATOR_1,
                     released: leaving_amount,
                 },
+                Event::PendingOperationsExecuted {
+                    executor: ACCOUNT_DELEGATOR_1,
+                    delegators: vec![ACCOUNT_DELEGATOR_1],
+                    count: 1,
+                    total_staked: 0,
+                    total_released: leaving_amount,
+                },
+            ]);
+        })
+    }
+);
+
+pool_test!(
+    fn undelegating_more_than_delegated_fails<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+
+            assert_noop!(
+                Staking::request_undelegate(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    SharesOrStake::Shares(3),
+                ),
+                Error::<Runtime>::NotEnoughShares
+            );
+            assert_noop!(
+                Staking::request_undelegate(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    SharesOrStake::Stake(amount + 1),
+                ),
+                Error::<Runtime>::NotEnoughStake
+            );
+
+            // The full position can still be undelegated
+            FullUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Shares(2),
+                expected_removed: amount,
+                expected_leaving: round_down(amount, 3),
+                ..default()
+            }
+            .test::<P>();
+        })
+    }
+);
+
+pool_test!(
+    fn early_undelegation_is_penalized<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            MinStakeDuration::set(10);
+            EarlyExitPenalty::set(Permill::from_percent(10));
+
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let undelegated = InitialManualClaimShareValue::get();
+            let penalty = undelegated / 10;
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+            let executed_at = block_number();
+
+            // Before the minimum duration the penalty is taken from the undelegated stake
+            assert_ok!(Staking::request_undelegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                SharesOrStake::Stake(undelegated),
+            ));
+            assert_eq!(total_balance(&ACCOUNT_PENALTY_DESTINATION), penalty);
+            assert_eq_last_events!(vec![
+                Event::<Runtime>::EarlyExitPenalized {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    destination: ACCOUNT_PENALTY_DESTINATION,
+                    amount: penalty,
+                },
+                Event::RequestedUndelegate {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    from: P::target_pool(),
+                    pending: undelegated - penalty,
+                    released: 0,
+                },
             ]);
+
+            // After it the whole undelegated stake is leaving
+            roll_to(executed_at + MinStakeDuration::get());
+            assert_ok!(Staking::request_undelegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                SharesOrStake::Stake(undelegated),
+            ));
+            assert_eq!(total_balance(&ACCOUNT_PENALTY_DESTINATION), penalty);
+            assert_eq_last_events!(vec![Event::<Runtime>::RequestedUndelegate {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                from: P::target_pool(),
+                pending: round_down(undelegated, 3),
+                released: undelegated - round_down(undelegated, 3),
+            }]);
+        })
+    }
+);
+
+pool_test!(
+    fn early_undelegation_penalizes_only_recently_joined_stake<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            MinStakeDuration::set(10);
+            EarlyExitPenalty::set(Permill::from_percent(10));
+
+            let amount = 2 * InitialManualClaimShareValue::get();
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+
+            roll_to(block_number() + MinStakeDuration::get());
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+
+            // Only the stake joined by the second delegation is penalized
+            assert_ok!(Staking::request_undelegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                SharesOrStake::Stake(2 * amount),
+            ));
+            assert_eq!(total_balance(&ACCOUNT_PENALTY_DESTINATION), amount / 10);
+        })
+    }
+);
+
+pool_test!(
+    fn top_up_does_not_reset_early_exit_lock<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            MinStakeDuration::set(10);
+            EarlyExitPenalty::set(Permill::from_percent(10));
+
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let undelegated = InitialManualClaimShareValue::get();
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+            let executed_at = block_number();
+
+            // A top-up of the same stake executed 4 blocks later moves the lock by half of it
+            roll_to(executed_at + 2);
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+            let unlocked_at = executed_at + 2 + MinStakeDuration::get();
+
+            roll_to(unlocked_at - 1);
+            assert_ok!(Staking::request_undelegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                SharesOrStake::Stake(undelegated),
+            ));
+            assert_eq!(
+                total_balance(&ACCOUNT_PENALTY_DESTINATION),
+                undelegated / 10
+            );
+
+            roll_to(unlocked_at);
+            assert_ok!(Staking::request_undelegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                SharesOrStake::Stake(undelegated),
+            ));
+            assert_eq!(
+                total_balance(&ACCOUNT_PENALTY_DESTINATION),
+                undelegated / 10
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn undelegation_forced_by_candidate_leave_is_not_penalized<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            MinStakeDuration::set(100);
+            EarlyExitPenalty::set(Permill::from_percent(10));
+
+            let amount = 2 * InitialManualClaimShareValue::get();
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+
+            let at = block_number() + CANDIDATE_LEAVE_DELAY;
+            assert_ok!(Staking::request_candidate_leave(RuntimeOrigin::signed(
+                ACCOUNT_CANDIDATE_1
+            )));
+
+            // The delegation is moved to the leaving pool before `MinStakeDuration`, without
+            // any penalty.
+            roll_to(at);
+            assert_eq!(P::shares(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_1).0, 0);
+            let leaving = PoolState::extract::<Leaving>(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1);
+            assert_eq!(leaving.stake, round_down(amount, 3));
+            assert_eq!(total_balance(&ACCOUNT_PENALTY_DESTINATION), 0);
+        })
+    }
+);
+
+pool_test!(
+    fn conversion_helpers_match_undelegation_amounts_in_shares<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            // Same amounts as `undelegation_execution_amount_in_shares`
+            let joining_amount = 2 * InitialManualClaimShareValue::get();
+            let joining_requested_amount = joining_amount + 10;
+
+            let leaving_requested_amount = InitialManualClaimShareValue::get();
+            let leaving_amount = round_down(leaving_requested_amount, 3);
+
+            // Joining shares are worth the full requested amount, rounding happens when they
+            // are converted into shares of the target pool
+            let joining_shares = Staking::stake_to_shares(
+                &ACCOUNT_CANDIDATE_1,
+                AllTargetPool::Joining,
+                joining_requested_amount,
+            )
+            .unwrap();
+            assert_eq!(
+                Staking::shares_to_stake(
+                    &ACCOUNT_CANDIDATE_1,
+                    AllTargetPool::Joining,
+                    joining_shares
+                ),
+                Some(joining_requested_amount)
+            );
+            let shares = Staking::stake_to_shares(
+                &ACCOUNT_CANDIDATE_1,
+                P::target_pool().into(),
+                joining_requested_amount,
+            )
+            .unwrap();
+            assert_eq!(shares, 2);
+            assert_eq!(
+                Staking::shares_to_stake(&ACCOUNT_CANDIDATE_1, P::target_pool().into(), shares),
+                Some(joining_amount)
+            );
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: joining_requested_amount,
+                expected_increase: joining_amount,
+                ..default()
+            }
+            .test::<P>();
+
+            assert_eq!(
+                Staking::shares_to_stake(&ACCOUNT_CANDIDATE_1, P::target_pool().into(), 1),
+                Some(leaving_requested_amount)
+            );
+            let leaving_shares = Staking::stake_to_shares(
+                &ACCOUNT_CANDIDATE_1,
+                AllTargetPool::Leaving,
+                leaving_requested_amount,
+            )
+            .unwrap();
+            assert_eq!(
+                Staking::shares_to_stake(
+                    &ACCOUNT_CANDIDATE_1,
+                    AllTargetPool::Leaving,
+                    leaving_shares
+                ),
+                Some(leaving_amount)
+            );
+
+            FullUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Shares(1),
+                expected_removed: leaving_requested_amount,
+                expected_leaving: leaving_amount,
+                ..default()
+            }
+            .test::<P>();
+        })
+    }
+);
+
+pool_test!(
+    fn joining_and_leaving_delays_are_independent<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            JoiningDelay::set(2);
+            LeavingDelay::set(5);
+
+            let amount = 2 * InitialManualClaimShareValue::get();
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+
+            let block_number = block_number();
+
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                amount,
+                expected_joining: amount,
+            }
+            .test();
+
+            RequestUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Stake(amount),
+                expected_removed: amount,
+                expected_leaving: round_down(amount, 3),
+                ..default()
+            }
+            .test::<P>();
+
+            let leaving_operation = PendingOperationQuery {
+                delegator: ACCOUNT_DELEGATOR_1,
+                operation: PendingOperationKey::Leaving {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    at: block_number,
+                },
+            };
+
+            // Joining delay is elapsed but not leaving delay.
+            roll_to(block_number + JoiningDelay::get());
+
+            ExecuteDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                block_number,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+
+            assert_noop!(
+                Staking::execute_pending_operations(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    vec![leaving_operation.clone()]
+                ),
+                Error::<Runtime>::RequestCannotBeExecuted(0)
+            );
+
+            roll_to(block_number + LeavingDelay::get());
+
+            ExecuteUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                block_number,
+                expected_decrease: round_down(amount, 3),
+            }
+            .test();
+        })
+    }
+);
+
+pool_test!(
+    fn delegation_by_authorized_proxy<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let block_number = block_number();
+            let proxy_balance = Balances::free_balance(ACCOUNT_DELEGATOR_2);
+
+            assert_noop!(
+                Staking::delegate_for(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+                    ACCOUNT_DELEGATOR_1,
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    amount,
+                ),
+                Error::<Runtime>::NotAuthorizedProxy
+            );
+
+            assert_ok!(Staking::set_delegation_proxy(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_DELEGATOR_2,
+                true,
+            ));
+            assert_ok!(Staking::delegate_for(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+                ACCOUNT_DELEGATOR_1,
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                amount,
+            ));
+
+            // The pending operation belongs to the owner, which funds it.
+            assert_eq!(
+                operation_stake(
+                    ACCOUNT_CANDIDATE_1,
+                    ACCOUNT_DELEGATOR_1,
+                    P::target_pool(),
+                    block_number
+                ),
+                amount
+            );
+            assert_eq!(
+                operation_stake(
+                    ACCOUNT_CANDIDATE_1,
+                    ACCOUNT_DELEGATOR_2,
+                    P::target_pool(),
+                    block_number
+                ),
+                0
+            );
+            assert_eq!(balance_hold(&ACCOUNT_DELEGATOR_1), amount);
+            assert_eq!(Balances::free_balance(ACCOUNT_DELEGATOR_2), proxy_balance);
+
+            roll_to(block_number + JoiningDelay::get());
+
+            assert_ok!(Staking::execute_pending_operations(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+                vec![PendingOperationQuery {
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    operation: P::joining_operation_key(ACCOUNT_CANDIDATE_1, block_number)
+                }]
+            ));
+
+            assert_eq!(
+                P::computed_stake(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_1)
+                    .unwrap()
+                    .0,
+                amount
+            );
+            assert_eq!(
+                P::shares(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_2),
+                Shares(0)
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn delegators_per_candidate_are_limited<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            MaxDelegatorsPerCandidate::set(2);
+
+            for delegator in [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2] {
+                RequestDelegation {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator,
+                    pool: P::target_pool(),
+                    amount,
+                    expected_joining: amount,
+                }
+                .test();
+            }
+            assert_eq!(DelegatorsCount::<Runtime>::get(ACCOUNT_CANDIDATE_1), 2);
+
+            assert_noop!(
+                Staking::request_delegate(
+                    RuntimeOrigin::signed(ACCOUNT_CANDIDATE_2),
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    amount,
+                    None,
+                ),
+                Error::<Runtime>::TooManyDelegators
+            );
+
+            // Existing delegators can still increase their stake.
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                amount,
+                expected_joining: amount,
+            }
+            .test();
+            assert_eq!(DelegatorsCount::<Runtime>::get(ACCOUNT_CANDIDATE_1), 2);
+        })
+    }
+);
+
+pool_test!(
+    fn stats_count_delegators_until_fully_undelegated<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let final_amount = 2 * InitialManualClaimShareValue::get();
+            let requested_amount = final_amount + 10; // test share rounding
+            let leaving_amount = round_down(final_amount, 3); // test leaving rounding
+
+            assert_eq!(Staking::stats(), StakingStats::default());
+
+            for delegator in [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2] {
+                FullDelegation {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator,
+                    request_amount: requested_amount,
+                    expected_increase: final_amount,
+                    ..default()
+                }
+                .test::<P>();
+            }
+            assert_eq!(
+                Staking::stats(),
+                StakingStats {
+                    total_candidates: 1,
+                    total_delegators: 2,
+                    total_staked: 2 * final_amount,
+                }
+            );
+
+            // The delegator is counted until its last position is removed.
+            FullUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Stake(final_amount),
+                expected_removed: final_amount,
+                expected_leaving: leaving_amount,
+                ..default()
+            }
+            .test::<P>();
+            assert_eq!(
+                Staking::stats(),
+                StakingStats {
+                    total_candidates: 1,
+                    total_delegators: 1,
+                    total_staked: final_amount,
+                }
+            );
+
+            FullUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_2,
+                request_amount: SharesOrStake::Stake(final_amount),
+                expected_removed: final_amount,
+                expected_leaving: leaving_amount,
+                ..default()
+            }
+            .test::<P>();
+            assert_eq!(Staking::stats(), StakingStats::default());
+        })
+    }
+);
+
+pool_test!(
+    fn delegations_are_limited_by_global_stake_cap<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            MaxTotalStake::set(2 * amount);
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_2,
+                delegator: ACCOUNT_DELEGATOR_2,
+                pool: P::target_pool(),
+                amount,
+                expected_joining: amount,
+            }
+            .test();
+            assert_eq!(Staking::total_stake(), 2 * amount);
+
+            assert_noop!(
+                Staking::request_delegate(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    amount,
+                    None,
+                ),
+                Error::<Runtime>::GlobalStakeCapReached
+            );
+
+            // Undelegating is still allowed, and frees room for new delegations.
+            assert_ok!(Staking::request_undelegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                SharesOrStake::Stake(amount),
+            ));
+            assert_eq!(Staking::total_stake(), amount);
+
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                amount,
+                expected_joining: amount,
+            }
+            .test();
+        })
+    }
+);
+
+pool_test!(
+    fn locks_show_when_pending_operations_unlock<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let leaving_amount = round_down(amount, 3);
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+            assert_eq!(Staking::locks(&ACCOUNT_DELEGATOR_1), vec![]);
+
+            let block_number = block_number();
+            assert_ok!(Staking::request_undelegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                SharesOrStake::Stake(amount),
+            ));
+            assert_ok!(Staking::request_delegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_2,
+                P::target_pool(),
+                amount,
+                None,
+            ));
+
+            let locks = Staking::locks(&ACCOUNT_DELEGATOR_1);
+            assert_eq!(locks.len(), 2);
+            assert!(locks.contains(&Lock {
+                candidate: ACCOUNT_CANDIDATE_1,
+                amount: leaving_amount,
+                unlock_at: block_number + LeavingDelay::get(),
+                kind: LockKind::Leaving,
+            }));
+            assert!(locks.contains(&Lock {
+                candidate: ACCOUNT_CANDIDATE_2,
+                amount,
+                unlock_at: block_number + JoiningDelay::get(),
+                kind: LockKind::Joining,
+            }));
+        })
+    }
+);
+
+pool_test!(
+    fn delegation_receipt_is_minted_and_burned_with_the_position<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let minted = Receipt::Minted {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+            };
+            let burned = Receipt::Burned {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+            };
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+            assert_eq!(DelegationReceipts::get(), vec![minted.clone()]);
+
+            // Increasing the position doesn't mint another receipt
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+            assert_eq!(DelegationReceipts::get(), vec![minted.clone()]);
+
+            // Partial undelegation keeps the receipt
+            FullUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Stake(amount),
+                expected_removed: amount,
+                expected_leaving: round_down(amount, 3),
+                ..default()
+            }
+            .test::<P>();
+            assert_eq!(DelegationReceipts::get(), vec![minted.clone()]);
+
+            FullUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Stake(amount),
+                expected_removed: amount,
+                expected_leaving: round_down(amount, 3),
+                ..default()
+            }
+            .test::<P>();
+            assert_eq!(DelegationReceipts::get(), vec![minted, burned]);
+        })
+    }
+);
+
+pool_test!(
+    fn undelegation_dust_is_swept_to_collector<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let final_amount = 2 * InitialManualClaimShareValue::get();
+            let leaving_amount = round_down(final_amount, 3); // test leaving rounding
+            let dust = final_amount - leaving_amount;
+            assert_eq!(dust, 2);
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: final_amount,
+                expected_increase: final_amount,
+                ..default()
+            }
+            .test::<P>();
+
+            DustCollector::set(Some(ACCOUNT_DELEGATOR_2));
+            let before = State::extract(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1);
+            let collector_before = total_balance(&ACCOUNT_DELEGATOR_2);
+
+            assert_ok!(Staking::request_undelegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                SharesOrStake::Stake(final_amount),
+            ));
+
+            let after = State::extract(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1);
+            let leaving = PoolState::extract::<Leaving>(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1);
+            let collected = total_balance(&ACCOUNT_DELEGATOR_2) - collector_before;
+
+            // The requested amount is either leaving or collected, nothing is lost.
+            assert_eq!(leaving.stake, leaving_amount);
+            assert_eq!(collected, dust);
+            assert_eq!(leaving.stake + collected, final_amount);
+            assert_eq!(
+                before.delegator_hold - final_amount + leaving_amount,
+                after.delegator_hold
+            );
+            assert_eq!(before.delegator_balance - dust, after.delegator_balance);
+
+            assert_eq_last_events!(vec![
+                Event::<Runtime>::SweptDust {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    collector: ACCOUNT_DELEGATOR_2,
+                    amount: dust,
+                },
+                Event::RequestedUndelegate {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    from: P::target_pool(),
+                    pending: leaving_amount,
+                    released: dust,
+                },
+            ]);
+        })
+    }
+);
+
+pool_test!(
+    fn scheduled_delegation_activates_at_block<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let activate_at = block_number() + 2;
+            let before = State::extract(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1);
+
+            assert_ok!(Staking::schedule_delegation(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                amount,
+                activate_at,
+            ));
+
+            // Funds are held right away but don't join the candidate yet.
+            let after = State::extract(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1);
+            assert_eq!(before.delegator_hold + amount, after.delegator_hold);
+            assert_eq!(before.candidate_total_stake, after.candidate_total_stake);
+
+            roll_one_block();
+            let joining = PoolState::extract::<Joining>(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1);
+            assert_eq!(joining.stake, 0);
+
+            roll_one_block();
+            assert_eq!(block_number(), activate_at);
+
+            let after = State::extract(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1);
+            let joining = PoolState::extract::<Joining>(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1);
+            assert_eq!(joining.stake, amount);
+            assert_eq!(joining.hold, amount);
+            assert_eq!(before.delegator_hold + amount, after.delegator_hold);
+            assert_eq!(
+                before.candidate_total_stake + amount,
+                after.candidate_total_stake
+            );
+            assert_eq!(
+                operation_stake(
+                    ACCOUNT_CANDIDATE_1,
+                    ACCOUNT_DELEGATOR_1,
+                    P::target_pool(),
+                    activate_at
+                ),
+                amount
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn pool_totals_match_sum_over_candidates<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let final_amount = 2 * InitialManualClaimShareValue::get();
+            let leaving_amount = round_down(final_amount, 3); // test leaving rounding
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: final_amount,
+                expected_increase: final_amount,
+                ..default()
+            }
+            .test::<P>();
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_2,
+                delegator: ACCOUNT_DELEGATOR_2,
+                request_amount: 2 * final_amount,
+                expected_increase: 2 * final_amount,
+                ..default()
+            }
+            .test::<P>();
+
+            RequestUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Stake(final_amount),
+                expected_removed: final_amount,
+                expected_leaving: leaving_amount,
+                ..default()
+            }
+            .test::<P>();
+
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_2,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                amount: 11,
+                expected_joining: round_down(11, 2),
+            }
+            .test();
+
+            fn sum<P: Pool<Runtime>>() -> Balance {
+                [ACCOUNT_CANDIDATE_1, ACCOUNT_CANDIDATE_2]
+                    .iter()
+                    .map(|candidate| P::total_staked(candidate).0)
+                    .sum()
+            }
+
+            assert_eq!(Staking::total_joining(), sum::<Joining>());
+            assert_eq!(Staking::total_joining(), round_down(11, 2));
+            assert_eq!(
+                Staking::total_auto_compounding(),
+                sum::<pools::AutoCompounding<Runtime>>()
+            );
+            assert_eq!(
+                Staking::total_manual_claim(),
+                sum::<pools::ManualRewards<Runtime>>()
+            );
+            assert_eq!(Staking::total_leaving(), sum::<Leaving>());
+            assert_eq!(Staking::total_leaving(), leaving_amount);
+            assert_eq!(
+                Staking::total_auto_compounding() + Staking::total_manual_claim(),
+                2 * final_amount
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn undelegate_from_all_candidates<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let final_amount = 2 * InitialManualClaimShareValue::get();
+            let leaving_amount = round_down(final_amount, 3); // test leaving rounding
+            let candidates = [
+                ACCOUNT_CANDIDATE_1,
+                ACCOUNT_CANDIDATE_2,
+                ACCOUNT_CANDIDATE_3,
+            ];
+
+            for candidate in candidates {
+                FullDelegation {
+                    candidate,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    request_amount: final_amount,
+                    expected_increase: final_amount,
+                    ..default()
+                }
+                .test::<P>();
+            }
+
+            assert_ok!(Staking::request_undelegate_all(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                P::target_pool(),
+            ));
+
+            let requested: Vec<_> = events()
+                .into_iter()
+                .filter(|e| matches!(e, Event::RequestedUndelegate { .. }))
+                .collect();
+            let expected: Vec<_> = candidates
+                .into_iter()
+                .map(|candidate| Event::<Runtime>::RequestedUndelegate {
+                    candidate,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    from: P::target_pool(),
+                    pending: leaving_amount,
+                    released: final_amount - leaving_amount,
+                })
+                .collect();
+            assert_eq!(requested, expected);
+
+            for candidate in candidates {
+                let pool = PoolState::extract::<P>(candidate, ACCOUNT_DELEGATOR_1);
+                assert_eq!(pool.stake, 0);
+            }
+
+            // Nothing left to undelegate
+            assert_noop!(
+                Staking::request_undelegate_all(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    P::target_pool(),
+                ),
+                Error::<Runtime>::NothingToUndelegate
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn joining_delay_change_keeps_schedule_of_pending_requests<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let block_number_1 = block_number();
+
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                amount,
+                expected_joining: amount,
+            }
+            .test();
+
+            assert_ok!(Staking::set_joining_delay(RuntimeOrigin::root(), 5));
+            assert_eq!(Staking::joining_delay(), 5);
+
+            roll_one_block();
+            let block_number_2 = block_number();
+
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                amount,
+                expected_joining: amount,
+            }
+            .test();
+
+            // The request made before the change keeps its original delay.
+            roll_to(block_number_1 + BLOCKS_TO_WAIT);
+            ExecuteDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                block_number: block_number_1,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+
+            // The request made after the change uses the new delay.
+            roll_to(block_number_2 + BLOCKS_TO_WAIT);
+            assert_noop!(
+                Staking::execute_pending_operations(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    vec![PendingOperationQuery {
+                        delegator: ACCOUNT_DELEGATOR_1,
+                        operation: P::joining_operation_key(ACCOUNT_CANDIDATE_1, block_number_2),
+                    }]
+                ),
+                Error::<Runtime>::RequestCannotBeExecuted(0)
+            );
+
+            roll_to(block_number_2 + 5);
+            ExecuteDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                block_number: block_number_2,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+        })
+    }
+);
+
+pool_test!(
+    fn joining_requests_in_same_block_are_merged<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let block_number = block_number();
+
+            for _ in 0..2 {
+                RequestDelegation {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    pool: P::target_pool(),
+                    amount,
+                    expected_joining: amount,
+                }
+                .test();
+            }
+
+            assert_eq!(
+                PendingOperations::<Runtime>::iter_prefix(ACCOUNT_DELEGATOR_1).count(),
+                1
+            );
+            assert_eq!(
+                operation_stake(
+                    ACCOUNT_CANDIDATE_1,
+                    ACCOUNT_DELEGATOR_1,
+                    P::target_pool(),
+                    block_number
+                ),
+                2 * amount
+            );
+
+            roll_to(block_number + BLOCKS_TO_WAIT);
+            ExecuteDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                block_number,
+                expected_increase: 2 * amount,
+                ..default()
+            }
+            .test::<P>();
+        })
+    }
+);
+
+pool_test!(
+    fn cancel_undelegation_restores_stake<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            // Multiple of the leaving precision and the initial share value, so no stake
+            // is lost to rounding.
+            let amount = 3 * InitialManualClaimShareValue::get();
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+
+            let block_number = block_number();
+            RequestUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Stake(amount),
+                expected_removed: amount,
+                expected_leaving: amount,
+                ..default()
+            }
+            .test::<P>();
+            assert_eq!(
+                Candidates::<Runtime>::total_stake(&ACCOUNT_CANDIDATE_1).0,
+                0
+            );
+
+            let before = State::extract(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1);
+            assert_ok!(Staking::cancel_undelegation(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                block_number,
+                P::target_pool(),
+            ));
+            let after = State::extract(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1);
+
+            assert_eq!(after.candidate_total_stake, amount);
+            assert_eq!(after.delegator_hold, before.delegator_hold);
+            assert_eq!(
+                PoolState::extract::<P>(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1).stake,
+                amount
+            );
+            assert_eq!(
+                PoolState::extract::<Leaving>(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1).stake,
+                0
+            );
+            assert_eq!(
+                PendingOperations::<Runtime>::get(
+                    ACCOUNT_DELEGATOR_1,
+                    PendingOperationKey::Leaving {
+                        candidate: ACCOUNT_CANDIDATE_1,
+                        at: block_number,
+                    }
+                ),
+                0
+            );
+            assert_eq_last_events!(vec![Event::<Runtime>::CancelledUndelegate {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                staked: amount,
+                released: 0,
+            }]);
+
+            assert_noop!(
+                Staking::cancel_undelegation(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    ACCOUNT_CANDIDATE_1,
+                    block_number,
+                    P::target_pool(),
+                ),
+                Error::<Runtime>::NoPendingOperation
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn best_effort_execution_of_several_delegators<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let block_number = block_number();
+
+            for delegator in [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2] {
+                RequestDelegation {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator,
+                    pool: P::target_pool(),
+                    amount,
+                    expected_joining: amount,
+                }
+                .test();
+            }
+
+            roll_to(block_number + JoiningDelay::get());
+
+            // A third party executes the operations of both delegators in a single call.
+            // The operation queried for the candidate doesn't exist and is skipped.
+            let query = |delegator| PendingOperationQuery {
+                delegator,
+                operation: P::joining_operation_key(ACCOUNT_CANDIDATE_1, block_number),
+            };
+            assert_ok!(Staking::execute_pending_operations_best_effort(
+                RuntimeOrigin::signed(ACCOUNT_CANDIDATE_2),
+                vec![
+                    query(ACCOUNT_DELEGATOR_1),
+                    query(ACCOUNT_CANDIDATE_1),
+                    query(ACCOUNT_DELEGATOR_2),
+                ]
+            ));
+            assert_eq_last_events!(vec![Event::<Runtime>::ExecutedPendingOperations {
+                executed: vec![0, 2],
+            }]);
+
+            for delegator in [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2] {
+                assert_eq!(
+                    operation_stake(
+                        ACCOUNT_CANDIDATE_1,
+                        delegator,
+                        P::target_pool(),
+                        block_number
+                    ),
+                    0
+                );
+                assert_eq!(
+                    PoolState::extract::<P>(ACCOUNT_CANDIDATE_1, delegator).stake,
+                    amount
+                );
+            }
+        })
+    }
+);
+
+pool_test!(
+    fn multi_execution_skips_operations_not_ready<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let block_number = block_number();
+
+            for delegator in [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2] {
+                RequestDelegation {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator,
+                    pool: P::target_pool(),
+                    amount,
+                    expected_joining: amount,
+                }
+                .test();
+            }
+
+            roll_to(block_number + JoiningDelay::get());
+
+            // A request made now is not ready yet and is skipped.
+            let late_block_number = block_number();
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_2,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                amount,
+                expected_joining: amount,
+            }
+            .test();
+
+            assert_ok!(Staking::execute_pending_operations_multi(
+                RuntimeOrigin::signed(ACCOUNT_CANDIDATE_2),
+                vec![
+                    PendingOperationQuery {
+                        delegator: ACCOUNT_DELEGATOR_1,
+                        operation: P::joining_operation_key(ACCOUNT_CANDIDATE_1, block_number),
+                    },
+                    PendingOperationQuery {
+                        delegator: ACCOUNT_DELEGATOR_1,
+                        operation: P::joining_operation_key(ACCOUNT_CANDIDATE_2, late_block_number),
+                    },
+                    PendingOperationQuery {
+                        delegator: ACCOUNT_DELEGATOR_2,
+                        operation: P::joining_operation_key(ACCOUNT_CANDIDATE_1, block_number),
+                    },
+                ]
+            ));
+            assert_eq_last_events!(vec![
+                Event::<Runtime>::ExecutedPendingOperations {
+                    executed: vec![0, 2],
+                },
+                Event::PendingOperationsExecuted {
+                    executor: ACCOUNT_CANDIDATE_2,
+                    delegators: vec![ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2],
+                    count: 2,
+                    total_staked: 2 * amount,
+                    total_released: 0,
+                },
+            ]);
+
+            for delegator in [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2] {
+                assert_eq!(
+                    PoolState::extract::<P>(ACCOUNT_CANDIDATE_1, delegator).stake,
+                    amount
+                );
+            }
+            assert_eq!(
+                operation_stake(
+                    ACCOUNT_CANDIDATE_2,
+                    ACCOUNT_DELEGATOR_1,
+                    P::target_pool(),
+                    late_block_number
+                ),
+                amount
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn execution_summary_combines_all_operations<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let leaving_amount = round_down(amount, 3);
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+
+            let block_number = block_number();
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_2,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                amount,
+                expected_joining: amount,
+            }
+            .test();
+            assert_ok!(Staking::request_undelegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                SharesOrStake::Stake(amount),
+            ));
+
+            roll_to(block_number + JoiningDelay::get().max(LeavingDelay::get()));
+
+            assert_ok!(Staking::execute_pending_operations(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                vec![
+                    PendingOperationQuery {
+                        delegator: ACCOUNT_DELEGATOR_1,
+                        operation: P::joining_operation_key(ACCOUNT_CANDIDATE_2, block_number),
+                    },
+                    PendingOperationQuery {
+                        delegator: ACCOUNT_DELEGATOR_1,
+                        operation: PendingOperationKey::Leaving {
+                            candidate: ACCOUNT_CANDIDATE_1,
+                            at: block_number,
+                        },
+                    },
+                ]
+            ));
+
+            assert_eq_last_events!(vec![
+                Event::<Runtime>::ExecutedUndelegate {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    released: leaving_amount,
+                },
+                Event::PendingOperationsExecuted {
+                    executor: ACCOUNT_DELEGATOR_1,
+                    delegators: vec![ACCOUNT_DELEGATOR_1],
+                    count: 2,
+                    total_staked: amount,
+                    total_released: leaving_amount,
+                },
+            ]);
+        })
+    }
+);
+
+pool_test!(
+    fn delegation_below_candidate_minimum_is_rejected<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let min_delegation = 2 * amount;
+
+            assert_ok!(Staking::set_candidate_min_delegation(
+                RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+                min_delegation,
+            ));
+            assert_eq_last_events!(vec![Event::<Runtime>::UpdatedCandidateMinDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                min_delegation,
+            }]);
+
+            assert_noop!(
+                Staking::request_delegate(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    amount,
+                    None,
+                ),
+                Error::<Runtime>::BelowCandidateMinimum
+            );
+
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                amount: min_delegation,
+                expected_joining: min_delegation,
+            }
+            .test();
+
+            // Existing delegators can increase their stake by any amount
+            RequestDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                pool: P::target_pool(),
+                amount,
+                expected_joining: amount,
+            }
+            .test();
+        })
+    }
+);
+
+pool_test!(
+    fn set_candidate_min_delegation_requires_candidate<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            UnregisteredCandidatesList::set(vec![ACCOUNT_CANDIDATE_1]);
+
+            assert_noop!(
+                Staking::set_candidate_min_delegation(
+                    RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+                    amount,
+                ),
+                Error::<Runtime>::NotACandidate
+            );
+
+            // Candidates that are no longer registered can update it while they have stake.
+            UnregisteredCandidatesList::set(vec![]);
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+            UnregisteredCandidatesList::set(vec![ACCOUNT_CANDIDATE_1]);
+
+            assert_ok!(Staking::set_candidate_min_delegation(
+                RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+                amount,
+            ));
+        })
+    }
+);
+
+pool_test!(
+    fn existing_delegator_top_up_uses_reduced_joining_delay<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            ExistingDelegatorJoiningDelay::set(1);
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+
+            let block_number = block_number();
+            for delegator in [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2] {
+                RequestDelegation {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator,
+                    pool: P::target_pool(),
+                    amount,
+                    expected_joining: amount,
+                }
+                .test();
+            }
+            let query = |delegator| PendingOperationQuery {
+                delegator,
+                operation: P::joining_operation_key(ACCOUNT_CANDIDATE_1, block_number),
+            };
+
+            // The top-up can be executed before the first-time delegation
+            roll_to(block_number + ExistingDelegatorJoiningDelay::get());
+            assert_ok!(Staking::execute_pending_operations(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                vec![query(ACCOUNT_DELEGATOR_1)]
+            ));
+            assert_noop!(
+                Staking::execute_pending_operations(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+                    vec![query(ACCOUNT_DELEGATOR_2)]
+                ),
+                Error::<Runtime>::RequestCannotBeExecuted(0)
+            );
+
+            roll_to(block_number + JoiningDelay::get());
+            assert_ok!(Staking::execute_pending_operations(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+                vec![query(ACCOUNT_DELEGATOR_2)]
+            ));
+            assert_eq!(
+                PoolState::extract::<P>(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1).stake,
+                2 * amount
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn bonded_position_can_only_be_undelegated_after_its_term<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let amount = 2 * InitialManualClaimShareValue::get();
+            let block_number = block_number();
+            let until = block_number + JoiningDelay::get() + 10;
+
+            assert_noop!(
+                Staking::bonded_delegate(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    amount,
+                    block_number,
+                ),
+                Error::<Runtime>::BondMustEndInFuture
+            );
+
+            assert_ok!(Staking::bonded_delegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                amount,
+                until,
+            ));
+            assert_eq!(
+                last_event(),
+                RuntimeEvent::Staking(Event::BondedPosition {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    until,
+                })
+            );
+
+            roll_to(block_number + JoiningDelay::get());
+
+            ExecuteDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                block_number,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<P>();
+
+            assert_noop!(
+                Staking::request_undelegate(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    SharesOrStake::Stake(amount),
+                ),
+                Error::<Runtime>::StillBonded
+            );
+
+            roll_to(until);
+            assert_eq!(
+                Staking::bonded_until(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_1),
+                None
+            );
+
+            assert_ok!(Staking::request_undelegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                SharesOrStake::Stake(amount),
+            ));
+        })
+    }
+);
+
+pool_test!(
+    fn zero_stake_candidate_is_purged_after_grace_period<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            ZeroStakeCandidateGracePeriod::set(10);
+            let final_amount = 2 * InitialManualClaimShareValue::get();
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: final_amount,
+                expected_increase: final_amount,
+                ..default()
+            }
+            .test::<P>();
+
+            FullUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Stake(final_amount),
+                expected_removed: final_amount,
+                expected_leaving: round_down(final_amount, 3),
+                ..default()
+            }
+            .test::<P>();
+
+            // The candidate has no stake left but its pools still have entries.
+            let since = block_number();
+            assert_eq!(
+                ZeroStakeSince::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+                Some(since)
+            );
+            assert_eq!(
+                Candidates::<Runtime>::total_stake(&ACCOUNT_CANDIDATE_1).0,
+                0
+            );
+            assert!(Pools::<Runtime>::iter_prefix(ACCOUNT_CANDIDATE_1)
+                .next()
+                .is_some());
+
+            roll_to(since + 9);
+            assert!(ZeroStakeSince::<Runtime>::get(ACCOUNT_CANDIDATE_1).is_some());
+
+            roll_to(since + 10);
+            assert_eq!(ZeroStakeSince::<Runtime>::get(ACCOUNT_CANDIDATE_1), None);
+            assert!(Pools::<Runtime>::iter_prefix(ACCOUNT_CANDIDATE_1)
+                .next()
+                .is_none());
+            assert_eq!(DelegatorsCount::<Runtime>::get(ACCOUNT_CANDIDATE_1), 0);
+            assert!(!Staking::eligible_candidates()
+                .iter()
+                .any(|(candidate, _)| *candidate == ACCOUNT_CANDIDATE_1));
+            assert_eq!(
+                last_event(),
+                RuntimeEvent::Staking(Event::CandidatePurged {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                })
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn zero_stake_candidate_is_purged_over_several_blocks<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            ZeroStakeCandidateGracePeriod::set(10);
+            MaxPurgedEntriesPerBlock::set(2);
+            let final_amount = 2 * InitialManualClaimShareValue::get();
+
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: final_amount,
+                expected_increase: final_amount,
+                ..default()
+            }
+            .test::<P>();
+
+            FullUndelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: SharesOrStake::Stake(final_amount),
+                expected_removed: final_amount,
+                expected_leaving: round_down(final_amount, 3),
+                ..default()
+            }
+            .test::<P>();
+
+            let since = block_number();
+            roll_to(since + 10);
+            assert_eq!(ZeroStakeSince::<Runtime>::get(ACCOUNT_CANDIDATE_1), None);
+            assert!(PurgingCandidates::<Runtime>::contains_key(
+                ACCOUNT_CANDIDATE_1
+            ));
+            assert!(Pools::<Runtime>::iter_prefix(ACCOUNT_CANDIDATE_1)
+                .next()
+                .is_some());
+
+            // Candidates being purged don't accept delegations.
+            assert_noop!(
+                Staking::request_delegate(
+                    RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                    ACCOUNT_CANDIDATE_1,
+                    P::target_pool(),
+                    final_amount,
+                    None,
+                ),
+                Error::<Runtime>::CandidateBeingPurged
+            );
+
+            roll_to(since + 100);
+            assert!(!PurgingCandidates::<Runtime>::contains_key(
+                ACCOUNT_CANDIDATE_1
+            ));
+            assert!(Pools::<Runtime>::iter_prefix(ACCOUNT_CANDIDATE_1)
+                .next()
+                .is_none());
+            assert_event_emitted!(Event::<Runtime>::CandidatePurged {
+                candidate: ACCOUNT_CANDIDATE_1,
+            });
         })
     }
 );
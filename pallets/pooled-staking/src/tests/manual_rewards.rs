@@ -14,7 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
 
-use {super::*, crate::PoolsKey};
+use {
+    super::*,
+    crate::{assert_eq_last_events, PoolsKey, RewardDestination, RewardDestinations},
+};
 
 fn pending_rewards(candicate: AccountId, delegator: AccountId) -> Balance {
     pools::ManualRewards::<Runtime>::pending_rewards(&candicate, &delegator)
@@ -157,3 +160,121 @@ fn undelegation_transfer_rewards() {
         assert_eq!(pending_rewards(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1), 0);
     });
 }
+
+#[test]
+fn claim_rewards_batch_skips_candidates_without_rewards() {
+    ExtBuilder::default().build().execute_with(|| {
+        let amount = 2 * InitialManualClaimShareValue::get();
+
+        for candidate in [
+            ACCOUNT_CANDIDATE_1,
+            ACCOUNT_CANDIDATE_2,
+            ACCOUNT_CANDIDATE_3,
+        ] {
+            FullDelegation {
+                candidate,
+                delegator: ACCOUNT_DELEGATOR_1,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<pools::ManualRewards<Runtime>>();
+        }
+
+        // Set counters to simulate rewards for candidates 1 and 2 only.
+        crate::Pools::<Runtime>::set(ACCOUNT_CANDIDATE_1, &PoolsKey::ManualRewardsCounter, 10);
+        crate::Pools::<Runtime>::set(ACCOUNT_CANDIDATE_2, &PoolsKey::ManualRewardsCounter, 5);
+
+        let before = total_balance(&ACCOUNT_DELEGATOR_1);
+
+        assert_ok!(Staking::claim_rewards_batch(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            vec![
+                ACCOUNT_CANDIDATE_1,
+                ACCOUNT_CANDIDATE_2,
+                ACCOUNT_CANDIDATE_3
+            ],
+        ));
+
+        // 10 coins (counter) * 2 shares + 5 coins (counter) * 2 shares
+        assert_eq!(total_balance(&ACCOUNT_DELEGATOR_1), before + 20 + 10);
+
+        let claim_events: Vec<_> = events()
+            .into_iter()
+            .filter(|e| matches!(e, Event::ClaimedManualRewards { .. }))
+            .collect();
+
+        assert_eq!(
+            claim_events,
+            vec![
+                Event::ClaimedManualRewards {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    rewards: 20,
+                },
+                Event::ClaimedManualRewards {
+                    candidate: ACCOUNT_CANDIDATE_2,
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    rewards: 10,
+                },
+            ]
+        );
+
+        for candidate in [
+            ACCOUNT_CANDIDATE_1,
+            ACCOUNT_CANDIDATE_2,
+            ACCOUNT_CANDIDATE_3,
+        ] {
+            assert_eq!(pending_rewards(candidate, ACCOUNT_DELEGATOR_1), 0);
+        }
+    });
+}
+
+#[test]
+fn claimed_rewards_are_paid_to_reward_destination() {
+    ExtBuilder::default().build().execute_with(|| {
+        let amount = 2 * InitialManualClaimShareValue::get();
+
+        FullDelegation {
+            candidate: ACCOUNT_CANDIDATE_1,
+            delegator: ACCOUNT_DELEGATOR_1,
+            request_amount: amount,
+            expected_increase: amount,
+            ..default()
+        }
+        .test::<pools::ManualRewards<Runtime>>();
+
+        assert_eq!(
+            RewardDestinations::<Runtime>::get(ACCOUNT_DELEGATOR_1),
+            RewardDestination::SelfAccount
+        );
+        assert_ok!(Staking::set_reward_destination(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            RewardDestination::Account(ACCOUNT_DELEGATOR_2),
+        ));
+        assert_eq_last_events!(vec![Event::<Runtime>::UpdatedRewardDestination {
+            delegator: ACCOUNT_DELEGATOR_1,
+            destination: RewardDestination::Account(ACCOUNT_DELEGATOR_2),
+        }]);
+
+        // Set counter to simulate rewards.
+        crate::Pools::<Runtime>::set(ACCOUNT_CANDIDATE_1, &PoolsKey::ManualRewardsCounter, 10);
+
+        let delegator_before = total_balance(&ACCOUNT_DELEGATOR_1);
+        let destination_before = total_balance(&ACCOUNT_DELEGATOR_2);
+
+        assert_ok!(Staking::claim_rewards_batch(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            vec![ACCOUNT_CANDIDATE_1],
+        ));
+
+        // 10 coins (counter) * 2 shares
+        assert_eq!(total_balance(&ACCOUNT_DELEGATOR_1), delegator_before);
+        assert_eq!(total_balance(&ACCOUNT_DELEGATOR_2), destination_before + 20);
+        assert_eq_last_events!(vec![Event::<Runtime>::ClaimedManualRewards {
+            candidate: ACCOUNT_CANDIDATE_1,
+            delegator: ACCOUNT_DELEGATOR_1,
+            rewards: 20,
+        }]);
+    });
+}
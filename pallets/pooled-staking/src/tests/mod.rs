@@ -18,19 +18,20 @@ mod candidates;
 mod delegator_flow;
 mod manual_rewards;
 mod rebalance;
+mod rewards;
 
 use {
     crate::{
-        assert_eq_events, assert_fields_eq,
+        assert_eq_events, assert_eq_last_events, assert_fields_eq,
         candidate::Candidates,
         mock::*,
         pool_test,
         pools::{self, Pool},
-        AllTargetPool, Error, Event, PendingOperationKey, PendingOperationQuery, PendingOperations,
-        Shares, SharesOrStake, Stake, TargetPool,
+        AllTargetPool, DelegatorsCount, Error, Event, PendingOperationKey, PendingOperationQuery,
+        PendingOperations, SharePrecision, Shares, SharesOrStake, Stake, TargetPool,
     },
     frame_support::{assert_noop, assert_ok, traits::tokens::fungible::Mutate},
-    sp_runtime::TokenError,
+    sp_runtime::{Permill, TokenError},
 };
 
 pub type Joining = pools::Joining<Runtime>;
@@ -97,6 +98,7 @@ impl RequestDelegation {
             candidate,
             pool,
             amount,
+            None,
         ));
 
         let after = State::extract(candidate, delegator);
@@ -224,11 +226,11 @@ impl FullDelegation {
             delegator,
             pool: P::target_pool(),
             amount: request_amount,
-            expected_joining: round_down(request_amount, 2),
+            expected_joining: round_down(request_amount, StakingSharePrecision::get().joining),
         }
         .test();
 
-        roll_to(block_number + BLOCKS_TO_WAIT);
+        roll_to(block_number + JoiningDelay::get());
 
         ExecuteDelegation {
             candidate,
@@ -432,7 +434,7 @@ impl FullUndelegation {
         }
         .test::<P>();
 
-        roll_to(block_number + BLOCKS_TO_WAIT);
+        roll_to(block_number + LeavingDelay::get());
 
         ExecuteUndelegation {
             candidate,
@@ -0,0 +1,985 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    super::*,
+    crate::{
+        assert_eq_last_events, AuthorRole, CandidateCommission, CompoundingPreference,
+        CompoundingPreferencesCount, MaxSharePrices, PendingCommissionIncreases, PoolsStake,
+        ShareValueSnapshot,
+    },
+    sp_runtime::{Perbill, Percent},
+};
+
+fn pending_rewards(candidate: AccountId, delegator: AccountId) -> Balance {
+    pools::ManualRewards::<Runtime>::pending_rewards(&candidate, &delegator)
+        .unwrap()
+        .0
+}
+
+#[test]
+fn compounding_preference_defaults_to_full_compounding() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(
+            CompoundingPreference::<Runtime>::get(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1),
+            Percent::from_percent(100)
+        );
+    })
+}
+
+#[test]
+fn compounding_preference_splits_rewards() {
+    ExtBuilder::default().build().execute_with(|| {
+        let amount = 4 * InitialAutoCompoundingShareValue::get();
+
+        FullDelegation {
+            candidate: ACCOUNT_CANDIDATE_1,
+            delegator: ACCOUNT_DELEGATOR_1,
+            request_amount: amount,
+            expected_increase: amount,
+            ..default()
+        }
+        .test::<pools::AutoCompounding<Runtime>>();
+
+        assert_ok!(Staking::set_compounding_preference(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            ACCOUNT_CANDIDATE_1,
+            Percent::from_percent(50),
+        ));
+
+        // The whole delegation is auto compounding, the preference only applies to rewards.
+        assert_eq!(
+            PoolState::extract::<pools::AutoCompounding<Runtime>>(
+                ACCOUNT_CANDIDATE_1,
+                ACCOUNT_DELEGATOR_1,
+            )
+            .stake,
+            amount
+        );
+
+        // 20% goes to the candidate, the rest to the auto compounding pool. Half of it grows
+        // the value of the shares of the delegator and the other half becomes claimable.
+        let rewards = 5 * InitialAutoCompoundingShareValue::get();
+        let delegators_rewards = 4 * InitialAutoCompoundingShareValue::get();
+        assert_ok!(Balances::mint_into(&ACCOUNT_REWARD_POT, rewards));
+        assert_ok!(Staking::distribute_rewards(
+            ACCOUNT_CANDIDATE_1,
+            AuthorRole::Container,
+            rewards
+        ));
+
+        assert_eq!(
+            PoolState::extract::<pools::AutoCompounding<Runtime>>(
+                ACCOUNT_CANDIDATE_1,
+                ACCOUNT_DELEGATOR_1,
+            )
+            .stake,
+            amount + delegators_rewards / 2
+        );
+        assert_eq!(
+            Staking::claimable_rewards(&ACCOUNT_DELEGATOR_1, &ACCOUNT_CANDIDATE_1),
+            delegators_rewards / 2
+        );
+
+        assert_eq_last_events!(vec![
+            Event::<Runtime>::RewardedCollator {
+                collator: ACCOUNT_CANDIDATE_1,
+                role: AuthorRole::Container,
+                auto_compounding_rewards: 0,
+                manual_claim_rewards: rewards - delegators_rewards,
+            },
+            Event::RewardedDelegators {
+                collator: ACCOUNT_CANDIDATE_1,
+                auto_compounding_rewards: delegators_rewards / 2,
+                manual_claim_rewards: delegators_rewards / 2,
+            },
+        ]);
+
+        let balance_before = total_balance(&ACCOUNT_DELEGATOR_1);
+        assert_ok!(Staking::claim_rewards_batch(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            vec![ACCOUNT_CANDIDATE_1],
+        ));
+        assert_eq!(
+            total_balance(&ACCOUNT_DELEGATOR_1),
+            balance_before + delegators_rewards / 2
+        );
+        assert_eq!(
+            Staking::claimable_rewards(&ACCOUNT_DELEGATOR_1, &ACCOUNT_CANDIDATE_1),
+            0
+        );
+    })
+}
+
+#[test]
+fn compounding_preferences_are_bounded_to_auto_compounding_delegators() {
+    ExtBuilder::default().build().execute_with(|| {
+        let amount = 4 * InitialAutoCompoundingShareValue::get();
+        let half = Percent::from_percent(50);
+
+        // Delegators need auto compounding shares of the candidate.
+        assert_noop!(
+            Staking::set_compounding_preference(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                half,
+            ),
+            Error::<Runtime>::NoAutoCompoundingShares
+        );
+
+        for delegator in [
+            ACCOUNT_DELEGATOR_1,
+            ACCOUNT_DELEGATOR_2,
+            ACCOUNT_CANDIDATE_2,
+        ] {
+            FullDelegation {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator,
+                request_amount: amount,
+                expected_increase: amount,
+                ..default()
+            }
+            .test::<pools::AutoCompounding<Runtime>>();
+        }
+
+        // At most 2 delegators can compound less than 100% in the mock.
+        for delegator in [ACCOUNT_DELEGATOR_1, ACCOUNT_DELEGATOR_2] {
+            assert_ok!(Staking::set_compounding_preference(
+                RuntimeOrigin::signed(delegator),
+                ACCOUNT_CANDIDATE_1,
+                half,
+            ));
+        }
+        assert_noop!(
+            Staking::set_compounding_preference(
+                RuntimeOrigin::signed(ACCOUNT_CANDIDATE_2),
+                ACCOUNT_CANDIDATE_1,
+                half,
+            ),
+            Error::<Runtime>::TooManyCompoundingPreferences
+        );
+        assert_eq!(
+            CompoundingPreferencesCount::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+            2
+        );
+
+        // Undelegating all the auto compounding shares removes the preference.
+        assert_ok!(Staking::request_undelegate(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            SharesOrStake::Stake(amount),
+        ));
+        assert_eq!(
+            CompoundingPreference::<Runtime>::get(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_1),
+            Percent::from_percent(100)
+        );
+        assert_eq!(
+            CompoundingPreferencesCount::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+            1
+        );
+
+        assert_ok!(Staking::set_compounding_preference(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_2),
+            ACCOUNT_CANDIDATE_1,
+            half,
+        ));
+    })
+}
+
+#[test]
+fn orchestrator_author_rewards_are_multiplied() {
+    ExtBuilder::default().build().execute_with(|| {
+        OrchestratorRewardMultiplier::set(150);
+
+        // Both collators authored the same amount of blocks, worth `rewards`.
+        let rewards = 1_000;
+        let orchestrator_rewards = 1_500;
+        assert_eq!(
+            Staking::rewards_for_role(AuthorRole::Orchestrator, rewards),
+            Ok(orchestrator_rewards)
+        );
+        assert_eq!(
+            Staking::rewards_for_role(AuthorRole::Container, rewards),
+            Ok(rewards)
+        );
+
+        let orchestrator_balance_before = Balances::free_balance(ACCOUNT_CANDIDATE_1);
+        let container_balance_before = Balances::free_balance(ACCOUNT_CANDIDATE_2);
+
+        assert_ok!(Balances::mint_into(
+            &ACCOUNT_REWARD_POT,
+            orchestrator_rewards + rewards
+        ));
+        assert_ok!(Staking::distribute_rewards(
+            ACCOUNT_CANDIDATE_1,
+            AuthorRole::Orchestrator,
+            rewards
+        ));
+        assert_ok!(Staking::distribute_rewards(
+            ACCOUNT_CANDIDATE_2,
+            AuthorRole::Container,
+            rewards
+        ));
+
+        // Without any stake all rewards go to the collators.
+        assert_eq!(
+            Balances::free_balance(ACCOUNT_CANDIDATE_1) - orchestrator_balance_before,
+            orchestrator_rewards
+        );
+        assert_eq!(
+            Balances::free_balance(ACCOUNT_CANDIDATE_2) - container_balance_before,
+            rewards
+        );
+
+        assert_eq_last_events!(vec![
+            Event::<Runtime>::RewardedCollator {
+                collator: ACCOUNT_CANDIDATE_1,
+                role: AuthorRole::Orchestrator,
+                auto_compounding_rewards: 0,
+                manual_claim_rewards: orchestrator_rewards,
+            },
+            Event::RewardedDelegators {
+                collator: ACCOUNT_CANDIDATE_1,
+                auto_compounding_rewards: 0,
+                manual_claim_rewards: 0,
+            },
+            Event::RewardedCollator {
+                collator: ACCOUNT_CANDIDATE_2,
+                role: AuthorRole::Container,
+                auto_compounding_rewards: 0,
+                manual_claim_rewards: rewards,
+            },
+            Event::RewardedDelegators {
+                collator: ACCOUNT_CANDIDATE_2,
+                auto_compounding_rewards: 0,
+                manual_claim_rewards: 0,
+            },
+        ]);
+    })
+}
+
+#[test]
+fn candidate_commission_is_paid_before_growing_pools() {
+    ExtBuilder::default().build().execute_with(|| {
+        let commission = Perbill::from_percent(10);
+        let block_number = block_number();
+        assert_ok!(Staking::set_commission(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            commission,
+        ));
+
+        let amount = 4 * InitialAutoCompoundingShareValue::get();
+
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            amount,
+            None,
+        ));
+
+        roll_to(block_number + JoiningDelay::get());
+
+        // The commission increase applies after the joining delay.
+        assert_eq!(
+            Staking::candidate_commission(&ACCOUNT_CANDIDATE_1),
+            commission
+        );
+
+        assert_ok!(Staking::execute_pending_operations(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            vec![PendingOperationQuery {
+                delegator: ACCOUNT_DELEGATOR_1,
+                operation: PendingOperationKey::JoiningAutoCompounding {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    at: block_number,
+                }
+            }]
+        ));
+
+        let auto_stake_before = PoolState::extract::<pools::AutoCompounding<Runtime>>(
+            ACCOUNT_CANDIDATE_1,
+            ACCOUNT_DELEGATOR_1,
+        )
+        .stake;
+        assert_eq!(auto_stake_before, amount);
+        let candidate_balance_before = Balances::free_balance(ACCOUNT_CANDIDATE_1);
+
+        // 20% goes to the candidate, then 10% of the remaining 800 is paid as commission.
+        let rewards = 1_000;
+        let delegators_rewards = 800;
+        let commission_amount = 80;
+        assert_ok!(Balances::mint_into(&ACCOUNT_REWARD_POT, rewards));
+        assert_ok!(Staking::distribute_rewards(
+            ACCOUNT_CANDIDATE_1,
+            AuthorRole::Container,
+            rewards
+        ));
+
+        // The candidate has no stake, its rewards and the commission are sent to its account.
+        assert_eq!(
+            Balances::free_balance(ACCOUNT_CANDIDATE_1) - candidate_balance_before,
+            rewards - delegators_rewards + commission_amount
+        );
+
+        // Pool share value only grows with the rewards left after the commission.
+        let auto_stake_after = PoolState::extract::<pools::AutoCompounding<Runtime>>(
+            ACCOUNT_CANDIDATE_1,
+            ACCOUNT_DELEGATOR_1,
+        )
+        .stake;
+        assert_eq!(
+            auto_stake_after,
+            auto_stake_before + delegators_rewards - commission_amount
+        );
+
+        assert_eq_last_events!(vec![
+            Event::<Runtime>::CommissionPaid {
+                candidate: ACCOUNT_CANDIDATE_1,
+                amount: commission_amount,
+            },
+            Event::RewardedCollator {
+                collator: ACCOUNT_CANDIDATE_1,
+                role: AuthorRole::Container,
+                auto_compounding_rewards: 0,
+                manual_claim_rewards: rewards - delegators_rewards,
+            },
+            Event::RewardedDelegators {
+                collator: ACCOUNT_CANDIDATE_1,
+                auto_compounding_rewards: delegators_rewards - commission_amount,
+                manual_claim_rewards: 0,
+            },
+        ]);
+    })
+}
+
+#[test]
+fn set_commission_requires_candidate() {
+    ExtBuilder::default().build().execute_with(|| {
+        UnregisteredCandidatesList::set(vec![ACCOUNT_DELEGATOR_1]);
+
+        assert_noop!(
+            Staking::set_commission(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                Perbill::from_percent(10),
+            ),
+            Error::<Runtime>::NotACandidate
+        );
+    })
+}
+
+#[test]
+fn set_commission_above_max_fails() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            Staking::set_commission(
+                RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+                Perbill::from_percent(51),
+            ),
+            Error::<Runtime>::CommissionTooHigh
+        );
+        assert_ok!(Staking::set_commission(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            MaxCommission::get(),
+        ));
+    })
+}
+
+#[test]
+fn commission_increase_is_delayed_and_decrease_is_immediate() {
+    ExtBuilder::default().build().execute_with(|| {
+        let block_number = block_number();
+        let increased = Perbill::from_percent(20);
+        assert_ok!(Staking::set_commission(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            increased,
+        ));
+        assert_eq_last_events!(vec![Event::<Runtime>::CommissionIncreaseRequested {
+            candidate: ACCOUNT_CANDIDATE_1,
+            commission: increased,
+            at: block_number,
+        }]);
+
+        // The previous commission applies until the joining delay is elapsed.
+        roll_to(block_number + JoiningDelay::get() - 1);
+        assert_eq!(
+            Staking::candidate_commission(&ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(0)
+        );
+        roll_to(block_number + JoiningDelay::get());
+        assert_eq!(
+            Staking::candidate_commission(&ACCOUNT_CANDIDATE_1),
+            increased
+        );
+
+        // A new increase keeps the elapsed one until it applies itself.
+        assert_ok!(Staking::set_commission(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(30),
+        ));
+        assert_eq!(
+            CandidateCommission::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+            increased
+        );
+        assert_eq!(
+            Staking::candidate_commission(&ACCOUNT_CANDIDATE_1),
+            increased
+        );
+
+        // Decreasing applies immediately and cancels the pending increase.
+        let decreased = Perbill::from_percent(5);
+        assert_ok!(Staking::set_commission(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            decreased,
+        ));
+        assert_eq!(
+            Staking::candidate_commission(&ACCOUNT_CANDIDATE_1),
+            decreased
+        );
+        assert_eq!(
+            PendingCommissionIncreases::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+            None
+        );
+        assert_eq_last_events!(vec![Event::<Runtime>::UpdatedCandidateCommission {
+            candidate: ACCOUNT_CANDIDATE_1,
+            commission: decreased,
+        }]);
+    })
+}
+
+#[test]
+fn underfunded_rewards_are_distributed_pro_rata() {
+    ExtBuilder::default().build().execute_with(|| {
+        let amount = 4 * InitialAutoCompoundingShareValue::get();
+        let block_number = block_number();
+
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            amount,
+            None,
+        ));
+
+        roll_to(block_number + JoiningDelay::get());
+
+        assert_ok!(Staking::execute_pending_operations(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            vec![PendingOperationQuery {
+                delegator: ACCOUNT_DELEGATOR_1,
+                operation: PendingOperationKey::JoiningAutoCompounding {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    at: block_number,
+                }
+            }]
+        ));
+
+        let auto_stake_before = PoolState::extract::<pools::AutoCompounding<Runtime>>(
+            ACCOUNT_CANDIDATE_1,
+            ACCOUNT_DELEGATOR_1,
+        )
+        .stake;
+        let candidate_balance_before = Balances::free_balance(ACCOUNT_CANDIDATE_1);
+
+        // The pot only holds half of the requested rewards, which are split as usual:
+        // 20% goes to the candidate and the rest to the delegators.
+        let requested = 1_000;
+        let available = 500;
+        let delegators_rewards = 400;
+        assert_ok!(Balances::mint_into(&ACCOUNT_REWARD_POT, available));
+        assert_ok!(Staking::distribute_rewards(
+            ACCOUNT_CANDIDATE_1,
+            AuthorRole::Container,
+            requested
+        ));
+
+        assert_eq!(Balances::free_balance(ACCOUNT_REWARD_POT), 0);
+        assert_eq!(
+            Balances::free_balance(ACCOUNT_CANDIDATE_1) - candidate_balance_before,
+            available - delegators_rewards
+        );
+
+        let auto_stake_after = PoolState::extract::<pools::AutoCompounding<Runtime>>(
+            ACCOUNT_CANDIDATE_1,
+            ACCOUNT_DELEGATOR_1,
+        )
+        .stake;
+        assert_eq!(auto_stake_after, auto_stake_before + delegators_rewards);
+
+        assert_eq_last_events!(vec![
+            Event::<Runtime>::RewardsUnderfunded {
+                collator: ACCOUNT_CANDIDATE_1,
+                requested,
+                available,
+            },
+            Event::RewardedCollator {
+                collator: ACCOUNT_CANDIDATE_1,
+                role: AuthorRole::Container,
+                auto_compounding_rewards: 0,
+                manual_claim_rewards: available - delegators_rewards,
+            },
+            Event::RewardedDelegators {
+                collator: ACCOUNT_CANDIDATE_1,
+                auto_compounding_rewards: delegators_rewards,
+                manual_claim_rewards: 0,
+            },
+        ]);
+    })
+}
+
+#[test]
+fn pool_reward_weights_favor_heavier_pool() {
+    ExtBuilder::default().build().execute_with(|| {
+        AutoCompoundingRewardWeight::set(3);
+        ManualRewardsRewardWeight::set(1);
+
+        let amount = 4 * InitialAutoCompoundingShareValue::get();
+        let block_number = block_number();
+
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            amount,
+            None,
+        ));
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::ManualRewards,
+            amount,
+            None,
+        ));
+
+        roll_to(block_number + JoiningDelay::get());
+
+        assert_ok!(Staking::execute_pending_operations(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            vec![
+                PendingOperationQuery {
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    operation: PendingOperationKey::JoiningAutoCompounding {
+                        candidate: ACCOUNT_CANDIDATE_1,
+                        at: block_number,
+                    }
+                },
+                PendingOperationQuery {
+                    delegator: ACCOUNT_DELEGATOR_2,
+                    operation: PendingOperationKey::JoiningManualRewards {
+                        candidate: ACCOUNT_CANDIDATE_1,
+                        at: block_number,
+                    }
+                },
+            ]
+        ));
+
+        let auto_stake_before = PoolState::extract::<pools::AutoCompounding<Runtime>>(
+            ACCOUNT_CANDIDATE_1,
+            ACCOUNT_DELEGATOR_1,
+        )
+        .stake;
+
+        // Both pools have the same size, but the auto compounding pool weights 3 times more
+        // and gets 3/4 of the 800 delegators rewards instead of half of them.
+        let rewards = 1_000;
+        let auto_rewards = 600;
+        let manual_rewards = 200;
+        assert_ok!(Balances::mint_into(&ACCOUNT_REWARD_POT, rewards));
+        assert_ok!(Staking::distribute_rewards(
+            ACCOUNT_CANDIDATE_1,
+            AuthorRole::Container,
+            rewards
+        ));
+
+        let auto_stake_after = PoolState::extract::<pools::AutoCompounding<Runtime>>(
+            ACCOUNT_CANDIDATE_1,
+            ACCOUNT_DELEGATOR_1,
+        )
+        .stake;
+        assert_eq!(auto_stake_after, auto_stake_before + auto_rewards);
+        assert_eq!(
+            pending_rewards(ACCOUNT_CANDIDATE_1, ACCOUNT_DELEGATOR_2),
+            manual_rewards
+        );
+
+        assert_eq_last_events!(vec![Event::<Runtime>::RewardedDelegators {
+            collator: ACCOUNT_CANDIDATE_1,
+            auto_compounding_rewards: auto_rewards,
+            manual_claim_rewards: manual_rewards,
+        }]);
+    })
+}
+
+#[test]
+fn delegation_above_max_share_price_is_refunded() {
+    ExtBuilder::default().build().execute_with(|| {
+        let share_value = InitialAutoCompoundingShareValue::get();
+        let amount = 4 * share_value;
+        let block_number = block_number();
+
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            amount,
+            None,
+        ));
+        roll_to(block_number + JoiningDelay::get());
+        assert_ok!(Staking::execute_pending_operations(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            vec![PendingOperationQuery {
+                delegator: ACCOUNT_DELEGATOR_1,
+                operation: PendingOperationKey::JoiningAutoCompounding {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    at: block_number,
+                }
+            }]
+        ));
+
+        // The second delegator only accepts the current share price.
+        let block_number = block_number();
+        let operation = PendingOperationKey::JoiningAutoCompounding {
+            candidate: ACCOUNT_CANDIDATE_1,
+            at: block_number,
+        };
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            amount,
+            Some(share_value),
+        ));
+        assert_eq!(
+            MaxSharePrices::<Runtime>::get(ACCOUNT_DELEGATOR_2, &operation),
+            Some(share_value)
+        );
+        assert_eq!(DelegatorsCount::<Runtime>::get(ACCOUNT_CANDIDATE_1), 2);
+
+        // Rewards increase the price of the auto compounding shares before execution.
+        let rewards = 1_000;
+        assert_ok!(Balances::mint_into(&ACCOUNT_REWARD_POT, rewards));
+        assert_ok!(Staking::distribute_rewards(
+            ACCOUNT_CANDIDATE_1,
+            AuthorRole::Container,
+            rewards
+        ));
+        let share_price =
+            pools::share_price::<Runtime>(&ACCOUNT_CANDIDATE_1, TargetPool::AutoCompounding)
+                .unwrap();
+        assert!(share_price > share_value);
+
+        roll_to(block_number + JoiningDelay::get());
+        assert_ok!(Staking::execute_pending_operations(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_2),
+            vec![PendingOperationQuery {
+                delegator: ACCOUNT_DELEGATOR_2,
+                operation: operation.clone(),
+            }]
+        ));
+
+        // The whole stake is refunded and the delegator no longer delegates.
+        assert_eq!(balance_hold(&ACCOUNT_DELEGATOR_2), 0);
+        assert_eq!(
+            pools::AutoCompounding::<Runtime>::shares(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_2),
+            Shares(0)
+        );
+        assert_eq!(
+            Joining::shares(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_2),
+            Shares(0)
+        );
+        assert_eq!(
+            PendingOperations::<Runtime>::get(ACCOUNT_DELEGATOR_2, &operation),
+            0
+        );
+        assert_eq!(
+            MaxSharePrices::<Runtime>::get(ACCOUNT_DELEGATOR_2, &operation),
+            None
+        );
+        assert_eq!(DelegatorsCount::<Runtime>::get(ACCOUNT_CANDIDATE_1), 1);
+
+        assert_eq_last_events!(vec![
+            Event::<Runtime>::DelegationRefunded {
+                candidate: ACCOUNT_CANDIDATE_1,
+                delegator: ACCOUNT_DELEGATOR_2,
+                pool: TargetPool::AutoCompounding,
+                share_price,
+                max_share_price: share_value,
+                released: amount,
+            },
+            Event::<Runtime>::PendingOperationsExecuted {
+                executor: ACCOUNT_DELEGATOR_2,
+                delegators: vec![ACCOUNT_DELEGATOR_2],
+                count: 1,
+                total_staked: 0,
+                total_released: amount,
+            },
+        ]);
+    })
+}
+
+#[test]
+fn candidate_delegators_lists_current_stake_of_each_delegator() {
+    ExtBuilder::default().build().execute_with(|| {
+        let amount = 4 * InitialAutoCompoundingShareValue::get();
+        let block_number = block_number();
+
+        for (delegator, pool) in [
+            (ACCOUNT_DELEGATOR_1, TargetPool::AutoCompounding),
+            (ACCOUNT_DELEGATOR_2, TargetPool::ManualRewards),
+            (ACCOUNT_CANDIDATE_2, TargetPool::AutoCompounding),
+        ] {
+            assert_ok!(Staking::request_delegate(
+                RuntimeOrigin::signed(delegator),
+                ACCOUNT_CANDIDATE_1,
+                pool,
+                amount,
+                None,
+            ));
+        }
+
+        roll_to(block_number + JoiningDelay::get());
+        assert_ok!(Staking::execute_pending_operations(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            vec![
+                PendingOperationQuery {
+                    delegator: ACCOUNT_DELEGATOR_1,
+                    operation: PendingOperationKey::JoiningAutoCompounding {
+                        candidate: ACCOUNT_CANDIDATE_1,
+                        at: block_number,
+                    }
+                },
+                PendingOperationQuery {
+                    delegator: ACCOUNT_DELEGATOR_2,
+                    operation: PendingOperationKey::JoiningManualRewards {
+                        candidate: ACCOUNT_CANDIDATE_1,
+                        at: block_number,
+                    }
+                },
+                PendingOperationQuery {
+                    delegator: ACCOUNT_CANDIDATE_2,
+                    operation: PendingOperationKey::JoiningAutoCompounding {
+                        candidate: ACCOUNT_CANDIDATE_1,
+                        at: block_number,
+                    }
+                },
+            ]
+        ));
+
+        // Half of a position is leaving, and another delegation is pending.
+        assert_ok!(Staking::request_undelegate(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_2),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            SharesOrStake::Stake(amount / 2),
+        ));
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            amount,
+            None,
+        ));
+
+        // 800 are shared between the 6 auto compounding shares worth 6M and the manual
+        // rewards pool worth 4M, each auto compounding share gaining 80.
+        let rewards = 1_000;
+        assert_ok!(Balances::mint_into(&ACCOUNT_REWARD_POT, rewards));
+        assert_ok!(Staking::distribute_rewards(
+            ACCOUNT_CANDIDATE_1,
+            AuthorRole::Container,
+            rewards
+        ));
+
+        let candidate_2_stake = PoolsStake {
+            auto_compounding: amount / 2 + 160,
+            leaving: round_down(amount / 2, 3),
+            ..default()
+        };
+        let delegator_1_stake = PoolsStake {
+            joining: amount,
+            auto_compounding: amount + 320,
+            ..default()
+        };
+        let delegator_2_stake = PoolsStake {
+            manual_rewards: amount,
+            ..default()
+        };
+        let all = vec![
+            (ACCOUNT_CANDIDATE_2, candidate_2_stake),
+            (ACCOUNT_DELEGATOR_1, delegator_1_stake),
+            (ACCOUNT_DELEGATOR_2, delegator_2_stake),
+        ];
+        let (mut delegators, cursor) =
+            Staking::candidate_delegators(&ACCOUNT_CANDIDATE_1, None, u32::MAX);
+        delegators.sort_by_key(|(delegator, _)| *delegator);
+        assert_eq!(delegators, all);
+        assert_eq!(cursor, None);
+
+        // Delegators can be listed a page at a time, each of them appearing in a single page.
+        let mut pages = vec![];
+        let mut cursor = None;
+        loop {
+            let (page, next) = Staking::candidate_delegators(&ACCOUNT_CANDIDATE_1, cursor, 1);
+            assert_eq!(page.len(), 1);
+            pages.extend(page);
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        pages.sort_by_key(|(delegator, _)| *delegator);
+        assert_eq!(pages, all);
+
+        assert_eq!(
+            Staking::candidate_delegators(&ACCOUNT_CANDIDATE_2, None, 10),
+            (vec![], None)
+        );
+    })
+}
+
+#[test]
+fn share_value_history_is_snapshotted_each_session() {
+    ExtBuilder::default().build().execute_with(|| {
+        let share_value = InitialAutoCompoundingShareValue::get();
+        let amount = MinimumSelfDelegation::get();
+        let block_number = block_number();
+
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            amount,
+            None,
+        ));
+
+        roll_to(block_number + JoiningDelay::get());
+
+        assert_ok!(Staking::execute_pending_operations(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            vec![PendingOperationQuery {
+                delegator: ACCOUNT_CANDIDATE_1,
+                operation: PendingOperationKey::JoiningAutoCompounding {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    at: block_number,
+                }
+            }]
+        ));
+
+        // 20% of the rewards goes to the candidate (which is too little to get a new share),
+        // the rest increases the value of the 10 auto compounding shares.
+        let rewards = 1_000;
+        let increase_per_session = 80;
+        for session in 1..=4u32 {
+            assert_ok!(Balances::mint_into(&ACCOUNT_REWARD_POT, rewards));
+            assert_ok!(Staking::distribute_rewards(
+                ACCOUNT_CANDIDATE_1,
+                AuthorRole::Container,
+                rewards
+            ));
+            Staking::initializer_on_new_session(&session);
+        }
+
+        // Only the last 3 sessions are kept.
+        assert_eq!(
+            Staking::share_value_history(&ACCOUNT_CANDIDATE_1, TargetPool::AutoCompounding),
+            vec![
+                ShareValueSnapshot {
+                    session: 2,
+                    value: share_value + 2 * increase_per_session,
+                },
+                ShareValueSnapshot {
+                    session: 3,
+                    value: share_value + 3 * increase_per_session,
+                },
+                ShareValueSnapshot {
+                    session: 4,
+                    value: share_value + 4 * increase_per_session,
+                },
+            ]
+        );
+        // Nobody is in the manual rewards pool, its counter never increased.
+        assert_eq!(
+            Staking::share_value_history(&ACCOUNT_CANDIDATE_1, TargetPool::ManualRewards),
+            vec![
+                ShareValueSnapshot {
+                    session: 2,
+                    value: 0,
+                },
+                ShareValueSnapshot {
+                    session: 3,
+                    value: 0,
+                },
+                ShareValueSnapshot {
+                    session: 4,
+                    value: 0,
+                },
+            ]
+        );
+    })
+}
+
+#[test]
+fn claimable_rewards_matches_claimed_amount() {
+    ExtBuilder::default().build().execute_with(|| {
+        let amount = 2 * InitialManualClaimShareValue::get();
+
+        FullDelegation {
+            candidate: ACCOUNT_CANDIDATE_1,
+            delegator: ACCOUNT_DELEGATOR_1,
+            request_amount: amount,
+            expected_increase: amount,
+            ..default()
+        }
+        .test::<pools::ManualRewards<Runtime>>();
+
+        assert_eq!(
+            Staking::claimable_rewards(&ACCOUNT_DELEGATOR_1, &ACCOUNT_CANDIDATE_1),
+            0
+        );
+
+        // 20% goes to the candidate, the rest to the only delegator. Rewards distributed
+        // since the last claim accumulate.
+        let rewards = 1_000;
+        let delegators_rewards = 800;
+        for _ in 0..2 {
+            assert_ok!(Balances::mint_into(&ACCOUNT_REWARD_POT, rewards));
+            assert_ok!(Staking::distribute_rewards(
+                ACCOUNT_CANDIDATE_1,
+                AuthorRole::Container,
+                rewards
+            ));
+        }
+
+        let claimable = Staking::claimable_rewards(&ACCOUNT_DELEGATOR_1, &ACCOUNT_CANDIDATE_1);
+        assert_eq!(claimable, 2 * delegators_rewards);
+
+        let balance_before = total_balance(&ACCOUNT_DELEGATOR_1);
+        assert_ok!(Staking::claim_rewards_batch(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            vec![ACCOUNT_CANDIDATE_1],
+        ));
+
+        assert_eq!(
+            total_balance(&ACCOUNT_DELEGATOR_1),
+            balance_before + claimable
+        );
+        assert_eq!(
+            Staking::claimable_rewards(&ACCOUNT_DELEGATOR_1, &ACCOUNT_CANDIDATE_1),
+            0
+        );
+    });
+}
@@ -15,7 +15,7 @@
 // along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
 
 use {
-    crate::{Config, Error},
+    crate::{Config, Error, TargetPool},
     core::{fmt::Debug, marker::PhantomData},
     parity_scale_codec::FullCodec,
     scale_info::TypeInfo,
@@ -26,15 +26,27 @@ use {
 
 /// Allows to get the current instant and check if some duration is elapsed.
 pub trait Timer {
-    /// Type for the instant. Must implement some traits to be used easily with
-    /// the Pooled Staking pallet.
-    type Instant: FullCodec + TypeInfo + Clone + Debug + Eq;
+    /// Type for the instant, also used for delays. Must implement some traits to be used
+    /// easily with the Pooled Staking pallet.
+    type Instant: FullCodec + TypeInfo + Clone + Debug + Eq + Ord + Default;
 
     /// Get the current instant.
     fn now() -> Self::Instant;
 
-    /// Check if the timer started at `started` is elapsed.
-    fn is_elapsed(start: &Self::Instant) -> bool;
+    /// Delay used when it has not been changed in the pallet.
+    fn default_delay() -> Self::Instant;
+
+    /// Check if the timer started at `start` is elapsed after `delay`.
+    fn is_elapsed_after(start: &Self::Instant, delay: &Self::Instant) -> bool;
+
+    /// Instant at which the timer started at `start` is elapsed after `delay`, `None` if it
+    /// overflows.
+    fn elapsed_at(start: &Self::Instant, delay: &Self::Instant) -> Option<Self::Instant>;
+
+    /// Check if the timer started at `started` is elapsed after the default delay.
+    fn is_elapsed(start: &Self::Instant) -> bool {
+        Self::is_elapsed_after(start, &Self::default_delay())
+    }
 
     /// Returns an instant that will make `is_elapsed` true.
     #[cfg(feature = "runtime-benchmarks")]
@@ -60,14 +72,21 @@ where
         frame_system::Pallet::<T>::block_number()
     }
 
-    fn is_elapsed(start: &Self::Instant) -> bool {
-        let delay = G::get();
-        let Some(end) = start.checked_add(&delay) else {
+    fn default_delay() -> Self::Instant {
+        G::get()
+    }
+
+    fn is_elapsed_after(start: &Self::Instant, delay: &Self::Instant) -> bool {
+        let Some(end) = start.checked_add(delay) else {
             return false;
         };
         end <= Self::now()
     }
 
+    fn elapsed_at(start: &Self::Instant, delay: &Self::Instant) -> Option<Self::Instant> {
+        start.checked_add(delay)
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     fn elapsed_instant() -> Self::Instant {
         let delay = G::get();
@@ -102,6 +121,74 @@ impl<AccountId> IsCandidateEligible<AccountId> for () {
     fn make_candidate_eligible(_: &AccountId, _: bool) {}
 }
 
+/// Weight of the rewards of each pool relative to its size.
+pub trait PoolRewardWeight {
+    /// Weight of `pool`. Delegators rewards of a candidate are split between its pools
+    /// proportionally to their total stake multiplied by their weight.
+    fn pool_reward_weight(pool: TargetPool) -> u32;
+
+    /// Weight of the stake of bonded positions in `pool`, see `bonded_delegate`. The part
+    /// exceeding the weight of the pool is paid directly to the bonded delegator, a lower
+    /// weight is ignored. Defaults to the weight of the pool, in which case bonding doesn't
+    /// earn more.
+    fn bonded_reward_weight(pool: TargetPool) -> u32 {
+        Self::pool_reward_weight(pool)
+    }
+}
+
+/// Rewards all pools according to their size only.
+impl PoolRewardWeight for () {
+    fn pool_reward_weight(_: TargetPool) -> u32 {
+        1
+    }
+}
+
+/// Allows knowing if some account registered as a candidate.
+pub trait IsCandidateRegistered<AccountId> {
+    /// Did the provided account register as a candidate?
+    fn is_candidate_registered(a: &AccountId) -> bool;
+
+    /// Register the provided account as a candidate.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn make_candidate_registered(a: &AccountId);
+}
+
+impl<AccountId> IsCandidateRegistered<AccountId> for () {
+    fn is_candidate_registered(_: &AccountId) -> bool {
+        true
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn make_candidate_registered(_: &AccountId) {}
+}
+
+/// Allows knowing if a candidate requested to leave.
+pub trait IsCandidateLeaving<AccountId> {
+    /// Is the provided candidate leaving?
+    fn is_candidate_leaving(a: &AccountId) -> bool;
+}
+
+impl<AccountId> IsCandidateLeaving<AccountId> for () {
+    fn is_candidate_leaving(_: &AccountId) -> bool {
+        false
+    }
+}
+
+/// Notified when a delegator starts or stops having a delegation position for a candidate,
+/// allowing for example to mint and burn a receipt token representing that position.
+pub trait OnDelegation<AccountId> {
+    /// `delegator` got its first stake for `candidate`, by executing a joining request.
+    fn on_position_created(candidate: &AccountId, delegator: &AccountId);
+    /// `delegator` no longer has stake for `candidate`, after executing its last leaving
+    /// request.
+    fn on_position_removed(candidate: &AccountId, delegator: &AccountId);
+}
+
+impl<AccountId> OnDelegation<AccountId> for () {
+    fn on_position_created(_: &AccountId, _: &AccountId) {}
+    fn on_position_removed(_: &AccountId, _: &AccountId) {}
+}
+
 /// Error returned by math operations which can overflow.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct OverflowError;
@@ -58,6 +58,17 @@ pub trait WeightInfo {
 	fn claim_manual_rewards(b: u32, ) -> Weight;
 	fn rebalance_hold() -> Weight;
 	fn update_candidate_position(b: u32, ) -> Weight;
+	fn set_compounding_preference() -> Weight;
+	fn set_reward_destination() -> Weight;
+	fn set_commission() -> Weight;
+	fn set_delegation_proxy() -> Weight;
+	fn schedule_delegation() -> Weight;
+	fn set_request_delay() -> Weight;
+	fn request_candidate_leave() -> Weight;
+	fn cancel_undelegation() -> Weight;
+	fn set_candidate_min_delegation() -> Weight;
+	fn bonded_delegate() -> Weight;
+	fn distribute_rewards(b: u32, ) -> Weight;
 }
 
 /// Weights for pallet_pooled_staking using the Substrate node and recommended hardware.
@@ -77,13 +88,15 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
 	/// Storage: PooledStaking PendingOperations (r:1 w:1)
 	/// Proof Skipped: PooledStaking PendingOperations (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking PurgingCandidates (r:1 w:0)
+	/// Proof Skipped: PooledStaking PurgingCandidates (max_values: None, max_size: None, mode: Measured)
 	fn request_delegate() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `1328`
 		//  Estimated: `29543`
 		// Minimum execution time: 497_273_000 picoseconds.
 		Weight::from_parts(507_395_000, 29543)
-			.saturating_add(T::DbWeight::get().reads(17_u64))
+			.saturating_add(T::DbWeight::get().reads(18_u64))
 			.saturating_add(T::DbWeight::get().writes(9_u64))
 	}
 	/// Storage: PooledStaking PendingOperations (r:100 w:100)
@@ -96,19 +109,18 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof: Balances Holds (max_values: None, max_size: Some(73), added: 2548, mode: MaxEncodedLen)
 	/// Storage: System Account (r:1 w:1)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: PooledStaking RecentlyJoinedStake (r:100 w:100)
+	/// Proof Skipped: PooledStaking RecentlyJoinedStake (max_values: None, max_size: None, mode: Measured)
 	/// The range of component `b` is `[1, 100]`.
 	fn execute_pending_operations(b: u32, ) -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `550 + b * (390 ±0)`
-		//  Estimated: `3593 + b * (25141 ±0)`
-		// Minimum execution time: 333_447_000 picoseconds.
+		// Pending a benchmark run: the recently joined stake of each executed delegation is
+		// read and written on top of the previous measurement.
 		Weight::from_parts(335_790_000, 3593)
-			// Standard Error: 594_634
 			.saturating_add(Weight::from_parts(174_012_935, 0).saturating_mul(b.into()))
 			.saturating_add(T::DbWeight::get().reads(3_u64))
-			.saturating_add(T::DbWeight::get().reads((11_u64).saturating_mul(b.into())))
+			.saturating_add(T::DbWeight::get().reads((12_u64).saturating_mul(b.into())))
 			.saturating_add(T::DbWeight::get().writes(2_u64))
-			.saturating_add(T::DbWeight::get().writes((9_u64).saturating_mul(b.into())))
+			.saturating_add(T::DbWeight::get().writes((10_u64).saturating_mul(b.into())))
 			.saturating_add(Weight::from_parts(0, 25141).saturating_mul(b.into()))
 	}
 	/// Storage: PooledStaking Pools (r:13 w:9)
@@ -117,16 +129,20 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof Skipped: PooledStaking SortedEligibleCandidates (max_values: Some(1), max_size: None, mode: Measured)
 	/// Storage: PooledStaking PendingOperations (r:1 w:1)
 	/// Proof Skipped: PooledStaking PendingOperations (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CompoundingPreference (r:1 w:1)
+	/// Proof Skipped: PooledStaking CompoundingPreference (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CompoundingPreferencesCount (r:1 w:1)
+	/// Proof Skipped: PooledStaking CompoundingPreferencesCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking RecentlyJoinedStake (r:1 w:1)
+	/// Proof Skipped: PooledStaking RecentlyJoinedStake (max_values: None, max_size: None, mode: Measured)
 	fn request_undelegate() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `557`
-		//  Estimated: `33722`
-		// Minimum execution time: 249_510_000 picoseconds.
+		// Pending a benchmark run: the recently joined stake is read and written on top of
+		// the previous measurement.
 		Weight::from_parts(252_589_000, 33722)
-			.saturating_add(T::DbWeight::get().reads(15_u64))
-			.saturating_add(T::DbWeight::get().writes(11_u64))
+			.saturating_add(T::DbWeight::get().reads(18_u64))
+			.saturating_add(T::DbWeight::get().writes(14_u64))
 	}
-	/// Storage: PooledStaking Pools (r:300 w:100)
+	/// Storage: PooledStaking Pools (r:400 w:200)
 	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
 	/// Storage: System Account (r:2 w:2)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
@@ -140,9 +156,9 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			// Standard Error: 543_344
 			.saturating_add(Weight::from_parts(98_370_210, 0).saturating_mul(b.into()))
 			.saturating_add(T::DbWeight::get().reads(2_u64))
-			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(b.into())))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(b.into())))
 			.saturating_add(T::DbWeight::get().writes(2_u64))
-			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(b.into())))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(b.into())))
 			.saturating_add(Weight::from_parts(0, 7882).saturating_mul(b.into()))
 	}
 	/// Storage: PooledStaking Pools (r:4 w:1)
@@ -181,6 +197,187 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(b.into())))
 			.saturating_add(Weight::from_parts(0, 15206).saturating_mul(b.into()))
 	}
+	/// Storage: PooledStaking Pools (r:2 w:0)
+	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CompoundingPreference (r:1 w:1)
+	/// Proof Skipped: PooledStaking CompoundingPreference (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CompoundingPreferencesCount (r:1 w:1)
+	/// Proof Skipped: PooledStaking CompoundingPreferencesCount (max_values: None, max_size: None, mode: Measured)
+	fn set_compounding_preference() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 21_046_000 picoseconds.
+		Weight::from_parts(21_844_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: PooledStaking RewardDestinations (r:0 w:1)
+	/// Proof Skipped: PooledStaking RewardDestinations (max_values: None, max_size: None, mode: Measured)
+	fn set_reward_destination() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 20_512_000 picoseconds.
+		Weight::from_parts(21_138_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Session NextKeys (r:1 w:0)
+	/// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking PendingCommissionIncreases (r:1 w:1)
+	/// Proof Skipped: PooledStaking PendingCommissionIncreases (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking JoiningDelay (r:1 w:0)
+	/// Proof Skipped: PooledStaking JoiningDelay (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Session CurrentIndex (r:1 w:0)
+	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: PooledStaking CandidateCommission (r:0 w:1)
+	/// Proof Skipped: PooledStaking CandidateCommission (max_values: None, max_size: None, mode: Measured)
+	fn set_commission() -> Weight {
+		// Pending a benchmark run: the candidate check and the pending increase add storage
+		// reads to the previous measurement.
+		Weight::from_parts(32_981_000, 3700)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: PooledStaking DelegationProxies (r:0 w:1)
+	/// Proof Skipped: PooledStaking DelegationProxies (max_values: None, max_size: None, mode: Measured)
+	fn set_delegation_proxy() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 20_891_000 picoseconds.
+		Weight::from_parts(21_507_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: PooledStaking ScheduledDelegations (r:1 w:1)
+	/// Proof Skipped: PooledStaking ScheduledDelegations (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Proof: Balances Holds (max_values: None, max_size: Some(73), added: 2548, mode: MaxEncodedLen)
+	fn schedule_delegation() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `197`
+		//  Estimated: `3662`
+		// Minimum execution time: 38_716_000 picoseconds.
+		Weight::from_parts(39_842_000, 3662)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: PooledStaking JoiningDelay (r:1 w:1)
+	/// Proof Skipped: PooledStaking JoiningDelay (max_values: Some(1), max_size: None, mode: Measured)
+	fn set_request_delay() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4`
+		//  Estimated: `1489`
+		// Minimum execution time: 14_205_000 picoseconds.
+		Weight::from_parts(14_768_000, 1489)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: PooledStaking CandidateLeaveRequests (r:1 w:1)
+	/// Proof Skipped: PooledStaking CandidateLeaveRequests (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking Pools (r:1 w:0)
+	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CandidateLeaveSchedule (r:1 w:1)
+	/// Proof Skipped: PooledStaking CandidateLeaveSchedule (max_values: None, max_size: None, mode: Measured)
+	fn request_candidate_leave() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `238`
+		//  Estimated: `3703`
+		// Minimum execution time: 27_903_000 picoseconds.
+		Weight::from_parts(28_611_000, 3703)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: PooledStaking PendingOperations (r:1 w:1)
+	/// Proof Skipped: PooledStaking PendingOperations (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking Pools (r:12 w:8)
+	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking LeavingStakeRequested (r:1 w:1)
+	/// Proof Skipped: PooledStaking LeavingStakeRequested (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Proof: Balances Holds (max_values: None, max_size: Some(73), added: 2548, mode: MaxEncodedLen)
+	/// Storage: PooledStaking SortedEligibleCandidates (r:1 w:1)
+	/// Proof Skipped: PooledStaking SortedEligibleCandidates (max_values: Some(1), max_size: None, mode: Measured)
+	fn cancel_undelegation() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1203`
+		//  Estimated: `32943`
+		// Minimum execution time: 128_394_000 picoseconds.
+		Weight::from_parts(131_002_000, 32943)
+			.saturating_add(T::DbWeight::get().reads(16_u64))
+			.saturating_add(T::DbWeight::get().writes(12_u64))
+	}
+	/// Storage: Session NextKeys (r:1 w:0)
+	/// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking Pools (r:1 w:0)
+	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CandidateMinDelegation (r:0 w:1)
+	/// Proof Skipped: PooledStaking CandidateMinDelegation (max_values: None, max_size: None, mode: Measured)
+	fn set_candidate_min_delegation() -> Weight {
+		// Pending a benchmark run: the candidate check adds storage reads to the previous
+		// measurement.
+		Weight::from_parts(20_415_000, 0)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: PooledStaking Pools (r:11 w:5)
+	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Proof: Balances Holds (max_values: None, max_size: Some(73), added: 2548, mode: MaxEncodedLen)
+	/// Storage: PooledStaking SortedEligibleCandidates (r:1 w:1)
+	/// Proof Skipped: PooledStaking SortedEligibleCandidates (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: PooledStaking PendingOperations (r:1 w:1)
+	/// Proof Skipped: PooledStaking PendingOperations (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking Bonds (r:1 w:1)
+	/// Proof Skipped: PooledStaking Bonds (max_values: None, max_size: None, mode: Measured)
+	fn bonded_delegate() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1358`
+		//  Estimated: `29573`
+		// Minimum execution time: 512_904_000 picoseconds.
+		Weight::from_parts(523_117_000, 29573)
+			.saturating_add(T::DbWeight::get().reads(18_u64))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
+	}
+	/// Storage: System Account (r:3 w:3)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: PooledStaking PendingCommissionIncreases (r:1 w:0)
+	/// Proof Skipped: PooledStaking PendingCommissionIncreases (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking JoiningDelay (r:1 w:0)
+	/// Proof Skipped: PooledStaking JoiningDelay (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Session CurrentIndex (r:1 w:0)
+	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: PooledStaking CandidateCommission (r:1 w:0)
+	/// Proof Skipped: PooledStaking CandidateCommission (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking Pools (r:14 w:8)
+	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking Bonds (r:1 w:0)
+	/// Proof Skipped: PooledStaking Bonds (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CompoundingPreference (r:101 w:0)
+	/// Proof Skipped: PooledStaking CompoundingPreference (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking SortedEligibleCandidates (r:1 w:1)
+	/// Proof Skipped: PooledStaking SortedEligibleCandidates (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Session NextKeys (r:1 w:0)
+	/// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `b` is `[0, 100]`.
+	fn distribute_rewards(b: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1580 + b * (524 ±0)`
+		//  Estimated: `23146 + b * (6192 ±0)`
+		// Minimum execution time: 287_315_000 picoseconds.
+		// Pending a benchmark run: the pending commission increase and the joining delay are
+		// read on top of the previous measurement.
+		Weight::from_parts(291_604_512, 23146)
+			// Standard Error: 412_908
+			.saturating_add(Weight::from_parts(64_127_386, 0).saturating_mul(b.into()))
+			.saturating_add(T::DbWeight::get().reads(25_u64))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(b.into())))
+			.saturating_add(T::DbWeight::get().writes(12_u64))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(b.into())))
+			.saturating_add(Weight::from_parts(0, 6192).saturating_mul(b.into()))
+	}
 }
 
 // For backwards compatibility and tests
@@ -199,13 +396,15 @@ impl WeightInfo for () {
 	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
 	/// Storage: PooledStaking PendingOperations (r:1 w:1)
 	/// Proof Skipped: PooledStaking PendingOperations (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking PurgingCandidates (r:1 w:0)
+	/// Proof Skipped: PooledStaking PurgingCandidates (max_values: None, max_size: None, mode: Measured)
 	fn request_delegate() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `1328`
 		//  Estimated: `29543`
 		// Minimum execution time: 497_273_000 picoseconds.
 		Weight::from_parts(507_395_000, 29543)
-			.saturating_add(RocksDbWeight::get().reads(17_u64))
+			.saturating_add(RocksDbWeight::get().reads(18_u64))
 			.saturating_add(RocksDbWeight::get().writes(9_u64))
 	}
 	/// Storage: PooledStaking PendingOperations (r:100 w:100)
@@ -218,19 +417,18 @@ impl WeightInfo for () {
 	/// Proof: Balances Holds (max_values: None, max_size: Some(73), added: 2548, mode: MaxEncodedLen)
 	/// Storage: System Account (r:1 w:1)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: PooledStaking RecentlyJoinedStake (r:100 w:100)
+	/// Proof Skipped: PooledStaking RecentlyJoinedStake (max_values: None, max_size: None, mode: Measured)
 	/// The range of component `b` is `[1, 100]`.
 	fn execute_pending_operations(b: u32, ) -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `550 + b * (390 ±0)`
-		//  Estimated: `3593 + b * (25141 ±0)`
-		// Minimum execution time: 333_447_000 picoseconds.
+		// Pending a benchmark run: the recently joined stake of each executed delegation is
+		// read and written on top of the previous measurement.
 		Weight::from_parts(335_790_000, 3593)
-			// Standard Error: 594_634
 			.saturating_add(Weight::from_parts(174_012_935, 0).saturating_mul(b.into()))
 			.saturating_add(RocksDbWeight::get().reads(3_u64))
-			.saturating_add(RocksDbWeight::get().reads((11_u64).saturating_mul(b.into())))
+			.saturating_add(RocksDbWeight::get().reads((12_u64).saturating_mul(b.into())))
 			.saturating_add(RocksDbWeight::get().writes(2_u64))
-			.saturating_add(RocksDbWeight::get().writes((9_u64).saturating_mul(b.into())))
+			.saturating_add(RocksDbWeight::get().writes((10_u64).saturating_mul(b.into())))
 			.saturating_add(Weight::from_parts(0, 25141).saturating_mul(b.into()))
 	}
 	/// Storage: PooledStaking Pools (r:13 w:9)
@@ -239,16 +437,20 @@ impl WeightInfo for () {
 	/// Proof Skipped: PooledStaking SortedEligibleCandidates (max_values: Some(1), max_size: None, mode: Measured)
 	/// Storage: PooledStaking PendingOperations (r:1 w:1)
 	/// Proof Skipped: PooledStaking PendingOperations (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CompoundingPreference (r:1 w:1)
+	/// Proof Skipped: PooledStaking CompoundingPreference (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CompoundingPreferencesCount (r:1 w:1)
+	/// Proof Skipped: PooledStaking CompoundingPreferencesCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking RecentlyJoinedStake (r:1 w:1)
+	/// Proof Skipped: PooledStaking RecentlyJoinedStake (max_values: None, max_size: None, mode: Measured)
 	fn request_undelegate() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `557`
-		//  Estimated: `33722`
-		// Minimum execution time: 249_510_000 picoseconds.
+		// Pending a benchmark run: the recently joined stake is read and written on top of
+		// the previous measurement.
 		Weight::from_parts(252_589_000, 33722)
-			.saturating_add(RocksDbWeight::get().reads(15_u64))
-			.saturating_add(RocksDbWeight::get().writes(11_u64))
+			.saturating_add(RocksDbWeight::get().reads(18_u64))
+			.saturating_add(RocksDbWeight::get().writes(14_u64))
 	}
-	/// Storage: PooledStaking Pools (r:300 w:100)
+	/// Storage: PooledStaking Pools (r:400 w:200)
 	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
 	/// Storage: System Account (r:2 w:2)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
@@ -262,9 +464,9 @@ impl WeightInfo for () {
 			// Standard Error: 543_344
 			.saturating_add(Weight::from_parts(98_370_210, 0).saturating_mul(b.into()))
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
-			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(b.into())))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(b.into())))
 			.saturating_add(RocksDbWeight::get().writes(2_u64))
-			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(b.into())))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(b.into())))
 			.saturating_add(Weight::from_parts(0, 7882).saturating_mul(b.into()))
 	}
 	/// Storage: PooledStaking Pools (r:4 w:1)
@@ -303,4 +505,185 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(b.into())))
 			.saturating_add(Weight::from_parts(0, 15206).saturating_mul(b.into()))
 	}
+	/// Storage: PooledStaking Pools (r:2 w:0)
+	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CompoundingPreference (r:1 w:1)
+	/// Proof Skipped: PooledStaking CompoundingPreference (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CompoundingPreferencesCount (r:1 w:1)
+	/// Proof Skipped: PooledStaking CompoundingPreferencesCount (max_values: None, max_size: None, mode: Measured)
+	fn set_compounding_preference() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 21_046_000 picoseconds.
+		Weight::from_parts(21_844_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: PooledStaking RewardDestinations (r:0 w:1)
+	/// Proof Skipped: PooledStaking RewardDestinations (max_values: None, max_size: None, mode: Measured)
+	fn set_reward_destination() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 20_512_000 picoseconds.
+		Weight::from_parts(21_138_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Session NextKeys (r:1 w:0)
+	/// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking PendingCommissionIncreases (r:1 w:1)
+	/// Proof Skipped: PooledStaking PendingCommissionIncreases (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking JoiningDelay (r:1 w:0)
+	/// Proof Skipped: PooledStaking JoiningDelay (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Session CurrentIndex (r:1 w:0)
+	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: PooledStaking CandidateCommission (r:0 w:1)
+	/// Proof Skipped: PooledStaking CandidateCommission (max_values: None, max_size: None, mode: Measured)
+	fn set_commission() -> Weight {
+		// Pending a benchmark run: the candidate check and the pending increase add storage
+		// reads to the previous measurement.
+		Weight::from_parts(32_981_000, 3700)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: PooledStaking DelegationProxies (r:0 w:1)
+	/// Proof Skipped: PooledStaking DelegationProxies (max_values: None, max_size: None, mode: Measured)
+	fn set_delegation_proxy() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 20_891_000 picoseconds.
+		Weight::from_parts(21_507_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: PooledStaking ScheduledDelegations (r:1 w:1)
+	/// Proof Skipped: PooledStaking ScheduledDelegations (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Proof: Balances Holds (max_values: None, max_size: Some(73), added: 2548, mode: MaxEncodedLen)
+	fn schedule_delegation() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `197`
+		//  Estimated: `3662`
+		// Minimum execution time: 38_716_000 picoseconds.
+		Weight::from_parts(39_842_000, 3662)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: PooledStaking JoiningDelay (r:1 w:1)
+	/// Proof Skipped: PooledStaking JoiningDelay (max_values: Some(1), max_size: None, mode: Measured)
+	fn set_request_delay() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4`
+		//  Estimated: `1489`
+		// Minimum execution time: 14_205_000 picoseconds.
+		Weight::from_parts(14_768_000, 1489)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: PooledStaking CandidateLeaveRequests (r:1 w:1)
+	/// Proof Skipped: PooledStaking CandidateLeaveRequests (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking Pools (r:1 w:0)
+	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CandidateLeaveSchedule (r:1 w:1)
+	/// Proof Skipped: PooledStaking CandidateLeaveSchedule (max_values: None, max_size: None, mode: Measured)
+	fn request_candidate_leave() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `238`
+		//  Estimated: `3703`
+		// Minimum execution time: 27_903_000 picoseconds.
+		Weight::from_parts(28_611_000, 3703)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: PooledStaking PendingOperations (r:1 w:1)
+	/// Proof Skipped: PooledStaking PendingOperations (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking Pools (r:12 w:8)
+	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking LeavingStakeRequested (r:1 w:1)
+	/// Proof Skipped: PooledStaking LeavingStakeRequested (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Proof: Balances Holds (max_values: None, max_size: Some(73), added: 2548, mode: MaxEncodedLen)
+	/// Storage: PooledStaking SortedEligibleCandidates (r:1 w:1)
+	/// Proof Skipped: PooledStaking SortedEligibleCandidates (max_values: Some(1), max_size: None, mode: Measured)
+	fn cancel_undelegation() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1203`
+		//  Estimated: `32943`
+		// Minimum execution time: 128_394_000 picoseconds.
+		Weight::from_parts(131_002_000, 32943)
+			.saturating_add(RocksDbWeight::get().reads(16_u64))
+			.saturating_add(RocksDbWeight::get().writes(12_u64))
+	}
+	/// Storage: Session NextKeys (r:1 w:0)
+	/// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking Pools (r:1 w:0)
+	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CandidateMinDelegation (r:0 w:1)
+	/// Proof Skipped: PooledStaking CandidateMinDelegation (max_values: None, max_size: None, mode: Measured)
+	fn set_candidate_min_delegation() -> Weight {
+		// Pending a benchmark run: the candidate check adds storage reads to the previous
+		// measurement.
+		Weight::from_parts(20_415_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: PooledStaking Pools (r:11 w:5)
+	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Proof: Balances Holds (max_values: None, max_size: Some(73), added: 2548, mode: MaxEncodedLen)
+	/// Storage: PooledStaking SortedEligibleCandidates (r:1 w:1)
+	/// Proof Skipped: PooledStaking SortedEligibleCandidates (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: PooledStaking PendingOperations (r:1 w:1)
+	/// Proof Skipped: PooledStaking PendingOperations (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking Bonds (r:1 w:1)
+	/// Proof Skipped: PooledStaking Bonds (max_values: None, max_size: None, mode: Measured)
+	fn bonded_delegate() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1358`
+		//  Estimated: `29573`
+		// Minimum execution time: 512_904_000 picoseconds.
+		Weight::from_parts(523_117_000, 29573)
+			.saturating_add(RocksDbWeight::get().reads(18_u64))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
+	}
+	/// Storage: System Account (r:3 w:3)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: PooledStaking PendingCommissionIncreases (r:1 w:0)
+	/// Proof Skipped: PooledStaking PendingCommissionIncreases (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking JoiningDelay (r:1 w:0)
+	/// Proof Skipped: PooledStaking JoiningDelay (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Session CurrentIndex (r:1 w:0)
+	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: PooledStaking CandidateCommission (r:1 w:0)
+	/// Proof Skipped: PooledStaking CandidateCommission (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking Pools (r:14 w:8)
+	/// Proof Skipped: PooledStaking Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking Bonds (r:1 w:0)
+	/// Proof Skipped: PooledStaking Bonds (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking CompoundingPreference (r:101 w:0)
+	/// Proof Skipped: PooledStaking CompoundingPreference (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PooledStaking SortedEligibleCandidates (r:1 w:1)
+	/// Proof Skipped: PooledStaking SortedEligibleCandidates (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Session NextKeys (r:1 w:0)
+	/// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `b` is `[0, 100]`.
+	fn distribute_rewards(b: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1580 + b * (524 ±0)`
+		//  Estimated: `23146 + b * (6192 ±0)`
+		// Minimum execution time: 287_315_000 picoseconds.
+		// Pending a benchmark run: the pending commission increase and the joining delay are
+		// read on top of the previous measurement.
+		Weight::from_parts(291_604_512, 23146)
+			// Standard Error: 412_908
+			.saturating_add(Weight::from_parts(64_127_386, 0).saturating_mul(b.into()))
+			.saturating_add(RocksDbWeight::get().reads(25_u64))
+			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(b.into())))
+			.saturating_add(RocksDbWeight::get().writes(12_u64))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(b.into())))
+			.saturating_add(Weight::from_parts(0, 6192).saturating_mul(b.into()))
+	}
 }
@@ -37,13 +37,18 @@ fn create_funded_user<T: Config>(
 ) -> (T::AccountId, DepositBalanceOf<T>) {
     const SEED: u32 = 0;
     let user = account(string, n, SEED);
-    let min_reserve_amount = T::DepositAmount::get();
+    let min_reserve_amount = T::DepositAmount::get() + T::ReservationDeposit::get();
     let total = min_reserve_amount + extra;
     T::Currency::make_free_balance_be(&user, total);
     T::Currency::issue(total);
     (user, total)
 }
 
+/// The `i`-th para id that can be reserved.
+fn para_id<T: Config>(i: u32) -> ParaId {
+    (u32::from(T::FirstFreeParaId::get()) + i).into()
+}
+
 #[benchmarks]
 mod benchmarks {
     use super::*;
@@ -65,8 +70,8 @@ mod benchmarks {
     fn register(x: Linear<5, 3_000_000>, y: Linear<1, 50>, z: Linear<1, 10>) {
         let mut data = vec![];
         // Number of keys
-        for _i in 1..z {
-            data.push((b"code".to_vec(), vec![1; (x / z) as usize]).into())
+        for i in 1..z {
+            data.push((i.to_le_bytes().to_vec(), vec![1; (x / z) as usize]).into())
         }
 
         let storage = new_genesis_data(data);
@@ -75,9 +80,14 @@ mod benchmarks {
             // Twice the deposit just in case
             let (caller, _deposit_amount) =
                 create_funded_user::<T>("caller", i, T::DepositAmount::get());
+            Pallet::<T>::reserve_para_id(
+                RawOrigin::Signed(caller.clone()).into(),
+                Some(para_id::<T>(i)),
+            )
+            .expect("Failed to reserve para id");
             Pallet::<T>::register(
                 RawOrigin::Signed(caller.clone()).into(),
-                i.into(),
+                para_id::<T>(i),
                 storage.clone(),
             )
             .unwrap();
@@ -89,12 +99,16 @@ mod benchmarks {
         let (caller, _deposit_amount) =
             create_funded_user::<T>("caller", 0, T::DepositAmount::get());
 
+        Pallet::<T>::reserve_para_id(RawOrigin::Signed(caller.clone()).into(), None)
+            .expect("Failed to reserve para id");
+        let para_id = T::FirstFreeParaId::get();
+
         #[extrinsic_call]
-        Pallet::<T>::register(RawOrigin::Signed(caller), Default::default(), storage);
+        Pallet::<T>::register(RawOrigin::Signed(caller), para_id, storage);
 
         // verification code
         assert_eq!(Pallet::<T>::pending_verification().len(), y as usize);
-        assert!(Pallet::<T>::registrar_deposit(ParaId::default()).is_some());
+        assert!(Pallet::<T>::registrar_deposit(para_id).is_some());
     }
 
     #[benchmark]
@@ -106,9 +120,14 @@ mod benchmarks {
             // Twice the deposit just in case
             let (caller, _deposit_amount) =
                 create_funded_user::<T>("caller", i, T::DepositAmount::get());
+            Pallet::<T>::reserve_para_id(
+                RawOrigin::Signed(caller.clone()).into(),
+                Some(para_id::<T>(i)),
+            )
+            .expect("Failed to reserve para id");
             Pallet::<T>::register(
                 RawOrigin::Signed(caller.clone()).into(),
-                i.into(),
+                para_id::<T>(i),
                 storage.clone(),
             )
             .unwrap();
@@ -118,11 +137,11 @@ mod benchmarks {
         assert_eq!(Pallet::<T>::pending_verification().len(), y as usize);
 
         #[extrinsic_call]
-        Pallet::<T>::deregister(RawOrigin::Root, (y - 1).into());
+        Pallet::<T>::deregister(RawOrigin::Root, para_id::<T>(y - 1));
 
         // We should have y-1
         assert_eq!(Pallet::<T>::pending_verification().len(), (y - 1) as usize);
-        assert!(Pallet::<T>::registrar_deposit(ParaId::from(y - 1)).is_none());
+        assert!(Pallet::<T>::registrar_deposit(para_id::<T>(y - 1)).is_none());
     }
 
     #[benchmark]
@@ -134,9 +153,14 @@ mod benchmarks {
             // Twice the deposit just in case
             let (caller, _deposit_amount) =
                 create_funded_user::<T>("caller", i, T::DepositAmount::get());
+            Pallet::<T>::reserve_para_id(
+                RawOrigin::Signed(caller.clone()).into(),
+                Some(para_id::<T>(i)),
+            )
+            .expect("Failed to reserve para id");
             Pallet::<T>::register(
                 RawOrigin::Signed(caller.clone()).into(),
-                i.into(),
+                para_id::<T>(i),
                 storage.clone(),
             )
             .unwrap();
@@ -146,7 +170,7 @@ mod benchmarks {
         assert_eq!(Pallet::<T>::pending_verification().len(), y as usize);
 
         #[extrinsic_call]
-        Pallet::<T>::mark_valid_for_collating(RawOrigin::Root, (y - 1).into());
+        Pallet::<T>::mark_valid_for_collating(RawOrigin::Root, para_id::<T>(y - 1));
 
         // We should have y-1
         assert_eq!(Pallet::<T>::pending_verification().len(), (y - 1) as usize);
@@ -160,9 +184,14 @@ mod benchmarks {
         let (caller, _deposit_amount) =
             create_funded_user::<T>("caller", 0, T::DepositAmount::get());
 
+        Pallet::<T>::reserve_para_id(
+            RawOrigin::Signed(caller.clone()).into(),
+            Some(para_id::<T>(0)),
+        )
+        .expect("Failed to reserve para id");
         Pallet::<T>::register(
             RawOrigin::Signed(caller.clone()).into(),
-            Default::default(),
+            para_id::<T>(0),
             storage,
         )
         .expect("Failed to register chain");
@@ -176,7 +205,116 @@ mod benchmarks {
         .unwrap();
 
         #[extrinsic_call]
-        Pallet::<T>::set_boot_nodes(RawOrigin::Signed(caller), Default::default(), boot_nodes);
+        Pallet::<T>::set_boot_nodes(RawOrigin::Signed(caller), para_id::<T>(0), boot_nodes);
+    }
+
+    #[benchmark]
+    fn pause_container_chain(y: Linear<1, 50>) {
+        let storage = new_genesis_data(vec![]);
+
+        for i in 0..y {
+            let (caller, _deposit_amount) =
+                create_funded_user::<T>("caller", i, T::DepositAmount::get());
+            Pallet::<T>::reserve_para_id(
+                RawOrigin::Signed(caller.clone()).into(),
+                Some(para_id::<T>(i)),
+            )
+            .expect("Failed to reserve para id");
+            Pallet::<T>::register(
+                RawOrigin::Signed(caller).into(),
+                para_id::<T>(i),
+                storage.clone(),
+            )
+            .expect("Failed to register chain");
+            Pallet::<T>::mark_valid_for_collating(RawOrigin::Root.into(), para_id::<T>(i))
+                .expect("Failed to mark chain valid for collating");
+        }
+
+        #[extrinsic_call]
+        Pallet::<T>::pause_container_chain(RawOrigin::Root, para_id::<T>(y - 1));
+
+        assert_eq!(Pallet::<T>::paused(), vec![para_id::<T>(y - 1)]);
+    }
+
+    #[benchmark]
+    fn resume_container_chain(y: Linear<1, 50>) {
+        let storage = new_genesis_data(vec![]);
+
+        // All chains are paused, the worst case for the list of paused chains
+        for i in 0..y {
+            let (caller, _deposit_amount) =
+                create_funded_user::<T>("caller", i, T::DepositAmount::get());
+            Pallet::<T>::reserve_para_id(
+                RawOrigin::Signed(caller.clone()).into(),
+                Some(para_id::<T>(i)),
+            )
+            .expect("Failed to reserve para id");
+            Pallet::<T>::register(
+                RawOrigin::Signed(caller).into(),
+                para_id::<T>(i),
+                storage.clone(),
+            )
+            .expect("Failed to register chain");
+            Pallet::<T>::mark_valid_for_collating(RawOrigin::Root.into(), para_id::<T>(i))
+                .expect("Failed to mark chain valid for collating");
+            Pallet::<T>::pause_container_chain(RawOrigin::Root.into(), para_id::<T>(i))
+                .expect("Failed to pause chain");
+        }
+
+        #[extrinsic_call]
+        Pallet::<T>::resume_container_chain(RawOrigin::Root, para_id::<T>(y - 1));
+
+        assert_eq!(Pallet::<T>::paused().len(), (y - 1) as usize);
+    }
+
+    #[benchmark]
+    fn reserve_para_id(y: Linear<1, 50>) {
+        // The last para id tried has an expired reservation, to be taken over
+        let (expired_owner, _deposit_amount) =
+            create_funded_user::<T>("expired", 0, T::DepositAmount::get());
+        Pallet::<T>::reserve_para_id(
+            RawOrigin::Signed(expired_owner).into(),
+            Some(para_id::<T>(y - 1)),
+        )
+        .expect("Failed to reserve para id");
+        frame_system::Pallet::<T>::set_block_number(
+            frame_system::Pallet::<T>::block_number() + T::ReservationExpiry::get(),
+        );
+
+        // The previous para ids are reserved
+        for i in 0..y - 1 {
+            let (owner, _deposit_amount) =
+                create_funded_user::<T>("owner", i, T::DepositAmount::get());
+            Pallet::<T>::reserve_para_id(RawOrigin::Signed(owner).into(), Some(para_id::<T>(i)))
+                .expect("Failed to reserve para id");
+        }
+
+        let (caller, _deposit_amount) =
+            create_funded_user::<T>("caller", 0, T::DepositAmount::get());
+
+        #[extrinsic_call]
+        Pallet::<T>::reserve_para_id(RawOrigin::Signed(caller.clone()), None);
+
+        assert_eq!(
+            Pallet::<T>::reserved_para_ids(para_id::<T>(y - 1)).map(|r| r.owner),
+            Some(caller)
+        );
+    }
+
+    #[benchmark]
+    fn release_para_id() {
+        let (caller, _deposit_amount) =
+            create_funded_user::<T>("caller", 0, T::DepositAmount::get());
+        Pallet::<T>::reserve_para_id(
+            RawOrigin::Signed(caller.clone()).into(),
+            Some(para_id::<T>(0)),
+        )
+        .expect("Failed to reserve para id");
+
+        #[extrinsic_call]
+        Pallet::<T>::release_para_id(RawOrigin::Signed(caller), para_id::<T>(0));
+
+        assert!(Pallet::<T>::reserved_para_ids(para_id::<T>(0)).is_none());
     }
 
     impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -50,14 +50,16 @@ pub mod pallet {
             DefaultNoBound, LOG_TARGET,
         },
         frame_system::{pallet_prelude::*, EnsureSigned},
+        sp_core::storage::well_known_keys,
         sp_runtime::{
             traits::{AtLeast32BitUnsigned, BadOrigin},
             Either, Saturating,
         },
-        sp_std::prelude::*,
+        sp_std::{collections::btree_set::BTreeSet, prelude::*},
         tp_container_chain_genesis_data::ContainerChainGenesisData,
         tp_traits::{
-            GetCurrentContainerChains, GetSessionContainerChains, GetSessionIndex, ParaId,
+            GetContainerChainCollators, GetCurrentContainerChains, GetSessionContainerChains,
+            GetSessionIndex, ParaId,
         },
     };
 
@@ -137,6 +139,10 @@ pub mod pallet {
         #[pallet::constant]
         type MaxGenesisDataSize: Get<u32>;
 
+        /// Max length of the runtime code included in the genesis data
+        #[pallet::constant]
+        type MaxCodeSize: Get<u32>;
+
         type MaxBootNodes: Get<u32>;
         type MaxBootNodeUrlLen: Get<u32>;
         type MaxLengthTokenSymbol: Get<u32>;
@@ -153,6 +159,29 @@ pub mod pallet {
         #[pallet::constant]
         type DepositAmount: Get<<Self::Currency as Currency<Self::AccountId>>::Balance>;
 
+        /// First para id that can be allocated by `reserve_para_id`. Lower para ids cannot be
+        /// reserved
+        #[pallet::constant]
+        type FirstFreeParaId: Get<ParaId>;
+
+        /// Deposit reserved from the owner of a para id reservation until it is released,
+        /// registered or taken over after expiring
+        #[pallet::constant]
+        type ReservationDeposit: Get<<Self::Currency as Currency<Self::AccountId>>::Balance>;
+
+        /// Number of blocks after which a para id reservation expires if the para id has not been
+        /// registered
+        #[pallet::constant]
+        type ReservationExpiry: Get<Self::BlockNumber>;
+
+        /// Collators assigned to each container chain
+        type ContainerChainCollators: GetContainerChainCollators<Self::AccountId>;
+
+        /// Number of consecutive sessions a registered container chain can go without collators
+        /// before it is flagged for deregistration
+        #[pallet::constant]
+        type MaxIdleSessions: Get<u32>;
+
         type WeightInfo: WeightInfo;
     }
 
@@ -184,6 +213,13 @@ pub mod pallet {
     pub type PendingVerification<T: Config> =
         StorageValue<_, BoundedVec<ParaId, T::MaxLengthParaIds>, ValueQuery>;
 
+    /// Para ids that have been paused. They keep their genesis data, boot nodes and deposit, but
+    /// are not part of the registered para ids, so no collators are assigned to them.
+    #[pallet::storage]
+    #[pallet::getter(fn paused)]
+    pub type PausedChains<T: Config> =
+        StorageValue<_, BoundedVec<ParaId, T::MaxLengthParaIds>, ValueQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn boot_nodes)]
     pub type BootNodes<T: Config> = StorageMap<
@@ -211,6 +247,33 @@ pub mod pallet {
     #[pallet::getter(fn registrar_deposit)]
     pub type RegistrarDeposit<T: Config> = StorageMap<_, Blake2_128Concat, ParaId, DepositInfo<T>>;
 
+    #[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, scale_info::TypeInfo)]
+    #[scale_info(skip_type_params(T))]
+    pub struct ParaIdReservation<T: Config> {
+        pub owner: T::AccountId,
+        pub expires_at: T::BlockNumber,
+        pub deposit: DepositBalanceOf<T>,
+    }
+
+    /// Reserved para ids, a mapping from paraId to the account that reserved it, the block
+    /// at which the reservation expires and the deposit reserved from that account. A para id
+    /// must be reserved before it can be registered.
+    #[pallet::storage]
+    #[pallet::getter(fn reserved_para_ids)]
+    pub type ReservedParaIds<T: Config> =
+        StorageMap<_, Blake2_128Concat, ParaId, ParaIdReservation<T>>;
+
+    /// Next para id to try when reserving without specifying a para id
+    #[pallet::storage]
+    #[pallet::getter(fn next_free_para_id)]
+    pub type NextFreeParaId<T: Config> = StorageValue<_, ParaId, OptionQuery>;
+
+    /// Number of consecutive sessions each registered container chain had no collators
+    /// assigned. Chains that had collators in the last session are not present.
+    #[pallet::storage]
+    #[pallet::getter(fn idle_sessions)]
+    pub type IdleSessions<T: Config> = StorageMap<_, Blake2_128Concat, ParaId, u32, ValueQuery>;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -222,6 +285,20 @@ pub mod pallet {
         ParaIdValidForCollating { para_id: ParaId },
         /// The list of boot_nodes
         BootNodesChanged { para_id: ParaId },
+        /// A para id has been paused from collating. [para_id]
+        ParaIdPaused { para_id: ParaId },
+        /// A para id has been unpaused. [para_id]
+        ParaIdUnpaused { para_id: ParaId },
+        /// A para id has been reserved. [para_id, owner]
+        ParaIdReserved {
+            para_id: ParaId,
+            owner: T::AccountId,
+        },
+        /// A para id reservation has been released. [para_id]
+        ParaIdReservationReleased { para_id: ParaId },
+        /// A para id had no collators for `MaxIdleSessions` sessions and can be deregistered.
+        /// [para_id]
+        ParaIdFlaggedForDeregistration { para_id: ParaId },
     }
 
     #[pallet::error]
@@ -238,6 +315,26 @@ pub mod pallet {
         ParaIdNotInPendingVerification,
         /// Tried to register a ParaId with an account that did not have enough balance for the deposit
         NotSufficientDeposit,
+        /// Attempted to register a ParaId with a runtime code size greater than the limit
+        CodeTooLarge,
+        /// Attempted to register a ParaId with genesis storage containing empty or duplicated keys
+        InvalidGenesisStorage,
+        /// Tried to pause a ParaId that was already paused
+        ParaIdAlreadyPaused,
+        /// Tried to unpause a ParaId that was not paused
+        ParaIdNotPaused,
+        /// Tried to reserve a ParaId that is already reserved
+        ParaIdAlreadyReserved,
+        /// Tried to register or release a ParaId that has not been reserved
+        ParaIdNotReserved,
+        /// Tried to register or release a ParaId that was reserved by a different account
+        NotParaIdReservationOwner,
+        /// Tried to register a ParaId whose reservation has expired
+        ParaIdReservationExpired,
+        /// Tried to reserve a ParaId lower than `FirstFreeParaId`
+        ParaIdBelowFirstFree,
+        /// None of the `MaxLengthParaIds` ParaIds following `NextFreeParaId` is free
+        NoFreeParaId,
     }
 
     #[pallet::call]
@@ -264,7 +361,9 @@ pub mod pallet {
                 .last()
                 .map(|(_, paras)| paras.clone())
                 .unwrap_or_else(Self::registered_para_ids);
-            if base_paras.binary_search(&para_id).is_ok() {
+            if base_paras.binary_search(&para_id).is_ok()
+                || PausedChains::<T>::get().binary_search(&para_id).is_ok()
+            {
                 return Err(Error::<T>::ParaIdAlreadyRegistered.into());
             }
 
@@ -279,6 +378,17 @@ pub mod pallet {
                 }
             }
 
+            // The para id must have been reserved by the caller, and the reservation must not have
+            // expired yet
+            let reservation =
+                ReservedParaIds::<T>::get(para_id).ok_or(Error::<T>::ParaIdNotReserved)?;
+            if reservation.owner != account {
+                return Err(Error::<T>::NotParaIdReservationOwner.into());
+            }
+            if Self::reservation_expired(&reservation) {
+                return Err(Error::<T>::ParaIdReservationExpired.into());
+            }
+
             // The actual registration takes place 2 sessions after the call to
             // `mark_valid_for_collating`, but the genesis data is inserted now.
             // This is because collators should be able to start syncing the new container chain
@@ -288,13 +398,11 @@ pub mod pallet {
             // of key-values in `genesis_data.storage`, even if those key-values are empty. And we
             // won't detect that the size is too big until after iterating over all of them, so the
             // limit in that case would be the transaction size.
-            let genesis_data_size = genesis_data.encoded_size();
-            if genesis_data_size > T::MaxGenesisDataSize::get() as usize {
-                return Err(Error::<T>::GenesisDataTooBig.into());
-            }
+            Self::validate_genesis_data(&genesis_data)?;
 
-            // Reserve the deposit, we verified we can do this
+            // The reservation deposit is returned once the registration deposit is reserved
             T::Currency::reserve(&account, deposit)?;
+            T::Currency::unreserve(&account, reservation.deposit);
 
             // Update DepositInfo
             RegistrarDeposit::<T>::insert(
@@ -306,6 +414,7 @@ pub mod pallet {
             );
             ParaGenesisData::<T>::insert(para_id, genesis_data);
             PendingVerification::<T>::put(pending_verification);
+            ReservedParaIds::<T>::remove(para_id);
 
             Self::deposit_event(Event::ParaIdRegistered { para_id });
 
@@ -343,7 +452,19 @@ pub mod pallet {
                                 PendingVerification::<T>::put(para_ids);
                                 Ok(())
                             }
-                            Err(_) => Err(Error::<T>::ParaIdNotRegistered.into()),
+                            Err(_) => {
+                                // It may also be paused, in that case remove it from "PausedChains"
+                                let mut paused = PausedChains::<T>::get();
+
+                                match paused.binary_search(&para_id) {
+                                    Ok(index) => {
+                                        paused.remove(index);
+                                        PausedChains::<T>::put(paused);
+                                        Ok(())
+                                    }
+                                    Err(_) => Err(Error::<T>::ParaIdNotRegistered.into()),
+                                }
+                            }
                         }
                     }
                 }
@@ -441,6 +562,160 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Pause container-chain from collating. Does not remove its boot nodes nor its genesis config.
+        /// Only container-chains that have been marked as valid_for_collating can be paused.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::pause_container_chain(T::MaxLengthParaIds::get()))]
+        pub fn pause_container_chain(origin: OriginFor<T>, para_id: ParaId) -> DispatchResult {
+            T::RegistrarOrigin::ensure_origin(origin)?;
+
+            let mut paused = PausedChains::<T>::get();
+            let index = match paused.binary_search(&para_id) {
+                Ok(_) => return Err(Error::<T>::ParaIdAlreadyPaused.into()),
+                Err(index) => index,
+            };
+
+            Self::schedule_parachain_change(|para_ids| match para_ids.binary_search(&para_id) {
+                Ok(index) => {
+                    para_ids.remove(index);
+                    Ok(())
+                }
+                Err(_) => Err(Error::<T>::ParaIdNotRegistered.into()),
+            })?;
+
+            paused
+                .try_insert(index, para_id)
+                .map_err(|_e| Error::<T>::ParaIdListFull)?;
+            PausedChains::<T>::put(paused);
+
+            Self::deposit_event(Event::ParaIdPaused { para_id });
+
+            Ok(())
+        }
+
+        /// Unpause container-chain.
+        /// Only container-chains that have been paused can be unpaused.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::resume_container_chain(T::MaxLengthParaIds::get()))]
+        pub fn resume_container_chain(origin: OriginFor<T>, para_id: ParaId) -> DispatchResult {
+            T::RegistrarOrigin::ensure_origin(origin)?;
+
+            let mut paused = PausedChains::<T>::get();
+            match paused.binary_search(&para_id) {
+                Ok(index) => {
+                    paused.remove(index);
+                }
+                Err(_) => return Err(Error::<T>::ParaIdNotPaused.into()),
+            };
+
+            Self::schedule_parachain_change(|para_ids| match para_ids.binary_search(&para_id) {
+                Ok(_) => Err(Error::<T>::ParaIdAlreadyRegistered.into()),
+                Err(index) => {
+                    para_ids
+                        .try_insert(index, para_id)
+                        .map_err(|_e| Error::<T>::ParaIdListFull)?;
+
+                    Ok(())
+                }
+            })?;
+
+            PausedChains::<T>::put(paused);
+
+            Self::deposit_event(Event::ParaIdUnpaused { para_id });
+
+            Ok(())
+        }
+
+        /// Reserve a para id for the caller, so that it can be registered later.
+        ///
+        /// If `para_id` is `None`, the next free para id is allocated, among the
+        /// `T::MaxLengthParaIds` para ids following the last allocated one. Otherwise `para_id`
+        /// must not be lower than `T::FirstFreeParaId`. `T::ReservationDeposit` is reserved from
+        /// the caller until the reservation is released or registered. The reservation expires
+        /// after `T::ReservationExpiry` blocks if the para id is not registered before, and can
+        /// then be taken over, returning the deposit to its previous owner.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::reserve_para_id(T::MaxLengthParaIds::get()))]
+        pub fn reserve_para_id(origin: OriginFor<T>, para_id: Option<ParaId>) -> DispatchResult {
+            let account = ensure_signed(origin)?;
+
+            let para_id = match para_id {
+                Some(para_id) => {
+                    if para_id < T::FirstFreeParaId::get() {
+                        return Err(Error::<T>::ParaIdBelowFirstFree.into());
+                    }
+                    if ReservedParaIds::<T>::get(para_id).map_or(false, |reservation| {
+                        !Self::reservation_expired(&reservation)
+                    }) {
+                        return Err(Error::<T>::ParaIdAlreadyReserved.into());
+                    }
+                    if Self::is_para_id_in_use(para_id) {
+                        return Err(Error::<T>::ParaIdAlreadyRegistered.into());
+                    }
+
+                    para_id
+                }
+                None => {
+                    let first = NextFreeParaId::<T>::get().unwrap_or_else(T::FirstFreeParaId::get);
+                    let para_id = (0..T::MaxLengthParaIds::get())
+                        .map(|offset| ParaId::from(u32::from(first).saturating_add(offset)))
+                        .find(|para_id| !Self::is_para_id_taken(*para_id))
+                        .ok_or(Error::<T>::NoFreeParaId)?;
+                    NextFreeParaId::<T>::put(ParaId::from(u32::from(para_id).saturating_add(1)));
+
+                    para_id
+                }
+            };
+
+            let deposit = T::ReservationDeposit::get();
+            T::Currency::reserve(&account, deposit)
+                .map_err(|_| Error::<T>::NotSufficientDeposit)?;
+
+            // Only an expired reservation can be left at this point
+            if let Some(expired) = ReservedParaIds::<T>::get(para_id) {
+                T::Currency::unreserve(&expired.owner, expired.deposit);
+            }
+
+            let expires_at = frame_system::Pallet::<T>::block_number()
+                .saturating_add(T::ReservationExpiry::get());
+            ReservedParaIds::<T>::insert(
+                para_id,
+                ParaIdReservation {
+                    owner: account.clone(),
+                    expires_at,
+                    deposit,
+                },
+            );
+
+            Self::deposit_event(Event::ParaIdReserved {
+                para_id,
+                owner: account,
+            });
+
+            Ok(())
+        }
+
+        /// Release a para id reservation owned by the caller, without registering it. The
+        /// reservation deposit is returned.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::release_para_id())]
+        pub fn release_para_id(origin: OriginFor<T>, para_id: ParaId) -> DispatchResult {
+            let account = ensure_signed(origin)?;
+
+            let reservation =
+                ReservedParaIds::<T>::get(para_id).ok_or(Error::<T>::ParaIdNotReserved)?;
+            if reservation.owner != account {
+                return Err(Error::<T>::NotParaIdReservationOwner.into());
+            }
+
+            ReservedParaIds::<T>::remove(para_id);
+            T::Currency::unreserve(&account, reservation.deposit);
+
+            Self::deposit_event(Event::ParaIdReservationReleased { para_id });
+
+            Ok(())
+        }
     }
 
     pub struct SessionChangeOutcome<T: Config> {
@@ -482,11 +757,98 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Check that the genesis data and the runtime code in it are within the size limits, and
+        /// that the genesis storage does not contain empty or duplicated keys.
+        fn validate_genesis_data(
+            genesis_data: &ContainerChainGenesisData<T::MaxLengthTokenSymbol>,
+        ) -> DispatchResult {
+            let genesis_data_size = genesis_data.encoded_size();
+            if genesis_data_size > T::MaxGenesisDataSize::get() as usize {
+                return Err(Error::<T>::GenesisDataTooBig.into());
+            }
+
+            let mut keys = BTreeSet::new();
+            for item in genesis_data.storage.iter() {
+                if item.key.is_empty() || !keys.insert(&item.key) {
+                    return Err(Error::<T>::InvalidGenesisStorage.into());
+                }
+
+                if item.key == well_known_keys::CODE
+                    && item.value.len() > T::MaxCodeSize::get() as usize
+                {
+                    return Err(Error::<T>::CodeTooLarge.into());
+                }
+            }
+
+            Ok(())
+        }
+
+        fn reservation_expired(reservation: &ParaIdReservation<T>) -> bool {
+            frame_system::Pallet::<T>::block_number() >= reservation.expires_at
+        }
+
+        /// Whether the para id is registered, pending to be registered, or paused.
+        fn is_para_id_in_use(para_id: ParaId) -> bool {
+            let base_paras = PendingParaIds::<T>::get()
+                .last()
+                .map(|(_, paras)| paras.clone())
+                .unwrap_or_else(Self::registered_para_ids);
+
+            base_paras.binary_search(&para_id).is_ok()
+                || RegisteredParaIds::<T>::get()
+                    .binary_search(&para_id)
+                    .is_ok()
+                || PendingVerification::<T>::get()
+                    .binary_search(&para_id)
+                    .is_ok()
+                || PausedChains::<T>::get().binary_search(&para_id).is_ok()
+        }
+
+        /// Whether the para id is in use or has an active reservation.
+        fn is_para_id_taken(para_id: ParaId) -> bool {
+            ReservedParaIds::<T>::get(para_id).map_or(false, |reservation| {
+                !Self::reservation_expired(&reservation)
+            }) || Self::is_para_id_in_use(para_id)
+        }
+
         /// Return the session index that should be used for any future scheduled changes.
         fn scheduled_session() -> T::SessionIndex {
             T::CurrentSessionIndex::session_index().saturating_add(T::SessionDelay::get())
         }
 
+        /// Whether `para_id` had no collators for at least `MaxIdleSessions` sessions.
+        pub fn is_flagged_for_deregistration(para_id: ParaId) -> bool {
+            Self::idle_sessions(para_id) >= T::MaxIdleSessions::get()
+        }
+
+        /// Count the consecutive sessions each of the sorted `para_ids` had no collators
+        /// assigned, flagging the ones that reach `MaxIdleSessions` for deregistration.
+        fn update_idle_sessions(para_ids: &[ParaId]) {
+            // Forget the chains that are not registered anymore
+            let deregistered: Vec<_> = IdleSessions::<T>::iter_keys()
+                .filter(|para_id| para_ids.binary_search(para_id).is_err())
+                .collect();
+            for para_id in deregistered {
+                IdleSessions::<T>::remove(para_id);
+            }
+
+            for para_id in para_ids {
+                if !T::ContainerChainCollators::container_chain_collators(*para_id).is_empty() {
+                    IdleSessions::<T>::remove(para_id);
+                    continue;
+                }
+                let idle_sessions = IdleSessions::<T>::mutate(para_id, |idle_sessions| {
+                    *idle_sessions = idle_sessions.saturating_add(1);
+                    *idle_sessions
+                });
+                if idle_sessions == T::MaxIdleSessions::get() {
+                    Self::deposit_event(Event::ParaIdFlaggedForDeregistration {
+                        para_id: *para_id,
+                    });
+                }
+            }
+        }
+
         /// Called by the initializer to note that a new session has started.
         ///
         /// Returns the parachain list that was actual before the session change and the parachain list
@@ -498,6 +860,8 @@ pub mod pallet {
             let pending_paras = <PendingParaIds<T>>::get();
             let prev_paras = RegisteredParaIds::<T>::get();
 
+            Self::update_idle_sessions(&prev_paras);
+
             // No pending parachain changes, so we're done.
             if pending_paras.is_empty() {
                 return SessionChangeOutcome {
@@ -26,7 +26,7 @@ use {
         traits::{BlakeTwo256, IdentityLookup},
         BuildStorage,
     },
-    tp_traits::ParaId,
+    tp_traits::{GetContainerChainCollators, ParaId},
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -104,13 +104,31 @@ impl tp_traits::GetSessionIndex<u32> for CurrentSessionIndexGetter {
 
 parameter_types! {
     pub const DepositAmount: Balance = 100;
+    pub const ReservationDeposit: Balance = 10;
     pub const MaxLengthTokenSymbol: u32 = 255;
+    pub const FirstFreeParaId: ParaId = ParaId::new(10);
+}
+
+frame_support::parameter_types! {
+    pub static ParaIdsWithCollators: Vec<ParaId> = vec![];
+}
+
+pub struct ContainerChainCollators;
+impl GetContainerChainCollators<u64> for ContainerChainCollators {
+    fn container_chain_collators(para_id: ParaId) -> Vec<u64> {
+        if ParaIdsWithCollators::get().contains(&para_id) {
+            vec![ALICE]
+        } else {
+            vec![]
+        }
+    }
 }
 impl pallet_registrar::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type RegistrarOrigin = frame_system::EnsureRoot<u64>;
     type MaxLengthParaIds = ConstU32<1000>;
     type MaxGenesisDataSize = ConstU32<5_000_000>;
+    type MaxCodeSize = ConstU32<1_000>;
     type MaxBootNodes = ConstU32<10>;
     type MaxBootNodeUrlLen = ConstU32<200>;
     type MaxLengthTokenSymbol = MaxLengthTokenSymbol;
@@ -119,10 +137,16 @@ impl pallet_registrar::Config for Test {
     type CurrentSessionIndex = CurrentSessionIndexGetter;
     type Currency = Balances;
     type DepositAmount = DepositAmount;
+    type FirstFreeParaId = FirstFreeParaId;
+    type ReservationDeposit = ReservationDeposit;
+    type ReservationExpiry = ConstU64<100>;
+    type ContainerChainCollators = ContainerChainCollators;
+    type MaxIdleSessions = ConstU32<3>;
     type WeightInfo = ();
 }
 
 const ALICE: u64 = 1;
+const BOB: u64 = 2;
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -131,7 +155,7 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
         .unwrap();
 
     pallet_balances::GenesisConfig::<Test> {
-        balances: vec![(ALICE, 1_000)],
+        balances: vec![(ALICE, 1_000), (BOB, 1_000)],
     }
     .assimilate_storage(&mut t)
     .unwrap();
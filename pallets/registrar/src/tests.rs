@@ -18,7 +18,7 @@ use {
     crate::{mock::*, Error, Event},
     frame_support::{assert_noop, assert_ok, dispatch::GetDispatchInfo, BoundedVec},
     parity_scale_codec::Encode,
-    sp_core::Get,
+    sp_core::{storage::well_known_keys, Get},
     sp_runtime::DispatchError,
     tp_container_chain_genesis_data::ContainerChainGenesisData,
     tp_traits::ParaId,
@@ -31,6 +31,10 @@ const BOB: u64 = 2;
 fn register_para_id_42() {
     new_test_ext().execute_with(|| {
         System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_ok!(ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -60,6 +64,10 @@ fn register_para_id_42() {
 fn register_para_id_42_twice() {
     new_test_ext().execute_with(|| {
         System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_ok!(ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -88,6 +96,10 @@ fn register_para_id_42_genesis_data_size_too_big() {
             extensions: Default::default(),
             properties: Default::default(),
         };
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_noop!(
             ParaRegistrar::register(RuntimeOrigin::signed(ALICE), 42.into(), genesis_data,),
             Error::<Test>::GenesisDataTooBig,
@@ -95,6 +107,140 @@ fn register_para_id_42_genesis_data_size_too_big() {
     });
 }
 
+fn genesis_data_with_storage(
+    storage: Vec<(Vec<u8>, Vec<u8>)>,
+) -> ContainerChainGenesisData<MaxLengthTokenSymbol> {
+    ContainerChainGenesisData {
+        storage: storage.into_iter().map(Into::into).collect(),
+        ..empty_genesis_data()
+    }
+}
+
+#[test]
+fn register_para_id_42_genesis_data_size_at_limit() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let max_size = <Test as crate::Config>::MaxGenesisDataSize::get() as usize;
+        // The encoded size includes the length prefixes, so compute the overhead first
+        let overhead = genesis_data_with_storage(vec![(b"key".to_vec(), vec![0; max_size])])
+            .encoded_size()
+            - max_size;
+        let genesis_data =
+            genesis_data_with_storage(vec![(b"key".to_vec(), vec![0; max_size - overhead])]);
+        assert_eq!(genesis_data.encoded_size(), max_size);
+
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
+        assert_ok!(ParaRegistrar::register(
+            RuntimeOrigin::signed(ALICE),
+            42.into(),
+            genesis_data
+        ));
+    });
+}
+
+#[test]
+fn register_para_id_42_genesis_data_size_one_byte_over_limit() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let max_size = <Test as crate::Config>::MaxGenesisDataSize::get() as usize;
+        let overhead = genesis_data_with_storage(vec![(b"key".to_vec(), vec![0; max_size])])
+            .encoded_size()
+            - max_size;
+        let genesis_data =
+            genesis_data_with_storage(vec![(b"key".to_vec(), vec![0; max_size - overhead + 1])]);
+        assert_eq!(genesis_data.encoded_size(), max_size + 1);
+
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
+        assert_noop!(
+            ParaRegistrar::register(RuntimeOrigin::signed(ALICE), 42.into(), genesis_data),
+            Error::<Test>::GenesisDataTooBig
+        );
+    });
+}
+
+#[test]
+fn register_para_id_42_code_size_at_limit() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let max_code_size = <Test as crate::Config>::MaxCodeSize::get() as usize;
+        let genesis_data = genesis_data_with_storage(vec![(
+            well_known_keys::CODE.to_vec(),
+            vec![1; max_code_size],
+        )]);
+
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
+        assert_ok!(ParaRegistrar::register(
+            RuntimeOrigin::signed(ALICE),
+            42.into(),
+            genesis_data
+        ));
+    });
+}
+
+#[test]
+fn register_para_id_42_code_size_one_byte_over_limit() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let max_code_size = <Test as crate::Config>::MaxCodeSize::get() as usize;
+        let genesis_data = genesis_data_with_storage(vec![(
+            well_known_keys::CODE.to_vec(),
+            vec![1; max_code_size + 1],
+        )]);
+
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
+        assert_noop!(
+            ParaRegistrar::register(RuntimeOrigin::signed(ALICE), 42.into(), genesis_data),
+            Error::<Test>::CodeTooLarge
+        );
+    });
+}
+
+#[test]
+fn register_para_id_42_invalid_genesis_storage() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
+
+        // Empty key
+        assert_noop!(
+            ParaRegistrar::register(
+                RuntimeOrigin::signed(ALICE),
+                42.into(),
+                genesis_data_with_storage(vec![(vec![], vec![1])])
+            ),
+            Error::<Test>::InvalidGenesisStorage
+        );
+
+        // Duplicated key
+        assert_noop!(
+            ParaRegistrar::register(
+                RuntimeOrigin::signed(ALICE),
+                42.into(),
+                genesis_data_with_storage(vec![
+                    (b"key".to_vec(), vec![1]),
+                    (b"key".to_vec(), vec![2])
+                ])
+            ),
+            Error::<Test>::InvalidGenesisStorage
+        );
+    });
+}
+
 #[test]
 fn deregister_para_id_from_empty_list() {
     new_test_ext().execute_with(|| {
@@ -110,6 +256,10 @@ fn deregister_para_id_from_empty_list() {
 fn deregister_para_id_42() {
     new_test_ext().execute_with(|| {
         System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_ok!(ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -142,6 +292,10 @@ fn deregister_para_id_42() {
 fn deregister_para_id_42_after_session_changes() {
     new_test_ext().execute_with(|| {
         System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_ok!(ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -170,10 +324,47 @@ fn deregister_para_id_42_after_session_changes() {
     });
 }
 
+#[test]
+fn para_id_without_collators_is_flagged_for_deregistration() {
+    new_test_ext_with_genesis(vec![
+        (42.into(), empty_genesis_data(), vec![]),
+        (43.into(), empty_genesis_data(), vec![]),
+    ])
+    .execute_with(|| {
+        System::set_block_number(1);
+        ParaIdsWithCollators::set(vec![43.into()]);
+
+        for session in 1..3 {
+            ParaRegistrar::initializer_on_new_session(&session);
+        }
+        assert_eq!(ParaRegistrar::idle_sessions(ParaId::from(42)), 2);
+        assert!(!ParaRegistrar::is_flagged_for_deregistration(42.into()));
+
+        ParaRegistrar::initializer_on_new_session(&3);
+        assert!(ParaRegistrar::is_flagged_for_deregistration(42.into()));
+        System::assert_last_event(
+            Event::ParaIdFlaggedForDeregistration { para_id: 42.into() }.into(),
+        );
+
+        // Chains with collators are never flagged
+        assert_eq!(ParaRegistrar::idle_sessions(ParaId::from(43)), 0);
+        assert!(!ParaRegistrar::is_flagged_for_deregistration(43.into()));
+
+        // The count restarts once the chain gets collators
+        ParaIdsWithCollators::set(vec![42.into(), 43.into()]);
+        ParaRegistrar::initializer_on_new_session(&4);
+        assert!(!ParaRegistrar::is_flagged_for_deregistration(42.into()));
+    });
+}
+
 #[test]
 fn deregister_para_id_42_twice() {
     new_test_ext().execute_with(|| {
         System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_ok!(ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -211,6 +402,10 @@ fn deregister_para_id_removes_genesis_data() {
             extensions: Default::default(),
             properties: Default::default(),
         };
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_ok!(ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -349,6 +544,10 @@ fn genesis_error_genesis_data_size_too_big() {
 fn register_without_mark_valid_for_collating() {
     new_test_ext().execute_with(|| {
         System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_ok!(ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -369,6 +568,10 @@ fn register_without_mark_valid_for_collating() {
 fn mark_valid_for_collating_twice() {
     new_test_ext().execute_with(|| {
         System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_ok!(ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -400,6 +603,10 @@ fn mark_valid_for_collating_invalid_para_id() {
 fn mark_valid_for_collating_already_valid_para_id() {
     new_test_ext().execute_with(|| {
         System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_ok!(ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -428,6 +635,10 @@ fn deregister_returns_bond() {
         System::set_block_number(1);
         let bond = DepositAmount::get();
         let balance_before = Balances::free_balance(ALICE);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_ok!(ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -449,6 +660,10 @@ fn deregister_returns_bond() {
 fn can_deregister_before_valid_for_collating() {
     new_test_ext().execute_with(|| {
         System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_ok!(ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -479,6 +694,10 @@ fn set_boot_nodes_bad_origin() {
 fn set_boot_nodes_by_para_id_registrar() {
     new_test_ext().execute_with(|| {
         System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -499,6 +718,10 @@ fn set_boot_nodes_by_para_id_registrar() {
 fn set_boot_nodes_by_invalid_user() {
     new_test_ext().execute_with(|| {
         System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -542,6 +765,10 @@ fn set_boot_nodes_bad_para_id() {
 fn boot_nodes_removed_on_deregister() {
     new_test_ext().execute_with(|| {
         System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
         assert_ok!(ParaRegistrar::register(
             RuntimeOrigin::signed(ALICE),
             42.into(),
@@ -567,6 +794,339 @@ fn boot_nodes_removed_on_deregister() {
     });
 }
 
+#[test]
+fn pause_para_id_42_works() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
+        assert_ok!(ParaRegistrar::register(
+            RuntimeOrigin::signed(ALICE),
+            42.into(),
+            empty_genesis_data()
+        ));
+        assert_ok!(ParaRegistrar::mark_valid_for_collating(
+            RuntimeOrigin::root(),
+            42.into(),
+        ));
+        ParaRegistrar::initializer_on_new_session(&2);
+        assert_eq!(ParaRegistrar::registered_para_ids(), vec![42.into()]);
+
+        assert_ok!(ParaRegistrar::pause_container_chain(
+            RuntimeOrigin::root(),
+            42.into()
+        ));
+        System::assert_last_event(Event::ParaIdPaused { para_id: 42.into() }.into());
+        assert_eq!(ParaRegistrar::paused(), vec![42.into()]);
+
+        // Assert after two sessions it is removed from the registered para ids
+        ParaRegistrar::initializer_on_new_session(&4);
+        assert_eq!(ParaRegistrar::registered_para_ids(), vec![]);
+        // Genesis data and boot nodes are kept
+        assert!(ParaRegistrar::para_genesis_data(ParaId::from(42)).is_some());
+
+        assert_noop!(
+            ParaRegistrar::pause_container_chain(RuntimeOrigin::root(), 42.into()),
+            Error::<Test>::ParaIdAlreadyPaused
+        );
+        assert_noop!(
+            ParaRegistrar::register(
+                RuntimeOrigin::signed(ALICE),
+                42.into(),
+                empty_genesis_data()
+            ),
+            Error::<Test>::ParaIdAlreadyRegistered
+        );
+    });
+}
+
+#[test]
+fn resume_para_id_42_works() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
+        assert_ok!(ParaRegistrar::register(
+            RuntimeOrigin::signed(ALICE),
+            42.into(),
+            empty_genesis_data()
+        ));
+        assert_ok!(ParaRegistrar::mark_valid_for_collating(
+            RuntimeOrigin::root(),
+            42.into(),
+        ));
+        ParaRegistrar::initializer_on_new_session(&2);
+        assert_ok!(ParaRegistrar::pause_container_chain(
+            RuntimeOrigin::root(),
+            42.into()
+        ));
+        ParaRegistrar::initializer_on_new_session(&4);
+        assert_eq!(ParaRegistrar::registered_para_ids(), vec![]);
+
+        assert_ok!(ParaRegistrar::resume_container_chain(
+            RuntimeOrigin::root(),
+            42.into()
+        ));
+        System::assert_last_event(Event::ParaIdUnpaused { para_id: 42.into() }.into());
+        assert_eq!(ParaRegistrar::paused(), vec![]);
+
+        // Assert after two sessions it is registered again
+        ParaRegistrar::initializer_on_new_session(&6);
+        assert_eq!(ParaRegistrar::registered_para_ids(), vec![42.into()]);
+
+        assert_noop!(
+            ParaRegistrar::resume_container_chain(RuntimeOrigin::root(), 42.into()),
+            Error::<Test>::ParaIdNotPaused
+        );
+    });
+}
+
+#[test]
+fn pause_para_id_not_registered() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_noop!(
+            ParaRegistrar::pause_container_chain(RuntimeOrigin::root(), 42.into()),
+            Error::<Test>::ParaIdNotRegistered
+        );
+    });
+}
+
+#[test]
+fn pause_para_id_bad_origin() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_noop!(
+            ParaRegistrar::pause_container_chain(RuntimeOrigin::signed(ALICE), 42.into()),
+            DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn deregister_paused_para_id() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
+        assert_ok!(ParaRegistrar::register(
+            RuntimeOrigin::signed(ALICE),
+            42.into(),
+            empty_genesis_data()
+        ));
+        assert_ok!(ParaRegistrar::mark_valid_for_collating(
+            RuntimeOrigin::root(),
+            42.into(),
+        ));
+        ParaRegistrar::initializer_on_new_session(&2);
+        assert_ok!(ParaRegistrar::pause_container_chain(
+            RuntimeOrigin::root(),
+            42.into()
+        ));
+        ParaRegistrar::initializer_on_new_session(&4);
+
+        assert_ok!(ParaRegistrar::deregister(RuntimeOrigin::root(), 42.into()));
+        assert_eq!(ParaRegistrar::paused(), vec![]);
+        assert_eq!(ParaRegistrar::para_genesis_data(ParaId::from(42)), None);
+    });
+}
+
+#[test]
+fn reserve_then_register_para_id() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            None
+        ));
+        // The first free para id is allocated
+        System::assert_last_event(
+            Event::ParaIdReserved {
+                para_id: 10.into(),
+                owner: ALICE,
+            }
+            .into(),
+        );
+        assert!(ParaRegistrar::reserved_para_ids(ParaId::from(10)).is_some());
+
+        assert_ok!(ParaRegistrar::register(
+            RuntimeOrigin::signed(ALICE),
+            10.into(),
+            empty_genesis_data()
+        ));
+        // The reservation is consumed on registration
+        assert_eq!(ParaRegistrar::reserved_para_ids(ParaId::from(10)), None);
+        assert_eq!(ParaRegistrar::pending_verification(), vec![10.into()]);
+
+        // The next reservation skips the para id in use
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(BOB),
+            None
+        ));
+        assert!(ParaRegistrar::reserved_para_ids(ParaId::from(11)).is_some());
+    });
+}
+
+#[test]
+fn reserve_para_id_already_reserved() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
+        assert_noop!(
+            ParaRegistrar::reserve_para_id(RuntimeOrigin::signed(BOB), Some(42.into())),
+            Error::<Test>::ParaIdAlreadyReserved
+        );
+    });
+}
+
+#[test]
+fn register_without_reservation() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_noop!(
+            ParaRegistrar::register(
+                RuntimeOrigin::signed(ALICE),
+                42.into(),
+                empty_genesis_data()
+            ),
+            Error::<Test>::ParaIdNotReserved
+        );
+    });
+}
+
+#[test]
+fn register_para_id_reserved_by_other_account() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(BOB),
+            Some(42.into())
+        ));
+        assert_noop!(
+            ParaRegistrar::register(
+                RuntimeOrigin::signed(ALICE),
+                42.into(),
+                empty_genesis_data()
+            ),
+            Error::<Test>::NotParaIdReservationOwner
+        );
+    });
+}
+
+#[test]
+fn reserve_para_id_expires() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
+
+        let expiry: u64 = <Test as crate::Config>::ReservationExpiry::get();
+        System::set_block_number(1 + expiry);
+
+        assert_noop!(
+            ParaRegistrar::register(
+                RuntimeOrigin::signed(ALICE),
+                42.into(),
+                empty_genesis_data()
+            ),
+            Error::<Test>::ParaIdReservationExpired
+        );
+
+        // An expired reservation can be taken by someone else, returning its deposit
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(BOB),
+            Some(42.into())
+        ));
+        assert_eq!(
+            ParaRegistrar::reserved_para_ids(ParaId::from(42))
+                .unwrap()
+                .owner,
+            BOB
+        );
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::reserved_balance(BOB), ReservationDeposit::get());
+    });
+}
+
+#[test]
+fn reserve_para_id_takes_deposit() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), ReservationDeposit::get());
+
+        // The reservation deposit is replaced by the registration deposit
+        assert_ok!(ParaRegistrar::register(
+            RuntimeOrigin::signed(ALICE),
+            42.into(),
+            empty_genesis_data()
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), DepositAmount::get());
+
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(43.into())
+        ));
+        assert_ok!(ParaRegistrar::release_para_id(
+            RuntimeOrigin::signed(ALICE),
+            43.into()
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), DepositAmount::get());
+    });
+}
+
+#[test]
+fn reserve_para_id_below_first_free() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_noop!(
+            ParaRegistrar::reserve_para_id(RuntimeOrigin::signed(ALICE), Some(9.into())),
+            Error::<Test>::ParaIdBelowFirstFree
+        );
+    });
+}
+
+#[test]
+fn release_para_id_reservation() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(ParaRegistrar::reserve_para_id(
+            RuntimeOrigin::signed(ALICE),
+            Some(42.into())
+        ));
+        assert_noop!(
+            ParaRegistrar::release_para_id(RuntimeOrigin::signed(BOB), 42.into()),
+            Error::<Test>::NotParaIdReservationOwner
+        );
+
+        assert_ok!(ParaRegistrar::release_para_id(
+            RuntimeOrigin::signed(ALICE),
+            42.into()
+        ));
+        System::assert_last_event(Event::ParaIdReservationReleased { para_id: 42.into() }.into());
+        assert_eq!(ParaRegistrar::reserved_para_ids(ParaId::from(42)), None);
+
+        assert_noop!(
+            ParaRegistrar::release_para_id(RuntimeOrigin::signed(ALICE), 42.into()),
+            Error::<Test>::ParaIdNotReserved
+        );
+    });
+}
+
 #[test]
 fn weights_assigned_to_extrinsics_are_correct() {
     new_test_ext().execute_with(|| {
@@ -616,5 +1176,39 @@ fn weights_assigned_to_extrinsics_are_correct() {
                 0
             )
         );
+
+        assert_eq!(
+            crate::Call::<Test>::pause_container_chain { para_id: 42.into() }
+                .get_dispatch_info()
+                .weight,
+            <() as crate::weights::WeightInfo>::pause_container_chain(
+                <Test as crate::Config>::MaxLengthParaIds::get()
+            )
+        );
+
+        assert_eq!(
+            crate::Call::<Test>::resume_container_chain { para_id: 42.into() }
+                .get_dispatch_info()
+                .weight,
+            <() as crate::weights::WeightInfo>::resume_container_chain(
+                <Test as crate::Config>::MaxLengthParaIds::get()
+            )
+        );
+
+        assert_eq!(
+            crate::Call::<Test>::reserve_para_id { para_id: None }
+                .get_dispatch_info()
+                .weight,
+            <() as crate::weights::WeightInfo>::reserve_para_id(
+                <Test as crate::Config>::MaxLengthParaIds::get()
+            )
+        );
+
+        assert_eq!(
+            crate::Call::<Test>::release_para_id { para_id: 42.into() }
+                .get_dispatch_info()
+                .weight,
+            <() as crate::weights::WeightInfo>::release_para_id()
+        );
     });
 }
@@ -56,6 +56,10 @@ pub trait WeightInfo {
 	fn deregister(x: u32, y: u32, ) -> Weight;
 	fn mark_valid_for_collating(x: u32, y: u32, ) -> Weight;
 	fn set_boot_nodes(x: u32, y: u32, ) -> Weight;
+	fn pause_container_chain(y: u32, ) -> Weight;
+	fn resume_container_chain(y: u32, ) -> Weight;
+	fn reserve_para_id(y: u32, ) -> Weight;
+	fn release_para_id() -> Weight;
 }
 
 /// Weights for pallet_registrar using the Substrate node and recommended hardware.
@@ -162,6 +166,77 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(Weight::from_parts(525_540, 0).saturating_mul(y.into()))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: Registrar PausedChains (r:1 w:1)
+	/// Proof Skipped: Registrar PausedChains (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar PendingParaIds (r:1 w:1)
+	/// Proof Skipped: Registrar PendingParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Session CurrentIndex (r:1 w:0)
+	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
+	/// The range of component `y` is `[1, 50]`.
+	fn pause_container_chain(y: u32, ) -> Weight {
+		// Pending a benchmark run: the para id lists are updated like in `deregister`, so its
+		// base and per para id costs are used.
+		Weight::from_parts(41_531_716, 17205)
+			.saturating_add(Weight::from_parts(444_808, 0).saturating_mul(y.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 105).saturating_mul(y.into()))
+	}
+	/// Storage: Registrar PausedChains (r:1 w:1)
+	/// Proof Skipped: Registrar PausedChains (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar PendingParaIds (r:1 w:1)
+	/// Proof Skipped: Registrar PendingParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Session CurrentIndex (r:1 w:0)
+	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
+	/// The range of component `y` is `[1, 50]`.
+	fn resume_container_chain(y: u32, ) -> Weight {
+		// Pending a benchmark run: the para id lists are updated like in `deregister`, so its
+		// base and per para id costs are used.
+		Weight::from_parts(41_531_716, 17205)
+			.saturating_add(Weight::from_parts(444_808, 0).saturating_mul(y.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 105).saturating_mul(y.into()))
+	}
+	/// Storage: Registrar NextFreeParaId (r:1 w:1)
+	/// Proof Skipped: Registrar NextFreeParaId (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar ReservedParaIds (r:1 w:1)
+	/// Proof Skipped: Registrar ReservedParaIds (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Registrar PendingParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar PendingParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar PendingVerification (r:1 w:0)
+	/// Proof Skipped: Registrar PendingVerification (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar PausedChains (r:1 w:0)
+	/// Proof Skipped: Registrar PausedChains (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `y` is `[1, 50]`.
+	fn reserve_para_id(y: u32, ) -> Weight {
+		// Pending a benchmark run: each para id tried reads the reservation and the para id
+		// lists.
+		Weight::from_parts(17_400_000, 3574)
+			.saturating_add(Weight::from_parts(444_808, 0).saturating_mul(y.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(y.into())))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(y.into()))
+	}
+	/// Storage: Registrar ReservedParaIds (r:1 w:1)
+	/// Proof Skipped: Registrar ReservedParaIds (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn release_para_id() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(10_532_000, 3648)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -267,4 +342,75 @@ impl WeightInfo for () {
 			.saturating_add(Weight::from_parts(525_540, 0).saturating_mul(y.into()))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: Registrar PausedChains (r:1 w:1)
+	/// Proof Skipped: Registrar PausedChains (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar PendingParaIds (r:1 w:1)
+	/// Proof Skipped: Registrar PendingParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Session CurrentIndex (r:1 w:0)
+	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
+	/// The range of component `y` is `[1, 50]`.
+	fn pause_container_chain(y: u32, ) -> Weight {
+		// Pending a benchmark run: the para id lists are updated like in `deregister`, so its
+		// base and per para id costs are used.
+		Weight::from_parts(41_531_716, 17205)
+			.saturating_add(Weight::from_parts(444_808, 0).saturating_mul(y.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 105).saturating_mul(y.into()))
+	}
+	/// Storage: Registrar PausedChains (r:1 w:1)
+	/// Proof Skipped: Registrar PausedChains (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar PendingParaIds (r:1 w:1)
+	/// Proof Skipped: Registrar PendingParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Session CurrentIndex (r:1 w:0)
+	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
+	/// The range of component `y` is `[1, 50]`.
+	fn resume_container_chain(y: u32, ) -> Weight {
+		// Pending a benchmark run: the para id lists are updated like in `deregister`, so its
+		// base and per para id costs are used.
+		Weight::from_parts(41_531_716, 17205)
+			.saturating_add(Weight::from_parts(444_808, 0).saturating_mul(y.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 105).saturating_mul(y.into()))
+	}
+	/// Storage: Registrar NextFreeParaId (r:1 w:1)
+	/// Proof Skipped: Registrar NextFreeParaId (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar ReservedParaIds (r:1 w:1)
+	/// Proof Skipped: Registrar ReservedParaIds (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Registrar PendingParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar PendingParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar PendingVerification (r:1 w:0)
+	/// Proof Skipped: Registrar PendingVerification (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Registrar PausedChains (r:1 w:0)
+	/// Proof Skipped: Registrar PausedChains (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `y` is `[1, 50]`.
+	fn reserve_para_id(y: u32, ) -> Weight {
+		// Pending a benchmark run: each para id tried reads the reservation and the para id
+		// lists.
+		Weight::from_parts(17_400_000, 3574)
+			.saturating_add(Weight::from_parts(444_808, 0).saturating_mul(y.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(y.into())))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(y.into()))
+	}
+	/// Storage: Registrar ReservedParaIds (r:1 w:1)
+	/// Proof Skipped: Registrar ReservedParaIds (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn release_para_id() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(10_532_000, 3648)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
 }
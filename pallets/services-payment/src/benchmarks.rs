@@ -0,0 +1,153 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+#![cfg(feature = "runtime-benchmarks")]
+
+//! Benchmarking
+use {
+    crate::{
+        AutoTopupThreshold, BalanceOf, BlockProductionCredits, Call, Config, Pallet,
+        ProvideBlockProductionCost, ReserveBalance,
+    },
+    cumulus_primitives_core::ParaId,
+    frame_benchmarking::{account, benchmarks},
+    frame_support::traits::{Currency, EnsureOriginWithArg, Get},
+    frame_system::{pallet_prelude::BlockNumberFor, RawOrigin},
+    sp_std::vec::Vec,
+    tp_traits::GetCurrentContainerChains,
+};
+
+/// Create a user with `amount` free balance.
+fn create_funded_user<T: Config>(
+    string: &'static str,
+    n: u32,
+    amount: BalanceOf<T>,
+) -> T::AccountId {
+    const SEED: u32 = 0;
+    let user = account(string, n, SEED);
+    T::Currency::make_free_balance_be(&user, amount);
+    T::Currency::issue(amount);
+    user
+}
+
+/// Amount needed to buy `MaxCreditsStored` credits for `para_id`.
+fn max_credits_cost<T: Config>(para_id: &ParaId) -> BalanceOf<T>
+where
+    BalanceOf<T>: From<BlockNumberFor<T>>,
+{
+    let (block_cost, _weight) = T::ProvideBlockProductionCost::block_cost(para_id);
+    block_cost.saturating_mul(T::MaxCreditsStored::get().into())
+}
+
+benchmarks! {
+    where_clause { where BalanceOf<T>: From<BlockNumberFor<T>> }
+
+    deposit_reserve {
+        let para_id = ParaId::from(1001);
+        let amount = max_credits_cost::<T>(&para_id);
+        let caller = create_funded_user::<T>(
+            "caller",
+            0,
+            amount.saturating_add(T::Currency::minimum_balance()),
+        );
+    }: _(RawOrigin::Signed(caller), para_id, amount)
+    verify {
+        assert_eq!(ReserveBalance::<T>::get(para_id), amount);
+    }
+
+    set_auto_topup_threshold {
+        let para_id = ParaId::from(1001);
+        let origin = T::ManagerOrigin::try_successful_origin(&para_id)
+            .expect("failed to create ManagerOrigin");
+    }: _<T::RuntimeOrigin>(origin, para_id, Some(1u32.into()))
+    verify {
+        assert_eq!(AutoTopupThreshold::<T>::get(para_id), Some(1u32.into()));
+    }
+
+    withdraw_reserve {
+        let para_id = ParaId::from(1001);
+        let amount = max_credits_cost::<T>(&para_id);
+        let caller = create_funded_user::<T>(
+            "caller",
+            0,
+            amount.saturating_add(T::Currency::minimum_balance()),
+        );
+        Pallet::<T>::deposit_reserve(RawOrigin::Signed(caller).into(), para_id, amount)?;
+        let origin = T::ManagerOrigin::try_successful_origin(&para_id)
+            .expect("failed to create ManagerOrigin");
+    }: _<T::RuntimeOrigin>(origin, para_id, amount)
+    verify {
+        assert_eq!(ReserveBalance::<T>::get(para_id), 0u32.into());
+    }
+
+    auto_topup_credits {
+        // Worst case: every registered para is below its threshold and gets topped up
+        let x in 1..50;
+
+        let para_ids: Vec<ParaId> = (0..x).map(|i| ParaId::from(1000 + i)).collect();
+        T::ContainerChains::set_current_container_chains(&para_ids);
+
+        for para_id in para_ids.iter() {
+            let amount = max_credits_cost::<T>(para_id);
+            let caller = create_funded_user::<T>(
+                "caller",
+                u32::from(*para_id),
+                amount.saturating_add(T::Currency::minimum_balance()),
+            );
+            Pallet::<T>::deposit_reserve(RawOrigin::Signed(caller).into(), *para_id, amount)?;
+            AutoTopupThreshold::<T>::insert(para_id, T::MaxCreditsStored::get());
+        }
+    }: {
+        Pallet::<T>::auto_topup_credits();
+    }
+    verify {
+        for para_id in para_ids.iter() {
+            assert_eq!(
+                BlockProductionCredits::<T>::get(para_id),
+                Some(T::MaxCreditsStored::get())
+            );
+        }
+    }
+
+    refund_credits {
+        // Worst case: the credits were paid from the reserve, which gets the refund back
+        let para_id = ParaId::from(1001);
+        T::ContainerChains::set_current_container_chains(&[para_id]);
+
+        let amount = max_credits_cost::<T>(&para_id);
+        let caller = create_funded_user::<T>(
+            "caller",
+            0,
+            amount.saturating_add(T::Currency::minimum_balance()),
+        );
+        Pallet::<T>::deposit_reserve(RawOrigin::Signed(caller.clone()).into(), para_id, amount)?;
+        AutoTopupThreshold::<T>::insert(para_id, T::MaxCreditsStored::get());
+        Pallet::<T>::auto_topup_credits();
+
+        // Deregister
+        T::ContainerChains::set_current_container_chains(&[]);
+    }: _(RawOrigin::Signed(caller), para_id, T::ReserveAccount::get())
+    verify {
+        assert_eq!(ReserveBalance::<T>::get(para_id), amount);
+        assert_eq!(BlockProductionCredits::<T>::get(para_id), None);
+    }
+
+    impl_benchmark_test_suite!(
+        Pallet,
+        crate::mock::ExtBuilder::default().build(),
+        crate::mock::Test
+    );
+}
@@ -39,10 +39,14 @@ use {
     cumulus_primitives_core::ParaId,
     frame_support::{
         pallet_prelude::*,
-        sp_runtime::{traits::Zero, Saturating},
-        traits::Currency,
+        sp_runtime::{
+            traits::{UniqueSaturatedInto, Zero},
+            Perbill, Saturating,
+        },
+        traits::{Currency, EnsureOriginWithArg, ExistenceRequirement},
     },
     frame_system::pallet_prelude::*,
+    tp_traits::{GetCurrentContainerChains, GetSessionIndex},
 };
 
 #[cfg(test)]
@@ -51,6 +55,12 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+mod benchmarks;
+
+pub mod weights;
+use crate::weights::WeightInfo;
+
 pub use pallet::*;
 
 #[frame_support::pallet(dev_mode)]
@@ -69,6 +79,23 @@ pub mod pallet {
         type ProvideBlockProductionCost: ProvideBlockProductionCost<Self>;
         /// The maximum number of credits that can be accumulated
         type MaxCreditsStored: Get<Self::BlockNumber>;
+        /// Account holding the funds deposited in the reserve of each para. Credits it paid for
+        /// are refunded to the reserve of the para
+        type ReserveAccount: Get<Self::AccountId>;
+        /// Origin of the manager of a para, allowed to configure its auto top-up and to withdraw
+        /// its reserve
+        type ManagerOrigin: EnsureOriginWithArg<
+            Self::RuntimeOrigin,
+            ParaId,
+            Success = Self::AccountId,
+        >;
+        /// Currently registered container chains, credits can only be refunded once a para is
+        /// not registered anymore. Only the credits of registered paras are topped up
+        type ContainerChains: GetCurrentContainerChains;
+        /// Session index provider, credits are topped up once per session
+        type CurrentSessionIndex: GetSessionIndex<u32>;
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::error]
@@ -76,6 +103,10 @@ pub mod pallet {
         InsufficientFundsToPurchaseCredits,
         InsufficientCredits,
         CreditPriceTooExpensive,
+        ParaIdStillRegistered,
+        InsufficientReserve,
+        NothingToRefund,
+        RefundsNotSupported,
     }
 
     #[pallet::pallet]
@@ -95,6 +126,35 @@ pub mod pallet {
             para_id: ParaId,
             credits_remaining: T::BlockNumber,
         },
+        ReserveDeposited {
+            para_id: ParaId,
+            payer: T::AccountId,
+            amount: BalanceOf<T>,
+            reserve_remaining: BalanceOf<T>,
+        },
+        ReserveWithdrawn {
+            para_id: ParaId,
+            payee: T::AccountId,
+            amount: BalanceOf<T>,
+            reserve_remaining: BalanceOf<T>,
+        },
+        AutoTopupThresholdSet {
+            para_id: ParaId,
+            threshold: Option<T::BlockNumber>,
+        },
+        CreditsRefunded {
+            para_id: ParaId,
+            payee: T::AccountId,
+            refund: BalanceOf<T>,
+            credits_refunded: T::BlockNumber,
+        },
+        CreditsToppedUp {
+            para_id: ParaId,
+            fee: BalanceOf<T>,
+            credits_purchased: T::BlockNumber,
+            credits_remaining: T::BlockNumber,
+            reserve_remaining: BalanceOf<T>,
+        },
     }
 
     #[pallet::storage]
@@ -102,6 +162,76 @@ pub mod pallet {
     pub type BlockProductionCredits<T: Config> =
         StorageMap<_, Blake2_128Concat, ParaId, T::BlockNumber, OptionQuery>;
 
+    /// Funds deposited for each para, held in `T::ReserveAccount`, used to top up its credits
+    #[pallet::storage]
+    #[pallet::getter(fn reserve_balance)]
+    pub type ReserveBalance<T: Config> =
+        StorageMap<_, Blake2_128Concat, ParaId, BalanceOf<T>, ValueQuery>;
+
+    /// If the credits of a para fall below this threshold, they are topped up from its reserve
+    #[pallet::storage]
+    #[pallet::getter(fn auto_topup_threshold)]
+    pub type AutoTopupThreshold<T: Config> =
+        StorageMap<_, Blake2_128Concat, ParaId, T::BlockNumber, OptionQuery>;
+
+    /// Amount paid for the unused credits of a para, at their average price
+    #[derive(
+        RuntimeDebug, PartialEq, Eq, Encode, Decode, Clone, Default, TypeInfo, MaxEncodedLen,
+    )]
+    pub struct CreditsPayment<Balance> {
+        /// Amount paid for the credits that have not been burned yet
+        pub unused: Balance,
+        /// Sum of the contributions of the payers of the current round
+        pub contributions: Balance,
+        /// Incremented when the credits run out, invalidating the contributions of the payers
+        pub round: u32,
+    }
+
+    /// Amount paid for the unused credits of each para. It is refunded to the payers, in
+    /// proportion to their contributions, once the para is not registered anymore
+    #[pallet::storage]
+    #[pallet::getter(fn credits_payment)]
+    pub type CreditsPayments<T: Config> =
+        StorageMap<_, Blake2_128Concat, ParaId, CreditsPayment<BalanceOf<T>>, ValueQuery>;
+
+    /// Amount each account paid for the credits of a para, and the round of the payment
+    #[pallet::storage]
+    #[pallet::getter(fn credit_contribution)]
+    pub type CreditContributions<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ParaId,
+        Blake2_128Concat,
+        T::AccountId,
+        (u32, BalanceOf<T>),
+        OptionQuery,
+    >;
+
+    /// Last session at which credits were topped up
+    #[pallet::storage]
+    #[pallet::getter(fn last_auto_topup_session)]
+    pub type LastAutoTopupSession<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T>
+    where
+        BalanceOf<T>: From<BlockNumberFor<T>>,
+    {
+        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+            let session_index = T::CurrentSessionIndex::session_index();
+            let weight = T::DbWeight::get().reads(1);
+
+            if LastAutoTopupSession::<T>::get() == Some(session_index) {
+                return weight;
+            }
+            LastAutoTopupSession::<T>::put(session_index);
+
+            weight
+                .saturating_add(T::DbWeight::get().writes(1))
+                .saturating_add(Self::auto_topup_credits())
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T>
     where
@@ -143,6 +273,7 @@ pub mod pallet {
             )?;
 
             BlockProductionCredits::<T>::insert(para_id, updated_credits);
+            Self::record_payment(&para_id, &account, total_fee);
 
             Self::deposit_event(Event::<T>::CreditsPurchased {
                 para_id,
@@ -154,6 +285,235 @@ pub mod pallet {
 
             Ok(().into())
         }
+
+        /// Deposit funds in the reserve of a para, to be used for automatic credit top-ups.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::deposit_reserve())]
+        pub fn deposit_reserve(
+            origin: OriginFor<T>,
+            para_id: ParaId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let account = ensure_signed(origin)?;
+
+            T::Currency::transfer(
+                &account,
+                &T::ReserveAccount::get(),
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )?;
+
+            let reserve_remaining = ReserveBalance::<T>::mutate(para_id, |reserve| {
+                *reserve = reserve.saturating_add(amount);
+                *reserve
+            });
+
+            Self::deposit_event(Event::<T>::ReserveDeposited {
+                para_id,
+                payer: account,
+                amount,
+                reserve_remaining,
+            });
+
+            Ok(().into())
+        }
+
+        /// Set the credits threshold below which credits are topped up from the reserve of the
+        /// para at the end of each session. `None` disables the automatic top-up.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::set_auto_topup_threshold())]
+        pub fn set_auto_topup_threshold(
+            origin: OriginFor<T>,
+            para_id: ParaId,
+            threshold: Option<T::BlockNumber>,
+        ) -> DispatchResultWithPostInfo {
+            T::ManagerOrigin::ensure_origin(origin, &para_id)?;
+
+            AutoTopupThreshold::<T>::set(para_id, threshold);
+
+            Self::deposit_event(Event::<T>::AutoTopupThresholdSet { para_id, threshold });
+
+            Ok(().into())
+        }
+
+        /// Refund the share of `payer` in the unused credits of a para that is not registered,
+        /// at the price paid for them. Credits paid from the reserve are refunded to the reserve
+        /// of the para. Can be called by any signed origin.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::refund_credits())]
+        pub fn refund_credits(
+            origin: OriginFor<T>,
+            para_id: ParaId,
+            payer: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+
+            ensure!(
+                !T::ContainerChains::current_container_chains().contains(&para_id),
+                Error::<T>::ParaIdStillRegistered,
+            );
+
+            let payment = CreditsPayments::<T>::get(para_id);
+            let contribution = match CreditContributions::<T>::take(para_id, &payer) {
+                Some((round, contribution)) if round == payment.round => contribution,
+                _ => Zero::zero(),
+            };
+            ensure!(!contribution.is_zero(), Error::<T>::NothingToRefund);
+
+            let credits =
+                BlockProductionCredits::<T>::get(para_id).unwrap_or(T::BlockNumber::zero());
+            let (refund, credits_refunded) = if contribution >= payment.contributions {
+                (payment.unused, credits)
+            } else {
+                let share = Perbill::from_rational(contribution, payment.contributions);
+                (share.mul_floor(payment.unused), share.mul_floor(credits))
+            };
+
+            T::OnChargeForBlockCredit::refund_credits(&payer, &para_id, credits_refunded, refund)?;
+            if payer == T::ReserveAccount::get() {
+                ReserveBalance::<T>::mutate(para_id, |reserve| {
+                    *reserve = reserve.saturating_add(refund)
+                });
+            }
+
+            let credits_remaining = credits.saturating_sub(credits_refunded);
+            if contribution >= payment.contributions {
+                BlockProductionCredits::<T>::remove(para_id);
+                CreditsPayments::<T>::insert(
+                    para_id,
+                    CreditsPayment {
+                        round: payment.round.wrapping_add(1),
+                        ..Default::default()
+                    },
+                );
+            } else {
+                BlockProductionCredits::<T>::insert(para_id, credits_remaining);
+                CreditsPayments::<T>::insert(
+                    para_id,
+                    CreditsPayment {
+                        unused: payment.unused.saturating_sub(refund),
+                        contributions: payment.contributions.saturating_sub(contribution),
+                        round: payment.round,
+                    },
+                );
+            }
+
+            Self::deposit_event(Event::<T>::CreditsRefunded {
+                para_id,
+                payee: payer,
+                refund,
+                credits_refunded,
+            });
+
+            Ok(().into())
+        }
+
+        /// Withdraw funds from the reserve of a para to its manager.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::withdraw_reserve())]
+        pub fn withdraw_reserve(
+            origin: OriginFor<T>,
+            para_id: ParaId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let account = T::ManagerOrigin::ensure_origin(origin, &para_id)?;
+
+            let reserve = ReserveBalance::<T>::get(para_id);
+            ensure!(reserve >= amount, Error::<T>::InsufficientReserve);
+
+            T::Currency::transfer(
+                &T::ReserveAccount::get(),
+                &account,
+                amount,
+                ExistenceRequirement::AllowDeath,
+            )?;
+
+            let reserve_remaining = reserve.saturating_sub(amount);
+            ReserveBalance::<T>::insert(para_id, reserve_remaining);
+
+            Self::deposit_event(Event::<T>::ReserveWithdrawn {
+                para_id,
+                payee: account,
+                amount,
+                reserve_remaining,
+            });
+
+            Ok(().into())
+        }
+    }
+
+    impl<T: Config> Pallet<T>
+    where
+        BalanceOf<T>: From<BlockNumberFor<T>>,
+    {
+        /// Top up the credits of every registered para whose credits are below its auto top-up
+        /// threshold, using the funds in its reserve. Called on the first block of every
+        /// session, returns the weight consumed.
+        pub fn auto_topup_credits() -> Weight {
+            let container_chains = T::ContainerChains::current_container_chains();
+            let mut weight = T::WeightInfo::auto_topup_credits(container_chains.len() as u32);
+
+            for para_id in container_chains {
+                let threshold = match AutoTopupThreshold::<T>::get(para_id) {
+                    Some(threshold) => threshold,
+                    None => continue,
+                };
+                let existing_credits =
+                    BlockProductionCredits::<T>::get(para_id).unwrap_or(T::BlockNumber::zero());
+                if existing_credits >= threshold {
+                    continue;
+                }
+
+                let reserve = ReserveBalance::<T>::get(para_id);
+                let (block_cost, block_cost_weight) =
+                    T::ProvideBlockProductionCost::block_cost(&para_id);
+                weight.saturating_accrue(block_cost_weight);
+
+                let credits_purchasable =
+                    T::MaxCreditsStored::get().saturating_sub(existing_credits);
+                let credits_affordable: T::BlockNumber = if block_cost.is_zero() {
+                    credits_purchasable
+                } else {
+                    let affordable: u128 = (reserve / block_cost).unique_saturated_into();
+                    affordable.unique_saturated_into()
+                };
+                let credits_purchased = credits_purchasable.min(credits_affordable);
+                if credits_purchased.is_zero() {
+                    continue;
+                }
+
+                let fee = block_cost.saturating_mul(credits_purchased.into());
+                if let Err(e) = T::OnChargeForBlockCredit::charge_credits(
+                    &T::ReserveAccount::get(),
+                    &para_id,
+                    credits_purchased,
+                    fee,
+                ) {
+                    log::warn!(
+                        "Failed to top up credits of para {:?} from reserve: {:?}",
+                        para_id,
+                        e
+                    );
+                    continue;
+                }
+
+                let credits_remaining = existing_credits.saturating_add(credits_purchased);
+                let reserve_remaining = reserve.saturating_sub(fee);
+                BlockProductionCredits::<T>::insert(para_id, credits_remaining);
+                ReserveBalance::<T>::insert(para_id, reserve_remaining);
+                Self::record_payment(&para_id, &T::ReserveAccount::get(), fee);
+
+                Self::deposit_event(Event::<T>::CreditsToppedUp {
+                    para_id,
+                    fee,
+                    credits_purchased,
+                    credits_remaining,
+                    reserve_remaining,
+                });
+            }
+
+            weight
+        }
     }
 
     impl<T: Config> Pallet<T> {
@@ -170,6 +530,22 @@ pub mod pallet {
             let updated_credits = existing_credits.saturating_sub(1u32.into());
             BlockProductionCredits::<T>::insert(para_id, updated_credits);
 
+            CreditsPayments::<T>::mutate(para_id, |payment| {
+                if updated_credits.is_zero() {
+                    // Nothing left to refund, start a new round of payers
+                    *payment = CreditsPayment {
+                        round: payment.round.wrapping_add(1),
+                        ..Default::default()
+                    };
+                } else {
+                    let existing_credits: u128 = existing_credits.unique_saturated_into();
+                    let existing_credits: BalanceOf<T> = existing_credits.unique_saturated_into();
+                    payment.unused = payment
+                        .unused
+                        .saturating_sub(payment.unused / existing_credits);
+                }
+            });
+
             Self::deposit_event(Event::<T>::CreditBurned {
                 para_id: *para_id,
                 credits_remaining: updated_credits,
@@ -177,6 +553,22 @@ pub mod pallet {
 
             Ok(().into())
         }
+
+        /// Record that `payer` paid `fee` for credits of `para_id`.
+        fn record_payment(para_id: &ParaId, payer: &T::AccountId, fee: BalanceOf<T>) {
+            let round = CreditsPayments::<T>::mutate(para_id, |payment| {
+                payment.unused = payment.unused.saturating_add(fee);
+                payment.contributions = payment.contributions.saturating_add(fee);
+                payment.round
+            });
+            CreditContributions::<T>::mutate(para_id, payer, |contribution| {
+                let previous = match contribution {
+                    Some((contribution_round, amount)) if *contribution_round == round => *amount,
+                    _ => Zero::zero(),
+                };
+                *contribution = Some((round, previous.saturating_add(fee)));
+            });
+        }
     }
 
     #[pallet::genesis_config]
@@ -204,7 +596,7 @@ pub type BalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 /// Handler for fee charging. This will be invoked when fees need to be deducted from the fee
-/// account for a given paraId.
+/// account for a given paraId, or when unused credits are refunded.
 pub trait OnChargeForBlockCredit<T: Config> {
     fn charge_credits(
         payer: &T::AccountId,
@@ -212,6 +604,18 @@ pub trait OnChargeForBlockCredit<T: Config> {
         credits: T::BlockNumber,
         fee: BalanceOf<T>,
     ) -> Result<(), Error<T>>;
+
+    /// Return `refund`, previously charged by `charge_credits`, to `payee`. Implementations
+    /// charging credits in a non-native currency must refund them in that same currency. The
+    /// default implementation does not support refunds.
+    fn refund_credits(
+        _payee: &T::AccountId,
+        _para_id: &ParaId,
+        _credits: T::BlockNumber,
+        _refund: BalanceOf<T>,
+    ) -> Result<(), Error<T>> {
+        Err(Error::<T>::RefundsNotSupported)
+    }
 }
 
 /// Returns the cost for a given block credit at the current time. This can be a complex operation,
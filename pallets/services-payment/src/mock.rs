@@ -28,7 +28,7 @@
 //! Using those two requirements we can select who the author was based on the collators assigned
 //! to that containerChain, by simply assigning the slot position.
 
-use frame_support::traits::{Currency, WithdrawReasons};
+use frame_support::traits::Currency;
 
 use {
     crate::{self as payment_services_pallet, OnChargeForBlockCredit, ProvideBlockProductionCost},
@@ -36,13 +36,18 @@ use {
     frame_support::{
         pallet_prelude::*,
         parameter_types,
-        traits::{tokens::ExistenceRequirement, ConstU32, ConstU64, Everything},
+        traits::{
+            tokens::ExistenceRequirement, AsEnsureOriginWithArg, ConstU32, ConstU64, Everything,
+            Hooks,
+        },
     },
+    frame_system::EnsureSigned,
     sp_core::H256,
     sp_runtime::{
         testing::Header,
         traits::{BlakeTwo256, IdentityLookup},
     },
+    tp_traits::{GetCurrentContainerChains, GetSessionIndex},
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -111,6 +116,11 @@ impl pallet_balances::Config for Test {
 
 parameter_types! {
     pub const MaxCreditsStored: u64 = 5;
+    pub const ReserveAccount: AccountId = 999;
+    pub const FeesAccount: AccountId = 998;
+    pub static CurrentBlockCost: u128 = FIXED_BLOCK_PRODUCTION_COST;
+    pub static CurrentContainerChains: Vec<ParaId> = vec![];
+    pub static CurrentSessionIndex: u32 = 0;
 }
 
 impl payment_services_pallet::Config for Test {
@@ -119,6 +129,30 @@ impl payment_services_pallet::Config for Test {
     type Currency = Balances;
     type ProvideBlockProductionCost = BlockProductionCost<Test>;
     type MaxCreditsStored = MaxCreditsStored;
+    type ReserveAccount = ReserveAccount;
+    type ManagerOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+    type ContainerChains = MockContainerChains;
+    type CurrentSessionIndex = CurrentSessionIndexGetter;
+    type WeightInfo = ();
+}
+
+pub struct CurrentSessionIndexGetter;
+impl GetSessionIndex<u32> for CurrentSessionIndexGetter {
+    fn session_index() -> u32 {
+        CurrentSessionIndex::get()
+    }
+}
+
+pub struct MockContainerChains;
+impl GetCurrentContainerChains for MockContainerChains {
+    fn current_container_chains() -> Vec<ParaId> {
+        CurrentContainerChains::get()
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_current_container_chains(container_chains: &[ParaId]) {
+        CurrentContainerChains::set(container_chains.to_vec());
+    }
 }
 
 pub struct ChargeForBlockCredit<Test>(PhantomData<Test>);
@@ -129,20 +163,29 @@ impl OnChargeForBlockCredit<Test> for ChargeForBlockCredit<Test> {
         _credits: u64,
         fee: u128,
     ) -> Result<(), payment_services_pallet::Error<Test>> {
-        use frame_support::traits::tokens::imbalance::Imbalance;
-
-        let result = Balances::withdraw(
+        Balances::transfer(
             payer,
+            &FeesAccount::get(),
             fee,
-            WithdrawReasons::FEE,
             ExistenceRequirement::AllowDeath,
-        );
-        let imbalance = result
-            .map_err(|_| payment_services_pallet::Error::InsufficientFundsToPurchaseCredits)?;
+        )
+        .map_err(|_| payment_services_pallet::Error::InsufficientFundsToPurchaseCredits)
+    }
 
-        if imbalance.peek() != fee {
-            panic!("withdrawn balance incorrect");
-        }
+    fn refund_credits(
+        payee: &u64,
+        _para_id: &ParaId,
+        _credits: u64,
+        refund: u128,
+    ) -> Result<(), payment_services_pallet::Error<Test>> {
+        // Fees are kept in FeesAccount, so that they can be refunded
+        Balances::transfer(
+            &FeesAccount::get(),
+            payee,
+            refund,
+            ExistenceRequirement::AllowDeath,
+        )
+        .expect("refunds are lower than the fees kept in FeesAccount");
 
         Ok(())
     }
@@ -153,7 +196,7 @@ pub(crate) const FIXED_BLOCK_PRODUCTION_COST: u128 = 100;
 pub struct BlockProductionCost<Test>(PhantomData<Test>);
 impl ProvideBlockProductionCost<Test> for BlockProductionCost<Test> {
     fn block_cost(_para_id: &ParaId) -> (u128, Weight) {
-        (FIXED_BLOCK_PRODUCTION_COST, Weight::zero())
+        (CurrentBlockCost::get(), Weight::zero())
     }
 }
 
@@ -183,6 +226,13 @@ impl ExtBuilder {
     }
 }
 
+/// Move to the given session and run the services payment hook of its first block.
+pub(crate) fn start_session(session_index: u32) {
+    System::set_block_number(System::block_number() + 1);
+    CurrentSessionIndex::set(session_index);
+    PaymentServices::on_initialize(System::block_number());
+}
+
 pub(crate) fn events() -> Vec<payment_services_pallet::Event<Test>> {
     System::events()
         .into_iter()
@@ -30,10 +30,11 @@
 
 use {
     crate::{mock::*, pallet as payment_services_pallet, BlockProductionCredits},
-    frame_support::{assert_err, assert_ok},
+    frame_support::{assert_err, assert_ok, traits::Hooks},
 };
 
 const ALICE: u64 = 1;
+const BOB: u64 = 2;
 
 #[test]
 fn purchase_credits_works() {
@@ -308,3 +309,352 @@ fn buy_credits_limit_exceeds_price_works() {
             ),);
         });
 }
+
+#[test]
+fn auto_topup_funds_credits_from_reserve() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let para_id = 1.into();
+            CurrentContainerChains::set(vec![para_id]);
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                3u64,
+                None,
+            ));
+            assert_ok!(PaymentServices::deposit_reserve(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                1_000u128,
+            ));
+            assert_ok!(PaymentServices::set_auto_topup_threshold(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                Some(3u64),
+            ));
+            assert_eq!(Balances::free_balance(ReserveAccount::get()), 1_000);
+
+            // Credits are not below the threshold yet, nothing happens
+            start_session(1);
+            assert_eq!(<BlockProductionCredits<Test>>::get(para_id), Some(3));
+            assert_eq!(PaymentServices::reserve_balance(para_id), 1_000);
+
+            // Drain credits below the threshold
+            assert_ok!(PaymentServices::burn_credit_for_para(&para_id));
+            assert_ok!(PaymentServices::burn_credit_for_para(&para_id));
+            assert_eq!(<BlockProductionCredits<Test>>::get(para_id), Some(1));
+
+            // Credits are only topped up once per session
+            PaymentServices::on_initialize(System::block_number() + 1);
+            assert_eq!(<BlockProductionCredits<Test>>::get(para_id), Some(1));
+
+            start_session(2);
+
+            // The reserve pays for the credits up to MaxCreditsStored
+            assert_eq!(
+                <BlockProductionCredits<Test>>::get(para_id),
+                Some(MaxCreditsStored::get())
+            );
+            assert_eq!(PaymentServices::reserve_balance(para_id), 600);
+            assert_eq!(Balances::free_balance(ReserveAccount::get()), 600);
+            assert_eq!(
+                events().last().unwrap(),
+                &payment_services_pallet::Event::CreditsToppedUp {
+                    para_id,
+                    fee: 400,
+                    credits_purchased: 4,
+                    credits_remaining: MaxCreditsStored::get(),
+                    reserve_remaining: 600,
+                }
+            );
+        });
+}
+
+#[test]
+fn auto_topup_limited_by_reserve() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let para_id = 1.into();
+            CurrentContainerChains::set(vec![para_id]);
+            assert_ok!(PaymentServices::deposit_reserve(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                250u128,
+            ));
+            assert_ok!(PaymentServices::set_auto_topup_threshold(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                Some(1u64),
+            ));
+
+            start_session(1);
+
+            // Only 2 credits can be afforded with the reserve
+            assert_eq!(<BlockProductionCredits<Test>>::get(para_id), Some(2));
+            assert_eq!(PaymentServices::reserve_balance(para_id), 50);
+        });
+}
+
+#[test]
+fn auto_topup_skips_paras_not_registered() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let para_id = 1.into();
+            assert_ok!(PaymentServices::deposit_reserve(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                250u128,
+            ));
+            assert_ok!(PaymentServices::set_auto_topup_threshold(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                Some(1u64),
+            ));
+
+            start_session(1);
+
+            assert_eq!(<BlockProductionCredits<Test>>::get(para_id), None);
+            assert_eq!(PaymentServices::reserve_balance(para_id), 250);
+        });
+}
+
+#[test]
+fn withdraw_reserve_works() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let para_id = 1.into();
+            assert_ok!(PaymentServices::deposit_reserve(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                250u128,
+            ));
+            assert_eq!(Balances::free_balance(ALICE), 1_750);
+
+            assert_err!(
+                PaymentServices::withdraw_reserve(RuntimeOrigin::signed(ALICE), para_id, 251u128),
+                payment_services_pallet::Error::<Test>::InsufficientReserve,
+            );
+            assert_ok!(PaymentServices::withdraw_reserve(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                200u128,
+            ));
+
+            assert_eq!(Balances::free_balance(ALICE), 1_950);
+            assert_eq!(Balances::free_balance(ReserveAccount::get()), 50);
+            assert_eq!(PaymentServices::reserve_balance(para_id), 50);
+            assert_eq!(
+                events().last().unwrap(),
+                &payment_services_pallet::Event::ReserveWithdrawn {
+                    para_id,
+                    payee: ALICE,
+                    amount: 200,
+                    reserve_remaining: 50,
+                }
+            );
+        });
+}
+
+#[test]
+fn refund_credits_after_deregistration() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let para_id = 1.into();
+            CurrentContainerChains::set(vec![para_id]);
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                4u64,
+                None,
+            ));
+            assert_ok!(PaymentServices::burn_credit_for_para(&para_id));
+            assert_eq!(Balances::free_balance(ALICE), 600);
+
+            // Credits cannot be refunded while the para is registered
+            assert_err!(
+                PaymentServices::refund_credits(RuntimeOrigin::signed(ALICE), para_id, ALICE),
+                payment_services_pallet::Error::<Test>::ParaIdStillRegistered,
+            );
+
+            // Deregister
+            CurrentContainerChains::set(vec![]);
+
+            // The refund is based on the price paid, not on the current one
+            CurrentBlockCost::set(FIXED_BLOCK_PRODUCTION_COST * 2);
+
+            assert_ok!(PaymentServices::refund_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                ALICE,
+            ));
+
+            // The 3 unused credits are refunded
+            assert_eq!(Balances::free_balance(ALICE), 900);
+            assert_eq!(<BlockProductionCredits<Test>>::get(para_id), None);
+            assert_eq!(
+                events().last().unwrap(),
+                &payment_services_pallet::Event::CreditsRefunded {
+                    para_id,
+                    payee: ALICE,
+                    refund: 300,
+                    credits_refunded: 3,
+                }
+            );
+        });
+}
+
+#[test]
+fn refund_credits_returns_the_share_of_each_payer() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000), (BOB, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let para_id = 1.into();
+            CurrentContainerChains::set(vec![para_id]);
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                1u64,
+                None,
+            ));
+            CurrentBlockCost::set(FIXED_BLOCK_PRODUCTION_COST * 3);
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(BOB),
+                para_id,
+                1u64,
+                None,
+            ));
+            // 400 paid for 2 credits, one credit is burned at the average price
+            assert_ok!(PaymentServices::burn_credit_for_para(&para_id));
+            assert_eq!(PaymentServices::credits_payment(para_id).unused, 200);
+
+            CurrentContainerChains::set(vec![]);
+
+            // Only the payers can get a refund, not whoever manages the para
+            assert_err!(
+                PaymentServices::refund_credits(RuntimeOrigin::signed(ALICE), para_id, 3),
+                payment_services_pallet::Error::<Test>::NothingToRefund,
+            );
+
+            // Any account can trigger the refund of a payer
+            assert_ok!(PaymentServices::refund_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                BOB,
+            ));
+            assert_eq!(Balances::free_balance(BOB), 850);
+            assert_eq!(<BlockProductionCredits<Test>>::get(para_id), Some(1));
+
+            assert_ok!(PaymentServices::refund_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                ALICE,
+            ));
+            assert_eq!(Balances::free_balance(ALICE), 950);
+            assert_eq!(<BlockProductionCredits<Test>>::get(para_id), None);
+            assert_eq!(Balances::free_balance(FeesAccount::get()), 200);
+
+            // Each contribution is only refunded once
+            assert_err!(
+                PaymentServices::refund_credits(RuntimeOrigin::signed(ALICE), para_id, ALICE),
+                payment_services_pallet::Error::<Test>::NothingToRefund,
+            );
+        });
+}
+
+#[test]
+fn refund_credits_paid_from_reserve_goes_to_reserve() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let para_id = 1.into();
+            CurrentContainerChains::set(vec![para_id]);
+            assert_ok!(PaymentServices::deposit_reserve(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                1_000u128,
+            ));
+            assert_ok!(PaymentServices::set_auto_topup_threshold(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                Some(1u64),
+            ));
+            start_session(1);
+            assert_eq!(PaymentServices::reserve_balance(para_id), 500);
+
+            assert_ok!(PaymentServices::burn_credit_for_para(&para_id));
+            CurrentContainerChains::set(vec![]);
+
+            assert_ok!(PaymentServices::refund_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                ReserveAccount::get(),
+            ));
+
+            // The 4 unused credits are refunded to the reserve of the para
+            assert_eq!(PaymentServices::reserve_balance(para_id), 900);
+            assert_eq!(Balances::free_balance(ReserveAccount::get()), 900);
+        });
+}
+
+#[test]
+fn refund_credits_after_running_out_of_credits() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000), (BOB, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let para_id = 1.into();
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                1u64,
+                None,
+            ));
+            assert_ok!(PaymentServices::burn_credit_for_para(&para_id));
+
+            // The contribution of ALICE was used up, it doesn't share the credits of BOB
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(BOB),
+                para_id,
+                2u64,
+                None,
+            ));
+            assert_err!(
+                PaymentServices::refund_credits(RuntimeOrigin::signed(ALICE), para_id, ALICE),
+                payment_services_pallet::Error::<Test>::NothingToRefund,
+            );
+            assert_ok!(PaymentServices::refund_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                BOB,
+            ));
+            assert_eq!(Balances::free_balance(BOB), 1_000);
+        });
+}
@@ -0,0 +1,178 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+
+//! Weights for pallet_services_payment
+//!
+//! Pending a benchmark run: these weights are derived from the storage accesses of each call
+//! and must be regenerated with the benchmark CLI, using the same command as the other pallets.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_services_payment.
+pub trait WeightInfo {
+	fn deposit_reserve() -> Weight;
+	fn set_auto_topup_threshold() -> Weight;
+	fn withdraw_reserve() -> Weight;
+	fn auto_topup_credits(x: u32, ) -> Weight;
+	fn refund_credits() -> Weight;
+}
+
+/// Weights for pallet_services_payment using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ServicesPayment ReserveBalance (r:1 w:1)
+	/// Proof Skipped: ServicesPayment ReserveBalance (max_values: None, max_size: None, mode: Measured)
+	fn deposit_reserve() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(31_000_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: ServicesPayment AutoTopupThreshold (r:0 w:1)
+	/// Proof Skipped: ServicesPayment AutoTopupThreshold (max_values: None, max_size: None, mode: Measured)
+	fn set_auto_topup_threshold() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: ServicesPayment ReserveBalance (r:1 w:1)
+	/// Proof Skipped: ServicesPayment ReserveBalance (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn withdraw_reserve() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(32_000_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ServicesPayment AutoTopupThreshold (r:50 w:0)
+	/// Proof Skipped: ServicesPayment AutoTopupThreshold (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ServicesPayment BlockProductionCredits (r:50 w:50)
+	/// Proof Skipped: ServicesPayment BlockProductionCredits (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ServicesPayment ReserveBalance (r:50 w:50)
+	/// Proof Skipped: ServicesPayment ReserveBalance (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `x` is `[1, 50]`.
+	fn auto_topup_credits(x: u32, ) -> Weight {
+		// Pending a benchmark run: every para is topped up from the reserve account.
+		Weight::from_parts(5_000_000, 3593)
+			.saturating_add(Weight::from_parts(28_000_000, 0).saturating_mul(x.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(x.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(x.into())))
+			.saturating_add(Weight::from_parts(0, 2500).saturating_mul(x.into()))
+	}
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ServicesPayment CreditsPayments (r:1 w:1)
+	/// Proof Skipped: ServicesPayment CreditsPayments (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ServicesPayment CreditContributions (r:1 w:1)
+	/// Proof Skipped: ServicesPayment CreditContributions (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ServicesPayment BlockProductionCredits (r:1 w:1)
+	/// Proof Skipped: ServicesPayment BlockProductionCredits (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ServicesPayment ReserveBalance (r:1 w:1)
+	/// Proof Skipped: ServicesPayment ReserveBalance (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn refund_credits() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(42_000_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ServicesPayment ReserveBalance (r:1 w:1)
+	/// Proof Skipped: ServicesPayment ReserveBalance (max_values: None, max_size: None, mode: Measured)
+	fn deposit_reserve() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(31_000_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: ServicesPayment AutoTopupThreshold (r:0 w:1)
+	/// Proof Skipped: ServicesPayment AutoTopupThreshold (max_values: None, max_size: None, mode: Measured)
+	fn set_auto_topup_threshold() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: ServicesPayment ReserveBalance (r:1 w:1)
+	/// Proof Skipped: ServicesPayment ReserveBalance (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn withdraw_reserve() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(32_000_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ServicesPayment AutoTopupThreshold (r:50 w:0)
+	/// Proof Skipped: ServicesPayment AutoTopupThreshold (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ServicesPayment BlockProductionCredits (r:50 w:50)
+	/// Proof Skipped: ServicesPayment BlockProductionCredits (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ServicesPayment ReserveBalance (r:50 w:50)
+	/// Proof Skipped: ServicesPayment ReserveBalance (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `x` is `[1, 50]`.
+	fn auto_topup_credits(x: u32, ) -> Weight {
+		// Pending a benchmark run: every para is topped up from the reserve account.
+		Weight::from_parts(5_000_000, 3593)
+			.saturating_add(Weight::from_parts(28_000_000, 0).saturating_mul(x.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(x.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(x.into())))
+			.saturating_add(Weight::from_parts(0, 2500).saturating_mul(x.into()))
+	}
+	/// Storage: Registrar RegisteredParaIds (r:1 w:0)
+	/// Proof Skipped: Registrar RegisteredParaIds (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: ServicesPayment CreditsPayments (r:1 w:1)
+	/// Proof Skipped: ServicesPayment CreditsPayments (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ServicesPayment CreditContributions (r:1 w:1)
+	/// Proof Skipped: ServicesPayment CreditContributions (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ServicesPayment BlockProductionCredits (r:1 w:1)
+	/// Proof Skipped: ServicesPayment BlockProductionCredits (max_values: None, max_size: None, mode: Measured)
+	/// Storage: ServicesPayment ReserveBalance (r:1 w:1)
+	/// Proof Skipped: ServicesPayment ReserveBalance (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn refund_credits() -> Weight {
+		// Pending a benchmark run.
+		Weight::from_parts(42_000_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+}
@@ -0,0 +1,189 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+//! # Xcm Records Pallet
+//!
+//! This pallet declares the storage used by the XCM configuration of the runtime, such as the
+//! number of messages executed without payment in the current block, the most recent failed
+//! executions or the assets trapped by inbound messages. It is only written by the barriers,
+//! executors and asset traps of the runtime.
+//!
+//! Root can set the account receiving the trapped assets that are not claimed in time, which is
+//! done with `set_trapped_assets_destination`. While it is not set, trapped assets are kept
+//! until claimed.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+use {
+    frame_support::{pallet_prelude::*, weights::Weight},
+    sp_runtime::RuntimeDebug,
+    sp_std::vec::Vec,
+    xcm::latest::{Error as XcmError, MultiAssets, MultiLocation, XcmHash},
+};
+
+/// Failed execution of an inbound XCM message.
+///
+/// The index of the instruction that failed is not recorded: the XCM v3 executor only reports
+/// an `Outcome`, and keeps the index in its private state. The weight used up to the failure and
+/// the hash of the message allow finding it instead.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct XcmErrorRecord<BlockNumber> {
+    /// Block in which the message was executed.
+    pub block_number: BlockNumber,
+    /// Origin of the message.
+    pub origin: MultiLocation,
+    /// Hash of the message.
+    pub message_hash: XcmHash,
+    /// Error that stopped the execution.
+    pub error: XcmError,
+    /// Weight used by the instructions executed before the error.
+    pub weight_used: Weight,
+}
+
+/// Assets trapped by an inbound XCM message, kept until claimed or swept.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct TrappedAssetsRecord<BlockNumber> {
+    /// Block in which the assets were trapped.
+    pub trapped_at: BlockNumber,
+    /// Origin allowed to claim the assets.
+    pub origin: MultiLocation,
+    /// Trapped assets.
+    pub assets: MultiAssets,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use {super::*, frame_system::pallet_prelude::*};
+
+    #[pallet::pallet]
+    #[pallet::without_storage_info]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Maximum number of failed executions kept in `RecentXcmErrors`.
+        #[pallet::constant]
+        type MaxErrorRecords: Get<u32>;
+
+        /// Maximum number of asset traps tracked in `TrappedAssetsRecords`.
+        #[pallet::constant]
+        type MaxTrappedAssetsRecords: Get<u32>;
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// The account receiving the expired trapped assets was updated. `None` keeps them
+        /// trapped until claimed.
+        TrappedAssetsDestinationSet { destination: Option<T::AccountId> },
+    }
+
+    /// Block number and number of messages executed without payment in that block.
+    #[pallet::storage]
+    #[pallet::getter(fn unpaid_execution_usage)]
+    pub type UnpaidExecutionUsage<T: Config> =
+        StorageValue<_, (BlockNumberFor<T>, u32), ValueQuery>;
+
+    /// Most recent failed executions of inbound XCM messages, oldest first.
+    #[pallet::storage]
+    #[pallet::getter(fn recent_xcm_errors)]
+    pub type RecentXcmErrors<T: Config> = StorageValue<
+        _,
+        BoundedVec<XcmErrorRecord<BlockNumberFor<T>>, T::MaxErrorRecords>,
+        ValueQuery,
+    >;
+
+    /// Asset traps waiting to be swept, oldest first.
+    #[pallet::storage]
+    #[pallet::getter(fn trapped_assets_records)]
+    pub type TrappedAssetsRecords<T: Config> = StorageValue<
+        _,
+        BoundedVec<TrappedAssetsRecord<BlockNumberFor<T>>, T::MaxTrappedAssetsRecords>,
+        ValueQuery,
+    >;
+
+    /// Account receiving the trapped assets that are not claimed in time. If `None` they are
+    /// kept trapped until claimed.
+    #[pallet::storage]
+    #[pallet::getter(fn trapped_assets_destination)]
+    pub type TrappedAssetsDestination<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Set the account receiving the trapped assets that are not claimed in time, or stop
+        /// sweeping them with `None`.
+        #[pallet::call_index(0)]
+        #[pallet::weight((T::DbWeight::get().writes(1), DispatchClass::Operational))]
+        pub fn set_trapped_assets_destination(
+            origin: OriginFor<T>,
+            destination: Option<T::AccountId>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            TrappedAssetsDestination::<T>::set(destination.clone());
+            Self::deposit_event(Event::TrappedAssetsDestinationSet { destination });
+
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Records a failed execution in `RecentXcmErrors`, pruning the oldest record if there
+        /// are already `MaxErrorRecords` of them.
+        pub fn note_xcm_error(record: XcmErrorRecord<BlockNumberFor<T>>) {
+            RecentXcmErrors::<T>::mutate(|records| {
+                if records.is_full() && !records.is_empty() {
+                    records.remove(0);
+                }
+                // Only fails if `MaxErrorRecords` is zero
+                let _ = records.try_push(record);
+            });
+        }
+
+        /// Records trapped assets in `TrappedAssetsRecords`. When the records are full, the
+        /// oldest one is no longer tracked and its assets stay trapped until claimed.
+        pub fn note_trapped_assets(record: TrappedAssetsRecord<BlockNumberFor<T>>) {
+            TrappedAssetsRecords::<T>::mutate(|records| {
+                if records.is_full() && !records.is_empty() {
+                    records.remove(0);
+                }
+                // Only fails if `MaxTrappedAssetsRecords` is zero
+                let _ = records.try_push(record);
+            });
+        }
+
+        /// Removes from `TrappedAssetsRecords` and returns the records of the assets trapped at
+        /// or before `block_number`, oldest first.
+        pub fn take_trapped_assets_until(
+            block_number: BlockNumberFor<T>,
+        ) -> Vec<TrappedAssetsRecord<BlockNumberFor<T>>> {
+            TrappedAssetsRecords::<T>::mutate(|records| {
+                let num_expired = records
+                    .iter()
+                    .take_while(|record| record.trapped_at <= block_number)
+                    .count();
+                let mut expired = sp_std::mem::take(records).into_inner();
+                let remaining = expired.split_off(num_expired);
+                // Cannot fail, there are less records than before
+                *records = BoundedVec::try_from(remaining).unwrap_or_default();
+                expired
+            })
+        }
+    }
+}
@@ -36,6 +36,15 @@ pub struct AssignedCollators<AccountId> {
     pub container_chains: BTreeMap<ParaId, Vec<AccountId>>,
 }
 
+/// Parameters of the collator assignment, usually read from the host configuration.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, sp_core::RuntimeDebug, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct AssignmentConfig {
+    pub min_orchestrator_collators: u32,
+    pub max_orchestrator_collators: u32,
+    pub collators_per_container: u32,
+}
+
 // Manual default impl that does not require AccountId: Default
 impl<AccountId> Default for AssignedCollators<AccountId> {
     fn default() -> Self {
@@ -113,6 +122,23 @@ where
         }
     }
 
+    /// Move collators from the container chains to the orchestrator chain until it has
+    /// `num_orchestrator_chain` collators. Collators are taken from the container chain with
+    /// the most collators first.
+    pub fn fill_orchestrator_chain_from_container_chains(&mut self, num_orchestrator_chain: usize) {
+        while self.orchestrator_chain.len() < num_orchestrator_chain {
+            let largest_chain = self
+                .container_chains
+                .values_mut()
+                .filter(|cs| !cs.is_empty())
+                .max_by_key(|cs| cs.len());
+            match largest_chain.and_then(|cs| cs.pop()) {
+                Some(c) => self.orchestrator_chain.push(c),
+                None => return,
+            }
+        }
+    }
+
     pub fn fill_container_chain_collators<I>(
         &mut self,
         num_each_container_chain: usize,
@@ -19,8 +19,11 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-pub use cumulus_primitives_core::{relay_chain::Slot, ParaId};
-use sp_std::vec::Vec;
+pub use cumulus_primitives_core::{
+    relay_chain::{BlockNumber, Slot},
+    ParaId,
+};
+use {frame_support::weights::Weight, sp_std::vec::Vec};
 
 /// Get the current list of container chains parachain ids.
 pub trait GetCurrentContainerChains {
@@ -38,13 +41,69 @@ pub trait GetSessionContainerChains<SessionIndex> {
     fn set_session_container_chains(session_index: SessionIndex, container_chains: &[ParaId]);
 }
 
+/// Classifies container chains into parachains and parathreads. Parathreads only produce
+/// blocks on demand, and only need collators while they have an active order.
+pub trait GetParathreadChains {
+    fn is_parathread(para_id: ParaId) -> bool;
+    fn has_active_order(para_id: ParaId) -> bool;
+}
+
+/// No parathreads, all container chains are parachains.
+impl GetParathreadChains for () {
+    fn is_parathread(_para_id: ParaId) -> bool {
+        false
+    }
+
+    fn has_active_order(_para_id: ParaId) -> bool {
+        false
+    }
+}
+
+/// Returns the collators currently assigned to a container chain.
+pub trait GetContainerChainCollators<AccountId> {
+    fn container_chain_collators(para_id: ParaId) -> Vec<AccountId>;
+}
+
+/// Notified when the collators assigned to a container chain for the next session change.
+pub trait OnContainerChainCollatorsChanged<AccountId> {
+    /// `collators` is empty if the container chain is no longer assigned any collators.
+    fn on_collators_changed(para_id: ParaId, collators: &[AccountId]);
+}
+
+impl<AccountId> OnContainerChainCollatorsChanged<AccountId> for () {
+    fn on_collators_changed(_para_id: ParaId, _collators: &[AccountId]) {}
+}
+
 /// Returns author for a parachain id for the given slot.
 pub trait GetContainerChainAuthor<AccountId> {
     fn author_for_slot(slot: Slot, para_id: ParaId) -> Option<AccountId>;
+    /// Whether the author is one of the collators assigned to the parachain id, either currently
+    /// or during the previous session, whose blocks can still be noted after a session change.
+    fn is_author_assigned(para_id: ParaId, author: &AccountId) -> bool;
     #[cfg(feature = "runtime-benchmarks")]
     fn set_authors_for_para_id(para_id: ParaId, authors: Vec<AccountId>);
 }
 
+/// Notified when a new block of a container chain is noted, along with its author.
+pub trait AuthorNotingHook<AccountId> {
+    /// Returns the weight consumed by the hook.
+    fn on_container_author_noted(
+        author: &AccountId,
+        block_number: BlockNumber,
+        para_id: ParaId,
+    ) -> Weight;
+}
+
+impl<AccountId> AuthorNotingHook<AccountId> for () {
+    fn on_container_author_noted(
+        _author: &AccountId,
+        _block_number: BlockNumber,
+        _para_id: ParaId,
+    ) -> Weight {
+        Weight::zero()
+    }
+}
+
 /// Returns the host configuration composed of the amount of collators assigned
 /// to the orchestrator chain, and how many collators are assigned per container chain.
 pub trait GetHostConfiguration<SessionIndex> {
@@ -53,6 +112,19 @@ pub trait GetHostConfiguration<SessionIndex> {
     fn collators_per_container(session_index: SessionIndex) -> u32;
 }
 
+/// Source of the seed used to shuffle the collators assignment, such as a VRF output.
+pub trait GetRandomnessForSession<SessionIndex> {
+    /// Seed of the assignment that becomes active at `session_index`.
+    fn randomness_for_session(session_index: SessionIndex) -> [u8; 32];
+}
+
+/// No randomness, the zero seed keeps the collators order.
+impl<SessionIndex> GetRandomnessForSession<SessionIndex> for () {
+    fn randomness_for_session(_session_index: SessionIndex) -> [u8; 32] {
+        [0; 32]
+    }
+}
+
 /// Returns current session index.
 pub trait GetSessionIndex<SessionIndex> {
     fn session_index() -> SessionIndex;
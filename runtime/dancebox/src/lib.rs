@@ -45,8 +45,9 @@ use {
         pallet_prelude::DispatchResult,
         parameter_types,
         traits::{
-            ConstU128, ConstU32, ConstU64, ConstU8, Contains, InstanceFilter, OffchainWorker,
-            OnFinalize, OnIdle, OnInitialize, OnRuntimeUpgrade, ValidatorRegistration,
+            ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, Contains, GetDefault,
+            InstanceFilter, OffchainWorker, OnFinalize, OnIdle, OnInitialize, OnRuntimeUpgrade,
+            ValidatorRegistration,
         },
         weights::{
             constants::{
@@ -62,8 +63,12 @@ use {
         limits::{BlockLength, BlockWeights},
         EnsureRoot,
     },
-    nimbus_primitives::NimbusId,
-    pallet_pooled_staking::traits::{IsCandidateEligible, Timer},
+    nimbus_primitives::{NimbusId, SlotBeacon},
+    pallet_pooled_staking::{
+        traits::{IsCandidateEligible, IsCandidateRegistered, Timer},
+        weights::WeightInfo as _,
+        AuthorRole,
+    },
     pallet_registrar_runtime_api::ContainerChainGenesisData,
     pallet_session::{SessionManager, ShouldEndSession},
     pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier},
@@ -80,6 +85,8 @@ use {
     },
     sp_std::{marker::PhantomData, prelude::*},
     sp_version::RuntimeVersion,
+    tp_collator_assignment::{AssignedCollators, AssignmentConfig},
+    tp_traits::AuthorNotingHook,
 };
 pub use {
     sp_runtime::{MultiAddress, Perbill, Permill},
@@ -463,6 +470,29 @@ impl pallet_initializer::ApplyNewSession<Runtime> for OwnApplySession {
             &queued_id_to_nimbus_map,
             &assignments.next_assignment,
         );
+
+        // Next: PooledStaking
+        PooledStaking::initializer_on_new_session(&session_index);
+    }
+}
+
+/// Updates the authorities of the next session with an assignment deferred to the block after
+/// the session change, which still uses the same queued keys.
+pub struct DeferredAuthorityAssignment;
+impl pallet_collator_assignment::OnDeferredAssignment<Runtime> for DeferredAuthorityAssignment {
+    fn on_deferred_assignment(
+        session_index: &u32,
+        outcome: &pallet_collator_assignment::SessionChangeOutcome<Runtime>,
+    ) {
+        let queued_id_to_nimbus_map = Session::queued_keys()
+            .into_iter()
+            .map(|(account, keys)| (account, keys.nimbus))
+            .collect();
+        AuthorityAssignment::initializer_on_new_session(
+            session_index,
+            &queued_id_to_nimbus_map,
+            &outcome.next_assignment,
+        );
     }
 }
 
@@ -480,21 +510,16 @@ impl parachain_info::Config for Runtime {}
 /// Returns a list of collators by combining pallet_invulnerables and pallet_pooled_staking.
 pub struct CollatorsFromInvulnerablesAndThenFromStaking;
 
-/// Play the role of the session manager.
-impl SessionManager<AccountId> for CollatorsFromInvulnerablesAndThenFromStaking {
-    fn new_session(index: SessionIndex) -> Option<Vec<AccountId>> {
-        log::info!(
-            "assembling new collators for new session {} at #{:?}",
-            index,
-            <frame_system::Pallet<Runtime>>::block_number(),
-        );
-
+/// Collators of the next session: invulnerables first, then the eligible staking candidates,
+/// up to `max_collators`.
+impl Get<Vec<AccountId>> for CollatorsFromInvulnerablesAndThenFromStaking {
+    fn get() -> Vec<AccountId> {
         let invulnerables = Invulnerables::invulnerables().to_vec();
         let candidates_staking =
             pallet_pooled_staking::SortedEligibleCandidates::<Runtime>::get().to_vec();
         // Max number of collators is set in pallet_configuration
         let max_collators = Configuration::config().max_collators;
-        let collators = invulnerables
+        invulnerables
             .iter()
             .cloned()
             .chain(candidates_staking.into_iter().filter_map(|elig| {
@@ -507,7 +532,20 @@ impl SessionManager<AccountId> for CollatorsFromInvulnerablesAndThenFromStaking
                 }
             }))
             .take(max_collators as usize)
-            .collect();
+            .collect()
+    }
+}
+
+/// Play the role of the session manager.
+impl SessionManager<AccountId> for CollatorsFromInvulnerablesAndThenFromStaking {
+    fn new_session(index: SessionIndex) -> Option<Vec<AccountId>> {
+        log::info!(
+            "assembling new collators for new session {} at #{:?}",
+            index,
+            <frame_system::Pallet<Runtime>>::block_number(),
+        );
+
+        let collators = Self::get();
 
         // TODO: weight?
         /*
@@ -546,8 +584,42 @@ impl pallet_session::Config for Runtime {
 }
 
 impl pallet_collator_assignment::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
     type HostConfiguration = Configuration;
     type ContainerChains = Registrar;
+    type ParathreadChains = ();
+    // Collators are taken from the invulnerables first, pinning them to the orchestrator chain
+    // would leave container chains without collators
+    type Invulnerables = GetDefault;
+    // Only activate container chains that can be assigned `collators_per_container` collators
+    type MinCollatorsToActivate = ConstU32<{ u32::MAX }>;
+    // Collators are only moved on registration changes and config updates, no need to limit it
+    type MaxChurnPerSession = ConstU32<{ u32::MAX }>;
+    // Collators coming back within 2 sessions get their previous container chain back
+    type RecentCollatorChainTtl = ConstU32<2>;
+    // The orchestrator chain minimum does not depend on the number of container chains
+    type OrchestratorCollatorsPerContainerChain = ConstU32<0>;
+    // Keep the orchestrator collators that were assigned first
+    type OrchestratorSelection = GetDefault;
+    // Surplus collators stay unassigned, to backfill force unassigned collators
+    type SurplusCollators = GetDefault;
+    // The orchestrator chain is the only core chain
+    type CoreChains = GetDefault;
+    // No VRF output is available yet, the zero seed keeps the collators order
+    type Randomness = ();
+    // The zero seed cannot be manipulated, no need to commit it in advance
+    type RandomnessDelay = ConstU32<0>;
+    type NextSessionRotation = pallet_session::PeriodicSessions<Period, Offset>;
+    // Container chains cannot receive the notification yet
+    type CollatorsChangedHook = ();
+    type NotifyCollatorsChanged = ConstBool<false>;
+    type DeferredAssignmentHook = DeferredAuthorityAssignment;
+    type MaxCollatorPreferences = ConstU32<10>;
+    type MaxFrozenChains = ConstU32<10>;
+    type MaxAssignmentDiffEntries = ConstU32<100>;
+    type CurrentCollators = CollatorsFromInvulnerablesAndThenFromStaking;
+    type CurrentSessionIndex = CurrentSessionIndexGetter;
+    type ForceOrigin = EnsureRoot<AccountId>;
     type SessionIndex = u32;
     type WeightInfo = pallet_collator_assignment::weights::SubstrateWeight<Runtime>;
 }
@@ -563,6 +635,7 @@ impl pallet_author_noting::Config for Runtime {
     type SelfParaId = parachain_info::Pallet<Runtime>;
     type ContainerChainAuthor = CollatorAssignment;
     type RelayChainStateProvider = cumulus_pallet_parachain_system::RelaychainDataProvider<Self>;
+    type AuthorNotingHook = RewardAuthors;
     type WeightInfo = pallet_author_noting::weights::SubstrateWeight<Runtime>;
 }
 
@@ -588,6 +661,7 @@ impl pallet_invulnerables::Config for Runtime {
 parameter_types! {
     pub const MaxLengthParaIds: u32 = 100u32;
     pub const MaxEncodedGenesisDataSize: u32 = 5_000_000u32; // 5MB
+    pub const MaxGenesisCodeSize: u32 = 3_145_728u32; // 3MiB
     pub const MaxBootNodes: u32 = 10;
     pub const MaxBootNodeUrlLen: u32 = 200;
 }
@@ -612,12 +686,16 @@ impl pallet_configuration::Config for Runtime {
 parameter_types! {
     pub const DepositAmount: Balance = 100 * UNIT;
     pub const MaxLengthTokenSymbol: u32 = 255;
+    pub const FirstFreeParaId: ParaId = ParaId::new(2000);
+    pub const ParaIdReservationExpiry: BlockNumber = 7 * DAYS;
+    pub const ParaIdReservationDeposit: Balance = 10 * UNIT;
 }
 impl pallet_registrar::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type RegistrarOrigin = EnsureRoot<AccountId>;
     type MaxLengthParaIds = MaxLengthParaIds;
     type MaxGenesisDataSize = MaxEncodedGenesisDataSize;
+    type MaxCodeSize = MaxGenesisCodeSize;
     type MaxBootNodes = MaxBootNodes;
     type MaxBootNodeUrlLen = MaxBootNodeUrlLen;
     type MaxLengthTokenSymbol = MaxLengthTokenSymbol;
@@ -626,6 +704,12 @@ impl pallet_registrar::Config for Runtime {
     type CurrentSessionIndex = CurrentSessionIndexGetter;
     type Currency = Balances;
     type DepositAmount = DepositAmount;
+    type FirstFreeParaId = FirstFreeParaId;
+    type ReservationDeposit = ParaIdReservationDeposit;
+    type ReservationExpiry = ParaIdReservationExpiry;
+    type ContainerChainCollators = CollatorAssignment;
+    // One week of sessions
+    type MaxIdleSessions = ConstU32<{ 7 * 24 }>;
     type WeightInfo = pallet_registrar::weights::SubstrateWeight<Runtime>;
 }
 
@@ -825,7 +909,7 @@ pub struct MaintenanceHooks;
 
 impl OnInitialize<BlockNumber> for MaintenanceHooks {
     fn on_initialize(n: BlockNumber) -> Weight {
-        AllPalletsWithSystem::on_initialize(n)
+        AllPalletsWithSystem::on_initialize(n).saturating_add(RewardAuthors::on_initialize(n))
     }
 }
 
@@ -873,7 +957,11 @@ impl pallet_maintenance_mode::Config for Runtime {
     type MaintenanceDmpHandler = MaintenanceDmpHandler;
     // We use AllPalletsWithSystem because we dont want to change the hooks in normal
     // operation
-    type NormalExecutiveHooks = AllPalletsWithSystem;
+    type NormalExecutiveHooks = (
+        AllPalletsWithSystem,
+        xcm_config::SweepExpiredAssetTraps,
+        RewardAuthors,
+    );
     type MaintenanceExecutiveHooks = MaintenanceHooks;
 }
 
@@ -881,13 +969,26 @@ impl pallet_root_testing::Config for Runtime {}
 
 parameter_types! {
     pub StakingAccount: AccountId32 = PalletId(*b"POOLSTAK").into_account_truncating();
+    pub RewardPot: AccountId32 = PalletId(*b"POOLRWRD").into_account_truncating();
     pub const CurrencyHoldReason: HoldReason = HoldReason::PooledStake;
     pub const InitialManualClaimShareValue: u128 = currency::KILODANCE;
     pub const InitialAutoCompoundingShareValue: u128 = currency::KILODANCE;
+    // Joining and leaving stake is not rounded
+    pub const StakingSharePrecision: pallet_pooled_staking::SharePrecision<Balance> =
+        pallet_pooled_staking::SharePrecision { joining: 1, leaving: 1 };
     pub const MinimumSelfDelegation: u128 = 10 * currency::KILODANCE;
     pub const RewardsCollatorCommission: Perbill = Perbill::from_percent(20);
+    // Candidates cannot take more than half of the rewards of their delegators
+    pub const MaxCandidateCommission: Perbill = Perbill::from_percent(50);
     // Need to wait 2 sessions before being able to join or leave staking pools
-    pub const StakingSessionDelay: u32 = 2;
+    pub const StakingJoiningSessionDelay: u32 = 2;
+    pub const StakingLeavingSessionDelay: u32 = 2;
+    // Give delegators a session to move their stake before being removed from a leaving candidate
+    pub const CandidateLeaveDelay: BlockNumber = prod_or_fast!(1 * HOURS, 1 * MINUTES);
+    // Candidates without stake are kept for a week in case they delegate to themselves again
+    pub const ZeroStakeCandidateGracePeriod: BlockNumber = prod_or_fast!(7 * DAYS, 10 * MINUTES);
+    // Rewards of each block authored by a collator, before the `OrchestratorRewardMultiplier`
+    pub const RewardsPerBlock: Balance = currency::DANCE;
 }
 
 pub struct SessionTimer<G>(PhantomData<G>);
@@ -902,14 +1003,21 @@ where
         Session::current_index()
     }
 
-    fn is_elapsed(instant: &Self::Instant) -> bool {
-        let delay = G::get();
-        let Some(end) = instant.checked_add(delay) else {
+    fn default_delay() -> Self::Instant {
+        G::get()
+    }
+
+    fn is_elapsed_after(instant: &Self::Instant, delay: &Self::Instant) -> bool {
+        let Some(end) = instant.checked_add(*delay) else {
             return false;
         };
         end <= Self::now()
     }
 
+    fn elapsed_at(instant: &Self::Instant, delay: &Self::Instant) -> Option<Self::Instant> {
+        instant.checked_add(*delay)
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     fn elapsed_instant() -> Self::Instant {
         let delay = G::get();
@@ -928,6 +1036,16 @@ where
 }
 
 pub struct CandidateHasRegisteredKeys;
+impl IsCandidateRegistered<AccountId> for CandidateHasRegisteredKeys {
+    fn is_candidate_registered(a: &AccountId) -> bool {
+        <Session as ValidatorRegistration<AccountId>>::is_registered(a)
+    }
+    #[cfg(feature = "runtime-benchmarks")]
+    fn make_candidate_registered(a: &AccountId) {
+        Self::make_candidate_eligible(a, true)
+    }
+}
+
 impl IsCandidateEligible<AccountId> for CandidateHasRegisteredKeys {
     fn is_candidate_eligible(a: &AccountId) -> bool {
         <Session as ValidatorRegistration<AccountId>>::is_registered(a)
@@ -956,17 +1074,115 @@ impl pallet_pooled_staking::Config for Runtime {
     type Balance = Balance;
     type CurrencyHoldReason = CurrencyHoldReason;
     type StakingAccount = StakingAccount;
+    type RewardPot = RewardPot;
     type InitialManualClaimShareValue = InitialManualClaimShareValue;
     type InitialAutoCompoundingShareValue = InitialAutoCompoundingShareValue;
+    type SharePrecision = StakingSharePrecision;
     type MinimumSelfDelegation = MinimumSelfDelegation;
+    type SelfDelegationMultiplier = ConstU32<1>;
     type RewardsCollatorCommission = RewardsCollatorCommission;
-    type JoiningRequestTimer = SessionTimer<StakingSessionDelay>;
-    type LeavingRequestTimer = SessionTimer<StakingSessionDelay>;
+    type MaxCommission = MaxCandidateCommission;
+    type OrchestratorRewardMultiplier = ConstU32<100>;
+    type PoolRewardWeight = ();
+    type JoiningRequestTimer = SessionTimer<StakingJoiningSessionDelay>;
+    // Existing delegators wait the same delay as new ones
+    type ExistingDelegatorJoiningDelay = StakingJoiningSessionDelay;
+    type LeavingRequestTimer = SessionTimer<StakingLeavingSessionDelay>;
+    // No limit on the stake leaving a candidate yet
+    type MaxLeavingStakePerSession = ConstU128<{ u128::MAX }>;
+    type ShareValueHistoryDepth = ConstU32<28>;
     type EligibleCandidatesBufferSize = ConstU32<100>;
+    // Leaving dust is released to the delegators
+    type DustCollector = GetDefault;
+    // No penalty for early undelegations yet
+    type MinStakeDuration = ConstU32<0>;
+    type EarlyExitPenalty = GetDefault;
+    type EarlyExitPenaltyDestination = RewardPot;
+    // No limit on the number of delegators yet
+    type MaxDelegatorsPerCandidate = ConstU32<{ u32::MAX }>;
+    // No limit on the total stake yet
+    type MaxTotalStake = ConstU128<{ u128::MAX }>;
+    type MaxDelegationsPerDelegator = ConstU32<100>;
+    type MaxScheduledDelegationsPerBlock = ConstU32<100>;
+    type MaxBondsPerCandidate = ConstU32<100>;
+    type MaxCompoundingPreferencesPerCandidate = ConstU32<100>;
+    type RequestDelayOrigin = EnsureRoot<AccountId>;
     type EligibleCandidatesFilter = CandidateHasRegisteredKeys;
+    // Candidates register by setting their session keys
+    type RegisteredCandidates = CandidateHasRegisteredKeys;
+    // Candidates leave through `request_candidate_leave`
+    type LeavingCandidates = ();
+    type CandidateLeaveDelay = CandidateLeaveDelay;
+    type MaxCandidateLeavesPerBlock = ConstU32<10>;
+    type MaxCandidateLeaveEntriesPerBlock = ConstU32<100>;
+    type ZeroStakeCandidateGracePeriod = ZeroStakeCandidateGracePeriod;
+    type MaxPurgedEntriesPerBlock = ConstU32<100>;
+    // Delegation positions are not represented by receipt tokens
+    type OnDelegation = ();
     type WeightInfo = pallet_pooled_staking::weights::SubstrateWeight<Runtime>;
 }
 
+/// Rewards the author of each orchestrator chain block and of each container chain block noted
+/// by `AuthorNoting`. The role of the author is the chain `CollatorAssignment` assigned it to.
+pub struct RewardAuthors;
+
+impl RewardAuthors {
+    fn reward(author: AccountId, role: AuthorRole) -> Weight {
+        let result = frame_support::storage::with_storage_layer(|| {
+            PooledStaking::distribute_rewards(author, role, RewardsPerBlock::get())
+        });
+
+        let post_info = match result {
+            Ok(post_info) => post_info,
+            Err(e) => {
+                log::warn!(
+                    "Failed to distribute {:?} author rewards: {:?}",
+                    role,
+                    e.error
+                );
+                e.post_info
+            }
+        };
+
+        post_info.actual_weight.unwrap_or_else(|| {
+            <Runtime as pallet_pooled_staking::Config>::WeightInfo::distribute_rewards(0)
+        })
+    }
+}
+
+impl AuthorNotingHook<AccountId> for RewardAuthors {
+    fn on_container_author_noted(
+        author: &AccountId,
+        _block_number: BlockNumber,
+        _para_id: ParaId,
+    ) -> Weight {
+        // Author-noting only notes authors assigned to the container chain
+        Self::reward(author.clone(), AuthorRole::Container)
+    }
+}
+
+impl OnInitialize<BlockNumber> for RewardAuthors {
+    fn on_initialize(_n: BlockNumber) -> Weight {
+        let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+        let weight = db_weight.reads(2);
+
+        // Same author as the one expected by `CanAuthor`
+        let slot = <Runtime as pallet_author_inherent::Config>::SlotBeacon::slot();
+        let orchestrator_chain = CollatorAssignment::collator_container_chain().orchestrator_chain;
+        if orchestrator_chain.is_empty() {
+            return weight;
+        }
+        let author = orchestrator_chain[slot as usize % orchestrator_chain.len()].clone();
+
+        weight.saturating_add(Self::reward(author, AuthorRole::Orchestrator))
+    }
+}
+
+impl OnIdle<BlockNumber> for RewardAuthors {}
+impl OnFinalize<BlockNumber> for RewardAuthors {}
+impl OffchainWorker<BlockNumber> for RewardAuthors {}
+impl OnRuntimeUpgrade for RewardAuthors {}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
     pub enum Runtime where
@@ -1009,6 +1225,7 @@ construct_runtime!(
         CumulusXcm: cumulus_pallet_xcm::{Pallet, Event<T>, Origin} = 51,
         DmpQueue: cumulus_pallet_dmp_queue::{Pallet, Call, Storage, Event<T>} = 52,
         PolkadotXcm: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config} = 53,
+        XcmRecords: pallet_xcm_records = 54,
 
         RootTesting: pallet_root_testing = 100,
     }
@@ -1327,6 +1544,37 @@ impl_runtime_apis! {
                 assigned_collators.container_chains.get(&para_id).cloned()
             }
         }
+
+        /// Return the list of collators currently assigned to the orchestrator chain.
+        fn orchestrator_collators() -> Vec<AccountId> {
+            CollatorAssignment::orchestrator_collators()
+        }
+
+        /// Return the list of collators currently assigned to the given container chain.
+        /// Returns an empty list if the container chain has no collators.
+        fn container_collators(para_id: ParaId) -> Vec<AccountId> {
+            CollatorAssignment::container_collators(para_id)
+        }
+
+        /// Return the assignment that the next session change would produce with the
+        /// proposed configuration. Storage is not modified.
+        fn dry_run_assignment(proposed_config: AssignmentConfig) -> AssignedCollators<AccountId> {
+            CollatorAssignment::dry_run_assignment(
+                &Session::current_index().saturating_add(1),
+                proposed_config,
+            )
+        }
+
+        /// Return the collators eligible for the assignment that are currently not assigned to
+        /// any chain.
+        fn idle_collators() -> Vec<AccountId> {
+            CollatorAssignment::idle_collators()
+        }
+
+        /// Return the number of sessions the given `AccountId` was assigned to any chain.
+        fn collator_sessions_served(account: AccountId) -> u32 {
+            CollatorAssignment::collator_sessions_served(account)
+        }
     }
 
     impl pallet_registrar_runtime_api::RegistrarApi<Block, ParaId, MaxLengthTokenSymbol> for Runtime {
@@ -1348,6 +1596,64 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_pooled_staking_runtime_api::PooledStakingApi<Block, AccountId, Balance, u32> for Runtime {
+        /// Return the eligible candidates with their total stake, in the order they
+        /// are currently sorted.
+        fn eligible_candidates() -> Vec<(AccountId, Balance)> {
+            PooledStaking::eligible_candidates()
+        }
+
+        /// Return the manual rewards of `delegator` from `candidate` that claiming them
+        /// now would pay.
+        fn claimable_rewards(delegator: AccountId, candidate: AccountId) -> Balance {
+            PooledStaking::claimable_rewards(&delegator, &candidate)
+        }
+
+        /// Return the funds of `delegator` held by its pending operations, along with the
+        /// session from which each operation can be executed.
+        fn locks(delegator: AccountId) -> Vec<pallet_pooled_staking::Lock<AccountId, Balance, u32>> {
+            PooledStaking::locks(&delegator)
+        }
+
+        /// Return the amount of shares of `pool` that `stake` is worth for `candidate`,
+        /// rounded like the pallet does. Returns `None` if the conversion fails.
+        fn stake_to_shares(
+            candidate: AccountId,
+            pool: pallet_pooled_staking::AllTargetPool,
+            stake: Balance,
+        ) -> Option<Balance> {
+            PooledStaking::stake_to_shares(&candidate, pool, stake)
+        }
+
+        /// Return the amount of stake that `shares` of `pool` are worth for `candidate`,
+        /// rounded like the pallet does. Returns `None` if the conversion fails.
+        fn shares_to_stake(
+            candidate: AccountId,
+            pool: pallet_pooled_staking::AllTargetPool,
+            shares: Balance,
+        ) -> Option<Balance> {
+            PooledStaking::shares_to_stake(&candidate, pool, shares)
+        }
+
+        /// Return the number of candidates and delegators, along with the stake of all
+        /// candidates.
+        fn stats() -> pallet_pooled_staking::StakingStats<Balance> {
+            PooledStaking::stats()
+        }
+
+        /// Return the delegators of `candidate` along with the current value of their shares
+        /// in each pool, a page of at most `limit` of them (and never more than
+        /// `MAX_CANDIDATE_DELEGATORS_PAGE`) starting after the raw storage key `cursor`. Also
+        /// return the cursor of the next page, or `None` once all delegators have been listed.
+        fn candidate_delegators(
+            candidate: AccountId,
+            cursor: Option<Vec<u8>>,
+            limit: u32,
+        ) -> (Vec<(AccountId, pallet_pooled_staking::PoolsStake<Balance>)>, Option<Vec<u8>>) {
+            PooledStaking::candidate_delegators(&candidate, cursor, limit)
+        }
+    }
+
     impl pallet_author_noting_runtime_api::AuthorNotingApi<Block, AccountId, BlockNumber, ParaId> for Runtime
         where
         AccountId: parity_scale_codec::Codec,
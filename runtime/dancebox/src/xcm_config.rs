@@ -16,27 +16,38 @@
 
 use {
     super::{
-        weights::xcm::XcmWeight as XcmGenericWeights, AccountId, AllPalletsWithSystem, Balances,
-        ParachainInfo, ParachainSystem, PolkadotXcm, Runtime, RuntimeCall, RuntimeEvent,
-        RuntimeOrigin, WeightToFee, XcmpQueue,
+        weights::xcm::XcmWeight as XcmGenericWeights, AccountId, AllPalletsWithSystem, Balance,
+        Balances, BlockNumber, ParachainInfo, ParachainSystem, PolkadotXcm, PooledStaking, Runtime,
+        RuntimeBlockWeights, RuntimeCall, RuntimeEvent, RuntimeOrigin, WeightToFee, XcmRecords,
+        XcmpQueue, DAYS,
     },
     frame_support::{
         parameter_types,
-        traits::{Everything, Nothing, PalletInfoAccess},
-        weights::Weight,
+        traits::{
+            Contains, Equals, Everything, Get, Nothing, OffchainWorker, OnFinalize, OnIdle,
+            OnInitialize, OnRuntimeUpgrade, PalletInfoAccess, ProcessMessageError,
+        },
+        weights::{Weight, WeightToFee as WeightToFeeT},
     },
     frame_system::EnsureRoot,
     pallet_xcm::XcmPassthrough,
+    pallet_xcm_records::{TrappedAssetsRecord, UnpaidExecutionUsage, XcmErrorRecord},
     sp_core::ConstU32,
+    sp_runtime::Perbill,
+    sp_std::marker::PhantomData,
     xcm::latest::prelude::*,
     xcm_builder::{
-        AccountId32Aliases, AllowKnownQueryResponses, AllowSubscriptionsFrom,
-        AllowTopLevelPaidExecutionFrom, CurrencyAdapter, EnsureXcmOrigin, IsConcrete,
-        ParentIsPreset, RelayChainAsNative, SiblingParachainAsNative, SiblingParachainConvertsVia,
-        SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation,
-        TakeWeightCredit, UsingComponents, WeightInfoBounds, WithComputedOrigin,
+        AccountId32Aliases, AllowExplicitUnpaidExecutionFrom, AllowKnownQueryResponses,
+        AllowSubscriptionsFrom, AllowTopLevelPaidExecutionFrom, CurrencyAdapter, EnsureXcmOrigin,
+        IsConcrete, ParentIsPreset, RelayChainAsNative, SiblingParachainAsNative,
+        SiblingParachainConvertsVia, SignedAccountId32AsNative, SignedToAccountId32,
+        SovereignSignedViaLocation, TakeWeightCredit, UsingComponents, WeightInfoBounds,
+        WithComputedOrigin,
+    },
+    xcm_executor::{
+        traits::{ClaimAssets, DropAssets, ExecuteXcm, ShouldExecute, TransactAsset},
+        Assets, XcmExecutor,
     },
-    xcm_executor::XcmExecutor,
 };
 
 parameter_types! {
@@ -69,6 +80,89 @@ parameter_types! {
     // The universal location within the global consensus system
     pub UniversalLocation: InteriorMultiLocation =
     X2(GlobalConsensus(RelayNetwork::get()), Parachain(ParachainInfo::parachain_id().into()));
+
+    pub const ParentLocation: MultiLocation = MultiLocation::parent();
+
+    /// Maximum number of messages executed without payment in a single block.
+    pub const MaxUnpaidExecutionsPerBlock: u32 = 2;
+
+    /// Maximum number of failed XCM executions kept in the `RecentXcmErrors` of `XcmRecords`.
+    pub const MaxErrorRecords: u32 = 20;
+
+    /// Number of blocks after which unclaimed trapped assets are swept to the
+    /// `TrappedAssetsDestination` of `XcmRecords`. `None` keeps them trapped until claimed. Can
+    /// be updated with `set_storage`.
+    pub storage TrapExpiryBlocks: Option<BlockNumber> = Some(30 * DAYS);
+
+    /// Maximum number of asset traps tracked for expiry in the `TrappedAssetsRecords` of
+    /// `XcmRecords`.
+    pub const MaxTrappedAssetsRecords: u32 = 50;
+
+    /// Maximum number of assets a single instruction of an inbound message can carry.
+    pub const MaxAssetsPerMessage: u32 = 8;
+
+    // Staking rewards are paid in the native currency. Chains holding their pooled staking
+    // rewards here can refer to them with this location, which is transacted as `Balances`.
+    pub StakingRewardAsset: MultiLocation = MultiLocation {
+        parents: 0,
+        interior: Junctions::X1(
+            PalletInstance(<PooledStaking as PalletInfoAccess>::index() as u8)
+        )
+    };
+}
+
+/// Converts the weight of a message to a fee in the staking reward asset. As it is the native
+/// currency, the execution time is priced by `WeightToFee`. The proof size is priced like the
+/// execution time taking the same part of the block.
+pub struct StakingRewardAssetWeightToFee;
+impl WeightToFeeT for StakingRewardAssetWeightToFee {
+    type Balance = Balance;
+
+    fn weight_to_fee(weight: &Weight) -> Self::Balance {
+        let max_block = RuntimeBlockWeights::get().max_block;
+        let proof_size_as_ref_time =
+            Perbill::from_rational(weight.proof_size(), max_block.proof_size())
+                .mul_ceil(max_block.ref_time());
+
+        WeightToFee::weight_to_fee(&Weight::from_parts(
+            weight.ref_time().saturating_add(proof_size_as_ref_time),
+            0,
+        ))
+    }
+}
+
+/// Allows unpaid execution from `T` as `AllowExplicitUnpaidExecutionFrom` does, but only for
+/// `MaxPerBlock` messages per block. Once the budget of the block is exhausted, messages from `T`
+/// need to pay for their execution.
+pub struct AllowRateLimitedUnpaidExecutionFrom<T, MaxPerBlock>(PhantomData<(T, MaxPerBlock)>);
+impl<T: Contains<MultiLocation>, MaxPerBlock: Get<u32>> ShouldExecute
+    for AllowRateLimitedUnpaidExecutionFrom<T, MaxPerBlock>
+{
+    fn should_execute<RuntimeCall>(
+        origin: &MultiLocation,
+        instructions: &mut [Instruction<RuntimeCall>],
+        max_weight: Weight,
+        weight_credit: &mut Weight,
+    ) -> Result<(), ProcessMessageError> {
+        AllowExplicitUnpaidExecutionFrom::<T>::should_execute(
+            origin,
+            instructions,
+            max_weight,
+            weight_credit,
+        )?;
+
+        let now = frame_system::Pallet::<Runtime>::block_number();
+        let used = match UnpaidExecutionUsage::<Runtime>::get() {
+            (block_number, used) if block_number == now => used,
+            _ => 0,
+        };
+        if used >= MaxPerBlock::get() {
+            return Err(ProcessMessageError::Yield);
+        }
+        UnpaidExecutionUsage::<Runtime>::put((now, used.saturating_add(1)));
+
+        Ok(())
+    }
 }
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -76,22 +170,103 @@ parameter_types! {
     pub ReachableDest: Option<MultiLocation> = Some(Parent.into());
 }
 
-pub type XcmBarrier = (
-    // Weight that is paid for may be consumed.
-    TakeWeightCredit,
-    // Expected responses are OK.
-    AllowKnownQueryResponses<PolkadotXcm>,
-    WithComputedOrigin<
+/// Rejects messages carrying more than `MaxAssets` assets in a single `WithdrawAsset`,
+/// `ReserveAssetDeposited` or `ReceiveTeleportedAsset` instruction, and checks the rest with
+/// `Barrier`. Only top level instructions are inspected.
+pub struct LimitAssetsPerMessage<MaxAssets, Barrier>(PhantomData<(MaxAssets, Barrier)>);
+impl<MaxAssets: Get<u32>, Barrier: ShouldExecute> ShouldExecute
+    for LimitAssetsPerMessage<MaxAssets, Barrier>
+{
+    fn should_execute<RuntimeCall>(
+        origin: &MultiLocation,
+        instructions: &mut [Instruction<RuntimeCall>],
+        max_weight: Weight,
+        weight_credit: &mut Weight,
+    ) -> Result<(), ProcessMessageError> {
+        let too_many_assets = instructions.iter().any(|instruction| match instruction {
+            WithdrawAsset(assets)
+            | ReserveAssetDeposited(assets)
+            | ReceiveTeleportedAsset(assets) => assets.len() > MaxAssets::get() as usize,
+            _ => false,
+        });
+        if too_many_assets {
+            return Err(ProcessMessageError::Unsupported);
+        }
+
+        Barrier::should_execute(origin, instructions, max_weight, weight_credit)
+    }
+}
+
+/// Sibling parachains, such as the container chains, to which the native token can be forwarded
+/// with `DepositReserveAsset` or `TransferReserveAsset`, this chain acting as its reserve.
+pub struct ReserveTransferDestinations;
+impl Contains<MultiLocation> for ReserveTransferDestinations {
+    fn contains(location: &MultiLocation) -> bool {
+        matches!(
+            location,
+            MultiLocation {
+                parents: 1,
+                interior: X1(Parachain(_)),
+            }
+        )
+    }
+}
+
+/// Rejects messages depositing assets with `DepositReserveAsset` or `TransferReserveAsset` to a
+/// destination not in `Dests`, where they could not be used, and checks the rest with `Barrier`.
+/// Only top level instructions are inspected.
+pub struct LimitReserveTransfersTo<Dests, Barrier>(PhantomData<(Dests, Barrier)>);
+impl<Dests: Contains<MultiLocation>, Barrier: ShouldExecute> ShouldExecute
+    for LimitReserveTransfersTo<Dests, Barrier>
+{
+    fn should_execute<RuntimeCall>(
+        origin: &MultiLocation,
+        instructions: &mut [Instruction<RuntimeCall>],
+        max_weight: Weight,
+        weight_credit: &mut Weight,
+    ) -> Result<(), ProcessMessageError> {
+        let unsupported_dest = instructions.iter().any(|instruction| match instruction {
+            DepositReserveAsset { dest, .. } | TransferReserveAsset { dest, .. } => {
+                !Dests::contains(dest)
+            }
+            _ => false,
+        });
+        if unsupported_dest {
+            return Err(ProcessMessageError::Unsupported);
+        }
+
+        Barrier::should_execute(origin, instructions, max_weight, weight_credit)
+    }
+}
+
+pub type XcmBarrier = LimitAssetsPerMessage<
+    MaxAssetsPerMessage,
+    LimitReserveTransfersTo<
+        ReserveTransferDestinations,
         (
-            // If the message is one that immediately attemps to pay for execution, then allow it.
-            AllowTopLevelPaidExecutionFrom<Everything>,
-            // Subscriptions for version tracking are OK.
-            AllowSubscriptionsFrom<Everything>,
+            // Weight that is paid for may be consumed.
+            TakeWeightCredit,
+            // Expected responses are OK.
+            AllowKnownQueryResponses<PolkadotXcm>,
+            WithComputedOrigin<
+                (
+                    // If the message is one that immediately attemps to pay for execution, then
+                    // allow it.
+                    AllowTopLevelPaidExecutionFrom<Everything>,
+                    // The relay chain can execute a few messages per block for free.
+                    AllowRateLimitedUnpaidExecutionFrom<
+                        Equals<ParentLocation>,
+                        MaxUnpaidExecutionsPerBlock,
+                    >,
+                    // Subscriptions for version tracking are OK.
+                    AllowSubscriptionsFrom<Everything>,
+                ),
+                UniversalLocation,
+                ConstU32<8>,
+            >,
         ),
-        UniversalLocation,
-        ConstU32<8>,
     >,
-);
+>;
 
 /// Type for specifying how a `MultiLocation` can be converted into an `AccountId`. This is used
 /// when determining ownership of accounts for asset transacting and when attempting to use XCM
@@ -145,8 +320,18 @@ pub type XcmOriginToTransactDispatchOrigin = (
     XcmPassthrough<RuntimeOrigin>,
 );
 
+/// Means for transacting the native currency when referred to as the staking reward asset.
+pub type StakingRewardAssetTransactor = CurrencyAdapter<
+    Balances,
+    IsConcrete<StakingRewardAsset>,
+    LocationToAccountId,
+    AccountId,
+    // We don't track any teleports of `Balances`.
+    (),
+>;
+
 /// Means for transacting assets on this chain.
-pub type AssetTransactors = CurrencyTransactor;
+pub type AssetTransactors = (CurrencyTransactor, StakingRewardAssetTransactor);
 pub type XcmWeigher =
     WeightInfoBounds<XcmGenericWeights<RuntimeCall>, RuntimeCall, MaxInstructions>;
 
@@ -159,6 +344,24 @@ pub type XcmRouter = (
     XcmpQueue,
 );
 
+/// Calls that can be dispatched through an inbound XCM `Transact`. Anything else, such as sudo,
+/// staking or registrar calls, is rejected with `NoPermission` regardless of the origin.
+pub struct SafeCallFilter;
+impl Contains<RuntimeCall> for SafeCallFilter {
+    fn contains(call: &RuntimeCall) -> bool {
+        matches!(
+            call,
+            RuntimeCall::System(
+                frame_system::Call::remark { .. } | frame_system::Call::remark_with_event { .. }
+            ) | RuntimeCall::Balances(
+                pallet_balances::Call::transfer { .. }
+                    | pallet_balances::Call::transfer_keep_alive { .. }
+                    | pallet_balances::Call::transfer_all { .. }
+            )
+        )
+    }
+}
+
 pub struct XcmConfig;
 impl xcm_executor::Config for XcmConfig {
     type RuntimeCall = RuntimeCall;
@@ -170,11 +373,15 @@ impl xcm_executor::Config for XcmConfig {
     type UniversalLocation = UniversalLocation;
     type Barrier = XcmBarrier;
     type Weigher = XcmWeigher;
-    // Local token trader only
+    // Local token trader, also accepting the staking reward asset, unused fees being refunded
+    // in the asset they were paid with
     // TODO: update once we have a way to do fees
-    type Trader = UsingComponents<WeightToFee, SelfReserve, AccountId, Balances, ()>;
+    type Trader = (
+        UsingComponents<WeightToFee, SelfReserve, AccountId, Balances, ()>,
+        UsingComponents<StakingRewardAssetWeightToFee, StakingRewardAsset, AccountId, Balances, ()>,
+    );
     type ResponseHandler = PolkadotXcm;
-    type AssetTrap = PolkadotXcm;
+    type AssetTrap = AssetTrapWithExpiry;
     type AssetClaims = PolkadotXcm;
     type SubscriptionService = PolkadotXcm;
     type PalletInstancesInfo = AllPalletsWithSystem;
@@ -185,9 +392,153 @@ impl xcm_executor::Config for XcmConfig {
     type MessageExporter = ();
     type UniversalAliases = Nothing;
     type CallDispatcher = RuntimeCall;
-    type SafeCallFilter = Everything;
+    type SafeCallFilter = SafeCallFilter;
+}
+
+/// `XcmExecutor` that records failed executions in the `RecentXcmErrors` of `XcmRecords`.
+pub struct XcmExecutorWithErrorRecords;
+impl ExecuteXcm<RuntimeCall> for XcmExecutorWithErrorRecords {
+    type Prepared = <XcmExecutor<XcmConfig> as ExecuteXcm<RuntimeCall>>::Prepared;
+
+    fn prepare(message: Xcm<RuntimeCall>) -> Result<Self::Prepared, Xcm<RuntimeCall>> {
+        XcmExecutor::<XcmConfig>::prepare(message)
+    }
+
+    fn execute(
+        origin: impl Into<MultiLocation>,
+        pre: Self::Prepared,
+        hash: XcmHash,
+        weight_credit: Weight,
+    ) -> Outcome {
+        let origin = origin.into();
+        let outcome = XcmExecutor::<XcmConfig>::execute(origin, pre, hash, weight_credit);
+
+        let failure = match &outcome {
+            Outcome::Complete(_) => None,
+            Outcome::Incomplete(weight_used, error) => Some((*weight_used, error.clone())),
+            Outcome::Error(error) => Some((Weight::zero(), error.clone())),
+        };
+        if let Some((weight_used, error)) = failure {
+            XcmRecords::note_xcm_error(XcmErrorRecord {
+                block_number: frame_system::Pallet::<Runtime>::block_number(),
+                origin,
+                message_hash: hash,
+                error,
+                weight_used,
+            });
+        }
+
+        outcome
+    }
+
+    fn charge_fees(location: impl Into<MultiLocation>, fees: MultiAssets) -> XcmResult {
+        XcmExecutor::<XcmConfig>::charge_fees(location, fees)
+    }
+}
+
+/// Traps assets in `PolkadotXcm` and records them in the `TrappedAssetsRecords` of
+/// `XcmRecords`, so that they can be swept once expired.
+pub struct AssetTrapWithExpiry;
+impl DropAssets for AssetTrapWithExpiry {
+    fn drop_assets(origin: &MultiLocation, assets: Assets, context: &XcmContext) -> Weight {
+        if assets.is_empty() {
+            return Weight::zero();
+        }
+
+        let record = TrappedAssetsRecord {
+            trapped_at: frame_system::Pallet::<Runtime>::block_number(),
+            origin: *origin,
+            assets: assets.clone().into(),
+        };
+        let weight = PolkadotXcm::drop_assets(origin, assets, context);
+        XcmRecords::note_trapped_assets(record);
+
+        weight
+    }
+}
+
+/// Claims the assets trapped more than `TrapExpiryBlocks` ago and not claimed yet from
+/// `PolkadotXcm`, and deposits them to the `TrappedAssetsDestination` of `XcmRecords`. Assets
+/// that cannot be deposited are trapped again for the same origin. Nothing is swept while no
+/// destination is set.
+pub struct SweepExpiredAssetTraps;
+
+impl SweepExpiredAssetTraps {
+    fn sweep(n: BlockNumber) -> Weight {
+        let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+        let mut weight = db_weight.reads(2);
+
+        let destination = match XcmRecords::trapped_assets_destination() {
+            Some(destination) => destination,
+            None => return weight,
+        };
+        let expired_at = match TrapExpiryBlocks::get().and_then(|expiry| n.checked_sub(expiry)) {
+            Some(expired_at) => expired_at,
+            None => return weight,
+        };
+
+        let expired = XcmRecords::take_trapped_assets_until(expired_at);
+        weight.saturating_accrue(db_weight.reads_writes(1, 1));
+
+        let destination = MultiLocation::new(
+            0,
+            X1(AccountId32 {
+                network: None,
+                id: destination.into(),
+            }),
+        );
+        let context = XcmContext {
+            origin: None,
+            message_id: [0; 32],
+            topic: None,
+        };
+
+        for record in expired {
+            // Emits `AssetsClaimed` for the origin of the trap, fails if it was already claimed.
+            let claimed = <PolkadotXcm as ClaimAssets>::claim_assets(
+                &record.origin,
+                &MultiLocation::here(),
+                &record.assets,
+                &context,
+            );
+            weight.saturating_accrue(db_weight.reads_writes(1, 1));
+            if !claimed {
+                continue;
+            }
+
+            let mut not_deposited = Assets::new();
+            for asset in record.assets.inner() {
+                if let Err(e) = AssetTransactors::deposit_asset(asset, &destination, &context) {
+                    log::warn!("Failed to sweep trapped asset {:?}: {:?}", asset, e);
+                    not_deposited.subsume(asset.clone());
+                }
+            }
+            weight.saturating_accrue(db_weight.reads_writes(2, 2));
+
+            if !not_deposited.is_empty() {
+                weight.saturating_accrue(PolkadotXcm::drop_assets(
+                    &record.origin,
+                    not_deposited,
+                    &context,
+                ));
+            }
+        }
+
+        weight
+    }
 }
 
+impl OnInitialize<BlockNumber> for SweepExpiredAssetTraps {
+    fn on_initialize(n: BlockNumber) -> Weight {
+        Self::sweep(n)
+    }
+}
+
+impl OnIdle<BlockNumber> for SweepExpiredAssetTraps {}
+impl OnFinalize<BlockNumber> for SweepExpiredAssetTraps {}
+impl OffchainWorker<BlockNumber> for SweepExpiredAssetTraps {}
+impl OnRuntimeUpgrade for SweepExpiredAssetTraps {}
+
 impl pallet_xcm::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type SendXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
@@ -219,7 +570,7 @@ impl pallet_xcm::Config for Runtime {
 
 impl cumulus_pallet_xcmp_queue::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
-    type XcmExecutor = XcmExecutor<XcmConfig>;
+    type XcmExecutor = XcmExecutorWithErrorRecords;
     type ChannelInfo = ParachainSystem;
     type VersionWrapper = PolkadotXcm;
     type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
@@ -236,6 +587,12 @@ impl cumulus_pallet_xcm::Config for Runtime {
 
 impl cumulus_pallet_dmp_queue::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
-    type XcmExecutor = XcmExecutor<XcmConfig>;
+    type XcmExecutor = XcmExecutorWithErrorRecords;
     type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
 }
+
+impl pallet_xcm_records::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type MaxErrorRecords = MaxErrorRecords;
+    type MaxTrappedAssetsRecords = MaxTrappedAssetsRecords;
+}
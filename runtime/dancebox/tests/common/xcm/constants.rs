@@ -266,6 +266,7 @@ pub mod frontier_template {
 pub mod simple_template {
     use {
         super::*, container_chain_template_simple_runtime::UNIT as DEV, sp_runtime::BuildStorage,
+        xcm::prelude::*, xcm_builder::SiblingParachainConvertsVia, xcm_executor::traits::Convert,
     };
     pub const PARA_ID: u32 = 2002;
     pub const ORCHESTRATOR: u32 = 2000;
@@ -284,6 +285,18 @@ pub mod simple_template {
                     .iter()
                     .cloned()
                     .map(|k| (k, ENDOWMENT))
+                    // Give some balance to the orchestrator sovereign account
+                    .chain([(
+                        SiblingParachainConvertsVia::<
+                            polkadot_parachain::primitives::Sibling,
+                            container_chain_template_simple_runtime::AccountId,
+                        >::convert_ref(MultiLocation {
+                            parents: 1,
+                            interior: X1(Parachain(ORCHESTRATOR)),
+                        })
+                        .unwrap(),
+                        ENDOWMENT,
+                    )])
                     .collect(),
             },
             parachain_info: container_chain_template_simple_runtime::ParachainInfoConfig {
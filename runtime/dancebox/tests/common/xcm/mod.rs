@@ -18,6 +18,7 @@ mod constants;
 mod foreign_signed_based_sovereign;
 mod foreign_sovereigns;
 mod mocknets;
+mod reserve_transfer;
 mod transact;
 mod trap;
 
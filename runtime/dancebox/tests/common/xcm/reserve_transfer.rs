@@ -0,0 +1,158 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    crate::common::xcm::{
+        mocknets::{Dancebox, SimpleTemplate, SimpleTemplateReceiver, Westend, WestendPallet},
+        *,
+    },
+    container_chain_template_simple_runtime::xcm_config::OrchestratorBalancesPalletIndex,
+    frame_support::{
+        assert_ok,
+        weights::{Weight, WeightToFee},
+    },
+    xcm::{latest::prelude::*, VersionedMultiLocation, VersionedXcm},
+    xcm_builder::SiblingParachainConvertsVia,
+    xcm_executor::traits::Convert,
+};
+
+#[test]
+fn native_token_is_forwarded_to_container_chain_with_deposit_reserve_asset() {
+    // XcmPallet send arguments
+    let sudo_origin = <Westend as Relay>::RuntimeOrigin::root();
+    let dancebox_para_destination: VersionedMultiLocation =
+        Westend::child_location_of(Dancebox::para_id()).into();
+    let container_chain_destination =
+        MultiLocation::new(1, X1(Parachain(SimpleTemplate::para_id().into())));
+
+    let buy_execution_fee_amount =
+        dancebox_runtime::WeightToFee::weight_to_fee(&Weight::from_parts(10_000_000_000, 300_000));
+    let forwarded_amount = 100 * crate::UNIT;
+
+    let native_token = |amount| MultiAsset {
+        id: Concrete(dancebox_runtime::xcm_config::SelfReserve::get()),
+        fun: Fungible(amount),
+    };
+    // The native token of Dancebox, as seen from the container chain
+    let container_chain_fee = MultiAsset {
+        id: Concrete(MultiLocation::new(
+            1,
+            X2(
+                Parachain(Dancebox::para_id().into()),
+                PalletInstance(OrchestratorBalancesPalletIndex::get()),
+            ),
+        )),
+        fun: Fungible(crate::UNIT),
+    };
+
+    let xcm = VersionedXcm::from(Xcm(vec![
+        WithdrawAsset {
+            0: vec![native_token(buy_execution_fee_amount + forwarded_amount)].into(),
+        },
+        BuyExecution {
+            fees: native_token(buy_execution_fee_amount),
+            weight_limit: Unlimited,
+        },
+        DepositReserveAsset {
+            assets: Wild(AllCounted(1)),
+            dest: container_chain_destination,
+            xcm: Xcm(vec![
+                BuyExecution {
+                    fees: container_chain_fee,
+                    weight_limit: Unlimited,
+                },
+                DepositAsset {
+                    assets: Wild(AllCounted(1)),
+                    beneficiary: AccountId32 {
+                        network: None,
+                        id: SimpleTemplateReceiver::get().into(),
+                    }
+                    .into(),
+                },
+            ]),
+        },
+    ]));
+
+    let container_chain_sovereign = SiblingParachainConvertsVia::<
+        polkadot_parachain::primitives::Sibling,
+        crate::AccountId,
+    >::convert_ref(container_chain_destination)
+    .unwrap();
+    let sovereign_balance_before = Dancebox::execute_with(|| {
+        <Dancebox as Para>::System::account(&container_chain_sovereign)
+            .data
+            .free
+    });
+
+    // Send XCM message from Relay Chain
+    Westend::execute_with(|| {
+        assert_ok!(<Westend as WestendPallet>::XcmPallet::send(
+            sudo_origin,
+            bx!(dancebox_para_destination),
+            bx!(xcm),
+        ));
+
+        type RuntimeEvent = <Westend as Relay>::RuntimeEvent;
+
+        assert_expected_events!(
+            Westend,
+            vec![
+                RuntimeEvent::XcmPallet(pallet_xcm::Event::Sent { .. }) => {},
+            ]
+        );
+    });
+
+    // Dancebox holds the forwarded tokens in the sovereign account of the container chain
+    Dancebox::execute_with(|| {
+        type RuntimeEvent = <Dancebox as Para>::RuntimeEvent;
+        assert_expected_events!(
+            Dancebox,
+            vec![
+                RuntimeEvent::DmpQueue(
+                    cumulus_pallet_dmp_queue::Event::ExecutedDownward {
+                        outcome: Outcome::Complete(_w), ..
+                    }) => {},
+                RuntimeEvent::XcmpQueue(
+                    cumulus_pallet_xcmp_queue::Event::XcmpMessageSent { .. }) => {},
+            ]
+        );
+
+        let sovereign_balance = <Dancebox as Para>::System::account(&container_chain_sovereign)
+            .data
+            .free;
+        assert!(sovereign_balance >= sovereign_balance_before + forwarded_amount);
+    });
+
+    // The container chain receives them, minus the execution fee
+    SimpleTemplate::execute_with(|| {
+        type RuntimeEvent = <SimpleTemplate as Para>::RuntimeEvent;
+        assert_expected_events!(
+            SimpleTemplate,
+            vec![
+                RuntimeEvent::XcmpQueue(
+                    cumulus_pallet_xcmp_queue::Event::Success {
+                        ..
+                    }) => {},
+            ]
+        );
+
+        let received = container_chain_template_simple_runtime::OrchestratorBalances::free_balance(
+            SimpleTemplateReceiver::get(),
+        );
+        assert!(received > forwarded_amount - crate::UNIT);
+        assert!(received <= forwarded_amount);
+    });
+}
@@ -18,8 +18,8 @@ use crate::common::xcm::*;
 
 use {
     crate::common::xcm::mocknets::{
-        Dancebox, FrontierTemplate, FrontierTemplatePallet, SimpleTemplate, SimpleTemplatePallet,
-        Westend, WestendPallet,
+        Dancebox, DanceboxPallet, FrontierTemplate, FrontierTemplatePallet, SimpleTemplate,
+        SimpleTemplatePallet, Westend, WestendPallet,
     },
     frame_support::{
         assert_ok,
@@ -27,10 +27,7 @@ use {
     },
     parity_scale_codec::Encode,
     xcm::{
-        latest::{
-            prelude::*,
-            Error::{BadOrigin, Barrier},
-        },
+        latest::{prelude::*, Error::NoPermission},
         VersionedMultiLocation, VersionedXcm,
     },
     xcm_builder::{ParentIsPreset, SiblingParachainConvertsVia},
@@ -38,7 +35,7 @@ use {
 };
 
 #[test]
-fn transact_sudo_from_relay_hits_barrier_dancebox_without_buy_exec() {
+fn transact_sudo_from_relay_without_buy_exec_does_not_have_sudo_power() {
     let call = <Dancebox as Para>::RuntimeCall::Configuration(pallet_configuration::Call::<
         <Dancebox as Para>::Runtime,
     >::set_max_collators {
@@ -93,8 +90,11 @@ fn transact_sudo_from_relay_hits_barrier_dancebox_without_buy_exec() {
         assert_expected_events!(
             Dancebox,
             vec![
-                RuntimeEvent::DmpQueue(cumulus_pallet_dmp_queue::Event::ExecutedDownward { outcome, .. }) => {
-                    outcome: *outcome == Outcome::Error(Barrier),
+                RuntimeEvent::DmpQueue(
+                    cumulus_pallet_dmp_queue::Event::ExecutedDownward {
+                        outcome: Outcome::Incomplete(_w, error), ..
+                    }) => {
+                    error: *error == NoPermission,
                 },
             ]
         );
@@ -170,7 +170,7 @@ fn transact_sudo_from_relay_does_not_have_sudo_power() {
                     cumulus_pallet_dmp_queue::Event::ExecutedDownward {
                         outcome: Outcome::Incomplete(_w, error), ..
                     }) => {
-                    error: *error == BadOrigin,
+                    error: *error == NoPermission,
                 },
             ]
         );
@@ -255,6 +255,108 @@ fn transact_sudo_from_relay_has_signed_origin_powers() {
     });
 }
 
+/// Send `call` from the relay chain to Dancebox as a `Transact` dispatched with the relay
+/// sovereign account origin, paying for its execution.
+fn send_transact_from_relay_as_sovereign(call: Vec<u8>) {
+    let sudo_origin = <Westend as Relay>::RuntimeOrigin::root();
+    let dancebox_para_destination: VersionedMultiLocation =
+        Westend::child_location_of(Dancebox::para_id()).into();
+
+    let buy_execution_fee_amount = dancebox_runtime::WeightToFee::weight_to_fee(
+        &Weight::from_parts(10_000_000_000_000, 300_000),
+    );
+    let buy_execution_fee = MultiAsset {
+        id: Concrete(dancebox_runtime::xcm_config::SelfReserve::get()),
+        fun: Fungible(buy_execution_fee_amount),
+    };
+
+    let xcm = VersionedXcm::from(Xcm(vec![
+        WithdrawAsset {
+            0: vec![buy_execution_fee.clone()].into(),
+        },
+        BuyExecution {
+            fees: buy_execution_fee.clone(),
+            weight_limit: Unlimited,
+        },
+        Transact {
+            require_weight_at_most: Weight::from_parts(1000000000, 200000),
+            origin_kind: OriginKind::SovereignAccount,
+            call: call.into(),
+        },
+    ]));
+
+    Westend::execute_with(|| {
+        assert_ok!(<Westend as WestendPallet>::XcmPallet::send(
+            sudo_origin,
+            bx!(dancebox_para_destination),
+            bx!(xcm),
+        ));
+    });
+}
+
+#[test]
+fn transact_call_not_in_safe_call_filter_is_rejected() {
+    // The relay sovereign account could dispatch this call itself, but it is not allowed
+    // through XCM
+    let call = <Dancebox as Para>::RuntimeCall::PooledStaking(pallet_pooled_staking::Call::<
+        <Dancebox as Para>::Runtime,
+    >::set_commission {
+        commission: sp_runtime::Perbill::from_percent(10),
+    })
+    .encode();
+
+    send_transact_from_relay_as_sovereign(call);
+
+    Dancebox::execute_with(|| {
+        type RuntimeEvent = <Dancebox as Para>::RuntimeEvent;
+        assert_expected_events!(
+            Dancebox,
+            vec![
+                RuntimeEvent::DmpQueue(
+                    cumulus_pallet_dmp_queue::Event::ExecutedDownward {
+                        outcome: Outcome::Incomplete(_w, error), ..
+                    }) => {
+                    error: *error == NoPermission,
+                },
+            ]
+        );
+    });
+}
+
+#[test]
+fn transact_call_in_safe_call_filter_is_executed() {
+    let dest = dancebox_runtime::AccountId::from([7u8; 32]);
+    let amount = 100 * dancebox_runtime::EXISTENTIAL_DEPOSIT;
+    let call = <Dancebox as Para>::RuntimeCall::Balances(pallet_balances::Call::<
+        <Dancebox as Para>::Runtime,
+    >::transfer_keep_alive {
+        dest: dest.clone().into(),
+        value: amount,
+    })
+    .encode();
+
+    send_transact_from_relay_as_sovereign(call);
+
+    Dancebox::execute_with(|| {
+        type RuntimeEvent = <Dancebox as Para>::RuntimeEvent;
+        assert_expected_events!(
+            Dancebox,
+            vec![
+                RuntimeEvent::Balances(
+                    pallet_balances::Event::Transfer {
+                        from,
+                        to,
+                        amount: transferred,
+                    }) => {
+                    from: *from == ParentIsPreset::<dancebox_runtime::AccountId>::convert_ref(MultiLocation::parent()).unwrap(),
+                    to: *to == dest,
+                    transferred: *transferred == amount,
+                },
+            ]
+        );
+    });
+}
+
 #[test]
 fn transact_sudo_from_frontier_has_signed_origin_powers() {
     let call = <Dancebox as Para>::RuntimeCall::System(frame_system::Call::<
@@ -422,3 +524,83 @@ fn transact_sudo_from_simple_has_signed_origin_powers() {
         );
     });
 }
+
+#[test]
+fn transact_sudo_from_dancebox_has_root_powers_in_simple() {
+    let call = <SimpleTemplate as Para>::RuntimeCall::System(frame_system::Call::<
+        <SimpleTemplate as Para>::Runtime,
+    >::remark {
+        remark: b"Test".to_vec(),
+    })
+    .encode()
+    .into();
+
+    // XcmPallet send arguments
+    let sudo_origin = <Dancebox as Para>::RuntimeOrigin::root();
+    let simple_para_destination: VersionedMultiLocation = MultiLocation {
+        parents: 1,
+        interior: X1(Parachain(SimpleTemplate::para_id().into())),
+    }
+    .into();
+
+    let require_weight_at_most = Weight::from_parts(1000000000, 200000);
+    let origin_kind = OriginKind::Superuser;
+
+    let buy_execution_fee_amount =
+        container_chain_template_simple_runtime::WeightToFee::weight_to_fee(&Weight::from_parts(
+            10_000_000_000,
+            300_000,
+        ));
+
+    let buy_execution_fee = MultiAsset {
+        id: Concrete(container_chain_template_simple_runtime::xcm_config::SelfReserve::get()),
+        fun: Fungible(buy_execution_fee_amount),
+    };
+
+    let xcm = VersionedXcm::from(Xcm(vec![
+        WithdrawAsset {
+            0: vec![buy_execution_fee.clone()].into(),
+        },
+        BuyExecution {
+            fees: buy_execution_fee.clone(),
+            weight_limit: Unlimited,
+        },
+        Transact {
+            require_weight_at_most,
+            origin_kind,
+            call,
+        },
+    ]));
+
+    // Send XCM message from Dancebox
+    Dancebox::execute_with(|| {
+        assert_ok!(<Dancebox as DanceboxPallet>::PolkadotXcm::send(
+            sudo_origin,
+            bx!(simple_para_destination),
+            bx!(xcm),
+        ));
+
+        type RuntimeEvent = <Dancebox as Para>::RuntimeEvent;
+
+        assert_expected_events!(
+            Dancebox,
+            vec![
+                RuntimeEvent::PolkadotXcm(pallet_xcm::Event::Sent { .. }) => {},
+            ]
+        );
+    });
+
+    // Receive XCM message in Simple Template, the orchestrator is converted to root
+    SimpleTemplate::execute_with(|| {
+        type RuntimeEvent = <SimpleTemplate as Para>::RuntimeEvent;
+        assert_expected_events!(
+            SimpleTemplate,
+            vec![
+                RuntimeEvent::XcmpQueue(
+                    cumulus_pallet_xcmp_queue::Event::Success {
+                        ..
+                    }) => {},
+            ]
+        );
+    });
+}
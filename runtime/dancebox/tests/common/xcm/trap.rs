@@ -20,6 +20,7 @@ use {
     crate::common::xcm::mocknets::{Dancebox, Westend, WestendPallet},
     frame_support::{
         assert_ok,
+        traits::OnInitialize,
         weights::{Weight, WeightToFee},
     },
     xcm::{
@@ -90,5 +91,115 @@ fn trapping_asserts_works_with_polkadot_xcm() {
                 },
             ]
         );
+
+        // The failure is recorded, along with the message and the weight used until the error
+        let (message_id, weight_used) = dancebox_runtime::System::events()
+            .into_iter()
+            .find_map(|record| match record.event {
+                RuntimeEvent::DmpQueue(cumulus_pallet_dmp_queue::Event::ExecutedDownward {
+                    message_id,
+                    outcome: Outcome::Incomplete(weight_used, _),
+                }) => Some((message_id, weight_used)),
+                _ => None,
+            })
+            .expect("message was executed");
+        let records = dancebox_runtime::XcmRecords::recent_xcm_errors();
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0].block_number,
+            dancebox_runtime::System::block_number()
+        );
+        assert_eq!(records[0].origin, MultiLocation::parent());
+        assert_eq!(records[0].message_hash, message_id);
+        assert_eq!(records[0].error, TrapError(0));
+        assert_eq!(records[0].weight_used, weight_used);
+        assert!(!records[0].weight_used.is_zero());
+    });
+}
+
+#[test]
+fn expired_trapped_assets_are_swept_to_destination() {
+    // XcmPallet send arguments
+    let sudo_origin = <Westend as Relay>::RuntimeOrigin::root();
+    let dancebox_para_destination: VersionedMultiLocation =
+        Westend::child_location_of(Dancebox::para_id()).into();
+
+    let buy_execution_fee_amount =
+        dancebox_runtime::WeightToFee::weight_to_fee(&Weight::from_parts(10_000_000_000, 300_000));
+
+    let buy_execution_fee = MultiAsset {
+        id: Concrete(dancebox_runtime::xcm_config::SelfReserve::get()),
+        fun: Fungible(buy_execution_fee_amount),
+    };
+
+    let xcm = VersionedXcm::from(Xcm(vec![
+        WithdrawAsset {
+            0: vec![buy_execution_fee.clone()].into(),
+        },
+        BuyExecution {
+            fees: buy_execution_fee.clone(),
+            weight_limit: Unlimited,
+        },
+        Trap(0),
+    ]));
+
+    // Send XCM message from Relay Chain
+    Westend::execute_with(|| {
+        assert_ok!(<Westend as WestendPallet>::XcmPallet::send(
+            sudo_origin,
+            bx!(dancebox_para_destination),
+            bx!(xcm),
+        ));
+    });
+
+    Dancebox::execute_with(|| {
+        type RuntimeEvent = <Dancebox as Para>::RuntimeEvent;
+        use dancebox_runtime::{
+            xcm_config::{SweepExpiredAssetTraps, TrapExpiryBlocks},
+            XcmRecords,
+        };
+
+        let records = XcmRecords::trapped_assets_records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].origin, MultiLocation::parent());
+        let trapped_amount = match records[0].assets.inner()[0].fun {
+            Fungible(amount) => amount,
+            NonFungible(_) => panic!("native asset is fungible"),
+        };
+        let expires_at = records[0].trapped_at + TrapExpiryBlocks::get().unwrap();
+
+        // Nothing is swept while no destination is set
+        SweepExpiredAssetTraps::on_initialize(expires_at);
+        assert_eq!(XcmRecords::trapped_assets_records().len(), 1);
+
+        let destination = dancebox_runtime::AccountId::from([9u8; 32]);
+        assert_ok!(XcmRecords::set_trapped_assets_destination(
+            <Dancebox as Para>::RuntimeOrigin::root(),
+            Some(destination.clone()),
+        ));
+        assert_eq!(dancebox_runtime::System::account(&destination).data.free, 0);
+
+        // Nothing is swept before the expiry
+        SweepExpiredAssetTraps::on_initialize(expires_at - 1);
+        assert_eq!(XcmRecords::trapped_assets_records().len(), 1);
+        assert_eq!(dancebox_runtime::System::account(&destination).data.free, 0);
+
+        SweepExpiredAssetTraps::on_initialize(expires_at);
+        assert!(XcmRecords::trapped_assets_records().is_empty());
+        assert_eq!(
+            dancebox_runtime::System::account(&destination).data.free,
+            trapped_amount
+        );
+
+        // The trap is claimed on behalf of its origin
+        assert_expected_events!(
+            Dancebox,
+            vec![
+                RuntimeEvent::PolkadotXcm(
+                    pallet_xcm::Event::AssetsClaimed(_hash, origin, _assets)) => {
+                        origin: *origin == MultiLocation::parent(),
+                },
+            ]
+        );
     });
 }
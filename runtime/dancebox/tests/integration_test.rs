@@ -244,7 +244,7 @@ fn genesis_para_registrar_container_chain_genesis_data_runtime_api() {
                 Runtime::genesis_data(1002.into()).as_ref(),
                 Some(&genesis_data_1002)
             );
-            assert_eq!(Runtime::genesis_data(1003.into()).as_ref(), None);
+            assert_eq!(Runtime::genesis_data(2003.into()).as_ref(), None);
 
             // This API cannot be used to get the genesis data of the orchestrator chain,
             // with id 100
@@ -258,7 +258,7 @@ fn genesis_para_registrar_container_chain_genesis_data_runtime_api() {
             // TODO: they should stay until session 2, just like the para id does
             assert_eq!(Runtime::genesis_data(1002.into()).as_ref(), None);
 
-            let genesis_data_1003 = ContainerChainGenesisData {
+            let genesis_data_2003 = ContainerChainGenesisData {
                 storage: vec![(b"key3".to_vec(), b"value3".to_vec()).into()],
                 name: Default::default(),
                 id: Default::default(),
@@ -266,19 +266,23 @@ fn genesis_para_registrar_container_chain_genesis_data_runtime_api() {
                 extensions: vec![],
                 properties: Default::default(),
             };
+            assert_ok!(
+                Registrar::reserve_para_id(origin_of(ALICE.into()), Some(2003.into())),
+                ()
+            );
             assert_ok!(
                 Registrar::register(
                     origin_of(ALICE.into()),
-                    1003.into(),
-                    genesis_data_1003.clone()
+                    2003.into(),
+                    genesis_data_2003.clone()
                 ),
                 ()
             );
 
             // Registered container chains are inserted immediately
             assert_eq!(
-                Runtime::genesis_data(1003.into()).as_ref(),
-                Some(&genesis_data_1003)
+                Runtime::genesis_data(2003.into()).as_ref(),
+                Some(&genesis_data_2003)
             );
         });
 }
@@ -563,19 +567,28 @@ fn test_authors_paras_inserted_a_posteriori() {
             assert_eq!(authorities(), vec![alice_id, bob_id]);
 
             assert_ok!(
-                Registrar::register(origin_of(ALICE.into()), 1001.into(), empty_genesis_data()),
+                Registrar::reserve_para_id(origin_of(ALICE.into()), Some(2001.into())),
+                ()
+            );
+
+            assert_ok!(
+                Registrar::register(origin_of(ALICE.into()), 2001.into(), empty_genesis_data()),
+                ()
+            );
+            assert_ok!(
+                Registrar::mark_valid_for_collating(root_origin(), 2001.into()),
                 ()
             );
             assert_ok!(
-                Registrar::mark_valid_for_collating(root_origin(), 1001.into()),
+                Registrar::reserve_para_id(origin_of(ALICE.into()), Some(2002.into())),
                 ()
             );
             assert_ok!(
-                Registrar::register(origin_of(ALICE.into()), 1002.into(), empty_genesis_data()),
+                Registrar::register(origin_of(ALICE.into()), 2002.into(), empty_genesis_data()),
                 ()
             );
             assert_ok!(
-                Registrar::mark_valid_for_collating(root_origin(), 1002.into()),
+                Registrar::mark_valid_for_collating(root_origin(), 2002.into()),
                 ()
             );
 
@@ -585,10 +598,10 @@ fn test_authors_paras_inserted_a_posteriori() {
             assert!(assignment.container_chains.is_empty());
             run_to_session(2u32);
 
-            // Charlie and Dave should be assigne dot para 1001
+            // Charlie and Dave should be assigne dot para 2001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(
-                assignment.container_chains[&1001u32.into()],
+                assignment.container_chains[&2001u32.into()],
                 vec![CHARLIE.into(), DAVE.into()]
             );
         });
@@ -632,11 +645,16 @@ fn test_authors_paras_inserted_a_posteriori_with_collators_already_assigned() {
             assert_eq!(authorities(), vec![alice_id, bob_id, charlie_id, dave_id]);
 
             assert_ok!(
-                Registrar::register(origin_of(ALICE.into()), 1001.into(), empty_genesis_data()),
+                Registrar::reserve_para_id(origin_of(ALICE.into()), Some(2001.into())),
+                ()
+            );
+
+            assert_ok!(
+                Registrar::register(origin_of(ALICE.into()), 2001.into(), empty_genesis_data()),
                 ()
             );
             assert_ok!(
-                Registrar::mark_valid_for_collating(root_origin(), 1001.into()),
+                Registrar::mark_valid_for_collating(root_origin(), 2001.into()),
                 ()
             );
 
@@ -646,10 +664,10 @@ fn test_authors_paras_inserted_a_posteriori_with_collators_already_assigned() {
             assert!(assignment.container_chains.is_empty());
             run_to_session(2u32);
 
-            // Charlie and Dave are now assigned to para 1001
+            // Charlie and Dave are now assigned to para 2001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(
-                assignment.container_chains[&1001u32.into()],
+                assignment.container_chains[&2001u32.into()],
                 vec![CHARLIE.into(), DAVE.into()]
             );
             assert_eq!(
@@ -727,6 +745,82 @@ fn test_parachains_deregister_collators_re_assigned() {
         });
 }
 
+#[test]
+fn test_parachains_pause_collators_freed_and_resume_reassigned() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            // Alice gets 10k extra tokens for her mapping deposit
+            (AccountId::from(ALICE), 210_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+            (AccountId::from(DAVE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(ALICE), 210 * UNIT),
+            (AccountId::from(BOB), 100 * UNIT),
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+        ])
+        .with_para_ids(vec![
+            (1001, empty_genesis_data(), vec![]),
+            (1002, empty_genesis_data(), vec![]),
+        ])
+        .with_config(pallet_configuration::HostConfiguration {
+            max_collators: 100,
+            min_orchestrator_collators: 2,
+            max_orchestrator_collators: 2,
+            collators_per_container: 1,
+        })
+        .build()
+        .execute_with(|| {
+            run_to_block(2);
+
+            // Charlie to 1001, Dave to 1002
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert_eq!(
+                assignment.container_chains[&1001u32.into()],
+                vec![CHARLIE.into()]
+            );
+            assert_eq!(
+                assignment.container_chains[&1002u32.into()],
+                vec![DAVE.into()]
+            );
+
+            assert_ok!(
+                Registrar::pause_container_chain(root_origin(), 1001.into()),
+                ()
+            );
+
+            // Pausing takes effect after 2 sessions
+            run_to_session(2u32);
+
+            // 1001 does not need collators anymore, so Charlie is not assigned
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert!(!assignment.container_chains.contains_key(&1001u32.into()));
+            assert!(!assignment.find_collator(&CHARLIE.into()));
+            assert_eq!(
+                assignment.container_chains[&1002u32.into()],
+                vec![DAVE.into()]
+            );
+            // Genesis data is kept while paused
+            assert!(Registrar::para_genesis_data(ParaId::from(1001)).is_some());
+
+            assert_ok!(
+                Registrar::resume_container_chain(root_origin(), 1001.into()),
+                ()
+            );
+
+            run_to_session(4u32);
+
+            // Charlie is assigned to 1001 again
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert_eq!(
+                assignment.container_chains[&1001u32.into()],
+                vec![CHARLIE.into()]
+            );
+        });
+}
+
 #[test]
 fn test_parachains_deregister_collators_config_change_reassigned() {
     ExtBuilder::default()
@@ -2106,7 +2200,8 @@ fn test_staking_join() {
                 origin_of(ALICE.into()),
                 ALICE.into(),
                 TargetPool::AutoCompounding,
-                stake
+                stake,
+                None
             ));
 
             // Immediately after joining, Alice is the top candidate
@@ -2170,7 +2265,8 @@ fn test_staking_join_no_keys_registered() {
                 origin_of(new_account.clone()),
                 new_account.clone(),
                 TargetPool::AutoCompounding,
-                stake
+                stake,
+                None
             ));
 
             // The new account should be the top candidate but it has no keys registered in
@@ -2231,7 +2327,8 @@ fn test_staking_register_keys_after_joining() {
                 origin_of(new_account.clone()),
                 new_account.clone(),
                 TargetPool::AutoCompounding,
-                stake
+                stake,
+                None
             ));
 
             // The new account should be the top candidate but it has no keys registered in
@@ -2318,7 +2415,8 @@ fn test_staking_join_bad_origin() {
                     root_origin(),
                     ALICE.into(),
                     TargetPool::AutoCompounding,
-                    stake
+                    stake,
+                    None
                 ),
                 BadOrigin,
             );
@@ -2360,7 +2458,8 @@ fn test_staking_join_below_self_delegation_min() {
                 origin_of(ALICE.into()),
                 ALICE.into(),
                 TargetPool::AutoCompounding,
-                stake1
+                stake1,
+                None
             ));
 
             // Since stake is below MinimumSelfDelegation, the join operation succeeds
@@ -2375,6 +2474,7 @@ fn test_staking_join_below_self_delegation_min() {
                 ALICE.into(),
                 TargetPool::AutoCompounding,
                 stake2,
+                None,
             ));
 
             // Still below, missing 1 unit
@@ -2388,6 +2488,7 @@ fn test_staking_join_below_self_delegation_min() {
                 ALICE.into(),
                 TargetPool::AutoCompounding,
                 stake3,
+                None,
             ));
 
             // Increasing the stake to above MinimumSelfDelegation makes the candidate eligible
@@ -2440,6 +2541,7 @@ fn test_staking_join_no_self_delegation() {
                 ALICE.into(),
                 TargetPool::AutoCompounding,
                 stake,
+                None,
             ));
 
             let eligible_candidates =
@@ -2484,7 +2586,8 @@ fn test_staking_join_before_self_delegation() {
                 origin_of(BOB.into()),
                 ALICE.into(),
                 TargetPool::AutoCompounding,
-                stake
+                stake,
+                None
             ));
 
             let eligible_candidates =
@@ -2508,7 +2611,8 @@ fn test_staking_join_before_self_delegation() {
                 origin_of(ALICE.into()),
                 ALICE.into(),
                 TargetPool::AutoCompounding,
-                stake
+                stake,
+                None
             ));
 
             // Alice is a valid candidate, and Bob's stake is also counted
@@ -2559,7 +2663,8 @@ fn test_staking_join_twice_in_same_block() {
                 origin_of(ALICE.into()),
                 ALICE.into(),
                 TargetPool::AutoCompounding,
-                stake1
+                stake1,
+                None
             ));
 
             let stake2 = 9 * MinimumSelfDelegation::get();
@@ -2567,7 +2672,8 @@ fn test_staking_join_twice_in_same_block() {
                 origin_of(ALICE.into()),
                 ALICE.into(),
                 TargetPool::AutoCompounding,
-                stake2
+                stake2,
+                None
             ));
 
             // Both operations succeed and the total stake is the sum of the individual stakes
@@ -2634,7 +2740,8 @@ fn test_staking_join_execute_before_time() {
                 origin_of(ALICE.into()),
                 ALICE.into(),
                 TargetPool::AutoCompounding,
-                stake
+                stake,
+                None
             ));
 
             // Immediately after joining, Alice is the top candidate
@@ -2715,7 +2822,8 @@ fn test_staking_join_execute_any_origin() {
                 origin_of(ALICE.into()),
                 ALICE.into(),
                 TargetPool::AutoCompounding,
-                stake
+                stake,
+                None
             ));
 
             // Immediately after joining, Alice is the top candidate
@@ -2780,7 +2888,8 @@ fn test_staking_join_execute_bad_origin() {
                 origin_of(ALICE.into()),
                 ALICE.into(),
                 TargetPool::AutoCompounding,
-                stake
+                stake,
+                None
             ));
 
             // Immediately after joining, Alice is the top candidate
@@ -2855,6 +2964,7 @@ fn setup_staking_join_and_execute<R>(ops: Vec<A>, f: impl FnOnce() -> R) {
                     op.candidate.clone(),
                     op.target_pool,
                     op.stake,
+                    None,
                 ));
             }
 
@@ -3244,6 +3354,7 @@ fn test_pallet_session_takes_validators_from_invulnerables_and_staking() {
                 ALICE.into(),
                 TargetPool::AutoCompounding,
                 stake,
+                None,
             ));
 
             // Register Dave in pallet_session (invulnerables are automatically registered)
@@ -3261,6 +3372,7 @@ fn test_pallet_session_takes_validators_from_invulnerables_and_staking() {
                 DAVE.into(),
                 TargetPool::AutoCompounding,
                 stake,
+                None,
             ));
 
             let eligible_candidates =
@@ -3340,6 +3452,7 @@ fn test_pallet_session_limits_num_validators() {
                 ALICE.into(),
                 TargetPool::AutoCompounding,
                 stake,
+                None,
             ));
 
             // Register Dave in pallet_session (invulnerables are automatically registered)
@@ -3357,6 +3470,7 @@ fn test_pallet_session_limits_num_validators() {
                 DAVE.into(),
                 TargetPool::AutoCompounding,
                 stake,
+                None,
             ));
 
             let eligible_candidates =
@@ -3453,6 +3567,7 @@ fn test_pallet_session_limits_num_validators_from_staking() {
                 BOB.into(),
                 TargetPool::AutoCompounding,
                 stake,
+                None,
             ));
 
             assert_ok!(PooledStaking::request_delegate(
@@ -3460,6 +3575,7 @@ fn test_pallet_session_limits_num_validators_from_staking() {
                 CHARLIE.into(),
                 TargetPool::AutoCompounding,
                 stake,
+                None,
             ));
 
             assert_ok!(PooledStaking::request_delegate(
@@ -3467,6 +3583,7 @@ fn test_pallet_session_limits_num_validators_from_staking() {
                 DAVE.into(),
                 TargetPool::AutoCompounding,
                 stake,
+                None,
             ));
 
             let eligible_candidates =
@@ -3559,3 +3676,192 @@ fn test_migration_holds() {
             assert_eq!(new_holds[0].amount, 100u128);
         });
 }
+
+#[test]
+fn test_unpaid_execution_from_relay_is_rate_limited() {
+    use {
+        dancebox_runtime::xcm_config::{MaxUnpaidExecutionsPerBlock, XcmConfig},
+        frame_support::weights::Weight,
+        xcm::latest::prelude::*,
+        xcm_executor::XcmExecutor,
+    };
+
+    ExtBuilder::default().build().execute_with(|| {
+        let unpaid_message = || {
+            Xcm::<RuntimeCall>(vec![
+                UnpaidExecution {
+                    weight_limit: Unlimited,
+                    check_origin: None,
+                },
+                ClearOrigin,
+            ])
+        };
+        let execute = || {
+            XcmExecutor::<XcmConfig>::execute_xcm(
+                Parent,
+                unpaid_message(),
+                [0; 32],
+                Weight::from_parts(1_000_000_000_000, 1_000_000),
+            )
+        };
+
+        assert_eq!(MaxUnpaidExecutionsPerBlock::get(), 2);
+        assert!(matches!(execute(), Outcome::Complete(_)));
+        assert!(matches!(execute(), Outcome::Complete(_)));
+        // Budget exhausted, the message needs to pay for its execution
+        assert_eq!(execute(), Outcome::Error(XcmError::Barrier));
+
+        // The budget is renewed in the next block
+        run_to_block(2);
+        assert!(matches!(execute(), Outcome::Complete(_)));
+
+        // Other locations cannot execute without paying
+        assert_eq!(
+            XcmExecutor::<XcmConfig>::execute_xcm(
+                MultiLocation::new(1, X1(Parachain(2001))),
+                unpaid_message(),
+                [0; 32],
+                Weight::from_parts(1_000_000_000_000, 1_000_000),
+            ),
+            Outcome::Error(XcmError::Barrier)
+        );
+    });
+}
+
+#[test]
+fn test_inbound_messages_carrying_too_many_assets_are_rejected() {
+    use {
+        dancebox_runtime::{
+            xcm_config::{MaxAssetsPerMessage, SelfReserve, XcmConfig},
+            WeightToFee,
+        },
+        frame_support::weights::{Weight, WeightToFee as _},
+        xcm::latest::prelude::*,
+        xcm_builder::ParentIsPreset,
+        xcm_executor::{traits::Convert, XcmExecutor},
+    };
+
+    let parent_sovereign =
+        ParentIsPreset::<AccountId>::convert_ref(MultiLocation::parent()).unwrap();
+
+    ExtBuilder::default()
+        .with_balances(vec![(parent_sovereign, 100_000 * UNIT)])
+        .build()
+        .execute_with(|| {
+            let fee = MultiAsset {
+                id: Concrete(SelfReserve::get()),
+                fun: Fungible(WeightToFee::weight_to_fee(&Weight::from_parts(
+                    10_000_000_000,
+                    300_000,
+                ))),
+            };
+            let execute = |assets: MultiAssets| {
+                XcmExecutor::<XcmConfig>::execute_xcm(
+                    Parent,
+                    Xcm::<RuntimeCall>(vec![
+                        WithdrawAsset(assets),
+                        BuyExecution {
+                            fees: fee.clone(),
+                            weight_limit: Unlimited,
+                        },
+                        DepositAsset {
+                            assets: AllCounted(1).into(),
+                            beneficiary: AccountId32 {
+                                network: None,
+                                id: ALICE,
+                            }
+                            .into(),
+                        },
+                    ]),
+                    [0; 32],
+                    Weight::from_parts(1_000_000_000_000, 1_000_000),
+                )
+            };
+
+            assert!(matches!(
+                execute(vec![fee.clone()].into()),
+                Outcome::Complete(_)
+            ));
+
+            let too_many_assets: Vec<MultiAsset> = (0..MaxAssetsPerMessage::get())
+                .map(|i| MultiAsset {
+                    id: Concrete(MultiLocation::new(0, X1(GeneralIndex(i.into())))),
+                    fun: Fungible(1),
+                })
+                .chain([fee.clone()])
+                .collect();
+            assert_eq!(
+                execute(too_many_assets.into()),
+                Outcome::Error(XcmError::Barrier)
+            );
+        });
+}
+
+#[test]
+fn test_execution_can_be_bought_with_staking_reward_asset() {
+    use {
+        dancebox_runtime::xcm_config::{
+            StakingRewardAsset, StakingRewardAssetWeightToFee, XcmConfig,
+        },
+        frame_support::weights::{Weight, WeightToFee as _},
+        xcm::latest::prelude::*,
+        xcm_executor::XcmExecutor,
+    };
+
+    ExtBuilder::default()
+        .with_balances(vec![(AccountId::from(ALICE), 100 * UNIT)])
+        .build()
+        .execute_with(|| {
+            let fees = MultiAsset {
+                id: Concrete(StakingRewardAsset::get()),
+                fun: Fungible(UNIT),
+            };
+            let balance_before = System::account(AccountId::from(ALICE)).data.free;
+
+            let outcome = XcmExecutor::<XcmConfig>::execute_xcm(
+                AccountId32 {
+                    network: None,
+                    id: ALICE,
+                },
+                Xcm::<RuntimeCall>(vec![
+                    WithdrawAsset(fees.clone().into()),
+                    BuyExecution {
+                        fees,
+                        weight_limit: Unlimited,
+                    },
+                    RefundSurplus,
+                    // Only the staking reward asset is deposited, the unspent fees must be
+                    // returned in it
+                    DepositAsset {
+                        assets: Wild(AllOf {
+                            id: Concrete(StakingRewardAsset::get()),
+                            fun: WildFungible,
+                        }),
+                        beneficiary: AccountId32 {
+                            network: None,
+                            id: ALICE,
+                        }
+                        .into(),
+                    },
+                ]),
+                [0; 32],
+                Weight::from_parts(1_000_000_000_000, 1_000_000),
+            );
+
+            let Outcome::Complete(used) = outcome else {
+                panic!("execution failed: {:?}", outcome);
+            };
+            // Only the used weight is charged, at least at the price of the native currency
+            let expected_fee = StakingRewardAssetWeightToFee::weight_to_fee(&used);
+            assert!(expected_fee >= dancebox_runtime::WeightToFee::weight_to_fee(&used));
+            assert_eq!(
+                System::account(AccountId::from(ALICE)).data.free,
+                balance_before - expected_fee
+            );
+
+            // The proof size is charged too
+            assert!(
+                StakingRewardAssetWeightToFee::weight_to_fee(&Weight::from_parts(0, 64 * 1024)) > 0
+            );
+        });
+}
@@ -49,7 +49,10 @@ yargs(hideBin(process.argv))
                 const account = keyring.addFromUri(privKey);
 
                 const containerChainGenesisData = chainSpecToContainerChainGenesisData(api, rawSpec);
-                const tx = api.tx.registrar.register(rawSpec.para_id, containerChainGenesisData);
+                const tx = api.tx.utility.batchAll([
+                    api.tx.registrar.reserveParaId(rawSpec.para_id),
+                    api.tx.registrar.register(rawSpec.para_id, containerChainGenesisData),
+                ]);
                 process.stdout.write(`Sending transaction... `);
                 const txHash = await tx.signAndSend(account);
                 process.stdout.write(`${txHash.toHex()}\n`);
@@ -42,6 +42,8 @@ yargs(hideBin(process.argv))
 
                 const containerChainGenesisData = chainSpecToContainerChainGenesisData(api, rawSpec);
                 const txs = [];
+                const tx0 = api.tx.registrar.reserveParaId(rawSpec.para_id);
+                txs.push(tx0);
                 const tx1 = api.tx.registrar.register(rawSpec.para_id, containerChainGenesisData);
                 txs.push(tx1);
                 if (rawSpec.bootNodes?.length) {
@@ -53,11 +55,13 @@ yargs(hideBin(process.argv))
                 const tx3s = api.tx.sudo.sudo(tx3);
                 txs.push(tx3s);
 
-                if (txs.length == 2) {
-                    process.stdout.write(`Sending register transaction (register + markValidForCollating)... `);
+                if (txs.length == 3) {
+                    process.stdout.write(
+                        `Sending register transaction (reserveParaId + register + markValidForCollating)... `
+                    );
                 } else {
                     process.stdout.write(
-                        `Sending register transaction (register + setBootNodes + markValidForCollating)... `
+                        `Sending register transaction (reserveParaId + register + setBootNodes + markValidForCollating)... `
                     );
                 }
                 const txBatch = api.tx.utility.batchAll(txs);
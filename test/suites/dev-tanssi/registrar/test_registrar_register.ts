@@ -65,12 +65,14 @@ describeSuite({
                 };
                 const containerChainGenesisData = emptyGenesisData();
 
+                const tx0 = polkadotJs.tx.registrar.reserveParaId(2002);
                 const tx = polkadotJs.tx.registrar.register(2002, containerChainGenesisData);
                 const tx2 = polkadotJs.tx.registrar.markValidForCollating(2002);
                 const nonce = await polkadotJs.rpc.system.accountNextIndex(alice.publicKey);
                 await context.createBlock([
-                    await tx.signAsync(alice, { nonce }),
-                    await polkadotJs.tx.sudo.sudo(tx2).signAsync(alice, { nonce: nonce.addn(1) }),
+                    await tx0.signAsync(alice, { nonce }),
+                    await tx.signAsync(alice, { nonce: nonce.addn(1) }),
+                    await polkadotJs.tx.sudo.sudo(tx2).signAsync(alice, { nonce: nonce.addn(2) }),
                 ]);
 
                 const pendingParas = await polkadotJs.query.registrar.pendingParaIds();
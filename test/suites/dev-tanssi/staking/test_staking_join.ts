@@ -30,7 +30,8 @@ describeSuite({
                 const tx = polkadotJs.tx.pooledStaking.requestDelegate(
                     alice.address,
                     "AutoCompounding",
-                    10000000000000000n
+                    10000000000000000n,
+                    null
                 );
                 await context.createBlock([await tx.signAsync(alice)]);
                 const events = await polkadotJs.query.system.events();
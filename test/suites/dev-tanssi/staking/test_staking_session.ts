@@ -42,7 +42,8 @@ describeSuite({
                 const tx3 = polkadotJs.tx.pooledStaking.requestDelegate(
                     randomAccount.address,
                     "AutoCompounding",
-                    10000000000000000n
+                    10000000000000000n,
+                    null
                 );
                 await context.createBlock([await tx3.signAsync(randomAccount)]);
                 const events = await polkadotJs.query.system.events();
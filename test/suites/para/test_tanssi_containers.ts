@@ -238,8 +238,9 @@ describeSuite({
 
                 const chainSpec2002 = JSON.parse(spec2002);
                 const containerChainGenesisData = chainSpecToContainerChainGenesisData(paraApi, chainSpec2002);
+                const tx0 = paraApi.tx.registrar.reserveParaId(2002);
                 const tx = paraApi.tx.registrar.register(2002, containerChainGenesisData);
-                await signAndSendAndInclude(tx, alice);
+                await signAndSendAndInclude(paraApi.tx.utility.batchAll([tx0, tx]), alice);
                 const bootNodes = [
                     "/ip4/127.0.0.1/tcp/33051/ws/p2p/12D3KooWSDsmAa7iFbHdQW4X8B2KbeRYPDLarK6EbevUSYfGkeQw",
                 ];